@@ -29,7 +29,7 @@ usage() {
     echo "Optional:"
     echo "  --registry <registry>    Registry prefix (e.g., ghcr.io/eth-act/ere)"
     echo "  --cuda                   Enable CUDA support"
-    echo "  --cuda-archs <archs>     Set CUDA architectures (comma-separated, e.g., 89,120). Implies --cuda."
+    echo "  --cuda-archs <archs>     Set CUDA architectures (comma-separated, e.g., 89,120, or native for local GPUs). Implies --cuda."
     echo "  --rustflags <flags>      Pass RUSTFLAGS to build"
     exit 1
 }
@@ -134,6 +134,15 @@ if [ "$CUDA" = true ] && [ -z "$CUDA_ARCHS" ]; then
     CUDA_ARCHS="80,86,89,90,100,120" # Default: RTX 30/40/50, Ampere/Hopper/Blackwell Datacenter
 fi
 
+# Resolve native CUDA_ARCHS to the compute capabilities of local GPUs
+if [ "$CUDA_ARCHS" = "native" ]; then
+    CUDA_ARCHS=$(nvidia-smi --query-gpu=compute_cap --format=csv,noheader | tr -d '.' | sort -un | paste -sd, -)
+    if [ -z "$CUDA_ARCHS" ]; then
+        echo "Error: Failed to detect compute capabilities of local GPUs for --cuda-archs native"
+        exit 1
+    fi
+fi
+
 # Per-zkVM CUDA architecture translation
 if [ "$CUDA" = true ] && [ -n "$CUDA_ARCHS" ]; then
     case "$ZKVM" in
@@ -3,7 +3,7 @@
 use core::{iter, time::Duration};
 
 use ere_compiler_core::Elf;
-use ere_prover_core::{Input, RemoteProverConfig, zkVMVerifier};
+use ere_prover_core::{CommonError, Input, RemoteProverConfig, zkVMVerifier};
 use ere_verifier_zisk::{
     PROGRAM_VK_WORDS, PUBLIC_VALUES_BYTES, VadcopFinalProof, ZiskProgramVk, ZiskProof, ZiskVerifier,
 };
@@ -64,7 +64,7 @@ impl ZiskClusterClient {
     pub async fn create_prove_job(&self, input: &Input) -> Result<String, Error> {
         let mut client = self.client.clone();
         let req = JobRequestMessage {
-            job_kind: Some(prove_job(&self.hash_id, input)),
+            job_kind: Some(prove_job(&self.hash_id, input)?),
         };
         let job_id = client.job_request(req).await?.into_inner().job_id;
         Ok(job_id)
@@ -113,7 +113,7 @@ impl ZiskClusterClient {
     ) -> Result<(ZiskProof, Duration), Error> {
         let fut = async {
             let mut client = self.client.clone();
-            let job = prove_job(&self.hash_id, input);
+            let job = prove_job(&self.hash_id, input)?;
             loop {
                 let req = JobRequestMessage {
                     job_kind: Some(job.clone()),
@@ -224,20 +224,20 @@ async fn wait_job(
     }
 }
 
-fn prove_job(hash_id: &str, input: &Input) -> JobKind {
-    JobKind {
+fn prove_job(hash_id: &str, input: &Input) -> Result<JobKind, CommonError> {
+    Ok(JobKind {
         kind: Some(job_kind::Kind::Prove(ProveRequest {
             hash_id: hash_id.to_string(),
             input: Some(InputKind {
                 kind: Some(input_kind::Kind::Inline(InputChunk {
-                    data: framed_stdin(input.stdin()),
+                    data: framed_stdin(&input.stdin()?),
                 })),
             }),
             proof_dest: ProofKind::StarkMinimal as i32,
             proof_timeout: None,
             hints: None,
         })),
-    }
+    })
 }
 
 /// Returns `data` with a LE u64 length prefix and padding to multiple of 8.
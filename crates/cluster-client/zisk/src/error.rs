@@ -34,4 +34,7 @@ pub enum Error {
 
     #[error(transparent)]
     Verifier(#[from] ere_verifier_zisk::Error),
+
+    #[error(transparent)]
+    Common(#[from] ere_prover_core::CommonError),
 }
@@ -1,7 +1,7 @@
-use std::{env, path::Path};
+use std::path::Path;
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CargoBuildCmd, parse_cargo_features};
+use ere_util_compile::{CargoBuildCmd, CompileOption, parse_compile_args};
 
 use crate::Error;
 
@@ -35,16 +35,21 @@ impl Compiler for AirbenderRustRv32ima {
         guest_directory: impl AsRef<Path>,
         args: &[String],
     ) -> Result<Elf, Self::Error> {
-        let toolchain = env::var("ERE_RUST_TOOLCHAIN").unwrap_or_else(|_| "nightly".into());
+        let args = parse_compile_args(args)?;
+        args.check_supported(CompileOption::CARGO_BUILD)?;
         let elf = CargoBuildCmd::new()
             .linker_script(Some(LINKER_SCRIPT))
-            .toolchain(&toolchain)
+            .toolchain_from_env("nightly")
             .build_options(CARGO_BUILD_OPTIONS)
             .rustflags(RUSTFLAGS)
-            .features(&parse_cargo_features(args)?)
+            .compile_args(&args)
             .exec(guest_directory, TARGET_TRIPLE)?;
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(TARGET_TRIPLE)
+    }
 }
 
 #[cfg(test)]
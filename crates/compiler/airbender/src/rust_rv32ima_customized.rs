@@ -5,7 +5,7 @@ use airbender_build::{
 };
 use cargo_metadata::TargetKind;
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CommonError, cargo_metadata, parse_cargo_features, rustup_add_components};
+use ere_util_compile::{CommonError, cargo_metadata, parse_compile_args, rustup_add_components};
 use tempfile::tempdir;
 
 use crate::Error;
@@ -66,7 +66,10 @@ impl Compiler for AirbenderRustRv32imaCustomized {
         config.bin_name = Some(bin.name.clone());
         config.dist_dir = Some(tempdir.path().to_path_buf());
         config.target = Some(DEFAULT_GUEST_TARGET.into());
-        config.cargo_args = cargo_args(&linker_script_path, &parse_cargo_features(args)?);
+        let args = parse_compile_args(args)?;
+        // `airbender_build` only takes extra cargo arguments for features.
+        args.check_supported(&[])?;
+        config.cargo_args = cargo_args(&linker_script_path, &args.features);
         build_dist(&config)?;
 
         let elf_path = metadata
@@ -78,6 +81,10 @@ impl Compiler for AirbenderRustRv32imaCustomized {
             fs::read(&elf_path).map_err(|err| CommonError::read_file("elf", &elf_path, err))?;
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(DEFAULT_GUEST_TARGET)
+    }
 }
 
 fn cargo_args(linker_script_path: &Path, features: &[String]) -> Vec<String> {
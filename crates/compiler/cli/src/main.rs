@@ -1,9 +1,17 @@
-use std::path::PathBuf;
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Instant,
+};
 
 use anyhow::{Context, Error};
 use clap::Parser;
 use ere_catalog::CompilerKind;
-use ere_compiler_core::Elf;
+use ere_compiler_core::{CompileReport, Compiler, Elf};
+use tempfile::TempDir;
+use tracing::info;
 use tracing_subscriber::EnvFilter;
 
 // Compile-time check to ensure exactly one zkVM feature is enabled for `ere-compiler`
@@ -25,15 +33,30 @@ struct Args {
     /// Compiler kind to use
     #[arg(long, value_parser = <CompilerKind as core::str::FromStr>::from_str)]
     compiler_kind: CompilerKind,
-    /// Directory of the guest program
+    /// Directory of the guest program, relative to the repository root if `--git-url` is given.
+    ///
+    /// Can be given multiple times to compile guest programs in one run, then the ELF of the
+    /// `i`-th one is written to `{output_dir}/{i}/{elf_name}`.
+    #[arg(long, required = true)]
+    guest_dir: Vec<PathBuf>,
+    /// Git repository to clone the guest program from
     #[arg(long)]
-    guest_dir: PathBuf,
+    git_url: Option<String>,
+    /// Git revision (commit, branch or tag) to checkout after cloning
+    #[arg(long, requires = "git_url")]
+    git_rev: Option<String>,
     /// Directory where the compiled ELF will be written
     #[arg(long)]
     output_dir: PathBuf,
     /// Name of the output ELF file (optional)
     #[arg(long)]
     elf_name: Option<String>,
+    /// Compress the output ELF with zstd, which is decompressed transparently on load
+    #[arg(long)]
+    compress: bool,
+    /// Name of the output compile report file in JSON, written next to the ELF (optional)
+    #[arg(long)]
+    report_name: Option<String>,
     /// Extra args forwarded to the underlying compiler
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     args: Vec<String>,
@@ -52,24 +75,120 @@ fn main() -> Result<(), Error> {
             .with_context(|| "Failed to create output directory")?;
     }
 
-    let elf = compile(args.guest_dir, args.compiler_kind, &args.args)?;
+    // Removed on drop, after all guests are compiled.
+    let repo_dir = match &args.git_url {
+        Some(git_url) => Some(clone_git(git_url, args.git_rev.as_deref())?),
+        None => None,
+    };
 
-    if let Some(elf_name) = args.elf_name {
-        let path = args.output_dir.join(elf_name);
-        std::fs::write(&path, &elf).with_context(|| format!("Failed to write ELF to {path:?}"))?;
+    let multiple = args.guest_dir.len() > 1;
+    for (idx, guest_dir) in args.guest_dir.into_iter().enumerate() {
+        let guest_dir = match &repo_dir {
+            Some(repo_dir) => repo_dir.path().join(guest_dir),
+            None => guest_dir,
+        };
+
+        let start = Instant::now();
+        let (elf, target) = compile(guest_dir, args.compiler_kind, &args.args)?;
+        let mut report = CompileReport::new(&elf, start.elapsed()).with_target(target);
+
+        let output_dir = if multiple {
+            args.output_dir.join(idx.to_string())
+        } else {
+            args.output_dir.clone()
+        };
+        fs::create_dir_all(&output_dir)
+            .with_context(|| format!("Failed to create output directory {output_dir:?}"))?;
+
+        if let Some(elf_name) = &args.elf_name {
+            let path = output_dir.join(elf_name);
+            if let Some(compressed_elf_size) = write_elf(&elf, &path, args.compress)? {
+                report = report.with_compressed_elf_size(compressed_elf_size);
+            }
+        }
+
+        if let Some(report_name) = &args.report_name {
+            let path = output_dir.join(report_name);
+            let bytes = serde_json::to_vec_pretty(&report).context("Failed to encode report")?;
+            fs::write(&path, bytes)
+                .with_context(|| format!("Failed to write report to {path:?}"))?;
+        }
     }
 
     Ok(())
 }
 
-fn compile(guest_dir: PathBuf, compiler_kind: CompilerKind, args: &[String]) -> Result<Elf, Error> {
+/// Writes the ELF to `path`, and returns the compressed size if `compress`.
+fn write_elf(elf: &Elf, path: &Path, compress: bool) -> Result<Option<u64>, Error> {
+    let (bytes, compressed_size) = if compress {
+        let compressed = elf.compress().context("Failed to compress ELF")?;
+        info!(
+            "Compressed ELF from {} bytes to {} bytes",
+            elf.len(),
+            compressed.len()
+        );
+        let compressed_size = compressed.len() as u64;
+        (Cow::Owned(compressed), Some(compressed_size))
+    } else {
+        (Cow::Borrowed(&elf[..]), None)
+    };
+    fs::write(path, bytes).with_context(|| format!("Failed to write ELF to {path:?}"))?;
+    Ok(compressed_size)
+}
+
+/// Clones the git repository `url` at `rev` with submodules into a temporary directory unique to
+/// this run, and returns the repository root.
+fn clone_git(url: &str, rev: Option<&str>) -> Result<TempDir, Error> {
+    let repo_dir = tempfile::tempdir().context("Failed to create guest repository directory")?;
+
+    info!("Cloning guest repository {url}...");
+    git(
+        None,
+        [
+            "clone",
+            "--recurse-submodules",
+            url,
+            &repo_dir.path().to_string_lossy(),
+        ],
+    )?;
+    if let Some(rev) = rev {
+        git(Some(repo_dir.path()), ["checkout", rev])?;
+        git(
+            Some(repo_dir.path()),
+            ["submodule", "update", "--init", "--recursive"],
+        )?;
+    }
+
+    Ok(repo_dir)
+}
+
+fn git<'a>(dir: Option<&Path>, args: impl IntoIterator<Item = &'a str>) -> Result<(), Error> {
+    let mut cmd = Command::new("git");
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    cmd.args(args);
+    let status = cmd
+        .status()
+        .with_context(|| format!("Failed to run {cmd:?}"))?;
+    anyhow::ensure!(status.success(), "Command {cmd:?} failed with {status}");
+    Ok(())
+}
+
+/// Compiles the guest by the compiler of `compiler_kind`, and returns the ELF with the target it's
+/// built for.
+fn compile(
+    guest_dir: PathBuf,
+    compiler_kind: CompilerKind,
+    args: &[String],
+) -> Result<(Elf, Option<String>), Error> {
     #[cfg(feature = "airbender")]
-    let elf = {
+    let compiled = {
         use ere_compiler_airbender::*;
         match compiler_kind {
-            CompilerKind::Rust => AirbenderRustRv32ima.compile(guest_dir, args)?,
+            CompilerKind::Rust => compile_by(AirbenderRustRv32ima, guest_dir, args)?,
             CompilerKind::RustCustomized => {
-                AirbenderRustRv32imaCustomized.compile(guest_dir, args)?
+                compile_by(AirbenderRustRv32imaCustomized, guest_dir, args)?
             }
             _ => anyhow::bail!(unsupported_compiler_kind_err(
                 compiler_kind,
@@ -79,11 +198,13 @@ fn compile(guest_dir: PathBuf, compiler_kind: CompilerKind, args: &[String]) ->
     };
 
     #[cfg(feature = "openvm")]
-    let elf = {
+    let compiled = {
         use ere_compiler_openvm::*;
         match compiler_kind {
-            CompilerKind::Rust => OpenVMRustRv32ima.compile(guest_dir, args)?,
-            CompilerKind::RustCustomized => OpenVMRustRv32imaCustomized.compile(guest_dir, args)?,
+            CompilerKind::Rust => compile_by(OpenVMRustRv32ima, guest_dir, args)?,
+            CompilerKind::RustCustomized => {
+                compile_by(OpenVMRustRv32imaCustomized, guest_dir, args)?
+            }
             _ => anyhow::bail!(unsupported_compiler_kind_err(
                 compiler_kind,
                 [CompilerKind::Rust, CompilerKind::RustCustomized]
@@ -92,11 +213,13 @@ fn compile(guest_dir: PathBuf, compiler_kind: CompilerKind, args: &[String]) ->
     };
 
     #[cfg(feature = "risc0")]
-    let elf = {
+    let compiled = {
         use ere_compiler_risc0::*;
         match compiler_kind {
-            CompilerKind::Rust => Risc0RustRv32ima.compile(guest_dir, args)?,
-            CompilerKind::RustCustomized => Risc0RustRv32imaCustomized.compile(guest_dir, args)?,
+            CompilerKind::Rust => compile_by(Risc0RustRv32ima, guest_dir, args)?,
+            CompilerKind::RustCustomized => {
+                compile_by(Risc0RustRv32imaCustomized, guest_dir, args)?
+            }
             _ => anyhow::bail!(unsupported_compiler_kind_err(
                 compiler_kind,
                 [CompilerKind::Rust, CompilerKind::RustCustomized]
@@ -105,11 +228,11 @@ fn compile(guest_dir: PathBuf, compiler_kind: CompilerKind, args: &[String]) ->
     };
 
     #[cfg(feature = "sp1")]
-    let elf = {
+    let compiled = {
         use ere_compiler_sp1::*;
         match compiler_kind {
-            CompilerKind::Rust => SP1RustRv64ima.compile(guest_dir, args)?,
-            CompilerKind::RustCustomized => SP1RustRv64imaCustomized.compile(guest_dir, args)?,
+            CompilerKind::Rust => compile_by(SP1RustRv64ima::default(), guest_dir, args)?,
+            CompilerKind::RustCustomized => compile_by(SP1RustRv64imaCustomized, guest_dir, args)?,
             _ => anyhow::bail!(unsupported_compiler_kind_err(
                 compiler_kind,
                 [CompilerKind::Rust, CompilerKind::RustCustomized]
@@ -118,16 +241,25 @@ fn compile(guest_dir: PathBuf, compiler_kind: CompilerKind, args: &[String]) ->
     };
 
     #[cfg(feature = "zisk")]
-    let elf = {
+    let compiled = {
         use ere_compiler_zisk::*;
         match compiler_kind {
-            CompilerKind::Rust => ZiskRustRv64ima.compile(guest_dir, args)?,
-            CompilerKind::RustCustomized => ZiskRustRv64imaCustomized.compile(guest_dir, args)?,
-            CompilerKind::GoCustomized => ZiskGoCustomized.compile(guest_dir, args)?,
+            CompilerKind::Rust => compile_by(ZiskRustRv64ima, guest_dir, args)?,
+            CompilerKind::RustCustomized => compile_by(ZiskRustRv64imaCustomized, guest_dir, args)?,
+            CompilerKind::GoCustomized => compile_by(ZiskGoCustomized, guest_dir, args)?,
         }
     };
 
-    Ok(elf)
+    Ok(compiled)
+}
+
+fn compile_by<C: Compiler>(
+    compiler: C,
+    guest_dir: PathBuf,
+    args: &[String],
+) -> Result<(Elf, Option<String>), Error> {
+    let elf = compiler.compile(guest_dir, args)?;
+    Ok((elf, compiler.target().map(ToString::to_string)))
 }
 
 #[allow(dead_code)]
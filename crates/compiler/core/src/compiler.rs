@@ -17,4 +17,10 @@ pub trait Compiler {
         guest_directory: impl AsRef<Path>,
         args: &[String],
     ) -> Result<Elf, Self::Error>;
+
+    /// Returns the target triple the compiler builds for (`GOOS/GOARCH` for Go), `None` if it's
+    /// not known (e.g. when the compiler runs another one in a container).
+    fn target(&self) -> Option<&str> {
+        None
+    }
 }
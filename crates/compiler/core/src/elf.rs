@@ -1,12 +1,88 @@
 use core::{fmt, ops::Deref};
+use std::{fs, io, path::Path};
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// ELF binary of a compiled guest program.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Elf(pub Vec<u8>);
 
+/// Magic number of a zstd frame, which can't be mistaken for the ELF magic `\x7fELF`.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+#[cfg(feature = "zstd")]
+impl Elf {
+    /// Compresses the ELF with zstd for storage or transport.
+    ///
+    /// ELFs with proving keys embedded (e.g. ZisK and Risc0) could reach hundreds of MB, which
+    /// compress well.
+    pub fn compress(&self) -> io::Result<Vec<u8>> {
+        zstd::encode_all(self.0.as_slice(), 0)
+    }
+
+    /// Returns the ELF from `bytes`, decompressing if compressed by [`Elf::compress`].
+    pub fn from_maybe_compressed(bytes: Vec<u8>) -> io::Result<Self> {
+        if bytes.starts_with(&ZSTD_MAGIC) {
+            zstd::decode_all(bytes.as_slice()).map(Self)
+        } else {
+            Ok(Self(bytes))
+        }
+    }
+}
+
+impl Elf {
+    /// Returns the SHA-256 digest of the ELF, which identifies the program.
+    pub fn digest(&self) -> [u8; 32] {
+        Sha256::digest(&self.0).into()
+    }
+
+    /// Writes the ELF to `path`, compressed by [`Elf::compress`] if feature `zstd` is enabled.
+    ///
+    /// This allows compiling on one machine and proving on another (e.g. an air-gapped prover),
+    /// with the ELF read back by [`Elf::read_from`].
+    ///
+    /// Without feature `zstd`, returns an error if the ELF starts with the zstd magic number,
+    /// since it would be decompressed when read back with the feature enabled.
+    pub fn write_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        #[cfg(feature = "zstd")]
+        let bytes = self.compress()?;
+        #[cfg(not(feature = "zstd"))]
+        let bytes = {
+            if self.0.starts_with(&ZSTD_MAGIC) {
+                return Err(zstd_feature_disabled());
+            }
+            self.0.as_slice()
+        };
+        fs::write(path, bytes)
+    }
+
+    /// Reads the ELF from `path`, decompressing if compressed when feature `zstd` is enabled.
+    ///
+    /// Without feature `zstd`, returns an error if the file is compressed.
+    pub fn read_from(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        #[cfg(feature = "zstd")]
+        return Self::from_maybe_compressed(bytes);
+        #[cfg(not(feature = "zstd"))]
+        if bytes.starts_with(&ZSTD_MAGIC) {
+            Err(zstd_feature_disabled())
+        } else {
+            Ok(Self(bytes))
+        }
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_feature_disabled() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "ELF starts with the zstd magic number, enable feature `zstd` to read and write \
+         compressed ELFs",
+    )
+}
+
 impl fmt::Debug for Elf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Elf").field("len", &self.0.len()).finish()
@@ -31,3 +107,46 @@ impl From<Vec<u8>> for Elf {
         Self(v)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "zstd"))]
+    use std::io;
+
+    use crate::Elf;
+    #[cfg(not(feature = "zstd"))]
+    use crate::elf::ZSTD_MAGIC;
+
+    #[test]
+    fn test_write_read() {
+        let elf = Elf([b"\x7fELF".as_slice(), &[0; 1024]].concat());
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("guest.elf");
+        elf.write_to(&path).unwrap();
+        assert_eq!(Elf::read_from(&path).unwrap(), elf);
+    }
+
+    #[test]
+    #[cfg(not(feature = "zstd"))]
+    fn test_write_read_zstd_magic() {
+        let elf = Elf([ZSTD_MAGIC.as_slice(), &[0; 1024]].concat());
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("guest.elf");
+        let err = elf.write_to(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+
+        std::fs::write(&path, &elf.0).unwrap();
+        let err = Elf::read_from(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn test_compress() {
+        let elf = Elf([b"\x7fELF".as_slice(), &[0; 1024]].concat());
+        let compressed = elf.compress().unwrap();
+        assert!(compressed.len() < elf.len());
+        assert_eq!(Elf::from_maybe_compressed(compressed).unwrap(), elf);
+        assert_eq!(Elf::from_maybe_compressed(elf.0.clone()).unwrap(), elf);
+    }
+}
@@ -2,5 +2,12 @@
 
 mod compiler;
 mod elf;
+mod report;
+mod reproducibility;
 
-pub use crate::{compiler::Compiler, elf::Elf};
+pub use crate::{
+    compiler::Compiler,
+    elf::Elf,
+    report::CompileReport,
+    reproducibility::{ElfDifference, ReproducibilityReport, verify_reproducibility},
+};
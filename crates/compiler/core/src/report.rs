@@ -0,0 +1,149 @@
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Elf;
+
+/// Report of a compilation, to track binary size regressions across zkVM SDK upgrades.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompileReport {
+    /// Size of the ELF in bytes.
+    pub elf_size: u64,
+    /// Size of the ELF compressed by [`Elf::compress`] in bytes, if compressed.
+    pub compressed_elf_size: Option<u64>,
+    /// Target triple the ELF is built for, see [`Compiler::target`](crate::Compiler::target).
+    pub target: Option<String>,
+    /// `rustc --version` of the toolchain the ELF is built with, read from its `.comment` section,
+    /// `None` if not recorded there (e.g. for Go guests or stripped ELFs).
+    pub toolchain: Option<String>,
+    /// Duration of the compilation.
+    pub build_duration: Duration,
+}
+
+impl CompileReport {
+    pub fn new(elf: &Elf, build_duration: Duration) -> Self {
+        Self {
+            elf_size: elf.len() as u64,
+            toolchain: elf_rustc_version(elf),
+            build_duration,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_compressed_elf_size(mut self, compressed_elf_size: u64) -> Self {
+        self.compressed_elf_size = Some(compressed_elf_size);
+        self
+    }
+
+    pub fn with_target(mut self, target: Option<impl Into<String>>) -> Self {
+        self.target = target.map(Into::into);
+        self
+    }
+}
+
+/// Returns the rustc version recorded in the `.comment` section of the ELF, like the output of
+/// `rustc --version`, `None` if it's not an ELF or there is none.
+fn elf_rustc_version(elf: &[u8]) -> Option<String> {
+    const RUSTC_VERSION_PREFIX: &[u8] = b"rustc version ";
+
+    let comment = elf_section(elf, b".comment")?;
+    let version = comment
+        .split(|byte| *byte == 0)
+        .find_map(|entry| entry.strip_prefix(RUSTC_VERSION_PREFIX))?;
+    Some(format!("rustc {}", String::from_utf8_lossy(version)))
+}
+
+/// Returns the content of the section `name` of a little-endian ELF, `None` if it's not found.
+fn elf_section<'a>(elf: &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+    // Reads the little-endian field of `len` bytes at `base + offset`.
+    let read = |base: usize, offset: usize, len: usize| -> Option<usize> {
+        let start = base.checked_add(offset)?;
+        let bytes = elf.get(start..start.checked_add(len)?)?;
+        let mut buf = [0; 8];
+        buf[..len].copy_from_slice(bytes);
+        usize::try_from(u64::from_le_bytes(buf)).ok()
+    };
+
+    if elf.get(..4)? != b"\x7fELF" || *elf.get(5)? != 1 {
+        return None;
+    }
+    // Offsets of `e_shoff`, `e_shentsize`, `sh_offset` and word size of ELF32 or ELF64.
+    let (shoff, shentsize, sh_offset, word) = match elf.get(4)? {
+        1 => (0x20, 0x2e, 0x10, 4),
+        2 => (0x28, 0x3a, 0x18, 8),
+        _ => return None,
+    };
+    let section_headers = read(0, shoff, word)?;
+    let entry_size = read(0, shentsize, 2)?;
+    let entries = read(0, shentsize + 2, 2)?;
+    let names_index = read(0, shentsize + 4, 2)?;
+
+    let header = |index: usize| section_headers.checked_add(index.checked_mul(entry_size)?);
+    let content = |header: usize| {
+        let offset = read(header, sh_offset, word)?;
+        let size = read(header, sh_offset + word, word)?;
+        elf.get(offset..offset.checked_add(size)?)
+    };
+    let names = content(header(names_index)?)?;
+    (0..entries).find_map(|index| {
+        let header = header(index)?;
+        let section_name = names.get(read(header, 0, 4)?..)?;
+        let section_name = &section_name[..section_name.iter().position(|byte| *byte == 0)?];
+        (section_name == name).then(|| content(header)).flatten()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::elf_rustc_version;
+
+    /// Returns an ELF of `class` with only the section `.comment` of `comment`.
+    fn elf(class: u8, comment: &[u8]) -> Vec<u8> {
+        let (header_len, shoff, shentsize, sh_offset, word, entry_size) = match class {
+            1 => (0x34, 0x20, 0x2e, 0x10, 4, 0x28),
+            _ => (0x40, 0x28, 0x3a, 0x18, 8, 0x40),
+        };
+        let names = b"\0.shstrtab\0.comment\0";
+        let write = |elf: &mut Vec<u8>, offset: usize, value: usize, len: usize| {
+            elf[offset..offset + len].copy_from_slice(&value.to_le_bytes()[..len]);
+        };
+
+        let mut elf = vec![0; header_len];
+        elf[..6].copy_from_slice(&[0x7f, b'E', b'L', b'F', class, 1]);
+        elf.extend_from_slice(names);
+        elf.extend_from_slice(comment);
+        let section_headers = elf.len();
+        elf.resize(section_headers + 3 * entry_size, 0);
+        write(&mut elf, shoff, section_headers, word);
+        write(&mut elf, shentsize, entry_size, 2);
+        write(&mut elf, shentsize + 2, 3, 2);
+        write(&mut elf, shentsize + 4, 1, 2);
+        // Section 0 is null, 1 is `.shstrtab` and 2 is `.comment`.
+        for (index, name, offset, size) in [
+            (1, 1, header_len, names.len()),
+            (2, 11, header_len + names.len(), comment.len()),
+        ] {
+            let header = section_headers + index * entry_size;
+            write(&mut elf, header, name, 4);
+            write(&mut elf, header + sh_offset, offset, word);
+            write(&mut elf, header + sh_offset + word, size, word);
+        }
+        elf
+    }
+
+    #[test]
+    fn test_elf_rustc_version() {
+        let comment = b"Linker: LLD 20.1.1\0rustc version 1.88.0-nightly (6f6971078 2025-05-28)\0";
+        for class in [1, 2] {
+            assert_eq!(
+                elf_rustc_version(&elf(class, comment)).as_deref(),
+                Some("rustc 1.88.0-nightly (6f6971078 2025-05-28)")
+            );
+            assert_eq!(elf_rustc_version(&elf(class, b"Linker: LLD 20.1.1\0")), None);
+        }
+        let elf = elf(2, comment);
+        assert_eq!(elf_rustc_version(&elf[..0x40]), None);
+        assert_eq!(elf_rustc_version(b"not an elf"), None);
+    }
+}
@@ -0,0 +1,194 @@
+use std::{env, path::Path};
+
+use sha2::{Digest, Sha256};
+
+use crate::{Compiler, Elf};
+
+/// Report of [`verify_reproducibility`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReproducibilityReport {
+    /// SHA-256 digests of the ELF of each compilation, in order.
+    pub digests: Vec<[u8; 32]>,
+    /// Differences of the ELF of each compilation from the first one.
+    pub differences: Vec<ElfDifference>,
+    /// Host paths found in the ELF (e.g. the guest directory or home directory), which makes the
+    /// ELF depend on where it is compiled.
+    pub leaked_paths: Vec<String>,
+}
+
+impl ReproducibilityReport {
+    /// Returns `true` if all compilations produce the same ELF without leaking host paths.
+    pub fn is_reproducible(&self) -> bool {
+        self.differences.is_empty() && self.leaked_paths.is_empty()
+    }
+}
+
+/// Difference of the ELF of a compilation from the one of the first compilation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElfDifference {
+    /// Index of the compilation.
+    pub index: usize,
+    /// Offset of the first differing byte.
+    pub offset: usize,
+    /// Number of differing bytes, where the extra bytes of the longer ELF are counted as
+    /// differing.
+    pub num_bytes: usize,
+}
+
+/// Compiles the guest program at `guest_directory` `n` times and compares the ELFs, to audit
+/// whether the compiler is deterministic.
+///
+/// Compiling on different machines (e.g. with the dockerized compiler) could additionally reveal
+/// nondeterminism from the environment, which the [`ReproducibilityReport::leaked_paths`] hint.
+///
+/// # Panics
+///
+/// Panics if `n` is less than 2, since there would be nothing to compare.
+pub fn verify_reproducibility<C: Compiler>(
+    compiler: &C,
+    guest_directory: impl AsRef<Path>,
+    args: &[String],
+    n: usize,
+) -> Result<ReproducibilityReport, C::Error> {
+    assert!(
+        n >= 2,
+        "At least 2 compilations are required to compare, got {n}"
+    );
+
+    let guest_directory = guest_directory.as_ref();
+    let elfs = (0..n)
+        .map(|_| compiler.compile(guest_directory, args))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let digests = elfs.iter().map(|elf| Sha256::digest(elf).into()).collect();
+
+    let differences = elfs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(index, elf)| elf_difference(&elfs[0], elf, index))
+        .collect();
+
+    let candidates = [
+        guest_directory.canonicalize().ok(),
+        env::home_dir().filter(|home| home.parent().is_some()),
+    ];
+    let leaked_paths = candidates
+        .into_iter()
+        .flatten()
+        .map(|path| path.to_string_lossy().to_string())
+        .filter(|path| elfs.iter().any(|elf| contains(elf, path.as_bytes())))
+        .collect();
+
+    Ok(ReproducibilityReport {
+        digests,
+        differences,
+        leaked_paths,
+    })
+}
+
+fn elf_difference(a: &Elf, b: &Elf, index: usize) -> Option<ElfDifference> {
+    let num_bytes =
+        a.iter().zip(b.iter()).filter(|(a, b)| a != b).count() + a.len().abs_diff(b.len());
+    let offset = a
+        .iter()
+        .zip(b.iter())
+        .position(|(a, b)| a != b)
+        .unwrap_or(a.len().min(b.len()));
+    (num_bytes > 0).then_some(ElfDifference {
+        index,
+        offset,
+        num_bytes,
+    })
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    !needle.is_empty()
+        && haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, convert::Infallible, path::Path};
+
+    use crate::{
+        Compiler, Elf,
+        reproducibility::{ElfDifference, verify_reproducibility},
+    };
+
+    /// Compiler that embeds the number of compilations and optionally the guest directory.
+    struct TestCompiler {
+        count: Cell<u8>,
+        deterministic: bool,
+        leak_path: bool,
+    }
+
+    impl Compiler for TestCompiler {
+        type Error = Infallible;
+
+        fn compile(
+            &self,
+            guest_directory: impl AsRef<Path>,
+            _: &[String],
+        ) -> Result<Elf, Infallible> {
+            self.count.set(self.count.get() + 1);
+            let mut elf = vec![0x7f, b'E', b'L', b'F'];
+            if !self.deterministic {
+                elf.push(self.count.get());
+            }
+            if self.leak_path {
+                let path = guest_directory.as_ref().canonicalize().unwrap();
+                elf.extend(path.to_string_lossy().as_bytes());
+            }
+            Ok(Elf(elf))
+        }
+    }
+
+    fn compiler(deterministic: bool, leak_path: bool) -> TestCompiler {
+        TestCompiler {
+            count: Cell::new(0),
+            deterministic,
+            leak_path,
+        }
+    }
+
+    #[test]
+    fn test_verify_reproducibility() {
+        let guest_directory = env!("CARGO_MANIFEST_DIR");
+
+        let report =
+            verify_reproducibility(&compiler(true, false), guest_directory, &[], 3).unwrap();
+        assert!(report.is_reproducible());
+        assert_eq!(report.digests.len(), 3);
+
+        let report =
+            verify_reproducibility(&compiler(false, false), guest_directory, &[], 3).unwrap();
+        assert!(!report.is_reproducible());
+        assert_eq!(
+            report.differences,
+            [1, 2].map(|index| ElfDifference {
+                index,
+                offset: 4,
+                num_bytes: 1,
+            })
+        );
+
+        let report =
+            verify_reproducibility(&compiler(true, true), guest_directory, &[], 2).unwrap();
+        assert!(!report.is_reproducible());
+        assert!(report.differences.is_empty());
+        let guest_directory = Path::new(guest_directory).canonicalize().unwrap();
+        assert!(
+            report
+                .leaked_paths
+                .contains(&guest_directory.to_string_lossy().to_string())
+        );
+    }
+    #[test]
+    #[should_panic(expected = "At least 2 compilations are required to compare, got 1")]
+    fn test_verify_reproducibility_single_compilation() {
+        let _ = verify_reproducibility(&compiler(true, false), env!("CARGO_MANIFEST_DIR"), &[], 1);
+    }
+}
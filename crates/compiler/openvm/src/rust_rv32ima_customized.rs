@@ -1,11 +1,14 @@
 use std::{fs, path::Path};
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CommonError, parse_cargo_features, rustup_add_rust_src};
+use ere_util_compile::{CommonError, CompileOption, parse_compile_args, rustup_add_rust_src};
 use openvm_build::{GuestOptions, get_rustup_toolchain_name};
 
 use crate::Error;
 
+/// Target `openvm_build` builds guests for.
+const TARGET_TRIPLE: &str = "riscv32im-risc0-zkvm-elf";
+
 /// Compiler for Rust guest program to RV32IMA architecture, using customized
 /// target `riscv32im-risc0-zkvm-elf`.
 pub struct OpenVMRustRv32imaCustomized;
@@ -22,10 +25,12 @@ impl Compiler for OpenVMRustRv32imaCustomized {
 
         // Inlining `openvm_sdk::Sdk::build` in order to get raw elf bytes.
         let guest_directory = guest_directory.as_ref();
+        let args = parse_compile_args(args)?;
+        args.check_supported(&[CompileOption::Profile])?;
         let pkg = openvm_build::get_package(guest_directory);
         let guest_opts = GuestOptions::default()
-            .with_profile("release".to_string())
-            .with_features(parse_cargo_features(args)?);
+            .with_profile(args.profile.unwrap_or_else(|| "release".to_string()))
+            .with_features(args.features);
         let target_dir = match openvm_build::build_guest_package(&pkg, &guest_opts, None, &None) {
             Ok(target_dir) => target_dir,
             Err(Some(code)) => return Err(Error::BuildFailed(code))?,
@@ -39,6 +44,10 @@ impl Compiler for OpenVMRustRv32imaCustomized {
 
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(TARGET_TRIPLE)
+    }
 }
 
 #[cfg(test)]
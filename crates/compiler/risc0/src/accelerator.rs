@@ -0,0 +1,115 @@
+use std::{fmt, path::Path};
+
+use ere_util_compile::{cargo_metadata, cargo_metadata_with_config};
+
+use crate::Error;
+
+/// Fork of a crate accelerated by the Risc0 precompiles, see
+/// <https://dev.risczero.com/api/zkvm/precompiles>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcceleratorPatch {
+    /// Name of the patched crate on crates.io.
+    pub crate_name: &'static str,
+    /// `{major}.{minor}` of the crate versions the fork is compatible with.
+    pub version: &'static str,
+    pub git: &'static str,
+    pub tag: &'static str,
+}
+
+impl AcceleratorPatch {
+    /// Returns the `[patch.crates-io]` entry as `--config` values of cargo.
+    pub(crate) fn cargo_config(&self) -> [String; 2] {
+        let Self {
+            crate_name,
+            git,
+            tag,
+            ..
+        } = self;
+        [
+            format!("patch.crates-io.{crate_name}.git=\"{git}\""),
+            format!("patch.crates-io.{crate_name}.tag=\"{tag}\""),
+        ]
+    }
+}
+
+impl fmt::Display for AcceleratorPatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.crate_name, self.tag)
+    }
+}
+
+/// Accelerated forks supported by Risc0 v3.
+pub const ACCELERATOR_PATCHES: &[AcceleratorPatch] = &[
+    AcceleratorPatch {
+        crate_name: "sha2",
+        version: "0.10",
+        git: "https://github.com/risc0/RustCrypto-hashes",
+        tag: "sha2-v0.10.8-risczero.0",
+    },
+    AcceleratorPatch {
+        crate_name: "k256",
+        version: "0.13",
+        git: "https://github.com/risc0/RustCrypto-elliptic-curves",
+        tag: "k256/v0.13.4-risczero.1",
+    },
+    AcceleratorPatch {
+        crate_name: "crypto-bigint",
+        version: "0.5",
+        git: "https://github.com/risc0/RustCrypto-crypto-bigint",
+        tag: "v0.5.5-risczero.0",
+    },
+];
+
+/// Returns the [`ACCELERATOR_PATCHES`] applying to the guest, which are the ones whose crate is a
+/// dependency from crates.io in a compatible version.
+///
+/// Dependencies already patched in the guest manifest are not from crates.io, so they are
+/// skipped.
+pub fn accelerator_patches(
+    guest_directory: impl AsRef<Path>,
+) -> Result<Vec<AcceleratorPatch>, Error> {
+    let metadata = cargo_metadata(guest_directory)?;
+    Ok(ACCELERATOR_PATCHES
+        .iter()
+        .filter(|patch| {
+            metadata.packages.iter().any(|package| {
+                package.name == patch.crate_name
+                    && package
+                        .source
+                        .as_ref()
+                        .is_some_and(|source| source.is_crates_io())
+                    && format!("{}.{}", package.version.major, package.version.minor)
+                        == patch.version
+            })
+        })
+        .copied()
+        .collect())
+}
+
+/// Returns the `patches` in effect for the guest, which are the ones whose crate resolves to the
+/// fork once the patches are passed to cargo.
+///
+/// Cargo ignores a `[patch]` whose version doesn't match the one locked in the guest
+/// `Cargo.lock`, so the patches passed to the build aren't necessarily applied.
+pub fn applied_accelerator_patches(
+    guest_directory: impl AsRef<Path>,
+    patches: &[AcceleratorPatch],
+) -> Result<Vec<AcceleratorPatch>, Error> {
+    let config = patches
+        .iter()
+        .flat_map(AcceleratorPatch::cargo_config)
+        .collect::<Vec<_>>();
+    let metadata = cargo_metadata_with_config(guest_directory, &config)?;
+    Ok(patches
+        .iter()
+        .filter(|patch| {
+            metadata.packages.iter().any(|package| {
+                package.name == patch.crate_name
+                    && package.source.as_ref().is_some_and(|source| {
+                        source.repr.starts_with(&format!("git+{}", patch.git))
+                    })
+            })
+        })
+        .copied()
+        .collect())
+}
@@ -17,4 +17,15 @@ pub enum Error {
 
     #[error("`risc0_build::build_package` succeeded but failed to find guest")]
     Risc0BuildMissingGuest,
+
+    #[error(
+        "Accelerator patches are unsupported by `risc0_build`, add them to `[patch.crates-io]` of the guest manifest instead"
+    )]
+    AcceleratorPatchesUnsupported,
+
+    #[error(
+        "Accelerator patches [{}] were not applied, update the crates locked in `Cargo.lock` of the guest to versions compatible with the forks",
+        patches.join(", ")
+    )]
+    AcceleratorPatchesNotApplied { patches: Vec<String> },
 }
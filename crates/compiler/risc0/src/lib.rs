@@ -1,5 +1,6 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod accelerator;
 mod error;
 mod rust_rv32ima;
 mod rust_rv32ima_customized;
@@ -7,6 +8,10 @@ mod rust_rv32ima_customized;
 pub use ere_compiler_core::*;
 
 pub use crate::{
-    error::Error, rust_rv32ima::Risc0RustRv32ima,
+    accelerator::{
+        ACCELERATOR_PATCHES, AcceleratorPatch, accelerator_patches, applied_accelerator_patches,
+    },
+    error::Error,
+    rust_rv32ima::Risc0RustRv32ima,
     rust_rv32ima_customized::Risc0RustRv32imaCustomized,
 };
@@ -1,11 +1,14 @@
-use std::{env, path::Path};
+use std::path::Path;
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CargoBuildCmd, parse_cargo_features};
+use ere_util_compile::{CargoBuildCmd, CompileOption, parse_compile_args};
 use risc0_binfmt::ProgramBinary;
 use tracing::info;
 
-use crate::Error;
+use crate::{
+    Error,
+    accelerator::{AcceleratorPatch, accelerator_patches, applied_accelerator_patches},
+};
 
 // TODO: Make this with `zkos` package building to avoid binary file storing in repo.
 // File taken from https://github.com/risc0/risc0/blob/v3.0.5/risc0/zkos/v1compat/elfs/v1compat.elf
@@ -41,20 +44,64 @@ impl Compiler for Risc0RustRv32ima {
         guest_directory: impl AsRef<Path>,
         args: &[String],
     ) -> Result<Elf, Self::Error> {
-        let toolchain = env::var("ERE_RUST_TOOLCHAIN").unwrap_or_else(|_| "nightly".into());
+        let guest_directory = guest_directory.as_ref();
+        let args = parse_compile_args(args)?;
+        args.check_supported(&[
+            CompileOption::NoDefaultFeatures,
+            CompileOption::Profile,
+            CompileOption::ToolchainPolicy,
+            CompileOption::AcceleratorPatches,
+        ])?;
+
+        let patches = match args.accelerator_patches {
+            true => accelerator_patches(guest_directory)?,
+            false => Vec::new(),
+        };
+        let config = patches
+            .iter()
+            .flat_map(AcceleratorPatch::cargo_config)
+            .collect::<Vec<_>>();
+
         let elf = CargoBuildCmd::new()
-            .toolchain(toolchain)
+            .toolchain_from_env("nightly")
             .build_options(CARGO_BUILD_OPTIONS)
+            .config(&config)
             .rustflags(RUSTFLAGS)
-            .features(&parse_cargo_features(args)?)
+            .compile_args(&args)
             .exec(guest_directory, TARGET_TRIPLE)?;
 
+        if !patches.is_empty() {
+            let applied = applied_accelerator_patches(guest_directory, &patches)?;
+            let not_applied = patches
+                .iter()
+                .filter(|patch| !applied.contains(patch))
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            if !not_applied.is_empty() {
+                return Err(Error::AcceleratorPatchesNotApplied {
+                    patches: not_applied,
+                });
+            }
+            info!(
+                "Applied Risc0 accelerator patches: [{}]",
+                applied
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
         let program = ProgramBinary::new(elf.as_slice(), V1COMPAT_ELF);
 
         info!("Risc0 program compiled OK - {} bytes", elf.len());
 
         Ok(Elf(program.encode()))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(TARGET_TRIPLE)
+    }
 }
 
 #[cfg(test)]
@@ -64,7 +111,9 @@ mod tests {
     use ere_prover_risc0::Risc0Prover;
     use ere_util_test::host::testing_guest_directory;
 
-    use crate::Risc0RustRv32ima;
+    use crate::{
+        ACCELERATOR_PATCHES, Risc0RustRv32ima, accelerator_patches, applied_accelerator_patches,
+    };
 
     #[test]
     fn test_compile() {
@@ -80,4 +129,35 @@ mod tests {
         let zkvm = Risc0Prover::new(elf, ProverResource::Cpu).unwrap();
         zkvm.execute(&Input::new()).unwrap();
     }
+
+    #[test]
+    fn test_compile_accelerator_patches() {
+        let guest_directory = testing_guest_directory("risc0", "stock_nightly_no_std");
+        // The guest has no dependencies to patch.
+        assert!(accelerator_patches(&guest_directory).unwrap().is_empty());
+
+        let args = ["--accelerator-patches".to_string()];
+        let elf = Risc0RustRv32ima.compile(guest_directory, &args).unwrap();
+        assert!(!elf.is_empty(), "ELF bytes should not be empty.");
+    }
+
+    #[test]
+    fn test_compile_accelerator_patches_sha2() {
+        let guest_directory = testing_guest_directory("risc0", "stock_nightly_sha2");
+        let sha2 = ACCELERATOR_PATCHES
+            .iter()
+            .filter(|patch| patch.crate_name == "sha2")
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(accelerator_patches(&guest_directory).unwrap(), sha2);
+        assert_eq!(
+            applied_accelerator_patches(&guest_directory, &sha2).unwrap(),
+            sha2
+        );
+
+        let args = ["--accelerator-patches".to_string()];
+        let elf = Risc0RustRv32ima.compile(&guest_directory, &args).unwrap();
+        let zkvm = Risc0Prover::new(elf, ProverResource::Cpu).unwrap();
+        zkvm.execute(&Input::new()).unwrap();
+    }
 }
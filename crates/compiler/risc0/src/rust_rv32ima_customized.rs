@@ -1,12 +1,15 @@
 use std::path::Path;
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{cargo_metadata, parse_cargo_features};
+use ere_util_compile::{cargo_metadata, parse_compile_args};
 use risc0_build::GuestOptionsBuilder;
 use tracing::info;
 
 use crate::Error;
 
+/// Target `risc0_build` builds guests for.
+const TARGET_TRIPLE: &str = "riscv32im-risc0-zkvm-elf";
+
 /// Compiler for Rust guest program to RV32IMA architecture, using customized
 /// Rust toolchain of Risc0.
 pub struct Risc0RustRv32imaCustomized;
@@ -22,13 +25,21 @@ impl Compiler for Risc0RustRv32imaCustomized {
         let guest_directory = guest_directory.as_ref();
         info!("Compiling Risc0 program at {}", guest_directory.display());
 
+        let args = parse_compile_args(args)?;
+        // `risc0_build` doesn't take extra cargo configuration.
+        if args.accelerator_patches {
+            return Err(Error::AcceleratorPatchesUnsupported);
+        }
+        // Nor cargo options other than features.
+        args.check_supported(&[])?;
+
         let metadata = cargo_metadata(guest_directory)?;
         let package = metadata.root_package().unwrap();
 
         // Use `risc0_build::build_package` to build package instead of calling
         // `cargo-risczero build` for the `unstable` features.
         let guest_opts = GuestOptionsBuilder::default()
-            .features(parse_cargo_features(args)?)
+            .features(args.features)
             .build()
             .unwrap();
         let guest = risc0_build::build_package(package, &metadata.target_directory, guest_opts)
@@ -46,6 +57,10 @@ impl Compiler for Risc0RustRv32imaCustomized {
 
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(TARGET_TRIPLE)
+    }
 }
 
 #[cfg(test)]
@@ -53,7 +68,7 @@ mod tests {
     use ere_compiler_core::Compiler;
     use ere_util_test::host::testing_guest_directory;
 
-    use crate::Risc0RustRv32imaCustomized;
+    use crate::{Error, Risc0RustRv32imaCustomized};
 
     #[test]
     fn test_compile() {
@@ -63,4 +78,14 @@ mod tests {
             .unwrap();
         assert!(!elf.is_empty(), "ELF bytes should not be empty.");
     }
+
+    #[test]
+    fn test_compile_accelerator_patches_unsupported() {
+        let guest_directory = testing_guest_directory("risc0", "basic");
+        let args = ["--accelerator-patches".to_string()];
+        let err = Risc0RustRv32imaCustomized
+            .compile(guest_directory, &args)
+            .unwrap_err();
+        assert!(matches!(err, Error::AcceleratorPatchesUnsupported));
+    }
 }
@@ -5,4 +5,11 @@ use thiserror::Error;
 pub enum Error {
     #[error(transparent)]
     CommonError(#[from] CommonError),
+
+    #[error("Crates {crates:?} depend on `std`, which isn't available for the target: {err}")]
+    StdDependency {
+        crates: Vec<String>,
+        #[source]
+        err: CommonError,
+    },
 }
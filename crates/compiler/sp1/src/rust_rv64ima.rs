@@ -1,7 +1,7 @@
-use std::{env, path::Path};
+use std::{borrow::Cow, path::Path};
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CargoBuildCmd, RustTarget, parse_cargo_features};
+use ere_util_compile::{CargoBuildCmd, CommonError, CompileOption, RustTarget, parse_compile_args};
 
 use crate::Error;
 
@@ -15,8 +15,10 @@ use crate::Error;
 ///     > ./crates/compiler/sp1/src/rust_rv64ima/riscv64ima-unknown-none-elf.json
 /// ```
 const TARGET: RustTarget = RustTarget::SpecJson {
-    name: "riscv64ima-unknown-none-elf",
-    json: include_str!("./rust_rv64ima/riscv64ima-unknown-none-elf.json"),
+    name: Cow::Borrowed("riscv64ima-unknown-none-elf"),
+    json: Cow::Borrowed(include_str!(
+        "./rust_rv64ima/riscv64ima-unknown-none-elf.json"
+    )),
 };
 
 /// According to https://github.com/succinctlabs/sp1/blob/v6.1.0/crates/build/src/command/utils.rs#L62.
@@ -43,8 +45,48 @@ const CARGO_BUILD_OPTIONS: &[&str] = &[
     "-Zjson-target-spec",
 ];
 
-/// Compiler for Rust guest program to RV64IMA architecture.
-pub struct SP1RustRv64ima;
+/// Compiler for `no_std` Rust guest program to RV64IMA architecture with stock Rust toolchain.
+///
+/// The target spec, linker script and opt-level can be customized for guests with their own
+/// runtime. Guests depending on `std` fail with [`Error::StdDependency`].
+#[derive(Debug, Clone)]
+pub struct SP1RustRv64ima {
+    target: RustTarget,
+    linker_script: Option<String>,
+    opt_level: Option<String>,
+}
+
+impl Default for SP1RustRv64ima {
+    fn default() -> Self {
+        Self {
+            target: TARGET,
+            linker_script: None,
+            opt_level: None,
+        }
+    }
+}
+
+impl SP1RustRv64ima {
+    /// Target to build for, defaults to `riscv64ima-unknown-none-elf` with `atomic-cas = true`.
+    ///
+    /// A target spec JSON file given at runtime can be loaded by [`RustTarget::spec_json_file`].
+    pub fn with_target(mut self, target: RustTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Linker script to link with, defaults to the one of `rust-lld` for the target.
+    pub fn with_linker_script(mut self, linker_script: impl Into<String>) -> Self {
+        self.linker_script = Some(linker_script.into());
+        self
+    }
+
+    /// `-C opt-level` (e.g. `3` or `z`), overriding the one of the cargo profile.
+    pub fn with_opt_level(mut self, opt_level: impl Into<String>) -> Self {
+        self.opt_level = Some(opt_level.into());
+        self
+    }
+}
 
 impl Compiler for SP1RustRv64ima {
     type Error = Error;
@@ -54,15 +96,51 @@ impl Compiler for SP1RustRv64ima {
         guest_directory: impl AsRef<Path>,
         args: &[String],
     ) -> Result<Elf, Self::Error> {
-        let toolchain = env::var("ERE_RUST_TOOLCHAIN").unwrap_or_else(|_| "nightly".into());
+        let args = parse_compile_args(args)?;
+        args.check_supported(CompileOption::CARGO_BUILD)?;
+        let rustflags = RUSTFLAGS
+            .iter()
+            .map(ToString::to_string)
+            .chain(
+                self.opt_level
+                    .iter()
+                    .flat_map(|opt_level| ["-C".into(), format!("opt-level={opt_level}")]),
+            )
+            .collect::<Vec<_>>();
         let elf = CargoBuildCmd::new()
-            .toolchain(toolchain)
+            .toolchain_from_env("nightly")
             .build_options(CARGO_BUILD_OPTIONS)
-            .rustflags(RUSTFLAGS)
-            .features(&parse_cargo_features(args)?)
-            .exec(guest_directory, TARGET)?;
+            .rustflags(&rustflags)
+            .linker_script(self.linker_script.as_ref())
+            .compile_args(&args)
+            .diagnostics(true)
+            .exec(guest_directory, self.target.clone())
+            .map_err(std_dependency_err)?;
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(self.target.name())
+    }
+}
+
+/// Returns [`Error::StdDependency`] if the build failed for crates requiring `std`, which isn't
+/// available for the target.
+fn std_dependency_err(err: CommonError) -> Error {
+    let CommonError::CargoBuild { diagnostics, .. } = &err else {
+        return err.into();
+    };
+    let crates = diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            diagnostic.code.as_deref() == Some("E0463") && diagnostic.message.contains("`std`")
+        })
+        .map(|diagnostic| diagnostic.crate_name.clone())
+        .collect::<Vec<_>>();
+    if crates.is_empty() {
+        return err.into();
+    }
+    Error::StdDependency { crates, err }
 }
 
 #[cfg(test)]
@@ -72,19 +150,35 @@ mod tests {
     use ere_prover_sp1::SP1Prover;
     use ere_util_test::host::testing_guest_directory;
 
-    use crate::SP1RustRv64ima;
+    use crate::{Error, SP1RustRv64ima};
 
     #[test]
     fn test_compile() {
         let guest_directory = testing_guest_directory("sp1", "stock_nightly_no_std");
-        let elf = SP1RustRv64ima.compile(guest_directory, &[]).unwrap();
+        let elf = SP1RustRv64ima::default()
+            .compile(guest_directory, &[])
+            .unwrap();
         assert!(!elf.is_empty(), "ELF bytes should not be empty.");
     }
 
+    #[test]
+    fn test_compile_std_dependency() {
+        let guest_directory = testing_guest_directory("sp1", "stock_std");
+        let err = SP1RustRv64ima::default()
+            .compile(guest_directory, &[])
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::StdDependency { crates, .. } if crates == &["stock_std"]),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_execute() {
         let guest_directory = testing_guest_directory("sp1", "stock_nightly_no_std");
-        let elf = SP1RustRv64ima.compile(guest_directory, &[]).unwrap();
+        let elf = SP1RustRv64ima::default()
+            .compile(guest_directory, &[])
+            .unwrap();
         let zkvm = SP1Prover::new(elf, ProverResource::Cpu).unwrap();
         zkvm.execute(&Input::new()).unwrap();
     }
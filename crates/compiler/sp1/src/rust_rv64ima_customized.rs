@@ -1,12 +1,15 @@
 use std::{fs, path::Path, process::Command};
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CommonError, cargo_metadata, parse_cargo_features};
+use ere_util_compile::{CommonError, CompileOption, cargo_metadata, parse_compile_args};
 use tempfile::tempdir;
 use tracing::info;
 
 use crate::Error;
 
+/// Target `cargo prove build` builds guests for.
+const TARGET_TRIPLE: &str = "riscv64im-succinct-zkvm-elf";
+
 /// Compiler for Rust guest program to RV64IMA architecture, using customized
 /// Rust toolchain of Succinct.
 pub struct SP1RustRv64imaCustomized;
@@ -41,9 +44,14 @@ impl Compiler for SP1RustRv64imaCustomized {
             "--elf-name",
             "guest.elf",
         ]);
-        let features = parse_cargo_features(args)?;
-        if !features.is_empty() {
-            cmd.args(["--features", &features.join(",")]);
+        let args = parse_compile_args(args)?;
+        // `cargo prove build` has no profile or toolchain options.
+        args.check_supported(&[CompileOption::NoDefaultFeatures])?;
+        if !args.features.is_empty() {
+            cmd.args(["--features", &args.features.join(",")]);
+        }
+        if args.no_default_features {
+            cmd.arg("--no-default-features");
         }
         let status = cmd
             .status()
@@ -60,6 +68,10 @@ impl Compiler for SP1RustRv64imaCustomized {
 
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(TARGET_TRIPLE)
+    }
 }
 
 #[cfg(test)]
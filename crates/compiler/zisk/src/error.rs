@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use ere_util_compile::CommonError;
 use thiserror::Error;
 
@@ -5,4 +7,10 @@ use thiserror::Error;
 pub enum Error {
     #[error(transparent)]
     CommonError(#[from] CommonError),
+
+    #[error("`go.mod` not found in guest directory {0}, Go guests must be a module")]
+    GoModNotFound(PathBuf),
+
+    #[error("TamaGo toolchain not found at {0}, install it by `scripts/install_tamago.sh`")]
+    TamagoNotFound(PathBuf),
 }
@@ -1,5 +1,10 @@
-use std::{env, fs, path::Path, process::Command};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
+use clap::Parser;
 use ere_compiler_core::{Compiler, Elf};
 use ere_util_compile::CommonError;
 use tempfile::tempdir;
@@ -7,8 +12,50 @@ use tracing::info;
 
 use crate::Error;
 
+/// Linker flags setting the text and data addresses of the ZisK memory layout.
+const LDFLAGS: &str = "-T 0x80001000 -D 0xa0020000";
+
+/// Build tags required by TamaGo to target ZisK.
+const TAGS: &[&str] = &[
+    "tamago",
+    "linkcpuinit",
+    "linkramstart",
+    "linkramsize",
+    "linkprintk",
+    "tinygo.wasm",
+    "tinygo",
+    "riscv64",
+];
+
+/// Compiler for Go guest program using the TamaGo toolchain installed at `~/.tamago` by
+/// [`install_tamago.sh`].
+///
+/// Module dependencies of the guest `go.mod` are resolved by the `go` command as usual (e.g. with
+/// `GOPROXY`, `GOPRIVATE` and `GOFLAGS` from env).
+///
+/// [`install_tamago.sh`]: https://github.com/eth-act/ere/blob/master/scripts/install_tamago.sh
 pub struct ZiskGoCustomized;
 
+/// Arguments to [`ZiskGoCustomized`].
+#[derive(Parser, Debug, Clone, Default)]
+#[command(no_binary_name = true)]
+pub struct GoCompileArgs {
+    /// Build tags in addition to the ones required by TamaGo.
+    #[arg(long, value_delimiter = ',')]
+    pub tags: Vec<String>,
+    /// Linker flags appended to the ones setting the ZisK memory layout (e.g. `-X main.v=1`).
+    #[arg(long, allow_hyphen_values = true)]
+    pub ldflags: Option<String>,
+    /// Module download mode passed to `-mod` (e.g. `vendor` or `mod`).
+    #[arg(long = "mod")]
+    pub mod_mode: Option<String>,
+}
+
+/// Parse [`GoCompileArgs`] out of `args`.
+pub fn parse_go_compile_args(args: &[String]) -> Result<GoCompileArgs, CommonError> {
+    GoCompileArgs::try_parse_from(args).map_err(CommonError::invalid_args)
+}
+
 impl Compiler for ZiskGoCustomized {
     type Error = Error;
 
@@ -17,39 +64,54 @@ impl Compiler for ZiskGoCustomized {
         guest_directory: impl AsRef<Path>,
         args: &[String],
     ) -> Result<Elf, Self::Error> {
-        if !args.is_empty() {
-            return Err(CommonError::invalid_args("Extra args is not supported"))?;
-        }
+        let args = parse_go_compile_args(args)?;
         let guest_directory = guest_directory.as_ref();
         info!(
             "Compiling TamaGo ZisK program at {}",
             guest_directory.display()
         );
 
+        if !guest_directory.join("go.mod").is_file() {
+            return Err(Error::GoModNotFound(guest_directory.to_path_buf()));
+        }
+
         let home_dir = env::var("HOME")
-            .map(std::path::PathBuf::from)
+            .map(PathBuf::from)
             .map_err(|var_error| CommonError::env_var_error("HOME".to_string(), var_error))?;
+        let goroot = home_dir.join(".tamago");
+        let go = goroot.join("bin").join("go");
+        if !go.is_file() {
+            return Err(Error::TamagoNotFound(go));
+        }
 
-        let ldflags = ["-ldflags", "-T 0x80001000 -D 0xa0020000"];
-        let tags = [
-            "-tags",
-            "tamago,linkcpuinit,linkramstart,linkramsize,linkprintk,tinygo.wasm,tinygo,riscv64",
-        ];
+        let ldflags = match &args.ldflags {
+            Some(ldflags) => format!("{LDFLAGS} {ldflags}"),
+            None => LDFLAGS.to_string(),
+        };
+        let tags = TAGS
+            .iter()
+            .copied()
+            .chain(args.tags.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(",");
 
         let tempdir = tempdir().map_err(CommonError::tempdir)?;
         let executable = tempdir.path().join("program.elf");
 
-        let mut cmd = Command::new(home_dir.join(".tamago").join("bin").join("go"));
-        let status = cmd
-            .current_dir(guest_directory)
+        let mut cmd = Command::new(&go);
+        cmd.current_dir(guest_directory)
             .env("CGO_ENABLED", "0")
-            .env("GOROOT", home_dir.join(".tamago").as_os_str())
+            .env("GOROOT", &goroot)
             .env("GOOS", "tamago")
             .env("GOARCH", "riscv64")
             .arg("build")
             .arg("-buildvcs=false")
-            .args(ldflags)
-            .args(tags)
+            .args(["-ldflags", &ldflags])
+            .args(["-tags", &tags]);
+        if let Some(mod_mode) = &args.mod_mode {
+            cmd.arg(format!("-mod={mod_mode}"));
+        }
+        let status = cmd
             .args(["-o", executable.to_str().unwrap()])
             .arg(".")
             .status()
@@ -64,6 +126,11 @@ impl Compiler for ZiskGoCustomized {
 
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        // `GOOS/GOARCH` the guest is built with.
+        Some("tamago/riscv64")
+    }
 }
 
 #[cfg(test)]
@@ -77,7 +144,7 @@ mod tests {
         program::basic::BasicProgram,
     };
 
-    use crate::ZiskGoCustomized;
+    use crate::{Error, ZiskGoCustomized, go_customized::parse_go_compile_args};
 
     #[test]
     fn test_compile() {
@@ -86,6 +153,35 @@ mod tests {
         assert!(!elf.is_empty(), "ELF bytes should not be empty.");
     }
 
+    #[test]
+    fn test_compile_args() {
+        let args = parse_go_compile_args(&[
+            "--tags".to_string(),
+            "a,b".to_string(),
+            "--ldflags".to_string(),
+            "-X main.v=1".to_string(),
+            "--mod".to_string(),
+            "vendor".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(args.tags, ["a", "b"]);
+        assert_eq!(args.ldflags.as_deref(), Some("-X main.v=1"));
+        assert_eq!(args.mod_mode.as_deref(), Some("vendor"));
+
+        let guest_directory = testing_guest_directory("zisk", "basic_go");
+        let elf = ZiskGoCustomized
+            .compile(guest_directory, &["--tags".to_string(), "ere".to_string()])
+            .unwrap();
+        assert!(!elf.is_empty(), "ELF bytes should not be empty.");
+    }
+
+    #[test]
+    fn test_compile_go_mod_not_found() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let err = ZiskGoCustomized.compile(tempdir.path(), &[]).unwrap_err();
+        assert!(matches!(err, Error::GoModNotFound(_)), "{err:?}");
+    }
+
     #[test]
     fn test_execute() {
         let guest_directory = testing_guest_directory("zisk", "basic_go");
@@ -8,6 +8,8 @@ mod rust_rv64ima_customized;
 pub use ere_compiler_core::*;
 
 pub use crate::{
-    error::Error, go_customized::ZiskGoCustomized, rust_rv64ima::ZiskRustRv64ima,
+    error::Error,
+    go_customized::{GoCompileArgs, ZiskGoCustomized, parse_go_compile_args},
+    rust_rv64ima::ZiskRustRv64ima,
     rust_rv64ima_customized::ZiskRustRv64imaCustomized,
 };
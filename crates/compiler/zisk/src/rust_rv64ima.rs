@@ -1,7 +1,7 @@
-use std::{env, path::Path};
+use std::{borrow::Cow, path::Path};
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CargoBuildCmd, RustTarget, parse_cargo_features};
+use ere_util_compile::{CargoBuildCmd, CompileOption, RustTarget, parse_compile_args};
 
 use crate::Error;
 
@@ -14,9 +14,11 @@ use crate::Error;
 ///     | jq '.["atomic-cas"] = true' \
 ///     > ./crates/compiler/zisk/src/rust_rv64ima/riscv64ima-unknown-none-elf.json
 /// ```
-const TARGET: RustTarget = RustTarget::SpecJson {
-    name: "riscv64ima-unknown-none-elf",
-    json: include_str!("./rust_rv64ima/riscv64ima-unknown-none-elf.json"),
+static TARGET: RustTarget = RustTarget::SpecJson {
+    name: Cow::Borrowed("riscv64ima-unknown-none-elf"),
+    json: Cow::Borrowed(include_str!(
+        "./rust_rv64ima/riscv64ima-unknown-none-elf.json"
+    )),
 };
 
 const RUSTFLAGS: &[&str] = &[
@@ -50,16 +52,21 @@ impl Compiler for ZiskRustRv64ima {
         guest_directory: impl AsRef<Path>,
         args: &[String],
     ) -> Result<Elf, Self::Error> {
-        let toolchain = env::var("ERE_RUST_TOOLCHAIN").unwrap_or_else(|_| "nightly".into());
+        let args = parse_compile_args(args)?;
+        args.check_supported(CompileOption::CARGO_BUILD)?;
         let elf = CargoBuildCmd::new()
             .linker_script(Some(LINKER_SCRIPT))
-            .toolchain(toolchain)
+            .toolchain_from_env("nightly")
             .build_options(CARGO_BUILD_OPTIONS)
             .rustflags(RUSTFLAGS)
-            .features(&parse_cargo_features(args)?)
-            .exec(guest_directory, TARGET)?;
+            .compile_args(&args)
+            .exec(guest_directory, TARGET.clone())?;
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(TARGET.name())
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use ere_compiler_core::{Compiler, Elf};
-use ere_util_compile::{CargoBuildCmd, parse_cargo_features};
+use ere_util_compile::{CargoBuildCmd, CompileOption, parse_compile_args};
 
 use crate::Error;
 
@@ -22,13 +22,19 @@ impl Compiler for ZiskRustRv64imaCustomized {
         guest_directory: impl AsRef<Path>,
         args: &[String],
     ) -> Result<Elf, Self::Error> {
+        let args = parse_compile_args(args)?;
+        args.check_supported(CompileOption::CARGO_BUILD)?;
         let elf = CargoBuildCmd::new()
-            .toolchain(ZISK_TOOLCHAIN)
+            .explicit_toolchain(ZISK_TOOLCHAIN)
             .rustflags(RUSTFLAGS)
-            .features(&parse_cargo_features(args)?)
+            .compile_args(&args)
             .exec(guest_directory, ZISK_TARGET)?;
         Ok(Elf(elf))
     }
+
+    fn target(&self) -> Option<&str> {
+        Some(ZISK_TARGET)
+    }
 }
 
 #[cfg(test)]
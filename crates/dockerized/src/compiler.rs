@@ -1,19 +1,31 @@
 use std::{
-    fs, iter,
+    env, fs, iter, mem,
     path::{Path, PathBuf},
+    sync::{
+        OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
 };
 
-use ere_compiler_core::{Compiler, Elf};
+use ere_compiler_core::{CompileReport, Compiler, Elf};
 use ere_prover_core::CommonError;
 use tempfile::TempDir;
-use tracing::info;
+use tracing::{error, info};
 
 use crate::{
-    CompilerKind,
-    image::{base_image, base_zkvm_image, compiler_zkvm_image},
+    CompilerKind, ContainerConfig, ContainerUser,
+    config::ImageLayout,
+    image::{base_image, build_base_image, compiler_base_zkvm_image, compiler_zkvm_image},
     util::{
-        docker::{DockerBuildCmd, DockerRunCmd, docker_image_exists, docker_pull_image},
-        env::{force_rebuild_docker_image, image_registry},
+        docker::{
+            DockerBuildCmd, DockerRunCmd, docker_cp, docker_exec, docker_image_exists,
+            docker_pull_image, docker_start, remove_docker_container,
+        },
+        env::{
+            force_rebuild_docker_image, image_layout, image_registry, remote_docker_host,
+            runtime_image_build_args,
+        },
+        source::{SOURCE_DIGEST_LABEL, base_zkvm_source_digest, is_source_stale, source_digest},
         workspace_dir,
     },
     zkVMKind,
@@ -30,22 +42,28 @@ pub use error::Error;
 ///    the selected zkVM feature
 ///
 /// Images are cached and only rebuilt if they don't exist or if the
-/// `ERE_FORCE_REBUILD_DOCKER_IMAGE` environment variable is set.
-fn build_compiler_image(zkvm_kind: zkVMKind) -> Result<(), Error> {
-    let force_rebuild = force_rebuild_docker_image();
-    let base_image = base_image(zkvm_kind, false);
-    let base_zkvm_image = base_zkvm_image(zkvm_kind, false);
-    let compiler_zkvm_image = compiler_zkvm_image(zkvm_kind);
+/// `ERE_FORCE_REBUILD_DOCKER_IMAGE` environment variable is set, or if they are built from sources
+/// other than the current ones (see [`is_source_stale`]).
+pub(crate) fn build_compiler_image(zkvm_kind: zkVMKind) -> Result<(), CommonError> {
+    let force_rebuild = force_rebuild_docker_image()?;
+    let base_image = base_image(zkvm_kind, false)?;
+    let base_zkvm_image = compiler_base_zkvm_image(zkvm_kind)?;
+    let compiler_zkvm_image = compiler_zkvm_image(zkvm_kind)?;
+    let base_zkvm_source_digest = base_zkvm_source_digest(zkvm_kind)?;
+    let source_digest = source_digest()?;
 
     if !force_rebuild {
-        if docker_image_exists(&compiler_zkvm_image)? {
+        if docker_image_exists(&compiler_zkvm_image)?
+            && !is_source_stale(&compiler_zkvm_image, source_digest)?
+        {
             info!("Image {compiler_zkvm_image} exists, skip building");
             return Ok(());
         }
 
-        if image_registry().is_some()
+        if image_registry()?.is_some()
             && docker_pull_image(&compiler_zkvm_image).is_ok()
             && docker_image_exists(&compiler_zkvm_image)?
+            && !is_source_stale(&compiler_zkvm_image, source_digest)?
         {
             info!("Image {compiler_zkvm_image} pulled, skip building");
             return Ok(());
@@ -57,46 +75,98 @@ fn build_compiler_image(zkvm_kind: zkVMKind) -> Result<(), Error> {
     let docker_zkvm_dir = docker_dir.join(zkvm_kind.as_str());
 
     // Build `ere-base`
-    if force_rebuild || !docker_image_exists(&base_image)? {
-        info!("Building image {base_image}...");
-
-        DockerBuildCmd::new()
-            .file(docker_dir.join("Dockerfile.base"))
-            .tag(&base_image)
-            .exec(&workspace_dir)?;
-    }
+    build_base_image(zkvm_kind, false)?;
 
     // Build `ere-base-{zkvm_kind}`
-    if force_rebuild || !docker_image_exists(&base_zkvm_image)? {
+    if force_rebuild
+        || !docker_image_exists(&base_zkvm_image)?
+        || is_source_stale(&base_zkvm_image, &base_zkvm_source_digest)?
+    {
         info!("Building image {base_zkvm_image}...");
 
-        DockerBuildCmd::new()
+        let mut cmd = DockerBuildCmd::new()
             .file(docker_zkvm_dir.join("Dockerfile.base"))
             .tag(&base_zkvm_image)
+            .label(SOURCE_DIGEST_LABEL, &base_zkvm_source_digest)
             .build_arg("BASE_IMAGE", &base_image)
-            .build_arg_from_env("RUSTFLAGS")
-            .exec(&workspace_dir)?;
+            .build_arg_from_env("RUSTFLAGS");
+
+        // Skips installing runtime dependencies only needed by the server.
+        if image_layout()? == ImageLayout::Split {
+            cmd = cmd.build_arg("COMPILER_ONLY", "1");
+        }
+
+        cmd.exec(&workspace_dir)?;
     }
 
     // Build `ere-compiler-{zkvm_kind}`
     info!("Building image {compiler_zkvm_image}...");
 
-    DockerBuildCmd::new()
+    let mut cmd = DockerBuildCmd::new()
         .file(docker_zkvm_dir.join("Dockerfile.compiler"))
         .tag(&compiler_zkvm_image)
-        .build_arg("BASE_ZKVM_IMAGE", &base_zkvm_image)
-        .exec(&workspace_dir)?;
+        .label(SOURCE_DIGEST_LABEL, source_digest)
+        .build_arg("BASE_ZKVM_IMAGE", &base_zkvm_image);
+
+    for (key, image) in runtime_image_build_args()? {
+        cmd = cmd.build_arg(key, image);
+    }
+
+    cmd.exec(&workspace_dir)?;
 
     Ok(())
 }
 
+/// Credential forwarded to the compiler container to fetch private git dependencies.
+#[derive(Clone, Debug)]
+pub enum GitCredential {
+    /// Forwards the SSH agent socket at `SSH_AUTH_SOCK` of this process, for `ssh://` remotes.
+    SshAgent,
+    /// Token used as the password for `https://` remotes with username `x-access-token` (e.g. a
+    /// GitHub personal access token).
+    ///
+    /// The token is mounted as a file instead of passed by env variable, so it doesn't show up in
+    /// the `docker run` command.
+    Token(String),
+}
+
+/// Long-lived compiler container kept by [`DockerizedCompiler::with_keep_warm`].
+struct WarmContainer {
+    id: String,
+    output_dir: TempDir,
+    _secrets_dir: TempDir,
+    next_request: AtomicUsize,
+}
+
+impl Drop for WarmContainer {
+    fn drop(&mut self) {
+        if let Err(err) = remove_docker_container(&self.id) {
+            error!("Failed to remove docker container: {err}");
+        }
+    }
+}
+
 pub struct DockerizedCompiler {
     zkvm_kind: zkVMKind,
     compiler_kind: CompilerKind,
     mount_directory: PathBuf,
+    envs: Vec<(String, String)>,
+    cargo_cache: bool,
+    git_credentials: Vec<GitCredential>,
+    compile_args: Vec<String>,
+    keep_warm: bool,
+    container_config: ContainerConfig,
+    warm_container: OnceLock<WarmContainer>,
+    /// Whether to copy files into and out of the container instead of bind mounting, since bind
+    /// mounts refer to paths on the Docker host, which might be remote.
+    copy_files: bool,
 }
 
 impl DockerizedCompiler {
+    /// Returns the compiler of guest programs under `mount_directory`.
+    ///
+    /// With a remote Docker host, the mount directory is copied into the container for each
+    /// compile instead of bind-mounted, and owned by root in the container.
     pub fn new(
         zkvm_kind: zkVMKind,
         compiler_kind: CompilerKind,
@@ -106,10 +176,89 @@ impl DockerizedCompiler {
         Ok(Self {
             zkvm_kind,
             compiler_kind,
+            copy_files: remote_docker_host()?.is_some(),
             mount_directory: mount_directory.as_ref().to_path_buf(),
+            envs: Vec::new(),
+            cargo_cache: true,
+            git_credentials: Vec::new(),
+            compile_args: Vec::new(),
+            keep_warm: false,
+            container_config: ContainerConfig::default(),
+            warm_container: OnceLock::new(),
         })
     }
 
+    /// Sets whether to keep a long-lived compiler container (disabled by default), which is
+    /// started on the first compile and runs the following ones by `docker exec` until the
+    /// compiler is dropped, to save the container startup of each compile.
+    ///
+    /// The container is configured when started, so later changes of env variables inherited
+    /// from the host process don't apply.
+    pub fn with_keep_warm(mut self, keep_warm: bool) -> Self {
+        self.keep_warm = keep_warm;
+        self
+    }
+
+    /// Sets resource limits and security options of the compiler container, e.g.
+    /// [`ContainerUser::Host`](crate::ContainerUser::Host) to have build artifacts written into
+    /// the mount directory owned by the host user.
+    pub fn with_container_config(mut self, container_config: ContainerConfig) -> Self {
+        self.container_config = container_config;
+        self
+    }
+
+    /// Forwards `credential` to the compiler container for guest programs with private git
+    /// dependencies, replacing the one of the same kind forwarded before if any.
+    pub fn with_git_credential(mut self, credential: GitCredential) -> Self {
+        self.git_credentials
+            .retain(|c| mem::discriminant(c) != mem::discriminant(&credential));
+        self.git_credentials.push(credential);
+        self
+    }
+
+    /// Sets whether to mount named Docker volumes for the cargo registry and target directory
+    /// (enabled by default), so successive compiles reuse downloaded crates and build artifacts.
+    ///
+    /// The registry volume `ere-cargo-registry` is shared by all zkVMs, and the target volume
+    /// `ere-cargo-target-{zkvm}` by all guest programs of the zkVM.
+    pub fn with_cargo_cache(mut self, cargo_cache: bool) -> Self {
+        self.cargo_cache = cargo_cache;
+        self
+    }
+
+    /// Sets environment variable `key` to `value` in the compiler container (e.g.
+    /// `ERE_RUST_TOOLCHAIN` or `RUSTFLAGS`), which takes precedence over the ones inherited from
+    /// the host process.
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Enables cargo `features` of the guest program.
+    pub fn with_features(mut self, features: &[impl AsRef<str>]) -> Self {
+        if !features.is_empty() {
+            let features = features.iter().map(|v| v.as_ref()).collect::<Vec<_>>();
+            self.compile_args
+                .extend(["--features".to_string(), features.join(",")]);
+        }
+        self
+    }
+
+    /// Disables the `default` feature of the guest program, which the customized Rust compilers
+    /// of Risc0, OpenVM and Airbender reject.
+    pub fn with_no_default_features(mut self) -> Self {
+        self.compile_args.push("--no-default-features".to_string());
+        self
+    }
+
+    /// Sets the cargo profile to build the guest program with (default `release`), which the
+    /// customized Rust compilers of Risc0, SP1 and Airbender reject.
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.compile_args
+            .extend(["--profile".to_string(), profile.into()]);
+        self
+    }
+
     pub fn zkvm_kind(&self) -> zkVMKind {
         self.zkvm_kind
     }
@@ -117,37 +266,260 @@ impl DockerizedCompiler {
     pub fn compiler_kind(&self) -> CompilerKind {
         self.compiler_kind
     }
-}
 
-impl Compiler for DockerizedCompiler {
-    type Error = Error;
+    /// Clones the git repository `url` at `rev` inside the compiler container, and compiles the
+    /// guest program in `subdir` relative to the repository root.
+    ///
+    /// The guest program doesn't need to be under the mount directory.
+    pub fn compile_git(
+        &self,
+        url: &str,
+        rev: &str,
+        subdir: impl AsRef<Path>,
+        args: &[String],
+    ) -> Result<Elf, Error> {
+        let subdir = subdir.as_ref().to_string_lossy();
+        let guest_args = ["--git-url", url, "--git-rev", rev, "--guest-dir", &*subdir];
+        let mut outputs = self.run_compiler(
+            |_| Ok(guest_args.map(str::to_string).into()),
+            false,
+            1,
+            args,
+        )?;
+        Ok(outputs.remove(0).0)
+    }
 
-    fn compile(
+    /// Compiles the guest program like [`Compiler::compile`], and returns a [`CompileReport`]
+    /// along with the ELF.
+    pub fn compile_with_report(
         &self,
         guest_directory: impl AsRef<Path>,
         args: &[String],
-    ) -> Result<Elf, Self::Error> {
-        let guest_directory = guest_directory.as_ref();
+    ) -> Result<(Elf, CompileReport), Error> {
+        let mut outputs = self.run_compiler(
+            |guest_root| {
+                let guest_path_in_docker =
+                    self.guest_path_in_docker(guest_root, guest_directory.as_ref())?;
+                Ok(vec!["--guest-dir".to_string(), guest_path_in_docker])
+            },
+            true,
+            1,
+            args,
+        )?;
+        Ok(outputs.remove(0))
+    }
+
+    /// Compiles multiple guest programs under the mount directory in one container run, sharing
+    /// the toolchain setup and cargo cache, and returns their ELFs in the same order.
+    pub fn compile_many(
+        &self,
+        guest_directories: &[PathBuf],
+        args: &[String],
+    ) -> Result<Vec<Elf>, Error> {
+        if guest_directories.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let outputs = self.run_compiler(
+            |guest_root| {
+                let guest_args = guest_directories
+                    .iter()
+                    .map(|guest_directory| {
+                        let guest_path_in_docker =
+                            self.guest_path_in_docker(guest_root, guest_directory)?;
+                        Ok(["--guest-dir".to_string(), guest_path_in_docker])
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(guest_args.concat())
+            },
+            true,
+            guest_directories.len(),
+            args,
+        )?;
+        Ok(outputs.into_iter().map(|(elf, _)| elf).collect())
+    }
+
+    /// Returns the path of `guest_directory` in the container, where the mount directory is at
+    /// `guest_root`.
+    fn guest_path_in_docker(
+        &self,
+        guest_root: &Path,
+        guest_directory: &Path,
+    ) -> Result<String, Error> {
         let guest_relative_path = guest_directory
             .strip_prefix(&self.mount_directory)
             .map_err(|_| Error::GuestNotInMountingDirecty {
                 mounting_directory: self.mount_directory.to_path_buf(),
                 guest_directory: guest_directory.to_path_buf(),
             })?;
-        let guest_path_in_docker = PathBuf::from("/guest")
+        Ok(guest_root
             .join(guest_relative_path)
             .to_string_lossy()
-            .to_string();
+            .to_string())
+    }
 
-        let tempdir = TempDir::new().map_err(CommonError::tempdir)?;
+    /// Runs `ere-compiler` with the guest args returned by `guest_args` given the path of the
+    /// mount directory in the container, which is copied into the container if `copy_guest` and
+    /// files are copied instead of bind-mounted.
+    fn run_compiler(
+        &self,
+        guest_args: impl FnOnce(&Path) -> Result<Vec<String>, Error>,
+        copy_guest: bool,
+        num_guests: usize,
+        args: &[String],
+    ) -> Result<Vec<(Elf, CompileReport)>, Error> {
+        const ELF_NAME: &str = "guest.elf";
+        const REPORT_NAME: &str = "report.json";
+        let copy_guest = copy_guest && self.copy_files;
+
+        let warm = if self.keep_warm {
+            let container = self.warm_container()?;
+            let request = container
+                .next_request
+                .fetch_add(1, Ordering::Relaxed)
+                .to_string();
+            Some((container, request))
+        } else {
+            None
+        };
+        // Requests to the warm container might run concurrently, so guests copied into it are
+        // kept apart by request.
+        let guest_root = match &warm {
+            Some((_, request)) if self.copy_files => PathBuf::from(format!("/guest-{request}")),
+            _ => PathBuf::from("/guest"),
+        };
+        let guest_args = guest_args(&guest_root)?;
+
+        let mut compiler_args = vec!["--compiler-kind", self.compiler_kind.as_str()];
+        compiler_args.extend(guest_args.iter().map(String::as_str));
+        compiler_args.extend([
+            "--elf-name",
+            ELF_NAME,
+            "--compress",
+            "--report-name",
+            REPORT_NAME,
+        ]);
+        // Args set by `with_*` are forwarded along with `args` to the underlying compiler.
+        if !self.compile_args.is_empty() || !args.is_empty() {
+            compiler_args.push("--");
+            compiler_args.extend(self.compile_args.iter().chain(args).map(|arg| arg.as_str()));
+        }
 
-        let mut cmd = DockerRunCmd::new(compiler_zkvm_image(self.zkvm_kind))
-            .rm()
+        // Keeps the output directory of a one-off container alive until outputs are read.
+        let mut tempdir = None;
+        let output_dir = if let Some((container, request)) = warm {
+            let output_dir_in_docker = format!("/output/{request}");
+            let output_dir = container.output_dir.path().join(&request);
+            let result = (|| -> Result<(), Error> {
+                if copy_guest {
+                    docker_cp(
+                        &self.mount_directory,
+                        format!("{}:{}", container.id, guest_root.display()),
+                    )?;
+                }
+                docker_exec(
+                    &container.id,
+                    self.zkvm_kind,
+                    &request,
+                    iter::once("/ere/bin/ere-compiler")
+                        .chain(compiler_args)
+                        .chain(["--output-dir", &output_dir_in_docker]),
+                )?;
+                if self.copy_files {
+                    docker_cp(
+                        format!("{}:{output_dir_in_docker}", container.id),
+                        &output_dir,
+                    )?;
+                }
+                Ok(())
+            })();
+            if self.copy_files {
+                let guest_root = guest_root.to_string_lossy();
+                let cleanup = ["rm", "-rf", &*guest_root, output_dir_in_docker.as_str()];
+                if let Err(err) = docker_exec(&container.id, self.zkvm_kind, &request, cleanup) {
+                    error!("Failed to remove files of request {request} in container: {err}");
+                }
+            }
+            result?;
+            output_dir
+        } else {
+            let output_dir = TempDir::new().map_err(CommonError::tempdir)?;
+            let secrets_dir = TempDir::new().map_err(CommonError::tempdir)?;
+            let cmd = self
+                .container_cmd(output_dir.path(), secrets_dir.path())?
+                .forward_logs(self.zkvm_kind);
+            let compiler_args = compiler_args.into_iter().chain(["--output-dir", "/output"]);
+            if self.copy_files {
+                // Removed after outputs are copied out instead of by `--rm`.
+                let id = cmd.create(compiler_args)?;
+                let result = (|| -> Result<(), Error> {
+                    if copy_guest {
+                        docker_cp(
+                            &self.mount_directory,
+                            format!("{id}:{}", guest_root.display()),
+                        )?;
+                    }
+                    self.copy_secrets(&id, secrets_dir.path())?;
+                    docker_start(&id, self.zkvm_kind)?;
+                    docker_cp(format!("{id}:/output/."), output_dir.path())?;
+                    Ok(())
+                })();
+                if let Err(err) = remove_docker_container(&id) {
+                    error!("Failed to remove docker container: {err}");
+                }
+                result?;
+            } else {
+                cmd.rm().exec(compiler_args)?;
+            }
+            tempdir.insert(output_dir).path().to_path_buf()
+        };
+
+        let outputs: Result<Vec<_>, Error> = (0..num_guests)
+            .map(|idx| -> Result<_, Error> {
+                // `ere-compiler` writes into sub-directories by index for multiple guests.
+                let guest_output_dir = match num_guests {
+                    1 => output_dir.clone(),
+                    _ => output_dir.join(idx.to_string()),
+                };
+
+                let elf_path = guest_output_dir.join(ELF_NAME);
+                let elf = fs::read(&elf_path)
+                    .map_err(|err| CommonError::read_file("elf", &elf_path, err))?;
+                let elf = Elf::from_maybe_compressed(elf)
+                    .map_err(|err| CommonError::io("Failed to decompress ELF", err))?;
+
+                let report_path = guest_output_dir.join(REPORT_NAME);
+                let report = fs::read(&report_path)
+                    .map_err(|err| CommonError::read_file("report", &report_path, err))?;
+                let report = serde_json::from_slice(&report)
+                    .map_err(|err| CommonError::deserialize("report", "serde_json", err))?;
+
+                Ok((elf, report))
+            })
+            .collect();
+
+        // Outputs in the warm container's directory are kept until it's dropped otherwise.
+        if self.keep_warm {
+            let _ = fs::remove_dir_all(&output_dir);
+        }
+
+        outputs
+    }
+
+    /// Returns the `docker run` command of the compiler container, with `output_dir` mounted as
+    /// the output directory and secrets written into `secrets_dir`, unless files are copied (see
+    /// [`Self::copy_secrets`]).
+    fn container_cmd(&self, output_dir: &Path, secrets_dir: &Path) -> Result<DockerRunCmd, Error> {
+        let mut cmd = DockerRunCmd::new(compiler_zkvm_image(self.zkvm_kind)?)?;
+        if !self.copy_files {
+            cmd = cmd
+                .volume(&self.mount_directory, "/guest")
+                .volume(output_dir, "/output");
+        }
+        cmd = cmd
             .inherit_env("RUST_LOG")
             .inherit_env("NO_COLOR")
-            .inherit_env("ERE_RUST_TOOLCHAIN")
-            .volume(&self.mount_directory, "/guest")
-            .volume(tempdir.path(), "/output");
+            .inherit_env("ERE_RUST_TOOLCHAIN");
 
         cmd = match self.zkvm_kind {
             // OpenVM allows to select Rust toolchain for guest compilation.
@@ -155,34 +527,142 @@ impl Compiler for DockerizedCompiler {
             _ => cmd,
         };
 
-        const ELF_NAME: &str = "guest.elf";
-        let extra_args = (!args.is_empty())
-            .then(|| {
-                iter::empty()
-                    .chain(["--"])
-                    .chain(args.iter().map(|arg| arg.as_str()))
-            })
-            .into_iter()
-            .flatten();
-        cmd.exec(
-            [
-                "--compiler-kind",
-                self.compiler_kind.as_str(),
-                "--guest-dir",
-                &guest_path_in_docker,
-                "--output-dir",
-                "/output",
-                "--elf-name",
-                ELF_NAME,
-            ]
-            .into_iter()
-            .chain(extra_args),
-        )?;
+        if self.cargo_cache {
+            let registry = ("ere-cargo-registry", "/usr/local/cargo/registry");
+            let target = (
+                format!("ere-cargo-target-{}", self.zkvm_kind),
+                "/cargo-target",
+            );
+
+            // Volumes are created owned by root, so other users need them made writable first.
+            if self.container_config.user()? != ContainerUser::Image {
+                DockerRunCmd::new(compiler_zkvm_image(self.zkvm_kind)?)?
+                    .rm()
+                    .volume(registry.0, registry.1)
+                    .volume(&target.0, target.1)
+                    .entrypoint("chmod")
+                    .exec(["-R", "a+rwX", registry.1, target.1])?;
+            }
+
+            cmd = cmd
+                .volume(registry.0, registry.1)
+                .volume(target.0, target.1)
+                .env("CARGO_TARGET_DIR", target.1);
+        }
 
-        let elf_path = tempdir.path().join(ELF_NAME);
-        let elf =
-            fs::read(&elf_path).map_err(|err| CommonError::read_file("elf", &elf_path, err))?;
-        Ok(Elf(elf))
+        cmd = self.container_config.apply(cmd, None)?;
+        cmd = self.apply_git_credentials(cmd, secrets_dir)?;
+
+        for (key, value) in &self.envs {
+            cmd = cmd.env(key, value);
+        }
+
+        Ok(cmd)
+    }
+
+    /// Returns the warm container, starting it if not yet.
+    fn warm_container(&self) -> Result<&WarmContainer, Error> {
+        if let Some(container) = self.warm_container.get() {
+            return Ok(container);
+        }
+
+        let output_dir = TempDir::new().map_err(CommonError::tempdir)?;
+        let secrets_dir = TempDir::new().map_err(CommonError::tempdir)?;
+
+        info!("Starting compiler container...");
+
+        let id = self
+            .container_cmd(output_dir.path(), secrets_dir.path())?
+            .rm()
+            .entrypoint("sleep")
+            .detach(["infinity"])?;
+        if let Err(err) = self.copy_secrets(&id, secrets_dir.path()) {
+            let _ = remove_docker_container(&id);
+            return Err(err);
+        }
+        let container = WarmContainer {
+            id,
+            output_dir,
+            _secrets_dir: secrets_dir,
+            next_request: AtomicUsize::new(0),
+        };
+
+        // If another thread started one concurrently, ours is dropped and removed.
+        let _ = self.warm_container.set(container);
+        Ok(self.warm_container.get().unwrap())
+    }
+
+    /// Copies the secrets written into `secrets_dir` into the container `id` if files are copied.
+    fn copy_secrets(&self, id: &str, secrets_dir: &Path) -> Result<(), Error> {
+        let has_secrets = self
+            .git_credentials
+            .iter()
+            .any(|credential| matches!(credential, GitCredential::Token(_)));
+        if self.copy_files && has_secrets {
+            docker_cp(secrets_dir, format!("{id}:/run/ere-secrets"))?;
+        }
+        Ok(())
+    }
+
+    /// Applies [`GitCredential`]s to `cmd`, writing secrets into `secrets_dir` to be mounted, or
+    /// copied if files are copied.
+    fn apply_git_credentials(
+        &self,
+        mut cmd: DockerRunCmd,
+        secrets_dir: &Path,
+    ) -> Result<DockerRunCmd, Error> {
+        if self.git_credentials.is_empty() {
+            return Ok(cmd);
+        }
+
+        // Fetch with git CLI so the SSH agent and credential helper are used.
+        cmd = cmd.env("CARGO_NET_GIT_FETCH_WITH_CLI", "true");
+
+        for credential in &self.git_credentials {
+            match credential {
+                GitCredential::SshAgent => {
+                    // The socket can't be copied to a remote Docker host.
+                    if let Some(host) = remote_docker_host()? {
+                        return Err(Error::RemoteDockerHost { host });
+                    }
+                    let sock = env::var_os("SSH_AUTH_SOCK").ok_or(Error::SshAuthSockNotSet)?;
+                    cmd = cmd
+                        .volume(sock, "/run/ssh-agent.sock")
+                        .env("SSH_AUTH_SOCK", "/run/ssh-agent.sock")
+                        .env("GIT_SSH_COMMAND", "ssh -o StrictHostKeyChecking=accept-new");
+                }
+                GitCredential::Token(token) => {
+                    let path = secrets_dir.join("git-token");
+                    fs::write(&path, token)
+                        .map_err(|err| CommonError::write_file("git token", &path, err))?;
+                    if !self.copy_files {
+                        cmd = cmd.volume(secrets_dir, "/run/ere-secrets:ro");
+                    }
+                    cmd = cmd
+                        .env("GIT_CONFIG_COUNT", "1")
+                        .env("GIT_CONFIG_KEY_0", "credential.helper")
+                        .env(
+                            "GIT_CONFIG_VALUE_0",
+                            "!f() { echo username=x-access-token; echo password=$(cat /run/ere-secrets/git-token); }; f",
+                        );
+                }
+            }
+        }
+
+        Ok(cmd)
+    }
+}
+
+impl Compiler for DockerizedCompiler {
+    type Error = Error;
+
+    fn compile(
+        &self,
+        guest_directory: impl AsRef<Path>,
+        args: &[String],
+    ) -> Result<Elf, Self::Error> {
+        let (elf, _) = self.compile_with_report(guest_directory, args)?;
+        Ok(elf)
     }
 }
 
@@ -192,7 +672,10 @@ pub(crate) mod tests {
     use ere_util_test::host::testing_guest_directory;
     use tracing_subscriber::EnvFilter;
 
-    use crate::{CompilerKind, compiler::DockerizedCompiler, util::workspace_dir, zkVMKind};
+    use crate::{
+        CompilerKind, ContainerConfig, ContainerUser, compiler::DockerizedCompiler,
+        util::workspace_dir, zkVMKind,
+    };
 
     pub fn compile(zkvm_kind: zkVMKind, compiler_kind: CompilerKind, program: &'static str) -> Elf {
         let _ = tracing_subscriber::fmt()
@@ -257,6 +740,107 @@ pub(crate) mod tests {
         test_reproducible_elf!(Risc0, Rust, "stock_nightly_no_std");
     }
 
+    #[test]
+    fn test_compile_many() {
+        let zkvm_kind = zkVMKind::SP1;
+        let compiler = DockerizedCompiler::new(
+            zkvm_kind,
+            CompilerKind::RustCustomized,
+            workspace_dir().unwrap(),
+        )
+        .unwrap();
+        let programs = ["basic", "primitives"];
+        let elfs = compiler
+            .compile_many(
+                &programs.map(|program| testing_guest_directory(zkvm_kind.as_str(), program)),
+                &[],
+            )
+            .unwrap();
+        for (program, elf) in programs.into_iter().zip(elfs) {
+            assert!(elf == compile(zkvm_kind, CompilerKind::RustCustomized, program));
+        }
+    }
+
+    #[test]
+    fn test_compile_keep_warm() {
+        let zkvm_kind = zkVMKind::SP1;
+        let compiler = DockerizedCompiler::new(
+            zkvm_kind,
+            CompilerKind::RustCustomized,
+            workspace_dir().unwrap(),
+        )
+        .unwrap()
+        .with_keep_warm(true);
+        for program in ["basic", "primitives", "basic"] {
+            let elf = compiler
+                .compile(testing_guest_directory(zkvm_kind.as_str(), program), &[])
+                .unwrap();
+            assert!(elf == compile(zkvm_kind, CompilerKind::RustCustomized, program));
+        }
+    }
+
+    #[test]
+    fn test_compile_cap_drop() {
+        let zkvm_kind = zkVMKind::SP1;
+        let compiler = DockerizedCompiler::new(
+            zkvm_kind,
+            CompilerKind::RustCustomized,
+            workspace_dir().unwrap(),
+        )
+        .unwrap()
+        .with_container_config(ContainerConfig {
+            cap_drop: vec!["ALL".to_string()],
+            ..Default::default()
+        });
+        let elf = compiler
+            .compile(testing_guest_directory(zkvm_kind.as_str(), "basic"), &[])
+            .unwrap();
+        assert!(elf == compile(zkvm_kind, CompilerKind::RustCustomized, "basic"));
+    }
+
+    #[test]
+    fn test_compile_host_user() {
+        let zkvm_kind = zkVMKind::SP1;
+        for cargo_cache in [true, false] {
+            let compiler = DockerizedCompiler::new(
+                zkvm_kind,
+                CompilerKind::RustCustomized,
+                workspace_dir().unwrap(),
+            )
+            .unwrap()
+            .with_cargo_cache(cargo_cache)
+            .with_container_config(ContainerConfig {
+                user: ContainerUser::Host,
+                ..Default::default()
+            });
+            let elf = compiler
+                .compile(testing_guest_directory(zkvm_kind.as_str(), "basic"), &[])
+                .unwrap();
+            assert!(elf == compile(zkvm_kind, CompilerKind::RustCustomized, "basic"));
+        }
+    }
+
+    #[test]
+    fn test_compile_with_report() {
+        let zkvm_kind = zkVMKind::SP1;
+        let compiler = DockerizedCompiler::new(
+            zkvm_kind,
+            CompilerKind::RustCustomized,
+            workspace_dir().unwrap(),
+        )
+        .unwrap();
+        let (elf, report) = compiler
+            .compile_with_report(testing_guest_directory(zkvm_kind.as_str(), "basic"), &[])
+            .unwrap();
+        assert_eq!(report.elf_size, elf.len() as u64);
+        assert_eq!(
+            report.target.as_deref(),
+            Some("riscv64im-succinct-zkvm-elf")
+        );
+        assert!(report.compressed_elf_size.is_some());
+        assert!(report.toolchain.is_some());
+    }
+
     mod sp1 {
         test_compile!(SP1, RustCustomized, "basic");
         test_compile!(SP1, Rust, "stock_nightly_no_std");
@@ -14,4 +14,10 @@ pub enum Error {
         mounting_directory: PathBuf,
         guest_directory: PathBuf,
     },
+    #[error("Env variable `SSH_AUTH_SOCK` is not set, SSH agent not found to forward")]
+    SshAuthSockNotSet,
+    #[error(
+        "Forwarding the SSH agent to the compiler container is unsupported with remote Docker host {host}"
+    )]
+    RemoteDockerHost { host: String },
 }
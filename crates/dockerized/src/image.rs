@@ -1,7 +1,43 @@
-use crate::{DOCKER_IMAGE_TAG, util::env::image_registry, zkVMKind};
+use std::{
+    collections::BTreeSet,
+    io,
+    path::Path,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use tracing::{info, warn};
+
+use crate::{
+    CommonError, DOCKER_IMAGE_TAG, ProverResource,
+    compiler::build_compiler_image,
+    config::ImageLayout,
+    prover::build_server_image,
+    util::{
+        docker::{
+            DockerBuildCmd, docker_image_exists, docker_list_images, docker_load_images,
+            docker_push_image, docker_remove_image, docker_save_images, docker_tag_image,
+        },
+        env::{
+            base_image_build_args, build_parallelism, force_rebuild_docker_image, image_layout,
+            image_registry,
+        },
+        source::{SOURCE_DIGEST_LABEL, base_source_digest, is_source_stale},
+        workspace_dir,
+    },
+    zkVMKind,
+};
 
 /// Returns tag of images in format of `{version}{suffix}`.
 pub fn image_tag(zkvm_kind: zkVMKind, gpu: bool) -> String {
+    versioned_image_tag(DOCKER_IMAGE_TAG, zkvm_kind, gpu)
+}
+
+/// Returns tag of images of the given `version` in format of `{version}{suffix}`.
+pub fn versioned_image_tag(version: &str, zkvm_kind: zkVMKind, gpu: bool) -> String {
     let suffix = match (zkvm_kind, gpu) {
         // Only the following zkVMs requires CUDA setup in the base image
         // when GPU support is required.
@@ -15,35 +51,401 @@ pub fn image_tag(zkvm_kind: zkVMKind, gpu: bool) -> String {
         ) => "-cuda",
         _ => "",
     };
-    format!("{DOCKER_IMAGE_TAG}{suffix}")
+    format!("{version}{suffix}")
 }
 
 /// Returns `ere-base:{image_tag}`
-pub fn base_image(zkvm_kind: zkVMKind, gpu: bool) -> String {
+pub fn base_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<String, CommonError> {
     let image_tag = image_tag(zkvm_kind, gpu);
     with_image_registry(format!("ere-base:{image_tag}"))
 }
 
 /// Returns `ere-base-{zkvm_kind}:{image_tag}`
-pub fn base_zkvm_image(zkvm_kind: zkVMKind, gpu: bool) -> String {
+pub fn base_zkvm_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<String, CommonError> {
     let image_tag = image_tag(zkvm_kind, gpu);
     with_image_registry(format!("ere-base-{zkvm_kind}:{image_tag}"))
 }
 
+/// Returns `ere-base-{zkvm_kind}-compiler:{image_tag}` with [`ImageLayout::Split`], otherwise
+/// the same as [`base_zkvm_image`] without GPU support.
+pub fn compiler_base_zkvm_image(zkvm_kind: zkVMKind) -> Result<String, CommonError> {
+    match image_layout()? {
+        ImageLayout::Shared => base_zkvm_image(zkvm_kind, false),
+        ImageLayout::Split => {
+            let image_tag = image_tag(zkvm_kind, false);
+            with_image_registry(format!("ere-base-{zkvm_kind}-compiler:{image_tag}"))
+        }
+    }
+}
+
 /// Returns `ere-server-{zkvm_kind}:{image_tag}`
-pub fn server_zkvm_image(zkvm_kind: zkVMKind, gpu: bool) -> String {
-    let image_tag = image_tag(zkvm_kind, gpu);
+pub fn server_zkvm_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<String, CommonError> {
+    versioned_server_zkvm_image(DOCKER_IMAGE_TAG, zkvm_kind, gpu)
+}
+
+/// Returns `ere-server-{zkvm_kind}:{image_tag}` of the given `version`.
+pub fn versioned_server_zkvm_image(
+    version: &str,
+    zkvm_kind: zkVMKind,
+    gpu: bool,
+) -> Result<String, CommonError> {
+    let image_tag = versioned_image_tag(version, zkvm_kind, gpu);
     with_image_registry(format!("ere-server-{zkvm_kind}:{image_tag}"))
 }
 
 /// Returns `ere-compiler-{zkvm_kind}:{image_tag}`
-pub fn compiler_zkvm_image(zkvm_kind: zkVMKind) -> String {
+pub fn compiler_zkvm_image(zkvm_kind: zkVMKind) -> Result<String, CommonError> {
     let image_tag = image_tag(zkvm_kind, false);
     with_image_registry(format!("ere-compiler-{zkvm_kind}:{image_tag}"))
 }
 
-fn with_image_registry(image: String) -> String {
-    image_registry()
+/// Builds the compiler and server images of `zkvm_kinds` for `resource`, for up to
+/// `ERE_DOCKER_BUILD_PARALLELISM` zkVMs concurrently, so the later [`DockerizedCompiler::new`]
+/// and [`DockerizedzkVM::new`] don't need to build.
+///
+/// The `ere-base` images shared by zkVMs are built first, then each worker builds the remaining
+/// images of one zkVM at a time.
+///
+/// [`DockerizedCompiler::new`]: crate::DockerizedCompiler::new
+/// [`DockerizedzkVM::new`]: crate::DockerizedzkVM::new
+pub fn prepare_images(
+    zkvm_kinds: &[zkVMKind],
+    resource: &ProverResource,
+) -> Result<(), CommonError> {
+    let gpu = resource.is_gpu();
+
+    // Build the shared `ere-base` images once upfront if they are going to be needed, instead of
+    // racing to build them in workers. Skipped when images could be pulled from the registry.
+    if image_registry()?.is_none() {
+        let force_rebuild = force_rebuild_docker_image()?;
+        let mut base_images = BTreeSet::new();
+        for &zkvm_kind in zkvm_kinds {
+            // Compiler images are always built without GPU support.
+            for (base_gpu, image) in [
+                (false, compiler_zkvm_image(zkvm_kind)?),
+                (gpu, server_zkvm_image(zkvm_kind, gpu)?),
+            ] {
+                if (force_rebuild || !docker_image_exists(&image)?)
+                    && base_images.insert(base_image(zkvm_kind, base_gpu)?)
+                {
+                    build_base_image(zkvm_kind, base_gpu)?;
+                }
+            }
+        }
+    }
+
+    let next = AtomicUsize::new(0);
+    let parallelism = build_parallelism()?.min(zkvm_kinds.len());
+    thread::scope(|scope| {
+        let workers = (0..parallelism)
+            .map(|_| {
+                scope.spawn(|| -> Result<(), CommonError> {
+                    while let Some(&zkvm_kind) =
+                        zkvm_kinds.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        build_compiler_image(zkvm_kind)?;
+                        build_server_image(zkvm_kind, gpu)?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().expect("image build thread panicked"))
+    })
+}
+
+/// `ere-base` images built or checked up to date by this process, so they are built once even if
+/// `ERE_FORCE_REBUILD_DOCKER_IMAGE` is set, instead of by every worker of [`prepare_images`].
+/// Locked while building to serialize the builds.
+static BASE_IMAGES_READY: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Builds `ere-base:{image_tag}` if it doesn't exist, is built from other sources, or
+/// `ERE_FORCE_REBUILD_DOCKER_IMAGE` is set and it's not yet built by this process.
+pub(crate) fn build_base_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<(), CommonError> {
+    let base_image = base_image(zkvm_kind, gpu)?;
+    let mut ready = BASE_IMAGES_READY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if ready.contains(&base_image) {
+        return Ok(());
+    }
+
+    let base_source_digest = base_source_digest()?;
+    if !force_rebuild_docker_image()?
+        && docker_image_exists(&base_image)?
+        && !is_source_stale(&base_image, &base_source_digest)?
+    {
+        ready.insert(base_image);
+        return Ok(());
+    }
+
+    info!("Building image {base_image}...");
+
+    let workspace_dir = workspace_dir()?;
+    let mut cmd = DockerBuildCmd::new()
+        .file(workspace_dir.join("docker").join("Dockerfile.base"))
+        .tag(&base_image)
+        .label(SOURCE_DIGEST_LABEL, &base_source_digest);
+
+    for (key, image) in base_image_build_args()? {
+        cmd = cmd.build_arg(key, image);
+    }
+
+    if gpu {
+        cmd = cmd.build_arg("CUDA", "1");
+    }
+
+    cmd.exec(&workspace_dir)?;
+    ready.insert(base_image);
+    Ok(())
+}
+
+/// Exports the `ere-server-{zkvm_kind}:{image_tag}` image into a tar archive at `path`, to be
+/// imported by [`import_images`] on a prover host without access to the registry.
+///
+/// The image is built by [`DockerizedzkVM::new`] if it doesn't exist.
+///
+/// [`DockerizedzkVM::new`]: crate::DockerizedzkVM::new
+pub fn export_server_image(
+    zkvm_kind: zkVMKind,
+    gpu: bool,
+    path: impl AsRef<Path>,
+) -> Result<(), CommonError> {
+    docker_save_images([server_zkvm_image(zkvm_kind, gpu)?], path)
+}
+
+/// Imports images from a tar archive at `path` exported by [`export_server_image`] or saved by
+/// [`save_bundle`], after which the images are not rebuilt.
+pub fn import_images(path: impl AsRef<Path>) -> Result<(), CommonError> {
+    docker_load_images(path)
+}
+
+/// Saves all images of `zkvm_kind` of this version that exist locally (`ere-base`,
+/// `ere-base-{zkvm_kind}`, `ere-compiler-{zkvm_kind}` and `ere-server-{zkvm_kind}`, with and
+/// without GPU support) into a single tar archive at `path`, and returns the saved images.
+///
+/// Air-gapped hosts can then be provisioned from the archive by [`import_images`], after which the
+/// images are not rebuilt. Returns error if none of the images exist.
+pub fn save_bundle(
+    zkvm_kind: zkVMKind,
+    path: impl AsRef<Path>,
+) -> Result<Vec<String>, CommonError> {
+    let mut images = Vec::new();
+    for gpu in [false, true] {
+        for image in [
+            base_image(zkvm_kind, gpu)?,
+            base_zkvm_image(zkvm_kind, gpu)?,
+            server_zkvm_image(zkvm_kind, gpu)?,
+        ] {
+            if !images.contains(&image) && docker_image_exists(&image)? {
+                images.push(image);
+            }
+        }
+    }
+    let compiler_image = compiler_zkvm_image(zkvm_kind)?;
+    if docker_image_exists(&compiler_image)? {
+        images.push(compiler_image);
+    }
+    if images.is_empty() {
+        return Err(CommonError::io(
+            "No images to bundle",
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no image of {zkvm_kind} exists locally"),
+            ),
+        ));
+    }
+
+    info!("Saving images {images:?} into bundle...");
+    docker_save_images(&images, path)?;
+    Ok(images)
+}
+
+/// Pushes the locally built `ere-compiler-{zkvm_kind}:{tag}` and `ere-server-{zkvm_kind}:{tag}`
+/// images to `registry`, and returns the pushed images.
+///
+/// Images that don't exist locally are skipped (e.g. there is no compiler image with the `-cuda`
+/// suffix), and returns error if none of them exist. Other hosts could then pull them by setting
+/// `ERE_IMAGE_REGISTRY` to `registry`.
+pub fn push_images(
+    zkvm_kind: zkVMKind,
+    registry: &str,
+    tag: &str,
+) -> Result<Vec<String>, CommonError> {
+    let registry = registry.trim_end_matches('/');
+    let mut pushed = Vec::new();
+    for name in [
+        format!("ere-compiler-{zkvm_kind}:{tag}"),
+        format!("ere-server-{zkvm_kind}:{tag}"),
+    ] {
+        let local = with_image_registry(name.clone())?;
+        if !docker_image_exists(&local)? {
+            info!("Image {local} doesn't exist, skip pushing");
+            continue;
+        }
+
+        let remote = format!("{registry}/{name}");
+        if remote != local {
+            docker_tag_image(&local, &remote)?;
+        }
+        info!("Pushing image {remote}...");
+        docker_push_image(&remote)?;
+        pushed.push(remote);
+    }
+    if pushed.is_empty() {
+        return Err(CommonError::io(
+            "No images to push",
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no image of {zkvm_kind} with tag {tag} exists locally"),
+            ),
+        ));
+    }
+    Ok(pushed)
+}
+
+/// Image of Ere in the local image store, listed by [`list_ere_images`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EreImage {
+    /// Name in format of `[{registry}/]ere-{kind}:{version}{suffix}`.
+    pub name: String,
+    /// Version of the image, which is the tag without the GPU suffix.
+    pub version: String,
+    /// Whether the image is built with GPU support.
+    pub gpu: bool,
+    pub id: String,
+    /// Creation time in RFC 3339 format.
+    pub created: String,
+    /// Size in bytes, including layers shared with other images.
+    pub size: u64,
+}
+
+/// Lists images of Ere (`ere-base`, `ere-base-{zkvm}`, `ere-compiler-{zkvm}` and
+/// `ere-server-{zkvm}` of any version, GPU variant and registry) in the local image store, sorted
+/// by name.
+pub fn list_ere_images() -> Result<Vec<EreImage>, CommonError> {
+    let mut images = docker_list_images(|name| parse_ere_image(name).is_some())?
+        .into_iter()
+        .filter_map(|image| {
+            let (version, gpu) = parse_ere_image(&image.name)?;
+            let version = version.to_string();
+            Some(EreImage {
+                name: image.name,
+                version,
+                gpu,
+                id: image.id,
+                created: image.created,
+                size: image.size,
+            })
+        })
+        .collect::<Vec<_>>();
+    images.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(images)
+}
+
+/// Removes images of Ere of all but the `keep_latest_n` most recently created versions, and
+/// returns the removed images. Images of the version of this crate are always kept.
+///
+/// Images used by containers (e.g. a running `ere-server`) fail to be removed and are skipped.
+pub fn prune(keep_latest_n: usize) -> Result<Vec<EreImage>, CommonError> {
+    let images = list_ere_images()?;
+
+    // Versions sorted by the latest creation time of their images, most recent first.
+    let mut versions = Vec::<(&str, &str)>::new();
+    for image in &images {
+        match versions
+            .iter_mut()
+            .find(|(version, _)| *version == image.version)
+        {
+            Some((_, created)) => *created = (*created).max(image.created.as_str()),
+            None => versions.push((&image.version, &image.created)),
+        }
+    }
+    versions.sort_by(|a, b| b.1.cmp(a.1));
+    let kept = versions
+        .iter()
+        .take(keep_latest_n)
+        .map(|(version, _)| *version)
+        .chain([DOCKER_IMAGE_TAG])
+        .collect::<BTreeSet<_>>();
+
+    let mut removed = Vec::new();
+    for image in &images {
+        if kept.contains(image.version.as_str()) {
+            continue;
+        }
+        info!("Removing image {}...", image.name);
+        match docker_remove_image(&image.name) {
+            Ok(()) => removed.push(image.clone()),
+            Err(err) => warn!("Failed to remove image {}: {err}", image.name),
+        }
+    }
+    Ok(removed)
+}
+
+/// Returns the total size in bytes of images of Ere in the local image store.
+///
+/// Layers shared between images (e.g. `ere-base`) are counted once per image, so the actual disk
+/// usage is smaller, see `docker system df` for the exact one.
+pub fn disk_usage() -> Result<u64, CommonError> {
+    let mut ids = BTreeSet::new();
+    Ok(list_ere_images()?
+        .into_iter()
+        .filter(|image| ids.insert(image.id.clone()))
+        .map(|image| image.size)
+        .sum())
+}
+
+/// Parses `[{registry}/]ere-{kind}:{version}{suffix}` and returns the version and whether it has
+/// the GPU suffix, `None` if it's not an image of Ere.
+fn parse_ere_image(name: &str) -> Option<(&str, bool)> {
+    let (repository, tag) = name.rsplit_once(':')?;
+    let repository = repository.rsplit('/').next()?;
+    let kind = repository.strip_prefix("ere-")?;
+    let is_ere = kind == "base"
+        || ["base-", "compiler-", "server-"].iter().any(|prefix| {
+            kind.strip_prefix(prefix)
+                .is_some_and(|zkvm| !zkvm.is_empty())
+        });
+    if !is_ere {
+        return None;
+    }
+    Some(match tag.strip_suffix("-cuda") {
+        Some(version) => (version, true),
+        None => (tag, false),
+    })
+}
+
+fn with_image_registry(image: String) -> Result<String, CommonError> {
+    Ok(image_registry()?
         .map(|registry| format!("{}/{image}", registry.trim_end_matches('/')))
-        .unwrap_or_else(|| image)
+        .unwrap_or_else(|| image))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::parse_ere_image;
+
+    #[test]
+    fn test_parse_ere_image() {
+        assert_eq!(parse_ere_image("ere-base:0.5.0"), Some(("0.5.0", false)));
+        assert_eq!(
+            parse_ere_image("ere-server-sp1:0.5.0-cuda"),
+            Some(("0.5.0", true))
+        );
+        assert_eq!(
+            parse_ere_image("ghcr.io/eth-act/ere/ere-compiler-zisk:0.4.0"),
+            Some(("0.4.0", false))
+        );
+        assert_eq!(
+            parse_ere_image("localhost:5000/ere-base-risc0:0.5.0"),
+            Some(("0.5.0", false))
+        );
+        assert_eq!(parse_ere_image("ere-server-:0.5.0"), None);
+        assert_eq!(parse_ere_image("ere-other:0.5.0"), None);
+        assert_eq!(parse_ere_image("ubuntu:24.04"), None);
+        assert_eq!(parse_ere_image("ere-base"), None);
+    }
 }
@@ -19,9 +19,40 @@
 //! When [`ProverResource::Gpu`] is selected, the image with GPU support
 //! will be built and tagged with specific suffix.
 //!
+//! `ere-base` is built on `ubuntu:24.04`, or `nvidia/cuda:12.9.1-devel-ubuntu24.04` with GPU
+//! support, which can be overridden by `ERE_BASE_IMAGE` and `ERE_BASE_CUDA_IMAGE` (e.g. an internal
+//! hardened image, or a CUDA version matching the driver). Likewise the `ere-server` and compiler
+//! images run on `ubuntu:24.04`, or `nvidia/cuda:12.9.1-runtime-ubuntu24.04` with GPU support,
+//! which can be overridden by `ERE_RUNTIME_IMAGE` and `ERE_RUNTIME_CUDA_IMAGE`. All images are
+//! rebuilt when they change.
+//!
 //! To force rebuild all images, set the environment variable
 //! `ERE_FORCE_REBUILD_DOCKER_IMAGE` to non-empty value.
 //!
+//! For a prover host without network access, the server image can be exported by
+//! [`image::export_server_image`] on a connected machine and imported by
+//! [`image::import_images`], along with the ELF written by [`Elf::write_to`]. To provision
+//! both compiling and proving, all images of a zkVM can be saved into a single archive by
+//! [`image::save_bundle`] and loaded by [`image::import_images`] as well.
+//!
+//! Images accumulated across versions can be listed by [`image::list_ere_images`] and removed
+//! by [`image::prune`], with their total size reported by [`image::disk_usage`].
+//!
+//! ## Remote Docker host
+//!
+//! The Docker daemon is selected by `DOCKER_HOST`, `DOCKER_TLS_VERIFY` and `DOCKER_CERT_PATH`
+//! like the `docker` CLI, or the `[docker]` section of the configuration file. With a remote host
+//! (e.g. `tcp://gpu-server:2376`), [`DockerizedzkVM`] sends the ELF and inputs over the network
+//! and connects to the `ere-server` published on the remote host, and [`DockerizedCompiler`]
+//! copies the guest programs and outputs with `docker cp` instead of mounting local directories,
+//! so [`GitCredential::SshAgent`] is unsupported. Set `CUDA_ARCHS` for GPU images since the
+//! remote GPUs can't be detected.
+//!
+//! ## Remote server
+//!
+//! [`RemotezkVM`] connects to an `ere-server` started separately (e.g. on a GPU machine by
+//! `docker run` or a cluster scheduler), so this machine needs neither Docker nor the zkVM SDK.
+//!
 //! ## Example
 //!
 //! ```rust,no_run
@@ -77,13 +108,20 @@ mod util;
 pub mod compiler;
 pub mod image;
 pub mod prover;
+pub mod remote;
 
 pub use ere_catalog::{CompilerKind, DOCKER_IMAGE_TAG, zkVMKind};
 pub use ere_compiler_core::{Compiler, Elf};
 pub use ere_prover_core::*;
-pub use ere_server_client::{EncodedProgramVk, EncodedProof};
+pub use ere_server_client::{
+    CRATE_VERSION, EncodedProgramVk, EncodedProof, JobStatus, PROTOCOL_VERSION, ServerInfo,
+};
 
 pub use crate::{
-    compiler::DockerizedCompiler,
-    prover::{DockerizedzkVM, DockerizedzkVMConfig},
+    compiler::{DockerizedCompiler, GitCredential},
+    prover::{
+        ContainerConfig, ContainerNetwork, ContainerUser, DockerizedzkVM, DockerizedzkVMConfig,
+        JobHandle,
+    },
+    remote::{RemotezkVM, RemotezkVMConfig},
 };
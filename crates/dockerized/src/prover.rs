@@ -1,27 +1,60 @@
-use core::{future::Future, iter, pin::Pin, time::Duration};
-use std::time::Instant;
+use core::{
+    future::Future,
+    iter,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    time::Duration,
+};
+use std::{
+    collections::BTreeMap,
+    fs::{self, Permissions},
+    io,
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+    thread,
+    time::Instant,
+};
 
+use anyhow::Context;
 use ere_compiler_core::Elf;
 use ere_prover_core::{
-    Input, ProgramExecutionReport, ProgramProvingReport, ProverResource, PublicValues,
+    CommonError, ExecuteOptions, Input, ProgramExecutionReport, ProgramProvingReport, ProofKind,
+    ProverResource, PublicValues,
+};
+use ere_server_client::{
+    CRATE_VERSION, EncodedProgramVk, EncodedProof, JobStatus, PROTOCOL_VERSION, ServerInfo,
+    reqwest::Client, url::Url, zkVMClient,
 };
-use ere_server_client::{EncodedProgramVk, EncodedProof, reqwest::Client, url::Url, zkVMClient};
 use ere_util_tokio::block_on;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
 use tokio::{
     sync::{RwLock, RwLockReadGuard},
+    task,
     time::{sleep, timeout},
 };
 use tracing::{error, info, warn};
 
 use crate::{
-    image::{base_image, base_zkvm_image, server_zkvm_image},
+    DOCKER_IMAGE_TAG,
+    image::{
+        base_image, base_zkvm_image, build_base_image, server_zkvm_image,
+        versioned_server_zkvm_image,
+    },
     util::{
-        cuda::cuda_archs,
+        cuda::{CUDA_ARCHS_LABEL, cuda_archs, cuda_archs_label, is_cuda_archs_mismatched},
         docker::{
-            DockerBuildCmd, DockerRunCmd, docker_image_exists, docker_pull_image,
-            docker_wait_for_exit, remove_docker_container,
+            DockerBuildCmd, DockerRunCmd, docker_container_running, docker_find_running_container,
+            docker_image_exists, docker_inspect_exit_info, docker_published_port,
+            docker_pull_image, docker_wait_for_exit, remove_docker_container,
+        },
+        env::{
+            config, docker_network, force_rebuild_docker_image, image_registry, remote_docker_host,
+            runtime_image_build_args,
         },
-        env::{docker_network, force_rebuild_docker_image, image_registry},
+        free_port, host_user,
+        source::{SOURCE_DIGEST_LABEL, base_zkvm_source_digest, is_source_stale, source_digest},
         workspace_dir,
     },
     zkVMKind,
@@ -38,17 +71,20 @@ pub use error::Error;
 /// - OpenVM: `CUDA_ARCH` (comma-separated, e.g. "89,120")
 /// - Risc0: `NVCC_APPEND_FLAGS` (nvcc --generate-code flags)
 /// - Zisk: `CUDA_ARCHS` (comma-separated, e.g. "89,120")
+///
+/// The image of these zkVMs is also labeled with [`CUDA_ARCHS_LABEL`], to detect cached images
+/// built for other architectures.
 fn apply_cuda_build_args(
     cmd: DockerBuildCmd,
     zkvm_kind: zkVMKind,
     cuda_archs: &[u32],
-) -> Result<DockerBuildCmd, Error> {
+) -> Result<DockerBuildCmd, CommonError> {
     if cuda_archs.is_empty() {
         warn!("No CUDA_ARCHS set or detected, use default value in Dockerfile");
         return Ok(cmd);
     }
 
-    Ok(match zkvm_kind {
+    let cmd = match zkvm_kind {
         zkVMKind::Airbender => {
             let value = cuda_archs
                 .iter()
@@ -81,8 +117,9 @@ fn apply_cuda_build_args(
                 .join(",");
             cmd.build_arg("CUDA_ARCHS", value)
         }
-        _ => cmd,
-    })
+        _ => return Ok(cmd),
+    };
+    Ok(cmd.label(CUDA_ARCHS_LABEL, cuda_archs_label(cuda_archs)))
 }
 
 /// This method builds 3 Docker images in sequence:
@@ -92,25 +129,39 @@ fn apply_cuda_build_args(
 ///    selected zkVM feature
 ///
 /// When [`ProverResource::Gpu`] is selected, the image with GPU support
-/// will be built and tagged with specific suffix.
+/// will be built and tagged with specific suffix, and labeled with the CUDA architectures it's
+/// built for.
 ///
 /// Images are cached and only rebuilt if they don't exist or if the
-/// `ERE_FORCE_REBUILD_DOCKER_IMAGE` environment variable is set.
-fn build_server_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<(), Error> {
-    let force_rebuild = force_rebuild_docker_image();
-    let base_image = base_image(zkvm_kind, gpu);
-    let base_zkvm_image = base_zkvm_image(zkvm_kind, gpu);
-    let server_zkvm_image = server_zkvm_image(zkvm_kind, gpu);
+/// `ERE_FORCE_REBUILD_DOCKER_IMAGE` environment variable is set. Images are also rebuilt if
+/// they are built from sources other than the current ones (see [`is_source_stale`]), or for GPU
+/// images, for CUDA architectures not covering the ones set or detected on the host.
+pub(crate) fn build_server_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<(), CommonError> {
+    let force_rebuild = force_rebuild_docker_image()?;
+    let base_image = base_image(zkvm_kind, gpu)?;
+    let base_zkvm_image = base_zkvm_image(zkvm_kind, gpu)?;
+    let server_zkvm_image = server_zkvm_image(zkvm_kind, gpu)?;
+    let base_zkvm_source_digest = base_zkvm_source_digest(zkvm_kind)?;
+    let source_digest = source_digest()?;
+
+    // Resolve CUDA architectures once for checking cached images and both base-zkvm and server
+    // builds.
+    let cuda_archs = if gpu { cuda_archs()? } else { vec![] };
 
     if !force_rebuild {
-        if docker_image_exists(&server_zkvm_image)? {
+        if docker_image_exists(&server_zkvm_image)?
+            && !is_source_stale(&server_zkvm_image, source_digest)?
+            && !is_cuda_archs_mismatched(&server_zkvm_image, &cuda_archs)?
+        {
             info!("Image {server_zkvm_image} exists, skip building");
             return Ok(());
         }
 
-        if image_registry().is_some()
+        if image_registry()?.is_some()
             && docker_pull_image(&server_zkvm_image).is_ok()
             && docker_image_exists(&server_zkvm_image)?
+            && !is_source_stale(&server_zkvm_image, source_digest)?
+            && !is_cuda_archs_mismatched(&server_zkvm_image, &cuda_archs)?
         {
             info!("Image {server_zkvm_image} pulled, skip building");
             return Ok(());
@@ -121,31 +172,21 @@ fn build_server_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<(), Error> {
     let docker_dir = workspace_dir.join("docker");
     let docker_zkvm_dir = docker_dir.join(zkvm_kind.as_str());
 
-    // Resolve CUDA architectures once for both base-zkvm and server builds.
-    let cuda_archs = if gpu { cuda_archs() } else { vec![] };
-
     // Build `ere-base`
-    if force_rebuild || !docker_image_exists(&base_image)? {
-        info!("Building image {base_image}...");
-
-        let mut cmd = DockerBuildCmd::new()
-            .file(docker_dir.join("Dockerfile.base"))
-            .tag(&base_image);
-
-        if gpu {
-            cmd = cmd.build_arg("CUDA", "1");
-        }
-
-        cmd.exec(&workspace_dir)?;
-    }
+    build_base_image(zkvm_kind, gpu)?;
 
     // Build `ere-base-{zkvm_kind}`
-    if force_rebuild || !docker_image_exists(&base_zkvm_image)? {
+    if force_rebuild
+        || !docker_image_exists(&base_zkvm_image)?
+        || is_source_stale(&base_zkvm_image, &base_zkvm_source_digest)?
+        || is_cuda_archs_mismatched(&base_zkvm_image, &cuda_archs)?
+    {
         info!("Building image {base_zkvm_image}...");
 
         let mut cmd = DockerBuildCmd::new()
             .file(docker_zkvm_dir.join("Dockerfile.base"))
             .tag(&base_zkvm_image)
+            .label(SOURCE_DIGEST_LABEL, &base_zkvm_source_digest)
             .build_arg("BASE_IMAGE", &base_image)
             .build_arg_from_env("RUSTFLAGS");
 
@@ -163,9 +204,14 @@ fn build_server_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<(), Error> {
     let mut cmd = DockerBuildCmd::new()
         .file(docker_zkvm_dir.join("Dockerfile.server"))
         .tag(&server_zkvm_image)
+        .label(SOURCE_DIGEST_LABEL, source_digest)
         .build_arg("BASE_ZKVM_IMAGE", &base_zkvm_image)
         .build_arg_from_env("RUSTFLAGS");
 
+    for (key, image) in runtime_image_build_args()? {
+        cmd = cmd.build_arg(key, image);
+    }
+
     if gpu {
         cmd = cmd.build_arg("CUDA", "1");
         cmd = apply_cuda_build_args(cmd, zkvm_kind, &cuda_archs)?;
@@ -176,14 +222,53 @@ fn build_server_image(zkvm_kind: zkVMKind, gpu: bool) -> Result<(), Error> {
     Ok(())
 }
 
+/// Ensures the `ere-server` image of an older `version` exists, pulling it from the image registry
+/// if needed.
+///
+/// Unlike [`build_server_image`], it can't be built since the workspace is of the current version.
+fn pull_versioned_server_image(version: &str, zkvm_kind: zkVMKind, gpu: bool) -> Result<(), Error> {
+    let image = versioned_server_zkvm_image(version, zkvm_kind, gpu)?;
+
+    if docker_image_exists(&image)? {
+        info!("Image {image} exists, skip pulling");
+        return Ok(());
+    }
+
+    if image_registry()?.is_some()
+        && docker_pull_image(&image).is_ok()
+        && docker_image_exists(&image)?
+    {
+        info!("Image {image} pulled");
+        return Ok(());
+    }
+
+    Err(Error::ImageNotFound { image })
+}
+
 #[derive(Debug)]
 struct ServerContainer {
     id: String,
     client: zkVMClient,
+    /// Whether to keep the container running on drop, see [`DockerizedzkVMConfig::persistent`].
+    persistent: bool,
+    /// Directory bind-mounted into the container to write large stdin to, see
+    /// [`DockerizedzkVMConfig::input_file_threshold`].
+    _input_dir: Option<TempDir>,
+    /// Name of the Moongate container of the SP1 GPU prover, see [`Self::start_moongate`].
+    moongate: Option<String>,
 }
 
 impl Drop for ServerContainer {
     fn drop(&mut self) {
+        if self.persistent {
+            return;
+        }
+        // Removed first since it lives in the network namespace of the server container.
+        if let Some(moongate) = &self.moongate
+            && let Err(err) = remove_docker_container(moongate)
+        {
+            error!("Failed to remove Moongate container: {err}");
+        }
         if let Err(err) = remove_docker_container(&self.id) {
             error!("Failed to remove docker container: {err}");
         }
@@ -194,50 +279,239 @@ impl ServerContainer {
     /// Offset of port used for `ere-server`.
     const PORT_OFFSET: u16 = 4174;
 
-    fn new(zkvm_kind: zkVMKind, elf: &Elf, resource: &ProverResource) -> Result<Self, Error> {
-        let name = format!("ere-server-{zkvm_kind}");
-        remove_docker_container(&name)?;
+    /// Label of the port the container listens on.
+    const PORT_LABEL: &str = "ere.port";
+
+    /// Path the self-test input is mounted at in the container.
+    const SELFTEST_INPUT_PATH: &str = "/selftest/input";
+
+    /// Path the OpenVM key cache directory is mounted at in the container.
+    const OPENVM_KEY_CACHE_DIR: &str = "/openvm-key-cache";
 
-        let port = Self::PORT_OFFSET + zkvm_kind as u16;
+    /// Endpoint of the Moongate server in the network namespace of the SP1 server container.
+    const MOONGATE_ENDPOINT: &str = "http://127.0.0.1:3000";
 
+    /// Timeout to wait for the server container to run before starting the Moongate container, and
+    /// for Docker to publish its port.
+    const START_TIMEOUT: Duration = Duration::from_secs(30);
+
+    fn new(
+        zkvm_kind: zkVMKind,
+        elf: &Elf,
+        resource: &ProverResource,
+        config: &DockerizedzkVMConfig,
+    ) -> Result<Self, Error> {
         let gpu = resource.is_gpu();
-        let mut cmd = DockerRunCmd::new(server_zkvm_image(zkvm_kind, gpu))
+        let version = config.version.as_deref();
+
+        // Containers of other versions run side by side, so they are named by version and
+        // published on a host port assigned by Docker, or listen on a free port of the host with
+        // the host network, which assumes a local Docker host.
+        let name = Self::name(zkvm_kind, version);
+        let image = match version {
+            Some(version) => versioned_server_zkvm_image(version, zkvm_kind, gpu)?,
+            None => server_zkvm_image(zkvm_kind, gpu)?,
+        };
+        let port = match (version, &config.network) {
+            (Some(_), ContainerNetwork::Host) => free_port()?,
+            _ => Self::PORT_OFFSET + zkvm_kind as u16,
+        };
+        let host_port = Self::fixed_host_port(&config.network, version, port);
+        // The SP1 GPU prover is a Moongate container, which the server can't start without Docker
+        // access, so it's started here unless an endpoint of a running one is given.
+        let moongate =
+            (zkvm_kind == zkVMKind::SP1 && gpu && config()?.var("ERE_SP1_GPU_ENDPOINT").is_none())
+                .then(|| Self::moongate_name(&name));
+        if let Some(moongate) = &moongate {
+            remove_docker_container(moongate)?;
+        }
+        remove_docker_container(&name)?;
+
+        let mut cmd = DockerRunCmd::new(image)?
+            .forward_logs(zkvm_kind)
+            .label(Self::PORT_LABEL, port.to_string())
             .inherit_env("RUST_LOG")
             .inherit_env("RUST_BACKTRACE")
             .inherit_env("NO_COLOR")
-            .publish(port.to_string(), port.to_string())
+            .inherit_env("ERE_VERIFY_THREADS")
+            .inherit_env("ERE_MAX_PROVE_JOBS")
+            .inherit_env("ERE_WARMUP")
             .name(&name);
 
-        let host = if let Some(network) = docker_network() {
-            cmd = cmd.network(network);
-            name.as_str()
-        } else {
-            "127.0.0.1"
+        for (key, value) in Self::labels(zkvm_kind, elf, resource, version) {
+            cmd = cmd.label(key, value);
+        }
+
+        // Docker assigns the host port if the host part has none.
+        let host_port_arg = host_port.map(|port| port.to_string()).unwrap_or_default();
+        cmd = match &config.network {
+            ContainerNetwork::Default => {
+                let cmd = cmd.publish(&host_port_arg, port.to_string());
+                match docker_network()? {
+                    Some(network) => cmd.network(network),
+                    None => cmd,
+                }
+            }
+            ContainerNetwork::Bridge { host_ip, .. } => {
+                let host = match host_ip {
+                    Some(IpAddr::V6(host_ip)) => format!("[{host_ip}]:{host_port_arg}"),
+                    Some(host_ip) => format!("{host_ip}:{host_port_arg}"),
+                    None => host_port_arg,
+                };
+                cmd.publish(host, port.to_string())
+            }
+            ContainerNetwork::Host => cmd.network("host"),
+            ContainerNetwork::Custom(network) => cmd.network(network),
         };
 
+        // Metrics are published on the same interfaces as the server port.
+        if let Some(metrics_port) = config.metrics_port {
+            cmd = match &config.network {
+                ContainerNetwork::Default | ContainerNetwork::Bridge { host_ip: None, .. } => {
+                    cmd.publish(metrics_port.to_string(), metrics_port.to_string())
+                }
+                ContainerNetwork::Bridge {
+                    host_ip: Some(host_ip),
+                    ..
+                } => cmd.publish(
+                    SocketAddr::new(*host_ip, metrics_port).to_string(),
+                    metrics_port.to_string(),
+                ),
+                ContainerNetwork::Host | ContainerNetwork::Custom(_) => cmd,
+            };
+        }
+
         // zkVM specific options
         cmd = match zkvm_kind {
+            zkVMKind::OpenVM => cmd
+                .inherit_env("ERE_OPENVM_APP_CONFIG")
+                .inherit_env("ERE_OPENVM_SEGMENT_MAX_TRACE_HEIGHT")
+                .inherit_env("ERE_OPENVM_SEGMENT_MAX_CELLS")
+                .inherit_env("ERE_OPENVM_SEGMENT_MAX_INTERACTIONS"),
             zkVMKind::Risc0 => cmd
                 .inherit_env("ERE_RISC0_SEGMENT_PO2")
-                .inherit_env("ERE_RISC0_KECCAK_PO2"),
-            // SP1 uses shared memory to exchange data between processes, here
-            // we set 32G for safety.
-            zkVMKind::SP1 => cmd.option("shm-size", "32G"),
-            // ZisK uses shared memory to exchange data between processes, it
-            // requires at least 16G shared memory, here we set 32G for safety.
+                .inherit_env("ERE_RISC0_KECCAK_PO2")
+                .inherit_env("ERE_RISC0_MAX_SEGMENTS")
+                .inherit_env("ERE_RISC0_SESSION_LIMIT")
+                .inherit_env("ERE_RISC0_BONSAI_POLL_INTERVAL_SECS"),
+            zkVMKind::SP1 => cmd
+                .inherit_env("ERE_SP1_CLIENT_REUSE_POLICY")
+                .inherit_env("ERE_SP1_GPU_ENDPOINT")
+                .inherit_env("ERE_SP1_SHARD_SIZE")
+                .inherit_env("ERE_SP1_SHARD_BATCH_SIZE")
+                .inherit_env("ERE_SP1_RECONSTRUCT_COMMITMENTS")
+                .inherit_env("ERE_SP1_REPORT_EXECUTION"),
             zkVMKind::Zisk => cmd
-                .option("shm-size", "32G")
-                .option("ulimit", "memlock=-1:-1")
                 .inherit_env("ERE_ZISK_SETUP_ON_INIT")
                 .inherit_env("ERE_ZISK_UNLOCK_MAPPED_MEMORY")
                 .inherit_env("ERE_ZISK_MINIMAL_MEMORY")
                 .inherit_env("ERE_ZISK_MAX_STREAMS")
                 .inherit_env("ERE_ZISK_NUMBER_THREADS_WITNESS")
                 .inherit_env("ERE_ZISK_MAX_WITNESS_STORED")
-                .inherit_env("ERE_ZISK_CLUSTER_PROVE_TIMEOUT_SECS"),
+                .inherit_env("ERE_ZISK_CLUSTER_PROVE_TIMEOUT_SECS")
+                .inherit_env("ERE_ZISK_GPU_SM_ARCH"),
             _ => cmd,
         };
 
+        if zkvm_kind == zkVMKind::Risc0
+            && let Some(addr) = config()?.var("ERE_RISC0_R0VM_ADDR")
+        {
+            cmd = match Self::r0vm_addr_in_container(&addr, &config.network) {
+                Some(addr) => cmd
+                    .option("add-host", "host.docker.internal:host-gateway")
+                    .env("ERE_RISC0_R0VM_ADDR", addr),
+                None => cmd.env("ERE_RISC0_R0VM_ADDR", addr),
+            };
+        }
+
+        cmd = config.container.apply(cmd, Some(zkvm_kind))?;
+
+        // The server only listens after warming up, so it's ready once healthy.
+        if config.warmup {
+            cmd = cmd.env("ERE_WARMUP", "true");
+        }
+
+        if let Some(auth_token) = &config.auth_token {
+            cmd = cmd.secret_env("ERE_AUTH_TOKEN", auth_token)?;
+        }
+
+        // The self-test input is a file on the host, which is bind-mounted into the container.
+        let tuning = config()?;
+        match (
+            tuning.var("ERE_SELFTEST_INTERVAL_SECS"),
+            tuning.var("ERE_SELFTEST_INPUT_PATH"),
+        ) {
+            (Some(_), Some(_)) if remote_docker_host()?.is_some() => {
+                warn!("Docker runs on a remote host, the self-test input can't be mounted");
+            }
+            (Some(interval), Some(input_path)) => {
+                let input_path = fs::canonicalize(&input_path).map_err(|err| {
+                    CommonError::io(
+                        format!("Failed to resolve self-test input {input_path}"),
+                        err,
+                    )
+                })?;
+                cmd = cmd
+                    .env("ERE_SELFTEST_INTERVAL_SECS", interval)
+                    .volume(input_path, Self::SELFTEST_INPUT_PATH)
+                    .env("ERE_SELFTEST_INPUT_PATH", Self::SELFTEST_INPUT_PATH);
+            }
+            (Some(_), None) => {
+                warn!("ERE_SELFTEST_INTERVAL_SECS is set without ERE_SELFTEST_INPUT_PATH, skipped");
+            }
+            _ => {}
+        }
+
+        // The OpenVM key cache is a directory on the host, which is bind-mounted into the
+        // container to be shared with the host and other containers.
+        if zkvm_kind == zkVMKind::OpenVM
+            && let Some(cache_dir) = tuning.var("ERE_OPENVM_KEY_CACHE_DIR")
+        {
+            if remote_docker_host()?.is_some() {
+                warn!("Docker runs on a remote host, the OpenVM key cache can't be mounted");
+            } else {
+                fs::create_dir_all(&cache_dir)
+                    .map_err(|err| CommonError::create_dir("OpenVM key cache", &cache_dir, err))?;
+                let cache_dir = fs::canonicalize(&cache_dir).map_err(|err| {
+                    CommonError::io(
+                        format!("Failed to resolve OpenVM key cache {cache_dir}"),
+                        err,
+                    )
+                })?;
+                cmd = cmd
+                    .volume(cache_dir, Self::OPENVM_KEY_CACHE_DIR)
+                    .env("ERE_OPENVM_KEY_CACHE_DIR", Self::OPENVM_KEY_CACHE_DIR);
+            }
+        }
+
+        if let Some(job_volume) = &config.job_volume {
+            cmd = cmd.volume(job_volume, "/jobs").env("ERE_JOB_DIR", "/jobs");
+        }
+
+        // Bind mounts refer to paths on the Docker host.
+        let input_dir = match (config.input_file_threshold, remote_docker_host()?) {
+            (Some(_), Some(host)) => {
+                warn!("Docker runs on remote host {host}, inputs are sent over HTTP instead");
+                None
+            }
+            (Some(_), None) => {
+                let input_dir = TempDir::new().map_err(CommonError::tempdir)?;
+                // The server removes the inputs it reads, which requires write permission to the
+                // directory created by this process for users other than root and the host one.
+                if matches!(config.container.user()?, ContainerUser::Custom(_)) {
+                    fs::set_permissions(input_dir.path(), Permissions::from_mode(0o777))
+                        .map_err(|err| CommonError::io("Failed to share input directory", err))?;
+                }
+                Some(input_dir)
+            }
+            (None, _) => None,
+        };
+        if let Some(input_dir) = &input_dir {
+            cmd = cmd
+                .volume(input_dir.path(), "/inputs")
+                .env("ERE_INPUT_DIR", "/inputs");
+        }
+
         // zkVM specific options when using GPU
         if gpu {
             cmd = match zkvm_kind {
@@ -249,22 +523,466 @@ impl ServerContainer {
             }
         }
 
+        if moongate.is_some() {
+            cmd = cmd.env("ERE_SP1_GPU_ENDPOINT", Self::MOONGATE_ENDPOINT);
+        }
+
+        // Images of other versions might not decompress the ELF, so it's sent as-is to them.
+        let elf = match version {
+            Some(_) => elf.to_vec(),
+            None => elf
+                .compress()
+                .map_err(|err| CommonError::io("Failed to compress ELF", err))?,
+        };
+        let port_arg = port.to_string();
+        let metrics_port_arg = config.metrics_port.map(|port| port.to_string());
+        let resource_args = resource.to_args();
         let (_, container_id) = cmd.spawn(
             iter::empty()
-                .chain(["--port", &port.to_string()])
-                .chain(resource.to_args()),
-            elf,
+                .chain(["--port", &port_arg])
+                .chain(
+                    metrics_port_arg
+                        .iter()
+                        .flat_map(|port| ["--metrics-port", port.as_str()]),
+                )
+                .chain(resource_args.iter().map(String::as_str)),
+            &elf,
         )?;
 
-        let endpoint = Url::parse(&format!("http://{host}:{port}"))?;
+        if let Some(moongate) = &moongate
+            && let Err(err) = Self::start_moongate(moongate, &container_id, &config.network)
+        {
+            let _ = remove_docker_container(moongate);
+            let _ = remove_docker_container(&container_id);
+            return Err(err);
+        }
+
+        let host_port = match host_port {
+            Some(host_port) => host_port,
+            None => Self::wait_published_port(&container_id, &config.network, port)?,
+        };
+        let endpoint = Self::endpoint(&config.network, &name, port, host_port)?;
         let http_client = Client::new();
-        block_on(wait_until_healthy(&endpoint, http_client.clone()))?;
+        let ready_timeout = config.ready_timeout.unwrap_or(DEFAULT_READY_TIMEOUT);
+        block_on(wait_until_ready(
+            &endpoint,
+            http_client.clone(),
+            &container_id,
+            ready_timeout,
+        ))?;
+
+        let mut client = Self::client(endpoint, http_client, config)?;
+        if let (Some(input_dir), Some(threshold)) = (&input_dir, config.input_file_threshold) {
+            client = client.with_input_dir(input_dir.path(), threshold);
+        }
 
         Ok(ServerContainer {
             id: container_id,
-            client: zkVMClient::new(endpoint, http_client, vec![])?,
+            client,
+            persistent: config.persistent,
+            _input_dir: input_dir,
+            moongate,
         })
     }
+
+    /// Starts the Moongate container `name` of the SP1 GPU prover in the network namespace of the
+    /// server container `server_id`, where the server reaches it at [`Self::MOONGATE_ENDPOINT`].
+    ///
+    /// Its image, GPUs and memory limit are set by `ERE_SP1_GPU_IMAGE`, `ERE_SP1_GPU_DEVICE` and
+    /// `ERE_SP1_GPU_MEMORY_LIMIT` on the host. `ERE_SP1_GPU_PORT` doesn't apply since the port
+    /// isn't published.
+    fn start_moongate(
+        name: &str,
+        server_id: &str,
+        network: &ContainerNetwork,
+    ) -> Result<(), Error> {
+        // The network namespace of a container exists only while it runs.
+        let start = Instant::now();
+        while !docker_container_running(server_id)? {
+            if start.elapsed() > Self::START_TIMEOUT {
+                return Err(Error::Timeout {
+                    timeout: Self::START_TIMEOUT,
+                });
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        let config = config()?;
+        let image = config.var("ERE_SP1_GPU_IMAGE").unwrap_or_else(|| {
+            format!(
+                "public.ecr.aws/succinct-labs/moongate:v{}",
+                zkVMKind::SP1.sdk_version()
+            )
+        });
+        let network = match network {
+            ContainerNetwork::Host => "host".to_string(),
+            _ => format!("container:{server_id}"),
+        };
+        let mut cmd = DockerRunCmd::new(image)?.name(name).network(network);
+        cmd = match config.var("ERE_SP1_GPU_DEVICE") {
+            // Quoted for docker to take the comma-separated devices as one value.
+            Some(device) => cmd.option("gpus", format!("\"device={device}\"")),
+            None => cmd.gpus(),
+        };
+        if let Some(memory_limit) = config.var("ERE_SP1_GPU_MEMORY_LIMIT") {
+            cmd = cmd.option("memory", memory_limit);
+        }
+
+        info!("Starting Moongate container {name}...");
+        cmd.detach(iter::empty::<&str>())?;
+        Ok(())
+    }
+
+    fn moongate_name(name: &str) -> String {
+        format!("{name}-moongate")
+    }
+
+    /// Finds the running container of the same program and resource left by another process
+    /// (e.g. one that crashed or a persistent one), returns `None` if there is none.
+    fn find(
+        zkvm_kind: zkVMKind,
+        elf: &Elf,
+        resource: &ProverResource,
+        config: &DockerizedzkVMConfig,
+    ) -> Result<Option<Self>, Error> {
+        let version = config.version.as_deref();
+        let labels = Self::labels(zkvm_kind, elf, resource, version);
+        let labels = labels
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect::<Vec<_>>();
+        let Some((container_id, port)) = docker_find_running_container(&labels, Self::PORT_LABEL)?
+        else {
+            return Ok(None);
+        };
+        let Ok(port) = port.parse() else {
+            warn!("Container {container_id} has invalid port label {port:?}, skip reattaching");
+            return Ok(None);
+        };
+
+        let name = Self::name(zkvm_kind, version);
+        let host_port = match Self::fixed_host_port(&config.network, version, port) {
+            Some(host_port) => host_port,
+            None => docker_published_port(&container_id, port)?.unwrap_or(port),
+        };
+        let endpoint = Self::endpoint(&config.network, &name, port, host_port)?;
+        let moongate = Some(Self::moongate_name(&name))
+            .filter(|moongate| docker_container_running(moongate).unwrap_or(false));
+        // The directory bind-mounted into the container is gone with the process that created it,
+        // so inputs are sent over HTTP.
+        Ok(Some(ServerContainer {
+            id: container_id,
+            client: Self::client(endpoint, Client::new(), config)?,
+            persistent: config.persistent,
+            _input_dir: None,
+            moongate,
+        }))
+    }
+
+    /// Returns the client of the server at `endpoint`, sending the configured auth token if any.
+    fn client(
+        endpoint: Url,
+        http_client: Client,
+        config: &DockerizedzkVMConfig,
+    ) -> Result<zkVMClient, Error> {
+        let client = zkVMClient::new(endpoint, http_client, Vec::new())?;
+        if let Some(token) = &config.auth_token {
+            client.auth_token().set(token);
+        }
+        Ok(client)
+    }
+
+    /// Removes the container even if it's persistent, e.g. when it's unhealthy.
+    fn remove(mut self) {
+        self.persistent = false;
+    }
+
+    fn name(zkvm_kind: zkVMKind, version: Option<&str>) -> String {
+        match version {
+            Some(version) => format!("ere-server-{zkvm_kind}-{version}"),
+            None => format!("ere-server-{zkvm_kind}"),
+        }
+    }
+
+    /// Returns labels identifying the container of the program, to be found by [`Self::find`].
+    fn labels(
+        zkvm_kind: zkVMKind,
+        elf: &Elf,
+        resource: &ProverResource,
+        version: Option<&str>,
+    ) -> [(&'static str, String); 4] {
+        let elf_digest = Sha256::digest(elf)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        [
+            ("ere.zkvm", zkvm_kind.to_string()),
+            (
+                "ere.version",
+                version.unwrap_or(DOCKER_IMAGE_TAG).to_string(),
+            ),
+            ("ere.elf-sha256", elf_digest),
+            ("ere.resource", resource.kind().to_string()),
+        ]
+    }
+
+    /// Returns the host port to publish the server `port` on, `None` to have Docker assign one for
+    /// containers of other versions.
+    fn fixed_host_port(
+        network: &ContainerNetwork,
+        version: Option<&str>,
+        port: u16,
+    ) -> Option<u16> {
+        match (network, version) {
+            (
+                ContainerNetwork::Bridge {
+                    host_port: Some(host_port),
+                    ..
+                },
+                _,
+            ) => Some(*host_port),
+            (ContainerNetwork::Default | ContainerNetwork::Bridge { .. }, Some(_)) => None,
+            _ => Some(port),
+        }
+    }
+
+    /// Returns the `ERE_RISC0_R0VM_ADDR` with a loopback host replaced by `host.docker.internal`,
+    /// which the container reaches the host by, or `None` if `addr` is reachable as is.
+    ///
+    /// The `r0vm` server on the host then has to listen on the Docker bridge interface too, e.g.
+    /// on `0.0.0.0`, since the container connects from the bridge network.
+    fn r0vm_addr_in_container(addr: &str, network: &ContainerNetwork) -> Option<String> {
+        if matches!(network, ContainerNetwork::Host) {
+            return None;
+        }
+        let (host, port) = addr.rsplit_once(':')?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let is_loopback =
+            host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback());
+        is_loopback.then(|| format!("host.docker.internal:{port}"))
+    }
+
+    /// Waits until the server `port` of the container `container_id` is published, and returns
+    /// the host port Docker assigned to it, or `port` if `network` doesn't publish it.
+    fn wait_published_port(
+        container_id: &str,
+        network: &ContainerNetwork,
+        port: u16,
+    ) -> Result<u16, Error> {
+        if !matches!(
+            network,
+            ContainerNetwork::Default | ContainerNetwork::Bridge { .. }
+        ) {
+            return Ok(port);
+        }
+        // The port is assigned once the container starts.
+        let start = Instant::now();
+        loop {
+            if let Some(host_port) = docker_published_port(container_id, port)? {
+                return Ok(host_port);
+            }
+            if start.elapsed() > Self::START_TIMEOUT {
+                return Err(Error::Timeout {
+                    timeout: Self::START_TIMEOUT,
+                });
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Returns the endpoint to reach the server listening on `port` in the container `name`,
+    /// which is published on `host_port` of the Docker host.
+    fn endpoint(
+        network: &ContainerNetwork,
+        name: &str,
+        port: u16,
+        host_port: u16,
+    ) -> Result<Url, Error> {
+        // Ports are published on the Docker host, which might be remote.
+        let remote_docker_host = remote_docker_host()?;
+        let localhost = |port| match &remote_docker_host {
+            Some(host) => format!("{host}:{port}"),
+            None => SocketAddr::from(([127, 0, 0, 1], port)).to_string(),
+        };
+        let addr = match network {
+            ContainerNetwork::Default if docker_network()?.is_some() => format!("{name}:{port}"),
+            ContainerNetwork::Custom(_) => format!("{name}:{port}"),
+            ContainerNetwork::Bridge {
+                host_ip: Some(host_ip),
+                ..
+            } if !host_ip.is_unspecified() => SocketAddr::new(*host_ip, host_port).to_string(),
+            ContainerNetwork::Bridge { .. } | ContainerNetwork::Default => localhost(host_port),
+            ContainerNetwork::Host => localhost(port),
+        };
+        Ok(Url::parse(&format!("http://{addr}"))?)
+    }
+}
+
+/// User to run a container as.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ContainerUser {
+    /// User of the image, which is root.
+    #[default]
+    Image,
+    /// UID and GID of the user running this process, so files written into bind mounts (e.g.
+    /// build artifacts in the guest directory) are owned by the host user instead of root.
+    Host,
+    /// User passed to `--user` (e.g. `1000:1000` or `nobody`).
+    Custom(String),
+}
+
+/// Resource limits and security options of the `ere-server` or compiler container, each unset
+/// field falls back to the `[container]` section of [`Config`], then the zkVM default or the
+/// Docker default.
+///
+/// [`Config`]: crate::config::Config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContainerConfig {
+    /// Memory limit passed to `--memory` (e.g. `64g`), unbounded if `None`.
+    pub memory_limit: Option<String>,
+    /// Number of CPUs passed to `--cpus` (e.g. `8.5`), unbounded if `None`.
+    pub cpus: Option<f64>,
+    /// Size of `/dev/shm` passed to `--shm-size` (e.g. `64g`). Defaults to `32G` for SP1 and ZisK,
+    /// which exchange data between processes through shared memory.
+    pub shm_size: Option<String>,
+    /// Limits passed to `--ulimit` by name (e.g. `memlock` to `-1:-1`), which override the zkVM
+    /// defaults of the same name.
+    pub ulimits: BTreeMap<String, String>,
+    /// User to run the container as. A user other than the image one gets `/root` as `$HOME`,
+    /// where the images install the zkVM SDKs and make the directories writable by any user, and
+    /// the named Docker volumes of
+    /// [`DockerizedCompiler::with_cargo_cache`](crate::DockerizedCompiler::with_cargo_cache) are
+    /// made writable by any user before they are mounted.
+    pub user: ContainerUser,
+    /// Seccomp profile passed to `--security-opt seccomp=`, either a JSON file path or
+    /// `unconfined`, the Docker default profile if `None`.
+    pub seccomp_profile: Option<String>,
+    /// AppArmor profile loaded on the host passed to `--security-opt apparmor=`, the Docker
+    /// default profile if `None`.
+    pub apparmor_profile: Option<String>,
+    /// Capabilities passed to `--cap-drop` (e.g. `ALL` or `NET_RAW`).
+    pub cap_drop: Vec<String>,
+}
+
+impl ContainerConfig {
+    /// Returns the user to run the container as, which is the one of [`Config`] if
+    /// [`ContainerUser::Image`] is set.
+    ///
+    /// [`Config`]: crate::config::Config
+    pub(crate) fn user(&self) -> Result<ContainerUser, CommonError> {
+        if self.user != ContainerUser::Image {
+            return Ok(self.user.clone());
+        }
+        Ok(match config()?.container.user.as_deref() {
+            None => ContainerUser::Image,
+            Some("host") => ContainerUser::Host,
+            Some(user) => ContainerUser::Custom(user.to_string()),
+        })
+    }
+
+    /// Applies the options to the run command, with the limits on top of the defaults of
+    /// [`Config`] and the `ere-server` of `zkvm_kind` if given.
+    ///
+    /// [`Config`]: crate::config::Config
+    pub(crate) fn apply(
+        &self,
+        mut cmd: DockerRunCmd,
+        zkvm_kind: Option<zkVMKind>,
+    ) -> Result<DockerRunCmd, CommonError> {
+        let defaults = &config()?.container;
+        // SP1 and ZisK use shared memory to exchange data between processes, ZisK requires at
+        // least 16G shared memory, here we set 32G for safety.
+        let shm_size = match zkvm_kind {
+            Some(zkVMKind::SP1 | zkVMKind::Zisk) => Some("32G"),
+            _ => None,
+        };
+        let mut ulimits = match zkvm_kind {
+            Some(zkVMKind::Zisk) => BTreeMap::from([("memlock".to_string(), "-1:-1".to_string())]),
+            _ => BTreeMap::new(),
+        };
+        ulimits.extend(defaults.ulimits.clone());
+        ulimits.extend(self.ulimits.clone());
+
+        if let Some(memory_limit) = self
+            .memory_limit
+            .as_ref()
+            .or(defaults.memory_limit.as_ref())
+        {
+            cmd = cmd.option("memory", memory_limit);
+        }
+        if let Some(cpus) = self
+            .cpus
+            .map(|cpus| cpus.to_string())
+            .or_else(|| defaults.cpus.clone())
+        {
+            cmd = cmd.option("cpus", cpus);
+        }
+        if let Some(shm_size) = self
+            .shm_size
+            .as_deref()
+            .or(defaults.shm_size.as_deref())
+            .or(shm_size)
+        {
+            cmd = cmd.option("shm-size", shm_size);
+        }
+        for (name, value) in ulimits {
+            cmd = cmd.option("ulimit", format!("{name}={value}"));
+        }
+        // Docker sets `$HOME` to `/` for a UID without a passwd entry in the image.
+        cmd = match self.user()? {
+            ContainerUser::Image => cmd,
+            ContainerUser::Host => cmd.option("user", host_user()?).env("HOME", "/root"),
+            ContainerUser::Custom(user) => cmd.option("user", user).env("HOME", "/root"),
+        };
+        if let Some(profile) = self
+            .seccomp_profile
+            .as_ref()
+            .or(defaults.seccomp_profile.as_ref())
+        {
+            cmd = cmd.option("security-opt", format!("seccomp={profile}"));
+        }
+        if let Some(profile) = self
+            .apparmor_profile
+            .as_ref()
+            .or(defaults.apparmor_profile.as_ref())
+        {
+            cmd = cmd.option("security-opt", format!("apparmor={profile}"));
+        }
+        let cap_drop = if self.cap_drop.is_empty() {
+            &defaults.cap_drop
+        } else {
+            &self.cap_drop
+        };
+        for capability in cap_drop {
+            cmd = cmd.option("cap-drop", capability);
+        }
+        Ok(cmd)
+    }
+}
+
+/// Network of the `ere-server` container.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ContainerNetwork {
+    /// Publishes the server port on all interfaces of the host, and joins the network
+    /// `ERE_DOCKER_NETWORK` if set.
+    #[default]
+    Default,
+    /// Default bridge network with the server port published on `host_ip` (all interfaces if
+    /// `None`) and `host_port` (same as the server port if `None`).
+    ///
+    /// Use `127.0.0.1` to keep the server reachable only from the host, or `0.0.0.0` to make it
+    /// reachable from other hosts.
+    Bridge {
+        host_ip: Option<IpAddr>,
+        host_port: Option<u16>,
+    },
+    /// Host network, the server listens on the host directly.
+    Host,
+    /// Custom network joined without publishing any port, the server is reached by container name
+    /// so this process must run in the same network.
+    ///
+    /// For full isolation, create the network with `docker network create --internal`.
+    Custom(String),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -272,8 +990,80 @@ pub struct DockerizedzkVMConfig {
     pub execute_timeout: Option<Duration>,
     pub prove_timeout: Option<Duration>,
     pub verify_timeout: Option<Duration>,
+    /// Version of the `ere-server` image to run instead of the one of this crate (e.g. `0.5.0`),
+    /// to compare SDK upgrades for the same program side by side.
+    ///
+    /// The image is not built but must exist locally or in the image registry, and its server API
+    /// and report encoding must be compatible with this crate.
+    pub version: Option<String>,
+    /// Network of the `ere-server` container.
+    pub network: ContainerNetwork,
+    /// Serves `/metrics` of the `ere-server` container on this port as well, published on the
+    /// host unless [`ContainerNetwork::Host`] or [`ContainerNetwork::Custom`] is used, for a
+    /// Prometheus to scrape without reaching the API. `/metrics` is served on the server port
+    /// regardless.
+    pub metrics_port: Option<u16>,
+    /// Token the `ere-server` container requires as `Authorization: Bearer {token}` of API
+    /// requests, to keep others reaching its port (e.g. published on a LAN) from using it. It's
+    /// passed to the container as `ERE_AUTH_TOKEN` by an env file rather than the command line, so
+    /// it's visible in the container config (e.g. by `docker inspect`) and the environment of the
+    /// server process, but not in the `docker` arguments, logs or errors.
+    pub auth_token: Option<String>,
+    /// Host directory (absolute path on the Docker host) or named Docker volume to persist the
+    /// prove jobs of the `ere-server` container in. Jobs queued or running when the container
+    /// exits (e.g. OOM killed or rescheduled) are proved again by the container that replaces
+    /// it, so they can still be collected by [`DockerizedzkVM::resume_prove`].
+    pub job_volume: Option<PathBuf>,
+    /// Writes the stdin of inputs of at least this many bytes to a file in a temporary directory
+    /// bind-mounted into the `ere-server` container, for the server to read it directly instead
+    /// of receiving it over HTTP, which saves the copies of very large inputs (e.g. witnesses of
+    /// hundreds of MB). Disabled when not set, or when Docker runs on a remote host.
+    ///
+    /// Stdin set via [`Input::with_stdin_file`] is copied there from its file without being read
+    /// into memory.
+    pub input_file_threshold: Option<usize>,
+    /// Resource limits and security options of the `ere-server` container.
+    pub container: ContainerConfig,
+    /// Keeps the `ere-server` container running when dropped, and reuses the running container
+    /// of the same program, resource and version in [`DockerizedzkVM::new`], to skip the setup
+    /// (e.g. proving keys) repeated by a new container.
+    ///
+    /// The container is still replaced when unhealthy or timed out, or by an instance of another
+    /// program of the same zkVM. Remove it by `docker rm -f ere-server-{zkvm}` when done.
+    pub persistent: bool,
+    /// Sets up proving (e.g. loads proving keys and initializes GPUs) in the `ere-server`
+    /// container before [`DockerizedzkVM::new`] returns, instead of lazily in the first prove,
+    /// same as setting `ERE_WARMUP`.
+    pub warmup: bool,
+    /// Timeout to wait for the `ere-server` container to be ready, including the warmup if
+    /// enabled. Defaults to 5 minutes.
+    pub ready_timeout: Option<Duration>,
+    /// Recreates the `ere-server` container and retries the request once when the container exits
+    /// during it (e.g. crashed in CUDA), instead of returning [`Error::ContainerExited`]. Containers
+    /// OOM killed are not retried, since they would run out of memory again. The next request
+    /// recreates the container regardless.
+    pub retry_on_crash: bool,
 }
 
+/// Default of [`DockerizedzkVMConfig::ready_timeout`].
+const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Prove job submitted by [`DockerizedzkVM::prove_detached`], serializable for another process
+/// to collect it after [`DockerizedzkVM::reattach`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobHandle {
+    /// Id of the `ere-server` container the job is submitted to.
+    pub container_id: String,
+    pub job_id: u64,
+    pub proof_kind: ProofKind,
+}
+
+/// zkVM running in an `ere-server` container.
+///
+/// Requests can be sent concurrently over one container, e.g. to execute the next input while
+/// the previous one proves. Proves run one at a time in FIFO order, and up to
+/// `ERE_MAX_PROVE_JOBS` (default 4) can be queued or running, further ones fail. Note that
+/// [`DockerizedzkVMConfig::prove_timeout`] includes the time queued behind other proves.
 #[derive(Debug)]
 pub struct DockerizedzkVM {
     zkvm_kind: zkVMKind,
@@ -281,6 +1071,7 @@ pub struct DockerizedzkVM {
     resource: ProverResource,
     config: DockerizedzkVMConfig,
     program_vk: EncodedProgramVk,
+    protocol_version: u32,
     container: RwLock<Option<ServerContainer>>,
 }
 
@@ -291,9 +1082,27 @@ impl DockerizedzkVM {
         resource: ProverResource,
         config: DockerizedzkVMConfig,
     ) -> Result<Self, Error> {
-        build_server_image(zkvm_kind, resource.is_gpu())?;
+        match &config.version {
+            Some(version) => pull_versioned_server_image(version, zkvm_kind, resource.is_gpu())?,
+            None => build_server_image(zkvm_kind, resource.is_gpu())?,
+        }
 
-        let container = ServerContainer::new(zkvm_kind, &elf, &resource)?;
+        let running = if config.persistent {
+            ServerContainer::find(zkvm_kind, &elf, &resource, &config)?
+                .filter(|container| block_on(container.client.is_healthy()))
+        } else {
+            None
+        };
+        let container = match running {
+            Some(container) => {
+                info!("Reusing persistent container {}", container.id);
+                container
+            }
+            None => ServerContainer::new(zkvm_kind, &elf, &resource, &config)?,
+        };
+        let info = block_on(container.client.handshake())?;
+        check_server_compatible(&info, zkvm_kind, config.version.as_deref())?;
+        let protocol_version = info.protocol_version;
         let program_vk = block_on(container.client.program_vk())?;
 
         Ok(Self {
@@ -302,10 +1111,56 @@ impl DockerizedzkVM {
             resource,
             config,
             program_vk,
+            protocol_version,
             container: RwLock::new(Some(container)),
         })
     }
 
+    /// Same as [`DockerizedzkVM::new`] but with the resource of [`Config`], which is set by
+    /// `ERE_PROVER_RESOURCE` or the `[resource]` section of the configuration file.
+    ///
+    /// [`Config`]: crate::config::Config
+    pub fn from_config(
+        zkvm_kind: zkVMKind,
+        elf: Elf,
+        config: DockerizedzkVMConfig,
+    ) -> Result<Self, Error> {
+        let resource = crate::util::env::config()?.resource.clone();
+        Self::new(zkvm_kind, elf, resource, config)
+    }
+
+    /// Reattaches to the running `ere-server` container of the same program left by another
+    /// process (e.g. one that crashed during prove), instead of replacing it as
+    /// [`DockerizedzkVM::new`] does, so the in-flight prove can be collected by
+    /// [`DockerizedzkVM::resume_prove`] instead of proving again.
+    ///
+    /// Returns `None` if there is no such container.
+    pub fn reattach(
+        zkvm_kind: zkVMKind,
+        elf: Elf,
+        resource: ProverResource,
+        config: DockerizedzkVMConfig,
+    ) -> Result<Option<Self>, Error> {
+        let Some(container) = ServerContainer::find(zkvm_kind, &elf, &resource, &config)? else {
+            return Ok(None);
+        };
+        info!("Reattached to container {}", container.id);
+        let info = block_on(container.client.handshake())?;
+        check_server_compatible(&info, zkvm_kind, config.version.as_deref())?;
+        let protocol_version = info.protocol_version;
+        let program_vk = block_on(container.client.program_vk())?;
+
+        Ok(Some(Self {
+            zkvm_kind,
+            elf,
+            resource,
+            config,
+            program_vk,
+            protocol_version,
+            container: RwLock::new(Some(container)),
+        }))
+    }
+
     pub fn zkvm_kind(&self) -> zkVMKind {
         self.zkvm_kind
     }
@@ -314,10 +1169,17 @@ impl DockerizedzkVM {
         self.zkvm_kind.name()
     }
 
+    /// Returns the zkVM SDK version of this crate, which doesn't reflect
+    /// [`DockerizedzkVMConfig::version`] if set.
     pub fn sdk_version(&self) -> &'static str {
         self.zkvm_kind.sdk_version()
     }
 
+    /// Returns the version of the `ere-server` image in use.
+    pub fn version(&self) -> &str {
+        self.config.version.as_deref().unwrap_or(DOCKER_IMAGE_TAG)
+    }
+
     pub fn elf(&self) -> &Elf {
         &self.elf
     }
@@ -330,10 +1192,39 @@ impl DockerizedzkVM {
         &self.program_vk
     }
 
+    /// Returns the protocol version negotiated with the `ere-server`, which is lower than
+    /// [`PROTOCOL_VERSION`](crate::PROTOCOL_VERSION) for images of older versions (see
+    /// [`DockerizedzkVMConfig::version`]).
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    /// Returns `true` if the `ere-server` container is running and reports healthy, for liveness
+    /// probing. It's unhealthy if a prove has been running longer than `ERE_PROVE_TIMEOUT_MS` or
+    /// the last self-test failed.
+    pub fn health(&self) -> bool {
+        block_on(self.health_async())
+    }
+
+    pub async fn health_async(&self) -> bool {
+        match &*self.container.read().await {
+            Some(container) => container.client.is_healthy().await,
+            None => false,
+        }
+    }
+
     pub fn execute(&self, input: &Input) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
         block_on(self.execute_async(input.clone()))
     }
 
+    pub fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
+        block_on(self.execute_with_options_async(input.clone(), *options))
+    }
+
     pub fn prove(
         &self,
         input: &Input,
@@ -341,6 +1232,37 @@ impl DockerizedzkVM {
         block_on(self.prove_async(input.clone()))
     }
 
+    /// Collects the result of the prove of `input` already requested, see
+    /// [`DockerizedzkVM::reattach`].
+    pub fn resume_prove(
+        &self,
+        input: &Input,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        block_on(self.resume_prove_async(input))
+    }
+
+    /// Submits a prove job without waiting for it, and returns its handle to be polled by
+    /// [`DockerizedzkVM::detached_job_status`] and collected by
+    /// [`DockerizedzkVM::collect_detached`], possibly by another process after
+    /// [`DockerizedzkVM::reattach`].
+    ///
+    /// Requires [`DockerizedzkVMConfig::persistent`] for the container to outlive this instance.
+    pub fn prove_detached(&self, input: &Input, kind: ProofKind) -> anyhow::Result<JobHandle> {
+        block_on(self.prove_detached_async(input.clone(), kind))
+    }
+
+    pub fn detached_job_status(&self, handle: &JobHandle) -> anyhow::Result<JobStatus> {
+        block_on(self.detached_job_status_async(handle))
+    }
+
+    /// Waits for the prove job of `handle` and returns its result.
+    pub fn collect_detached(
+        &self,
+        handle: &JobHandle,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        block_on(self.collect_detached_async(handle))
+    }
+
     pub fn verify(&self, proof: &EncodedProof) -> anyhow::Result<PublicValues> {
         block_on(self.verify_async(proof.clone()))
     }
@@ -348,11 +1270,20 @@ impl DockerizedzkVM {
     pub async fn execute_async(
         &self,
         input: Input,
+    ) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
+        self.execute_with_options_async(input, ExecuteOptions::default())
+            .await
+    }
+
+    pub async fn execute_with_options_async(
+        &self,
+        input: Input,
+        options: ExecuteOptions,
     ) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
         self.with_retry(
             |client| {
                 let input = input.clone();
-                Box::pin(async move { client.execute(input).await })
+                Box::pin(async move { client.execute_with_options(input, options).await })
             },
             self.config.execute_timeout,
         )
@@ -373,6 +1304,116 @@ impl DockerizedzkVM {
         .await
     }
 
+    pub async fn resume_prove_async(
+        &self,
+        input: &Input,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        // A recreated container has the prove to resume only if jobs are persisted.
+        if self.config.job_volume.is_some() {
+            let input_commitment = input.commitment();
+            return self
+                .with_retry(
+                    |client| Box::pin(async move { client.resume_prove(input_commitment).await }),
+                    self.config.prove_timeout,
+                )
+                .await;
+        }
+        let guard = self.container.read().await;
+        let container = guard.as_ref().context("Container is not running")?;
+        let resume = container.client.resume_prove(input.commitment());
+        Ok(match self.config.prove_timeout {
+            Some(duration) => timeout(duration, resume)
+                .await
+                .map_err(|_| Error::Timeout { timeout: duration })??,
+            None => resume.await?,
+        })
+    }
+
+    pub async fn prove_detached_async(
+        &self,
+        input: Input,
+        kind: ProofKind,
+    ) -> anyhow::Result<JobHandle> {
+        self.ensure_jobs_supported("detached prove jobs")?;
+        anyhow::ensure!(
+            self.config.persistent,
+            "Detached prove jobs require a persistent container to outlive this instance"
+        );
+        // The server proves only the default kind.
+        if kind != ProofKind::Compressed {
+            return Err(Error::UnsupportedProofKind { kind }.into());
+        }
+        let container = self.container().await?;
+        let job_id = container
+            .client
+            .submit_prove(input)
+            .await
+            .map_err(Error::from)?;
+        Ok(JobHandle {
+            container_id: container.id.clone(),
+            job_id,
+            proof_kind: kind,
+        })
+    }
+
+    pub async fn detached_job_status_async(&self, handle: &JobHandle) -> anyhow::Result<JobStatus> {
+        let container = self.detached_job_container(handle).await?;
+        Ok(container
+            .client
+            .job_status(handle.job_id)
+            .await
+            .map_err(Error::from)?)
+    }
+
+    pub async fn collect_detached_async(
+        &self,
+        handle: &JobHandle,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        let container = self.detached_job_container(handle).await?;
+        let resume = container.client.resume_prove_job(handle.job_id);
+        Ok(match self.config.prove_timeout {
+            Some(duration) => timeout(duration, resume)
+                .await
+                .map_err(|_| Error::Timeout { timeout: duration })?
+                .map_err(Error::from)?,
+            None => resume.await.map_err(Error::from)?,
+        })
+    }
+
+    /// Returns the container running the job of `handle`, which is the one it's submitted to,
+    /// or the one replacing it if jobs are persisted (see [`DockerizedzkVMConfig::job_volume`]).
+    async fn detached_job_container(
+        &self,
+        handle: &JobHandle,
+    ) -> anyhow::Result<RwLockReadGuard<'_, ServerContainer>> {
+        self.ensure_jobs_supported("detached prove jobs")?;
+        let container = if self.config.job_volume.is_some() {
+            self.container().await?
+        } else {
+            let guard = self.container.read().await;
+            RwLockReadGuard::try_map(guard, Option::as_ref)
+                .map_err(|_| anyhow::anyhow!("Container is not running"))?
+        };
+        if container.id != handle.container_id && self.config.job_volume.is_none() {
+            return Err(Error::DetachedJobLost {
+                container_id: handle.container_id.clone(),
+                job_id: handle.job_id,
+            }
+            .into());
+        }
+        Ok(container)
+    }
+
+    fn ensure_jobs_supported(&self, feature: &'static str) -> Result<(), Error> {
+        if self.protocol_version < 1 {
+            return Err(Error::Unsupported {
+                feature,
+                protocol_version: self.protocol_version,
+            });
+        }
+        Ok(())
+    }
+
     pub async fn verify_async(&self, proof: EncodedProof) -> anyhow::Result<PublicValues> {
         self.with_retry(
             |client| {
@@ -397,6 +1438,7 @@ impl DockerizedzkVM {
         const DOCKER_WAIT_FOR_EXIT_TIMEOUT: Duration = Duration::from_secs(10);
 
         let mut attempt = 1;
+        let mut retried_on_crash = false;
         loop {
             if attempt > MAX_RETRY {
                 anyhow::bail!("Container is not available after {MAX_RETRY} attempts");
@@ -424,7 +1466,9 @@ impl DockerizedzkVM {
                             && container.id == container_id
                         {
                             info!("Operation timed out, removing container...");
-                            drop(guard.take())
+                            if let Some(container) = guard.take() {
+                                container.remove();
+                            }
                         }
 
                         return Err(Error::Timeout { timeout: duration }.into());
@@ -443,11 +1487,18 @@ impl DockerizedzkVM {
                 && let Some(exit_info) =
                     docker_wait_for_exit(&container.id, DOCKER_WAIT_FOR_EXIT_TIMEOUT).await
             {
-                return Err(Error::ContainerExited {
+                let oom_killed = exit_info.is_oom_killed();
+                let err = Error::ContainerExited {
                     container_id: container.id.clone(),
                     exit_info,
+                };
+                // An OOM-killed container would only run out of memory again.
+                if self.config.retry_on_crash && !retried_on_crash && !oom_killed {
+                    warn!("{err}, recreating and retrying...");
+                    retried_on_crash = true;
+                    continue;
                 }
-                .into());
+                return Err(err.into());
             }
 
             return Err(err.into());
@@ -476,47 +1527,129 @@ impl DockerizedzkVM {
         }
 
         info!("Server not healthy, recreating...");
-        drop(guard.take());
-        *guard = Some(ServerContainer::new(
-            self.zkvm_kind,
-            &self.elf,
-            &self.resource,
-        )?);
+        if let Some(container) = guard.take() {
+            container.remove();
+        }
+        let container =
+            ServerContainer::new(self.zkvm_kind, &self.elf, &self.resource, &self.config)?;
+        // Negotiates again for the client to stream large inputs and proofs.
+        container.client.handshake().await?;
+        *guard = Some(container);
 
         let guard = guard.downgrade();
         Ok(RwLockReadGuard::map(guard, |opt| opt.as_ref().unwrap()))
     }
 }
 
-async fn wait_until_healthy(endpoint: &Url, http_client: Client) -> Result<(), Error> {
-    const TIMEOUT: Duration = Duration::from_secs(300); // 5mins
+/// Checks the `ere-server` is of this crate, to fail early on a stale image (e.g. cached by an
+/// older crate version of the same tag) or persistent container, instead of on decoding its
+/// proofs and reports. Images of other `version`s are assumed compatible, see
+/// [`DockerizedzkVMConfig::version`].
+fn check_server_compatible(
+    info: &ServerInfo,
+    zkvm_kind: zkVMKind,
+    version: Option<&str>,
+) -> Result<(), Error> {
+    if version.is_some() {
+        return Ok(());
+    }
+    let checks = [
+        (
+            "protocol version",
+            info.protocol_version.to_string(),
+            PROTOCOL_VERSION.to_string(),
+        ),
+        (
+            "crate version",
+            info.crate_version.clone(),
+            CRATE_VERSION.to_string(),
+        ),
+        ("zkVM", info.zkvm_name.clone(), zkvm_kind.name().to_string()),
+        (
+            "zkVM SDK version",
+            info.zkvm_sdk_version.clone(),
+            zkvm_kind.sdk_version().to_string(),
+        ),
+    ];
+    for (what, found, expected) in checks {
+        if found != expected {
+            return Err(Error::IncompatibleServer {
+                what,
+                found,
+                expected,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Waits until the server in the container reports healthy, which it only listens after being
+/// initialized (and warmed up if enabled).
+///
+/// Returns [`Error::ContainerExited`] as soon as the container exits (e.g. failed to initialize
+/// GPU), or [`Error::ConnectionTimeout`] after `timeout`.
+async fn wait_until_ready(
+    endpoint: &Url,
+    http_client: Client,
+    container_id: &str,
+    timeout: Duration,
+) -> Result<(), Error> {
     const INTERVAL: Duration = Duration::from_millis(500);
 
-    let http_client = http_client.clone();
     let start = Instant::now();
     loop {
-        if start.elapsed() > TIMEOUT {
-            return Err(Error::ConnectionTimeout);
+        if start.elapsed() > timeout {
+            return Err(Error::ConnectionTimeout { timeout });
+        }
+
+        if let Ok(response) = http_client.get(endpoint.join("health")?).send().await
+            && response.status().is_success()
+        {
+            break Ok(());
         }
 
-        match http_client.get(endpoint.join("health")?).send().await {
-            Ok(response) if response.status().is_success() => break Ok(()),
-            _ => sleep(INTERVAL).await,
+        // `docker inspect` blocks, so it's run off the async runtime.
+        let container = container_id.to_string();
+        let running = task::spawn_blocking(move || docker_container_running(container))
+            .await
+            .map_err(|err| {
+                CommonError::io("Failed to inspect container", io::Error::other(err))
+            })??;
+        if !running {
+            return Err(Error::ContainerExited {
+                container_id: container_id.to_string(),
+                exit_info: docker_inspect_exit_info(container_id)?,
+            });
         }
+        sleep(INTERVAL).await;
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use core::time::Duration;
+    use core::{
+        net::{Ipv4Addr, Ipv6Addr},
+        time::Duration,
+    };
 
-    use ere_prover_core::{Input, ProverResource};
-    use ere_util_test::{codec::BincodeLegacy, host::TestCase, program::basic::BasicProgram};
+    use std::{env, fs, path::PathBuf};
+
+    use ere_prover_core::{Input, ProofKind, ProverResource};
+    use ere_server_client::JobStatus;
+    use ere_util_test::{
+        codec::BincodeLegacy,
+        host::{PrimitiveCostTable, TestCase, measure_primitive_costs},
+        program::basic::BasicProgram,
+    };
 
     use crate::{
         CompilerKind, DockerizedzkVMConfig,
         compiler::tests::compile,
-        prover::{DockerizedzkVM, Error},
+        prover::{
+            ContainerConfig, ContainerNetwork, ContainerUser, DockerizedzkVM, Error, JobHandle,
+            ServerContainer,
+        },
+        util::workspace_dir,
         zkVMKind,
     };
 
@@ -536,6 +1669,207 @@ mod tests {
         .unwrap()
     }
 
+    #[test]
+    fn test_endpoint() {
+        let endpoint = |network, host_port| {
+            ServerContainer::endpoint(&network, "ere-server-sp1", 4177, host_port)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            endpoint(ContainerNetwork::Host, 32768),
+            "http://127.0.0.1:4177/"
+        );
+        assert_eq!(
+            endpoint(ContainerNetwork::Custom("isolated".to_string()), 32768),
+            "http://ere-server-sp1:4177/"
+        );
+        assert_eq!(
+            endpoint(
+                ContainerNetwork::Bridge {
+                    host_ip: Some(Ipv4Addr::UNSPECIFIED.into()),
+                    host_port: Some(8080),
+                },
+                8080
+            ),
+            "http://127.0.0.1:8080/"
+        );
+        assert_eq!(
+            endpoint(
+                ContainerNetwork::Bridge {
+                    host_ip: Some(Ipv6Addr::LOCALHOST.into()),
+                    host_port: None,
+                },
+                32768
+            ),
+            "http://[::1]:32768/"
+        );
+
+        let fixed_host_port =
+            |network, version| ServerContainer::fixed_host_port(&network, version, 4177);
+        assert_eq!(fixed_host_port(ContainerNetwork::Default, None), Some(4177));
+        assert_eq!(
+            fixed_host_port(ContainerNetwork::Default, Some("0.1.0")),
+            None
+        );
+        assert_eq!(
+            fixed_host_port(
+                ContainerNetwork::Bridge {
+                    host_ip: None,
+                    host_port: Some(8080),
+                },
+                Some("0.1.0")
+            ),
+            Some(8080)
+        );
+
+        let r0vm_addr = |addr, network| ServerContainer::r0vm_addr_in_container(addr, &network);
+        assert_eq!(
+            r0vm_addr("127.0.0.1:9000", ContainerNetwork::Default).as_deref(),
+            Some("host.docker.internal:9000")
+        );
+        assert_eq!(
+            r0vm_addr("[::1]:9000", ContainerNetwork::Default).as_deref(),
+            Some("host.docker.internal:9000")
+        );
+        assert_eq!(r0vm_addr("localhost:9000", ContainerNetwork::Host), None);
+        assert_eq!(r0vm_addr("r0vm:9000", ContainerNetwork::Default), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persistent() {
+        let zkvm_kind = zkVMKind::SP1;
+        let elf = compile(zkvm_kind, CompilerKind::RustCustomized, "basic").clone();
+        let config = DockerizedzkVMConfig {
+            persistent: true,
+            ..Default::default()
+        };
+        let new = || {
+            DockerizedzkVM::new(zkvm_kind, elf.clone(), ProverResource::Cpu, config.clone())
+                .unwrap()
+        };
+
+        let id = |zkvm: &DockerizedzkVM| {
+            let guard = zkvm.container.try_read().unwrap();
+            guard.as_ref().unwrap().id.clone()
+        };
+        let first = id(&new());
+        let zkvm = new();
+        assert_eq!(id(&zkvm), first);
+
+        // Remove the container on drop.
+        let mut zkvm = zkvm;
+        zkvm.container.get_mut().as_mut().unwrap().persistent = false;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prove_detached() {
+        let zkvm_kind = zkVMKind::SP1;
+        let elf = compile(zkvm_kind, CompilerKind::RustCustomized, "basic").clone();
+        let config = DockerizedzkVMConfig {
+            persistent: true,
+            ..Default::default()
+        };
+        let zkvm = DockerizedzkVM::new(zkvm_kind, elf.clone(), ProverResource::Cpu, config.clone())
+            .unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let err = zkvm
+            .prove_detached(&test_case.input(), ProofKind::Groth16)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::UnsupportedProofKind { .. })
+        ));
+        let handle = zkvm
+            .prove_detached(&test_case.input(), ProofKind::Compressed)
+            .unwrap();
+        let handle = serde_json::to_string(&handle).unwrap();
+        drop(zkvm);
+
+        let handle = serde_json::from_str::<JobHandle>(&handle).unwrap();
+        let mut zkvm = DockerizedzkVM::reattach(zkvm_kind, elf, ProverResource::Cpu, config)
+            .unwrap()
+            .unwrap();
+        let (public_values, proof, _) = zkvm.collect_detached(&handle).unwrap();
+        assert!(matches!(
+            zkvm.detached_job_status(&handle).unwrap(),
+            JobStatus::Done
+        ));
+        test_case.assert_output(&public_values);
+        assert_eq!(zkvm.verify(&proof).unwrap(), public_values);
+
+        // Remove the container on drop.
+        zkvm.container.get_mut().as_mut().unwrap().persistent = false;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_input_file() {
+        let zkvm_kind = zkVMKind::SP1;
+        let elf = compile(zkvm_kind, CompilerKind::RustCustomized, "basic").clone();
+        let config = DockerizedzkVMConfig {
+            input_file_threshold: Some(0),
+            ..Default::default()
+        };
+        let zkvm = DockerizedzkVM::new(zkvm_kind, elf, ProverResource::Cpu, config).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let (public_values, _) = zkvm.execute(&test_case.input()).unwrap();
+        test_case.assert_output(&public_values);
+        let (public_values, proof, _) = zkvm.prove(&test_case.input()).unwrap();
+        test_case.assert_output(&public_values);
+        assert_eq!(zkvm.verify(&proof).unwrap(), public_values);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_host_user() {
+        let zkvm_kind = zkVMKind::SP1;
+        let elf = compile(zkvm_kind, CompilerKind::RustCustomized, "basic").clone();
+        let config = DockerizedzkVMConfig {
+            input_file_threshold: Some(0),
+            container: ContainerConfig {
+                user: ContainerUser::Host,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let zkvm = DockerizedzkVM::new(zkvm_kind, elf, ProverResource::Cpu, config).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let (public_values, proof, _) = zkvm.prove(&test_case.input()).unwrap();
+        test_case.assert_output(&public_values);
+        assert_eq!(zkvm.verify(&proof).unwrap(), public_values);
+    }
+
+    /// Measures the primitives of all zkVMs into one table, written to the path of env variable
+    /// `ERE_PRIMITIVE_COST_TABLE` or `target/primitive_costs.md` of the workspace.
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "Micro-benchmark of primitive operations"]
+    async fn test_primitive_cost_table() {
+        let mut table = PrimitiveCostTable::new();
+        for zkvm_kind in [
+            zkVMKind::Airbender,
+            zkVMKind::OpenVM,
+            zkVMKind::Risc0,
+            zkVMKind::SP1,
+            zkVMKind::Zisk,
+        ] {
+            let zkvm = zkvm(
+                zkvm_kind,
+                CompilerKind::RustCustomized,
+                "primitives",
+                ProverResource::Cpu,
+            );
+            let costs = measure_primitive_costs(|input| zkvm.execute(input), 64);
+            table.insert(zkvm.name(), &costs);
+        }
+
+        let path = env::var_os("ERE_PRIMITIVE_COST_TABLE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| workspace_dir().unwrap().join("target/primitive_costs.md"));
+        fs::write(path, table.to_string()).unwrap();
+    }
+
     macro_rules! test_execute {
         ($zkvm_kind:ident, $compiler_kind:ident, $program:literal, $valid_test_cases:expr, $invalid_test_cases:expr) => {
             #[tokio::test(flavor = "multi_thread")]
@@ -559,7 +1893,7 @@ mod tests {
                 for input in $invalid_test_cases {
                     let err = zkvm.execute(&input).unwrap_err();
                     assert!(
-                        matches!(err.downcast_ref::<Error>().unwrap(), Error::zkVM(_)),
+                        matches!(err.downcast_ref::<Error>().unwrap(), Error::zkVM { .. }),
                         "Expect error variant `Error::zkVM`, got {err:?}",
                     );
                 }
@@ -592,7 +1926,7 @@ mod tests {
             for input in $invalid_test_cases {
                 let err = zkvm.prove(&input).unwrap_err();
                 assert!(
-                    matches!(err.downcast_ref::<Error>().unwrap(), Error::zkVM(_)),
+                    matches!(err.downcast_ref::<Error>().unwrap(), Error::zkVM { .. }),
                     "Expect error variant `Error::zkVM`, got {err:?}",
                 );
             }
@@ -1,7 +1,7 @@
 use core::time::Duration;
 
-use ere_prover_core::CommonError;
-use ere_server_client::{TwirpErrorResponse, url};
+use ere_prover_core::{CommonError, ErrorKind, ProofKind, zkVMError};
+use ere_server_client::{TwirpErrorResponse, rpc_error_kind, url};
 use thiserror::Error;
 
 use crate::util::docker::ContainerExitInfo;
@@ -9,8 +9,9 @@ use crate::util::docker::ContainerExitInfo;
 impl From<ere_server_client::Error> for Error {
     fn from(value: ere_server_client::Error) -> Self {
         match value {
+            ere_server_client::Error::CommonError(err) => Self::CommonError(err),
             ere_server_client::Error::ParseUrl(err) => Self::ParseUrl(err),
-            ere_server_client::Error::zkVM(err) => Self::zkVM(err),
+            ere_server_client::Error::zkVM { message, kind } => Self::zkVM { message, kind },
             ere_server_client::Error::Rpc(err) => Self::Rpc(err),
         }
     }
@@ -23,17 +24,62 @@ pub enum Error {
     CommonError(#[from] CommonError),
     #[error(transparent)]
     ParseUrl(#[from] url::ParseError),
-    #[error("zkVM method error: {0}")]
-    zkVM(String),
-    #[error("Connection to zkVM server timeout after 5 minutes")]
-    ConnectionTimeout,
+    #[error("zkVM method error: {message}")]
+    zkVM { message: String, kind: ErrorKind },
+    #[error("zkVM server not ready after {timeout:?}")]
+    ConnectionTimeout { timeout: Duration },
     #[error("RPC to zkVM server error: {0}")]
     Rpc(TwirpErrorResponse),
-    #[error("Server container '{container_id}' exited during request: {exit_info}")]
+    #[error("Server container '{container_id}' exited: {exit_info}")]
     ContainerExited {
         container_id: String,
         exit_info: ContainerExitInfo,
     },
     #[error("Operation timed out after {timeout:?}")]
     Timeout { timeout: Duration },
+    #[error("Image {image} not found locally nor in the image registry")]
+    ImageNotFound { image: String },
+    #[error(
+        "zkVM server is stale, its {what} is {found:?} instead of {expected:?}, rebuild the image \
+         by ERE_FORCE_REBUILD_DOCKER_IMAGE or remove the persistent container"
+    )]
+    IncompatibleServer {
+        what: &'static str,
+        found: String,
+        expected: String,
+    },
+    #[error("zkVM server doesn't support {feature} (protocol version {protocol_version})")]
+    Unsupported {
+        feature: &'static str,
+        protocol_version: u32,
+    },
+    #[error("zkVM server doesn't support {kind} proofs")]
+    UnsupportedProofKind { kind: ProofKind },
+    #[error(
+        "Server container '{container_id}' of detached prove job {job_id} is gone, set job_volume \
+         to keep jobs across containers"
+    )]
+    DetachedJobLost { container_id: String, job_id: u64 },
+}
+
+impl zkVMError for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommonError(err) => err.kind(),
+            Self::zkVM { kind, .. } => kind.clone(),
+            Self::ContainerExited { exit_info, .. } if exit_info.is_oom_killed() => {
+                ErrorKind::OutOfMemory
+            }
+            Self::Rpc(err) => rpc_error_kind(err),
+            Self::ConnectionTimeout { .. } => ErrorKind::Transient,
+            Self::ParseUrl(_)
+            | Self::ContainerExited { .. }
+            | Self::Timeout { .. }
+            | Self::ImageNotFound { .. }
+            | Self::IncompatibleServer { .. }
+            | Self::Unsupported { .. }
+            | Self::UnsupportedProofKind { .. }
+            | Self::DetachedJobLost { .. } => ErrorKind::ProverInfrastructure,
+        }
+    }
 }
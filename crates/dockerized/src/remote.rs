@@ -0,0 +1,356 @@
+use core::{future::Future, time::Duration};
+
+use ere_compiler_core::Elf;
+use ere_prover_core::{
+    ExecuteOptions, Input, ProgramExecutionReport, ProgramProvingReport, PublicValues,
+};
+use ere_server_client::{
+    AuthToken, EncodedProgramVk, EncodedProof, JobStatus, reqwest::Client, url::Url, zkVMClient,
+};
+use ere_util_tokio::block_on;
+use tokio::time::timeout;
+
+use crate::prover::Error;
+
+#[derive(Debug, Clone, Default)]
+pub struct RemotezkVMConfig {
+    pub execute_timeout: Option<Duration>,
+    pub prove_timeout: Option<Duration>,
+    pub verify_timeout: Option<Duration>,
+    /// Token sent as `Authorization: Bearer {token}` for servers started with `--auth-token` or
+    /// `--auth-token-file`, see [`RemotezkVM::set_auth_token`] to rotate it.
+    pub auth_token: Option<String>,
+}
+
+/// zkVM served by an `ere-server` running elsewhere, which requires neither Docker nor the zkVM
+/// SDK on this machine.
+///
+/// The server can be started on the prover machine by e.g.
+/// `docker run --gpus all -p 3000:3000 ere-server-sp1:{version}-cuda --elf-url <URL> gpu`, see
+/// the `ere-server-api` crate for its HTTP API.
+///
+/// Unlike [`DockerizedzkVM`](crate::DockerizedzkVM), the server is not managed by this process,
+/// so it's not recreated and requests are not retried when it's unhealthy.
+///
+/// A server can serve other programs than the one it started with, see [`RemotezkVM::upload`].
+#[derive(Debug)]
+pub struct RemotezkVM {
+    endpoint: Url,
+    config: RemotezkVMConfig,
+    client: zkVMClient,
+    auth_token: AuthToken,
+    program_vk: EncodedProgramVk,
+    protocol_version: u32,
+}
+
+impl RemotezkVM {
+    /// Connects to the `ere-server` at `endpoint` (e.g. `http://gpu-server:3000`) and negotiates
+    /// the protocol version.
+    pub fn new(endpoint: Url, config: RemotezkVMConfig) -> Result<Self, Error> {
+        block_on(Self::new_async(endpoint, config))
+    }
+
+    /// Uploads `elf` to the `ere-server` at `endpoint`, which serves it besides the program it
+    /// started with, and requests of the returned zkVM are for `elf`.
+    ///
+    /// Uploading a program already served is cheap, so each client can upload the program it
+    /// uses instead of coordinating with others sharing the server.
+    pub fn upload(endpoint: Url, elf: &Elf, config: RemotezkVMConfig) -> Result<Self, Error> {
+        block_on(Self::upload_async(endpoint, elf, config))
+    }
+
+    pub async fn new_async(endpoint: Url, config: RemotezkVMConfig) -> Result<Self, Error> {
+        let (client, auth_token, protocol_version) = connect(&endpoint, &config).await?;
+        let program_vk = client.program_vk().await?;
+        Ok(Self {
+            endpoint,
+            config,
+            client,
+            auth_token,
+            program_vk,
+            protocol_version,
+        })
+    }
+
+    pub async fn upload_async(
+        endpoint: Url,
+        elf: &Elf,
+        config: RemotezkVMConfig,
+    ) -> Result<Self, Error> {
+        let (client, auth_token, protocol_version) = connect(&endpoint, &config).await?;
+        if protocol_version < 2 {
+            return Err(Error::Unsupported {
+                feature: "uploading programs",
+                protocol_version,
+            });
+        }
+        let (program_digest, program_vk) = client.upload_program(elf).await?;
+        let client = client.with_program(program_digest);
+        Ok(Self {
+            endpoint,
+            config,
+            client,
+            auth_token,
+            program_vk,
+            protocol_version,
+        })
+    }
+
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    pub fn program_vk(&self) -> &EncodedProgramVk {
+        &self.program_vk
+    }
+
+    /// Returns the protocol version negotiated with the `ere-server`.
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    /// Replaces the auth token sent by subsequent requests, e.g. after the server's token file
+    /// has been rotated.
+    pub fn set_auth_token(&self, token: impl Into<String>) {
+        self.auth_token.set(token);
+    }
+
+    /// Returns `true` if the `ere-server` is reachable and reports healthy.
+    pub fn health(&self) -> bool {
+        block_on(self.client.is_healthy())
+    }
+
+    pub fn execute(&self, input: &Input) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
+        block_on(self.execute_async(input.clone()))
+    }
+
+    pub fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
+        block_on(self.execute_with_options_async(input.clone(), *options))
+    }
+
+    pub fn prove(
+        &self,
+        input: &Input,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        block_on(self.prove_async(input.clone()))
+    }
+
+    /// Submits a prove job without waiting for it, and returns its id to be polled by
+    /// [`RemotezkVM::job_status`] and collected by [`RemotezkVM::resume_prove_job`].
+    pub fn submit_prove(&self, input: &Input) -> anyhow::Result<u64> {
+        block_on(self.submit_prove_async(input.clone()))
+    }
+
+    pub fn job_status(&self, job_id: u64) -> anyhow::Result<JobStatus> {
+        block_on(self.job_status_async(job_id))
+    }
+
+    /// Waits for the prove job with `job_id` and returns its result.
+    pub fn resume_prove_job(
+        &self,
+        job_id: u64,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        block_on(self.resume_prove_job_async(job_id))
+    }
+
+    pub fn verify(&self, proof: &EncodedProof) -> anyhow::Result<PublicValues> {
+        block_on(self.verify_async(proof.clone()))
+    }
+
+    pub async fn execute_async(
+        &self,
+        input: Input,
+    ) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
+        self.execute_with_options_async(input, ExecuteOptions::default())
+            .await
+    }
+
+    pub async fn execute_with_options_async(
+        &self,
+        input: Input,
+        options: ExecuteOptions,
+    ) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
+        with_timeout(
+            self.client.execute_with_options(input, options),
+            self.config.execute_timeout,
+        )
+        .await
+    }
+
+    pub async fn prove_async(
+        &self,
+        input: Input,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        with_timeout(self.client.prove(input), self.config.prove_timeout).await
+    }
+
+    pub async fn submit_prove_async(&self, input: Input) -> anyhow::Result<u64> {
+        self.ensure_jobs_supported()?;
+        with_timeout(self.client.submit_prove(input), None).await
+    }
+
+    pub async fn job_status_async(&self, job_id: u64) -> anyhow::Result<JobStatus> {
+        self.ensure_jobs_supported()?;
+        with_timeout(self.client.job_status(job_id), None).await
+    }
+
+    pub async fn resume_prove_job_async(
+        &self,
+        job_id: u64,
+    ) -> anyhow::Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        self.ensure_jobs_supported()?;
+        with_timeout(
+            self.client.resume_prove_job(job_id),
+            self.config.prove_timeout,
+        )
+        .await
+    }
+
+    pub async fn verify_async(&self, proof: EncodedProof) -> anyhow::Result<PublicValues> {
+        with_timeout(self.client.verify(proof), self.config.verify_timeout).await
+    }
+
+    fn ensure_jobs_supported(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.protocol_version >= 1,
+            "ere-server at {} doesn't support prove jobs (protocol version {})",
+            self.endpoint,
+            self.protocol_version
+        );
+        Ok(())
+    }
+}
+
+/// Returns a client of the `ere-server` at `endpoint` and the negotiated protocol version.
+async fn connect(
+    endpoint: &Url,
+    config: &RemotezkVMConfig,
+) -> Result<(zkVMClient, AuthToken, u32), Error> {
+    let client = zkVMClient::new(endpoint.clone(), Client::new(), Vec::new())?;
+    let auth_token = client.auth_token().clone();
+    if let Some(token) = &config.auth_token {
+        auth_token.set(token);
+    }
+    let protocol_version = client.handshake().await?.protocol_version;
+    Ok((client, auth_token, protocol_version))
+}
+
+async fn with_timeout<T>(
+    future: impl Future<Output = Result<T, ere_server_client::Error>>,
+    timeout_duration: Option<Duration>,
+) -> anyhow::Result<T> {
+    let result = match timeout_duration {
+        Some(duration) => timeout(duration, future)
+            .await
+            .map_err(|_| Error::Timeout { timeout: duration })?,
+        None => future.await,
+    };
+    Ok(result.map_err(Error::from)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::net::Ipv4Addr;
+
+    use ere_prover_core::ProverResource;
+    use ere_server_client::url::Url;
+    use ere_util_test::{codec::BincodeLegacy, host::TestCase, program::basic::BasicProgram};
+
+    use crate::{
+        CompilerKind, DockerizedzkVM, DockerizedzkVMConfig, JobStatus,
+        compiler::tests::compile,
+        prover::ContainerNetwork,
+        remote::{RemotezkVM, RemotezkVMConfig},
+        util::free_port,
+        zkVMKind,
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote() {
+        let zkvm_kind = zkVMKind::SP1;
+        let elf = compile(zkvm_kind, CompilerKind::RustCustomized, "basic").clone();
+        let port = free_port().unwrap();
+        let config = DockerizedzkVMConfig {
+            network: ContainerNetwork::Bridge {
+                host_ip: Some(Ipv4Addr::LOCALHOST.into()),
+                host_port: Some(port),
+            },
+            ..Default::default()
+        };
+        let server = DockerizedzkVM::new(zkvm_kind, elf, ProverResource::Cpu, config).unwrap();
+
+        let endpoint = format!("http://127.0.0.1:{port}").parse().unwrap();
+        let zkvm = RemotezkVM::new(endpoint, RemotezkVMConfig::default()).unwrap();
+        assert_eq!(zkvm.program_vk(), server.program_vk());
+        assert!(zkvm.health());
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let job_id = zkvm.submit_prove(&test_case.input()).unwrap();
+        let (prover_public_values, proof, _) = zkvm.resume_prove_job(job_id).unwrap();
+        assert!(matches!(zkvm.job_status(job_id).unwrap(), JobStatus::Done));
+        let verifier_public_values = zkvm.verify(&proof).unwrap();
+        assert_eq!(prover_public_values, verifier_public_values);
+        test_case.assert_output(&verifier_public_values);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_auth_token() {
+        let zkvm_kind = zkVMKind::SP1;
+        let elf = compile(zkvm_kind, CompilerKind::RustCustomized, "basic").clone();
+        let port = free_port().unwrap();
+        let config = DockerizedzkVMConfig {
+            network: ContainerNetwork::Bridge {
+                host_ip: Some(Ipv4Addr::LOCALHOST.into()),
+                host_port: Some(port),
+            },
+            auth_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let server = DockerizedzkVM::new(zkvm_kind, elf, ProverResource::Cpu, config).unwrap();
+
+        let endpoint: Url = format!("http://127.0.0.1:{port}").parse().unwrap();
+        assert!(RemotezkVM::new(endpoint.clone(), RemotezkVMConfig::default()).is_err());
+
+        let config = RemotezkVMConfig {
+            auth_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let zkvm = RemotezkVM::new(endpoint, config).unwrap();
+        assert_eq!(zkvm.program_vk(), server.program_vk());
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        zkvm.execute(&test_case.input()).unwrap();
+        zkvm.set_auth_token("wrong");
+        assert!(zkvm.execute(&test_case.input()).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_upload() {
+        let zkvm_kind = zkVMKind::SP1;
+        let elf = compile(zkvm_kind, CompilerKind::RustCustomized, "basic").clone();
+        let port = free_port().unwrap();
+        let config = DockerizedzkVMConfig {
+            network: ContainerNetwork::Bridge {
+                host_ip: Some(Ipv4Addr::LOCALHOST.into()),
+                host_port: Some(port),
+            },
+            ..Default::default()
+        };
+        let server =
+            DockerizedzkVM::new(zkvm_kind, elf.clone(), ProverResource::Cpu, config).unwrap();
+
+        let endpoint: Url = format!("http://127.0.0.1:{port}").parse().unwrap();
+        let zkvm = RemotezkVM::upload(endpoint, &elf, RemotezkVMConfig::default()).unwrap();
+        assert_eq!(zkvm.program_vk(), server.program_vk());
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let (prover_public_values, proof, _) = zkvm.prove(&test_case.input()).unwrap();
+        let verifier_public_values = zkvm.verify(&proof).unwrap();
+        assert_eq!(prover_public_values, verifier_public_values);
+        test_case.assert_output(&verifier_public_values);
+    }
+}
@@ -1,10 +1,11 @@
-use std::path::PathBuf;
+use std::{net::TcpListener, path::PathBuf, process::Command};
 
 use ere_prover_core::CommonError;
 
 pub mod cuda;
 pub mod docker;
 pub mod env;
+pub mod source;
 
 pub fn workspace_dir() -> Result<PathBuf, CommonError> {
     let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -13,3 +14,31 @@ pub fn workspace_dir() -> Result<PathBuf, CommonError> {
     dir.canonicalize()
         .map_err(|err| CommonError::io("Source code of Ere not found", err))
 }
+
+/// Returns a port on the host that is free at the moment.
+pub fn free_port() -> Result<u16, CommonError> {
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|err| CommonError::io("Failed to find a free port", err))
+}
+
+/// Returns `{uid}:{gid}` of the user running this process.
+pub fn host_user() -> Result<String, CommonError> {
+    let id = |flag: &str| -> Result<String, CommonError> {
+        let mut cmd = Command::new("id");
+        cmd.arg(flag);
+        let output = cmd
+            .output()
+            .map_err(|err| CommonError::command(&cmd, err))?;
+        if !output.status.success() {
+            return Err(CommonError::command_exit_non_zero(
+                &cmd,
+                output.status,
+                Some(&output),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    Ok(format!("{}:{}", id("-u")?, id("-g")?))
+}
@@ -1,61 +1,103 @@
-use std::{env, process::Command};
+use std::env;
 
+use ere_prover_core::CommonError;
+use ere_util_cuda::detect_compute_caps;
 use tracing::{info, warn};
 
-/// Detects CUDA compute capabilities of all visible GPUs.
-///
-/// Returns a sorted, deduplicated list of numeric compute capabilities
-/// (e.g. `[89, 120]` for a mix of RTX 40 and RTX 50 series GPUs).
-///
-/// Returns an empty vec if `nvidia-smi` is not available or fails.
-pub fn detect_compute_caps() -> Vec<u32> {
-    let Ok(output) = Command::new("nvidia-smi")
-        .args(["--query-gpu=compute_cap", "--format=csv,noheader"])
-        .output()
-    else {
-        return vec![];
-    };
+use crate::util::{docker::docker_image_label, env::remote_docker_host};
 
-    if !output.status.success() {
-        return vec![];
-    }
-
-    let mut caps: Vec<u32> = String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .filter_map(|line| line.trim().replace('.', "").parse::<u32>().ok())
-        .collect();
-    caps.sort_unstable();
-    caps.dedup();
-    caps
-}
+/// Label of the CUDA architectures a GPU image is built for (e.g. `89,120`).
+pub const CUDA_ARCHS_LABEL: &str = "ere.cuda-archs";
 
 /// Returns CUDA architectures as a list of numeric values (e.g. `[89, 120]`).
 ///
 /// It does the following checks and returns the first valid value:
-/// 1. Read env variable `CUDA_ARCHS` and validate format (comma-separated numbers).
-/// 2. Detect compute capabilities of all visible GPUs.
+/// 1. Read env variable `CUDA_ARCHS` and validate format (comma-separated numbers), or `native` to
+///    skip to the detection.
+/// 2. Detect compute capabilities of all visible GPUs, if the Docker host is local.
 ///
 /// Returns an empty vec if neither source provides valid architectures.
-pub fn cuda_archs() -> Vec<u32> {
-    if let Ok(val) = env::var("CUDA_ARCHS") {
+pub fn cuda_archs() -> Result<Vec<u32>, CommonError> {
+    if let Ok(val) = env::var("CUDA_ARCHS")
+        && val != "native"
+    {
         let archs: Option<Vec<u32>> = val.split(',').map(|s| s.parse::<u32>().ok()).collect();
         match archs {
             Some(archs) if !archs.is_empty() => {
                 info!("Using CUDA_ARCHS {val} from env variable");
-                return archs;
+                return Ok(archs);
             }
             _ => warn!(
                 "Skipping CUDA_ARCHS {val} from env variable \
-                 (expected comma-separated numbers, e.g. \"89,120\")"
+                 (expected comma-separated numbers, e.g. \"89,120\", or \"native\")"
             ),
         }
     }
 
+    // GPUs of a remote Docker host can't be detected locally.
+    if let Some(host) = remote_docker_host()? {
+        warn!(
+            "Skipping detecting local GPUs for remote Docker host {host}, set CUDA_ARCHS instead"
+        );
+        return Ok(vec![]);
+    }
+
     let caps = detect_compute_caps();
     if !caps.is_empty() {
         info!("Detected CUDA compute capabilities (CUDA_ARCHS={caps:?})");
-        return caps;
+        return Ok(caps);
+    }
+
+    Ok(vec![])
+}
+
+/// Returns the value of [`CUDA_ARCHS_LABEL`] for `cuda_archs`.
+pub fn cuda_archs_label(cuda_archs: &[u32]) -> String {
+    cuda_archs
+        .iter()
+        .map(|arch| arch.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Returns `true` if the local GPU `image` is built for CUDA architectures not covering
+/// `cuda_archs`, so it would fail at runtime (e.g. built for `sm_90` but running on `sm_89`).
+///
+/// Images without [`CUDA_ARCHS_LABEL`] (e.g. built by older versions or without any architecture
+/// set or detected) are assumed to be compatible.
+pub fn is_cuda_archs_mismatched(image: &str, cuda_archs: &[u32]) -> Result<bool, CommonError> {
+    if cuda_archs.is_empty() {
+        return Ok(false);
+    }
+    let Some(label) = docker_image_label(image, CUDA_ARCHS_LABEL)? else {
+        return Ok(false);
+    };
+    let mismatched = !covers_cuda_archs(&label, cuda_archs);
+    if mismatched {
+        warn!("Image {image} is built for CUDA_ARCHS {label}, but {cuda_archs:?} are required");
     }
+    Ok(mismatched)
+}
+
+/// Returns `true` if the architectures in `label` include all of `cuda_archs`.
+fn covers_cuda_archs(label: &str, cuda_archs: &[u32]) -> bool {
+    let built = label
+        .split(',')
+        .filter_map(|arch| arch.trim().parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    cuda_archs.iter().all(|arch| built.contains(arch))
+}
 
-    vec![]
+#[cfg(test)]
+mod tests {
+    use crate::util::cuda::{covers_cuda_archs, cuda_archs_label};
+
+    #[test]
+    fn test_covers_cuda_archs() {
+        assert_eq!(cuda_archs_label(&[89, 120]), "89,120");
+        assert!(covers_cuda_archs("89,120", &[89]));
+        assert!(covers_cuda_archs("89,120", &[89, 120]));
+        assert!(!covers_cuda_archs("90", &[89]));
+        assert!(!covers_cuda_archs("89", &[89, 120]));
+    }
 }
@@ -1,16 +1,45 @@
 use std::{
     env,
+    ffi::OsStr,
     fmt::{self, Display, Formatter},
-    io::Write,
+    io::{self, BufRead, BufReader, Read, Write},
     path::Path,
-    process::{Child, Command, Stdio},
+    process::{Child, Command, ExitStatus, Stdio},
+    thread::{self, JoinHandle},
     time::Duration,
 };
 
-use ere_prover_core::CommonError;
-use tracing::debug;
+use ere_prover_core::{
+    CommonError,
+    config::{Config, ContainerRuntime},
+};
+use tempfile::NamedTempFile;
+use tracing::{Level, debug, event};
+
+use crate::{util::env::config, zkVMKind};
 
-use crate::util::env::gpu_devices;
+/// Returns the command of the container runtime configured in [`Config`], connecting to the
+/// daemon configured in [`Config`], which falls back to the `DOCKER_*` env variables read by
+/// `docker` itself.
+///
+/// Podman accepts the same commands and options used here, except [`DockerRunCmd::gpus`]. With
+/// rootless Podman, root in the container is mapped to the invoking user, so files written to
+/// bind mounts are owned by the user without `--userns=keep-id`, which would instead run as a
+/// non-root user that can't write to the toolchain directories in the images.
+fn docker_cmd() -> Result<Command, CommonError> {
+    let docker = &config()?.docker;
+    let mut cmd = Command::new(docker.runtime.binary());
+    if let Some(host) = &docker.host {
+        cmd.env("DOCKER_HOST", host);
+    }
+    if docker.tls_verify {
+        cmd.env("DOCKER_TLS_VERIFY", "1");
+    }
+    if let Some(cert_path) = &docker.cert_path {
+        cmd.env("DOCKER_CERT_PATH", cert_path);
+    }
+    Ok(cmd)
+}
 
 #[derive(Clone)]
 struct CmdOption(String, Option<String>);
@@ -73,6 +102,10 @@ impl DockerBuildCmd {
         )
     }
 
+    pub fn label(self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.option("label", format!("{}={}", to_string(key), to_string(value)))
+    }
+
     pub fn build_arg_from_env(self, key: impl AsRef<str>) -> Self {
         let key = key.as_ref();
         match env::var(key) {
@@ -82,7 +115,7 @@ impl DockerBuildCmd {
     }
 
     pub fn exec(self, context: impl AsRef<Path>) -> Result<(), CommonError> {
-        let mut cmd = Command::new("docker");
+        let mut cmd = docker_cmd()?;
         cmd.arg("build");
         for option in self.options {
             cmd.args(option.to_args());
@@ -106,14 +139,29 @@ impl DockerBuildCmd {
 pub struct DockerRunCmd {
     options: Vec<CmdOption>,
     image: String,
+    logs: Option<zkVMKind>,
+    config: &'static Config,
+    /// File of `--env-file` with the variables set by [`DockerRunCmd::secret_env`], which is
+    /// read when the container is created and removed on drop.
+    env_file: Option<NamedTempFile>,
 }
 
 impl DockerRunCmd {
-    pub fn new(image: String) -> Self {
-        Self {
+    /// Returns error if [`Config::global`] fails to load, which the options are taken from.
+    pub fn new(image: String) -> Result<Self, CommonError> {
+        Ok(Self {
             options: Vec::new(),
             image,
-        }
+            logs: None,
+            config: config()?,
+            env_file: None,
+        })
+    }
+
+    /// Re-emits the output of the container as `tracing` events, see [`forward_logs`].
+    pub fn forward_logs(mut self, zkvm_kind: zkVMKind) -> Self {
+        self.logs = Some(zkvm_kind);
+        self
     }
 
     pub fn flag(mut self, key: impl AsRef<str>) -> Self {
@@ -126,11 +174,14 @@ impl DockerRunCmd {
         self
     }
 
+    /// Publishes `container` port on `host` (`{ip}:{port}`, `{port}` or `{ip}:`), or on a port
+    /// assigned by Docker on all interfaces if `host` is empty.
     pub fn publish(self, host: impl AsRef<str>, container: impl AsRef<str>) -> Self {
-        self.option(
-            "publish",
-            format!("{}:{}", host.as_ref(), container.as_ref()),
-        )
+        let value = match host.as_ref() {
+            "" => container.as_ref().to_string(),
+            host => format!("{host}:{}", container.as_ref()),
+        };
+        self.option("publish", value)
     }
 
     pub fn volume(self, host: impl AsRef<Path>, container: impl AsRef<Path>) -> Self {
@@ -148,9 +199,45 @@ impl DockerRunCmd {
         self.option("env", format!("{}={}", key.as_ref(), value.as_ref()))
     }
 
+    /// Sets environment variable `key` of the container by an `--env-file` only readable by the
+    /// user, so that `value` isn't in the arguments of the command, which are visible by `ps` and
+    /// in logs and errors.
+    pub fn secret_env(
+        mut self,
+        key: impl AsRef<str>,
+        value: impl AsRef<str>,
+    ) -> Result<Self, CommonError> {
+        let (key, value) = (key.as_ref(), value.as_ref());
+        if value.contains('\n') {
+            return Err(CommonError::io(
+                format!("Invalid value of {key}"),
+                io::Error::new(io::ErrorKind::InvalidInput, "value contains a newline"),
+            ));
+        }
+        if self.env_file.is_none() {
+            // Created with permission 0600.
+            let env_file = NamedTempFile::new()
+                .map_err(|err| CommonError::io("Failed to create env file", err))?;
+            self = self.option("env-file", env_file.path().to_string_lossy());
+            self.env_file = Some(env_file);
+        }
+        let env_file = self.env_file.as_mut().unwrap();
+        writeln!(env_file, "{key}={value}")
+            .and_then(|_| env_file.flush())
+            .map_err(|err| CommonError::io("Failed to write env file", err))?;
+        Ok(self)
+    }
+
     pub fn gpus(self) -> Self {
-        let devices = gpu_devices().unwrap_or_else(|| "all".to_string());
-        self.option("gpus", &devices)
+        let docker = &self.config.docker;
+        let devices = docker.gpu_devices.as_deref().unwrap_or("all");
+        match docker.runtime {
+            ContainerRuntime::Docker => self.option("gpus", devices),
+            // Podman passes through GPUs by CDI devices generated by `nvidia-ctk cdi generate`.
+            ContainerRuntime::Podman => cdi_gpu_devices(devices)
+                .into_iter()
+                .fold(self, |cmd, device| cmd.option("device", device)),
+        }
     }
 
     pub fn network(self, name: impl AsRef<str>) -> Self {
@@ -161,12 +248,17 @@ impl DockerRunCmd {
         self.option("name", name)
     }
 
-    /// Inherit environment variable `key` if it's set and valid.
+    pub fn label(self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.option("label", format!("{}={}", key.as_ref(), value.as_ref()))
+    }
+
+    /// Inherit environment variable `key` if it's set and valid, or the tuning knob `key` in
+    /// [`Config`].
     pub fn inherit_env(self, key: impl AsRef<str>) -> Self {
         let key = key.as_ref();
-        match env::var(key) {
-            Ok(val) => self.env(key, val),
-            Err(_) => self,
+        match self.config.var(key) {
+            Some(val) => self.env(key, val),
+            None => self,
         }
     }
 
@@ -174,18 +266,22 @@ impl DockerRunCmd {
         self.flag("rm")
     }
 
-    pub fn spawn(
+    pub fn entrypoint(self, entrypoint: impl AsRef<str>) -> Self {
+        self.option("entrypoint", entrypoint)
+    }
+
+    /// Creates the container without starting it and returns the container id, e.g. to copy
+    /// files into it before [`docker_start`].
+    pub fn create(
         self,
         commands: impl IntoIterator<Item: AsRef<str>>,
-        stdin: &[u8],
-    ) -> Result<(Child, String), CommonError> {
-        // `docker container create --interactive ...` to create container and get container id.
-        let mut cmd = Command::new("docker");
-        cmd.args(["container", "create", "--interactive"]);
-        for option in &self.options {
+    ) -> Result<String, CommonError> {
+        let mut cmd = docker_cmd()?;
+        cmd.args(["container", "create"]);
+        for option in self.options {
             cmd.args(option.to_args());
         }
-        cmd.arg(&self.image);
+        cmd.arg(self.image);
         for command in commands {
             cmd.arg(command.as_ref());
         }
@@ -202,10 +298,21 @@ impl DockerRunCmd {
                 Some(&output),
             ));
         }
-        let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn spawn(
+        self,
+        commands: impl IntoIterator<Item: AsRef<str>>,
+        stdin: &[u8],
+    ) -> Result<(Child, String), CommonError> {
+        // `docker container create --interactive ...` to create container and get container id.
+        let logs = self.logs;
+        let container_id = self.flag("interactive").create(commands)?;
 
         // `docker container start --attach --interactive ...` to start container with stdin.
-        let mut cmd = Command::new("docker");
+        let mut cmd = docker_cmd()?;
         cmd.args([
             "container",
             "start",
@@ -216,10 +323,14 @@ impl DockerRunCmd {
 
         debug!("Docker container start with command: {cmd:?}");
 
-        let mut child = cmd
-            .stdin(Stdio::piped())
-            .spawn()
-            .map_err(|err| CommonError::command(&cmd, err))?;
+        cmd.stdin(Stdio::piped());
+        if logs.is_some() {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+        let mut child = cmd.spawn().map_err(|err| CommonError::command(&cmd, err))?;
+        if let Some(zkvm_kind) = logs {
+            forward_logs(&mut child, short_id(&container_id), zkvm_kind, None);
+        }
 
         child
             .stdin
@@ -231,13 +342,50 @@ impl DockerRunCmd {
         Ok((child, container_id))
     }
 
+    /// Runs the container in background and returns the container id.
+    pub fn detach(
+        self,
+        commands: impl IntoIterator<Item: AsRef<str>>,
+    ) -> Result<String, CommonError> {
+        let mut cmd = docker_cmd()?;
+        cmd.args(["run", "--detach"]);
+        for option in self.options {
+            cmd.args(option.to_args());
+        }
+        cmd.arg(self.image);
+        for command in commands {
+            cmd.arg(command.as_ref());
+        }
+
+        debug!("Docker run detached with command: {cmd:?}");
+
+        let output = cmd
+            .output()
+            .map_err(|err| CommonError::command(&cmd, err))?;
+        if !output.status.success() {
+            return Err(CommonError::command_exit_non_zero(
+                &cmd,
+                output.status,
+                Some(&output),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     pub fn exec(self, commands: impl IntoIterator<Item: AsRef<str>>) -> Result<(), CommonError> {
-        let mut cmd = Command::new("docker");
+        // Created and started separately to log the output with the container id.
+        if let Some(zkvm_kind) = self.logs {
+            let container = self.create(commands)?;
+            return docker_start(container, zkvm_kind);
+        }
+
+        let mut cmd = docker_cmd()?;
         cmd.arg("run");
         for option in self.options {
             cmd.args(option.to_args());
         }
-        cmd.arg(self.image);
+        cmd.arg(&self.image);
         for command in commands {
             cmd.arg(command.as_ref());
         }
@@ -256,8 +404,200 @@ impl DockerRunCmd {
     }
 }
 
+/// Runs `commands` in the running `container` as `job`, with the output re-emitted as `tracing`
+/// events, see [`forward_logs`].
+pub fn docker_exec(
+    container: impl AsRef<str>,
+    zkvm_kind: zkVMKind,
+    job: &str,
+    commands: impl IntoIterator<Item: AsRef<str>>,
+) -> Result<(), CommonError> {
+    let container = container.as_ref();
+    let mut cmd = docker_cmd()?;
+    cmd.args(["exec", container]);
+    for command in commands {
+        cmd.arg(command.as_ref());
+    }
+
+    debug!("Docker exec with command: {cmd:?}");
+
+    let status = status_with_logs(&mut cmd, short_id(container), zkvm_kind, Some(job))
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !status.success() {
+        Err(CommonError::command_exit_non_zero(&cmd, status, None))?
+    }
+
+    Ok(())
+}
+
+/// Starts the `container` created by [`DockerRunCmd::create`] and waits until it exits, with the
+/// output re-emitted as `tracing` events, see [`forward_logs`].
+pub fn docker_start(container: impl AsRef<str>, zkvm_kind: zkVMKind) -> Result<(), CommonError> {
+    let container = container.as_ref();
+    let mut cmd = docker_cmd()?;
+    cmd.args(["container", "start", "--attach", container]);
+
+    debug!("Docker container start with command: {cmd:?}");
+
+    // The exit status is the one of the container with `--attach`.
+    let status = status_with_logs(&mut cmd, short_id(container), zkvm_kind, None)
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !status.success() {
+        Err(CommonError::command_exit_non_zero(&cmd, status, None))?
+    }
+
+    Ok(())
+}
+
+/// Copies files from `src` to `dst` like `docker cp`, either of which is a path in a container
+/// in format of `{container}:{path}`.
+///
+/// A directory `src` is copied as `dst` if `dst` doesn't exist, or into `dst` otherwise.
+pub fn docker_cp(src: impl AsRef<OsStr>, dst: impl AsRef<OsStr>) -> Result<(), CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args([OsStr::new("cp"), src.as_ref(), dst.as_ref()])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    Ok(())
+}
+
+/// Re-emits each line of stdout and stderr of `child` as `tracing` events with the `container`,
+/// `zkvm` and `job` fields, so a crash inside the container shows up without `docker logs`.
+///
+/// Lines are emitted at the level they are logged at in the container, see [`line_level`], and
+/// otherwise as `INFO` for stdout and `WARN` for stderr (e.g. panics). Returns the threads
+/// forwarding until the output is closed.
+pub fn forward_logs(
+    child: &mut Child,
+    container: &str,
+    zkvm_kind: zkVMKind,
+    job: Option<&str>,
+) -> Vec<JoinHandle<()>> {
+    fn forward(
+        output: impl Read + Send + 'static,
+        container: String,
+        zkvm_kind: zkVMKind,
+        job: Option<String>,
+        stderr: bool,
+    ) -> JoinHandle<()> {
+        thread::spawn(move || {
+            for line in BufReader::new(output).lines() {
+                let Ok(line) = line else { break };
+                let job = job.as_deref();
+                let default = if stderr { Level::WARN } else { Level::INFO };
+                macro_rules! emit {
+                    ($level:expr) => {
+                        event!(target: "ere_container", $level, container = %container, zkvm = %zkvm_kind, job, "{line}")
+                    };
+                }
+                match line_level(&line).unwrap_or(default) {
+                    Level::ERROR => emit!(Level::ERROR),
+                    Level::WARN => emit!(Level::WARN),
+                    Level::INFO => emit!(Level::INFO),
+                    Level::DEBUG => emit!(Level::DEBUG),
+                    Level::TRACE => emit!(Level::TRACE),
+                }
+            }
+        })
+    }
+
+    let container = container.to_string();
+    let job = job.map(str::to_string);
+    let stdout = child
+        .stdout
+        .take()
+        .map(|stdout| forward(stdout, container.clone(), zkvm_kind, job.clone(), false));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| forward(stderr, container, zkvm_kind, job, true));
+    stdout.into_iter().chain(stderr).collect()
+}
+
+/// Returns the level of a `line` logged by `tracing_subscriber::fmt` (e.g.
+/// `2025-01-01T00:00:00.000000Z  INFO ere_server: listening`), possibly with ANSI colors, or
+/// `None` if it has no level within the first tokens, e.g. a panic message.
+fn line_level(line: &str) -> Option<Level> {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skips the escape sequence up to its final letter, e.g. `\x1b[32m`.
+            chars.by_ref().find(char::is_ascii_alphabetic);
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+        .split_whitespace()
+        .take(3)
+        .find_map(|token| match token.trim_matches(['[', ']']) {
+            "ERROR" => Some(Level::ERROR),
+            "WARN" => Some(Level::WARN),
+            "INFO" => Some(Level::INFO),
+            "DEBUG" => Some(Level::DEBUG),
+            "TRACE" => Some(Level::TRACE),
+            _ => None,
+        })
+}
+
+/// Returns the host port TCP `port` of `container` is published on, `None` if it's not published
+/// (yet).
+pub fn docker_published_port(
+    container: impl AsRef<str>,
+    port: u16,
+) -> Result<Option<u16>, CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args(["port", container.as_ref(), &format!("{port}/tcp")])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    // `docker port` fails if the port is not published, and prints one line per interface (e.g.
+    // `0.0.0.0:32768` and `[::]:32768`) otherwise.
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.rsplit_once(':')?.1.trim().parse().ok()))
+}
+
+/// Returns the short form of the container id as shown by `docker ps`.
+fn short_id(id: &str) -> &str {
+    id.get(..12).unwrap_or(id)
+}
+
+/// Runs `cmd` to completion with its output forwarded by [`forward_logs`].
+fn status_with_logs(
+    cmd: &mut Command,
+    container: &str,
+    zkvm_kind: zkVMKind,
+    job: Option<&str>,
+) -> io::Result<ExitStatus> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let threads = forward_logs(&mut child, container, zkvm_kind, job);
+    let status = child.wait()?;
+    for thread in threads {
+        let _ = thread.join();
+    }
+    Ok(status)
+}
+
 pub fn remove_docker_container(container: impl AsRef<str>) -> Result<(), CommonError> {
-    let mut cmd = Command::new("docker");
+    let mut cmd = docker_cmd()?;
     let output = cmd
         .args(["rm", "-f", container.as_ref()])
         .output()
@@ -274,8 +614,101 @@ pub fn remove_docker_container(container: impl AsRef<str>) -> Result<(), CommonE
     Ok(())
 }
 
+/// Local image listed by [`docker_list_images`].
+pub struct DockerImage {
+    /// Name in format of `{repository}:{tag}`.
+    pub name: String,
+    pub id: String,
+    /// Creation time in RFC 3339 format.
+    pub created: String,
+    /// Size in bytes, including layers shared with other images.
+    pub size: u64,
+}
+
+/// Lists local images with a tag whose `{repository}:{tag}` name matches `filter`.
+pub fn docker_list_images(filter: impl Fn(&str) -> bool) -> Result<Vec<DockerImage>, CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args(["image", "ls", "--format", "{{.Repository}}:{{.Tag}}"])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+    let names = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.ends_with(":<none>") && filter(name))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            "{{.Id}}\t{{.Created}}\t{{.Size}}",
+        ])
+        .args(&names)
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+    Ok(names
+        .into_iter()
+        .zip(String::from_utf8_lossy(&output.stdout).lines())
+        .map(|(name, line)| {
+            let mut fields = line.split('\t');
+            let id = fields.next().unwrap_or_default().to_string();
+            let created = fields.next().unwrap_or_default().to_string();
+            let size = fields
+                .next()
+                .and_then(|size| size.parse().ok())
+                .unwrap_or(0);
+            DockerImage {
+                name,
+                id,
+                created,
+                size,
+            }
+        })
+        .collect())
+}
+
+/// Removes the tag `image`, and the image itself if it has no other tags.
+pub fn docker_remove_image(image: impl AsRef<str>) -> Result<(), CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args(["image", "rm", image.as_ref()])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    Ok(())
+}
+
 pub fn docker_pull_image(image: impl AsRef<str>) -> Result<(), CommonError> {
-    let mut cmd = Command::new("docker");
+    let mut cmd = docker_cmd()?;
     let output = cmd
         .args(["image", "pull", image.as_ref()])
         .stdout(Stdio::inherit())
@@ -293,8 +726,48 @@ pub fn docker_pull_image(image: impl AsRef<str>) -> Result<(), CommonError> {
     Ok(())
 }
 
+pub fn docker_tag_image(
+    source: impl AsRef<str>,
+    target: impl AsRef<str>,
+) -> Result<(), CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args(["image", "tag", source.as_ref(), target.as_ref()])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    Ok(())
+}
+
+pub fn docker_push_image(image: impl AsRef<str>) -> Result<(), CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args(["image", "push", image.as_ref()])
+        .stdout(Stdio::inherit())
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    Ok(())
+}
+
 pub fn docker_image_exists(image: impl AsRef<str>) -> Result<bool, CommonError> {
-    let mut cmd = Command::new("docker");
+    let mut cmd = docker_cmd()?;
     let output = cmd
         .args(["images", "--quiet", image.as_ref()])
         .output()
@@ -312,10 +785,125 @@ pub fn docker_image_exists(image: impl AsRef<str>) -> Result<bool, CommonError>
     Ok(!output.stdout.is_empty())
 }
 
+/// Returns the value of label `key` of the local `image`, `None` if the label is not set.
+pub fn docker_image_label(
+    image: impl AsRef<str>,
+    key: impl AsRef<str>,
+) -> Result<Option<String>, CommonError> {
+    let format = format!("{{{{ index .Config.Labels {:?} }}}}", key.as_ref());
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args(["image", "inspect", "--format", &format, image.as_ref()])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    // Missing label is printed as `<no value>`.
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((!value.is_empty() && value != "<no value>").then_some(value))
+}
+
+/// Saves `images` into a tar archive at `path`.
+pub fn docker_save_images(
+    images: impl IntoIterator<Item: AsRef<str>>,
+    path: impl AsRef<Path>,
+) -> Result<(), CommonError> {
+    let mut cmd = docker_cmd()?;
+    cmd.arg("image")
+        .arg("save")
+        .arg("--output")
+        .arg(path.as_ref())
+        .args(images.into_iter().map(to_string));
+    let output = cmd
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    Ok(())
+}
+
+/// Loads images from a tar archive at `path` saved by [`docker_save_images`].
+pub fn docker_load_images(path: impl AsRef<Path>) -> Result<(), CommonError> {
+    let mut cmd = docker_cmd()?;
+    cmd.arg("image")
+        .arg("load")
+        .arg("--input")
+        .arg(path.as_ref());
+    let output = cmd
+        .stdout(Stdio::inherit())
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    Ok(())
+}
+
+/// Returns the id and the value of label `key` of a running container that has all the `labels`,
+/// if any.
+pub fn docker_find_running_container(
+    labels: &[(&str, &str)],
+    key: &str,
+) -> Result<Option<(String, String)>, CommonError> {
+    let mut cmd = docker_cmd()?;
+    cmd.args(["ps", "--filter", "status=running"]);
+    for (label, value) in labels {
+        cmd.args(["--filter", &format!("label={label}={value}")]);
+    }
+    let output = cmd
+        .args(["--format", &format!("{{{{.ID}}}} {{{{.Label \"{key}\"}}}}")])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().and_then(|line| {
+        let (id, value) = line.split_once(' ')?;
+        Some((id.to_string(), value.to_string()))
+    }))
+}
+
 #[derive(Debug)]
 pub struct ContainerExitInfo {
     pub exit_code: i32,
     pub oom_killed: bool,
+    /// Last lines of the container logs, e.g. the panic message or CUDA error before a crash.
+    pub last_logs: Vec<String>,
+}
+
+impl ContainerExitInfo {
+    /// Returns whether the container was killed for running out of memory, by the flag or by
+    /// exit code `137` of `SIGKILL` (e.g. killed by the kernel OOM killer of the host).
+    pub fn is_oom_killed(&self) -> bool {
+        self.oom_killed || self.exit_code == 137
+    }
 }
 
 impl Display for ContainerExitInfo {
@@ -324,14 +912,37 @@ impl Display for ContainerExitInfo {
         if self.oom_killed {
             write!(f, ", OOM killed")?;
         }
+        if !self.last_logs.is_empty() {
+            write!(f, ", last logs:")?;
+            for line in &self.last_logs {
+                write!(f, "\n  {line}")?;
+            }
+        }
         Ok(())
     }
 }
 
+/// Returns `true` if the container is running, `false` if it has exited or doesn't exist.
+pub fn docker_container_running(container_id: impl AsRef<str>) -> Result<bool, CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args([
+            "inspect",
+            "--format",
+            "{{.State.Running}}",
+            container_id.as_ref(),
+        ])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    // `docker inspect` fails if the container doesn't exist (e.g. removed by `--rm`).
+    Ok(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true")
+}
+
 pub fn docker_inspect_exit_info(
     container_id: impl AsRef<str>,
 ) -> Result<ContainerExitInfo, CommonError> {
-    let mut cmd = Command::new("docker");
+    let mut cmd = docker_cmd()?;
     let output = cmd
         .args([
             "inspect",
@@ -354,20 +965,74 @@ pub fn docker_inspect_exit_info(
     let mut parts = stdout.split_whitespace();
     let exit_code = parts.next().and_then(|s| s.parse().ok()).unwrap_or(-1);
     let oom_killed = parts.next().is_some_and(|s| s == "true");
+    let last_logs = docker_logs_tail(container_id, CONTAINER_EXIT_LOG_LINES).unwrap_or_default();
 
     Ok(ContainerExitInfo {
         exit_code,
         oom_killed,
+        last_logs,
     })
 }
 
+/// Number of last lines of the container logs kept in [`ContainerExitInfo`].
+const CONTAINER_EXIT_LOG_LINES: usize = 20;
+
+/// Returns the last `lines` lines of the container logs, stdout and stderr interleaved in the order
+/// they are written.
+pub fn docker_logs_tail(
+    container_id: impl AsRef<str>,
+    lines: usize,
+) -> Result<Vec<String>, CommonError> {
+    let mut cmd = docker_cmd()?;
+    let output = cmd
+        .args([
+            "logs",
+            "--timestamps",
+            "--tail",
+            &lines.to_string(),
+            container_id.as_ref(),
+        ])
+        .output()
+        .map_err(|err| CommonError::command(&cmd, err))?;
+
+    if !output.status.success() {
+        Err(CommonError::command_exit_non_zero(
+            &cmd,
+            output.status,
+            Some(&output),
+        ))?
+    }
+
+    Ok(interleave_logs(
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+        lines,
+    ))
+}
+
+/// Returns the last `lines` lines of `stdout` and `stderr` of `docker logs --timestamps` in the
+/// order of the timestamps, which are stripped.
+fn interleave_logs(stdout: &str, stderr: &str, lines: usize) -> Vec<String> {
+    // Timestamps are in RFC 3339 format of fixed width, so they are ordered as strings.
+    let mut logs = stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(|line| line.split_once(' ').unwrap_or((line, "")))
+        .collect::<Vec<_>>();
+    logs.sort_by_key(|(timestamp, _)| *timestamp);
+    logs[logs.len().saturating_sub(lines)..]
+        .iter()
+        .map(|(_, line)| line.to_string())
+        .collect()
+}
+
 pub async fn docker_wait_for_exit(
     container_id: impl AsRef<str>,
     timeout: Duration,
 ) -> Option<ContainerExitInfo> {
     let container_id = container_id.as_ref();
     let result = tokio::time::timeout(timeout, async {
-        tokio::process::Command::new("docker")
+        tokio::process::Command::from(docker_cmd().ok()?)
             .arg("wait")
             .arg(container_id)
             .output()
@@ -381,6 +1046,96 @@ pub async fn docker_wait_for_exit(
     }
 }
 
+/// Returns the CDI devices of `--gpus` value `devices`, e.g. `all` or `"device=0,1"`.
+fn cdi_gpu_devices(devices: &str) -> Vec<String> {
+    let devices = devices.trim_matches('"');
+    match devices.strip_prefix("device=") {
+        Some(ids) => ids
+            .split(',')
+            .map(|id| format!("nvidia.com/gpu={}", id.trim()))
+            .collect(),
+        None => vec![format!("nvidia.com/gpu={devices}")],
+    }
+}
+
 fn to_string(s: impl AsRef<str>) -> String {
     s.as_ref().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tracing::Level;
+
+    use crate::util::docker::{DockerRunCmd, cdi_gpu_devices, interleave_logs, line_level};
+
+    #[test]
+    fn test_secret_env() {
+        let cmd = DockerRunCmd::new("image".to_string())
+            .unwrap()
+            .secret_env("ERE_AUTH_TOKEN", "secret")
+            .unwrap()
+            .secret_env("OTHER", "value")
+            .unwrap();
+        let args = cmd
+            .options
+            .iter()
+            .flat_map(|option| option.to_args())
+            .collect::<Vec<_>>();
+        assert!(args.iter().all(|arg| !arg.contains("secret")));
+        let env_file = cmd.env_file.as_ref().unwrap().path();
+        assert_eq!(args, ["--env-file", &*env_file.to_string_lossy()]);
+        assert_eq!(
+            fs::read_to_string(env_file).unwrap(),
+            "ERE_AUTH_TOKEN=secret\nOTHER=value\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(env_file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        assert!(
+            DockerRunCmd::new("image".to_string())
+                .unwrap()
+                .secret_env("ERE_AUTH_TOKEN", "a\nb")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cdi_gpu_devices() {
+        assert_eq!(cdi_gpu_devices("all"), ["nvidia.com/gpu=all"]);
+        assert_eq!(
+            cdi_gpu_devices("\"device=0,1\""),
+            ["nvidia.com/gpu=0", "nvidia.com/gpu=1"]
+        );
+        assert_eq!(cdi_gpu_devices("device=2"), ["nvidia.com/gpu=2"]);
+    }
+
+    #[test]
+    fn test_line_level() {
+        let line = "2025-01-01T00:00:00.000000Z ERROR ere_server: prove failed";
+        assert_eq!(line_level(line), Some(Level::ERROR));
+        let line = "\x1b[2m2025-01-01T00:00:00Z\x1b[0m \x1b[32m INFO\x1b[0m ere_server: listening";
+        assert_eq!(line_level(line), Some(Level::INFO));
+        let line = "[2025-01-01T00:00:00Z DEBUG sp1_core] shard 0";
+        assert_eq!(line_level(line), Some(Level::DEBUG));
+        let line = "thread 'main' panicked at src/main.rs:1:1:";
+        assert_eq!(line_level(line), None);
+    }
+
+    #[test]
+    fn test_interleave_logs() {
+        let stdout = "2025-01-01T00:00:00.100000000Z proving\n\
+                      2025-01-01T00:00:00.300000000Z shard 1\n";
+        let stderr = "2025-01-01T00:00:00.200000000Z warning\n\
+                      2025-01-01T00:00:00.400000000Z panicked\n";
+        assert_eq!(
+            interleave_logs(stdout, stderr, 3),
+            ["warning", "shard 1", "panicked"]
+        );
+    }
+}
@@ -1,32 +1,107 @@
-use std::env;
+use ere_prover_core::{
+    CommonError,
+    config::{Config, ImageLayout},
+};
+use ere_server_client::url::Url;
 
-pub const ERE_IMAGE_REGISTRY: &str = "ERE_IMAGE_REGISTRY";
-pub const ERE_FORCE_REBUILD_DOCKER_IMAGE: &str = "ERE_FORCE_REBUILD_DOCKER_IMAGE";
-pub const ERE_GPU_DEVICES: &str = "ERE_GPU_DEVICES";
-pub const ERE_DOCKER_NETWORK: &str = "ERE_DOCKER_NETWORK";
+/// Returns [`Config::global`], which fails if the configuration is invalid.
+pub fn config() -> Result<&'static Config, CommonError> {
+    Ok(Config::global()?)
+}
 
-/// Returns image registry from env variable `ERE_IMAGE_REGISTRY`.
+/// Returns image registry from env variable `ERE_IMAGE_REGISTRY` or [`Config`].
 ///
 /// If env varialbe is valid, it will be prepended to all images. For example
 /// if `ERE_IMAGE_REGISTRY=ghcr.io/eth-act/ere`, the [`base_image`] will return
 /// `ghcr.io/eth-act/ere/ere-base:{image_tag}`.
 ///
 /// [`base_image`]: crate::image::base_image
-pub fn image_registry() -> Option<String> {
-    env::var(ERE_IMAGE_REGISTRY).ok()
+pub fn image_registry() -> Result<Option<String>, CommonError> {
+    Ok(config()?.docker.image_registry.clone())
+}
+
+/// Returns whether env variable `ERE_FORCE_REBUILD_DOCKER_IMAGE` is set or not, or the value in
+/// [`Config`].
+pub fn force_rebuild_docker_image() -> Result<bool, CommonError> {
+    Ok(config()?.docker.force_rebuild_image)
+}
+
+/// Returns env variable `ERE_DOCKER_NETWORK` or the value in [`Config`].
+pub fn docker_network() -> Result<Option<String>, CommonError> {
+    Ok(config()?.docker.network.clone())
+}
+
+/// Returns env variable `ERE_DOCKER_BUILD_PARALLELISM` or the value in [`Config`], defaults to 2
+/// since image builds are CPU and memory heavy.
+pub fn build_parallelism() -> Result<usize, CommonError> {
+    Ok(config()?.docker.build_parallelism.unwrap_or(2).max(1))
 }
 
-/// Returns whether env variable `ERE_FORCE_REBUILD_DOCKER_IMAGE` is set or not.
-pub fn force_rebuild_docker_image() -> bool {
-    env::var_os(ERE_FORCE_REBUILD_DOCKER_IMAGE).is_some()
+/// Returns env variable `ERE_IMAGE_LAYOUT` or the value in [`Config`].
+pub fn image_layout() -> Result<ImageLayout, CommonError> {
+    Ok(config()?.docker.image_layout)
 }
 
-/// Returns env variable `ERE_GPU_DEVICES`.
-pub fn gpu_devices() -> Option<String> {
-    env::var(ERE_GPU_DEVICES).ok()
+/// Returns the build args of `docker/Dockerfile.base` overriding the images `ere-base` is built
+/// on, from env variables `ERE_BASE_IMAGE` and `ERE_BASE_CUDA_IMAGE` or the values in [`Config`].
+pub fn base_image_build_args() -> Result<Vec<(&'static str, String)>, CommonError> {
+    let docker = &config()?.docker;
+    Ok([
+        ("BASE_IMAGE", &docker.base_image),
+        ("BASE_CUDA_IMAGE", &docker.base_cuda_image),
+    ]
+    .into_iter()
+    .filter_map(|(key, image)| Some((key, image.clone()?)))
+    .collect())
 }
 
-/// Returns env variable `ERE_DOCKER_NETWORK`.
-pub fn docker_network() -> Option<String> {
-    env::var(ERE_DOCKER_NETWORK).ok()
+/// Returns the build args of the `Dockerfile.server` and `Dockerfile.compiler` of zkVMs
+/// overriding the images they run on, from env variables `ERE_RUNTIME_IMAGE` and
+/// `ERE_RUNTIME_CUDA_IMAGE` or the values in [`Config`].
+pub fn runtime_image_build_args() -> Result<Vec<(&'static str, String)>, CommonError> {
+    let docker = &config()?.docker;
+    Ok([
+        ("RUNTIME_IMAGE", &docker.runtime_image),
+        ("RUNTIME_CUDA_IMAGE", &docker.runtime_cuda_image),
+    ]
+    .into_iter()
+    .filter_map(|(key, image)| Some((key, image.clone()?)))
+    .collect())
+}
+
+/// Returns the host name of the Docker daemon if it's remote, from env variable `DOCKER_HOST` or
+/// the value in [`Config`].
+pub fn remote_docker_host() -> Result<Option<String>, CommonError> {
+    let host = config()?.docker.host.as_deref();
+    Ok(host.and_then(parse_remote_docker_host))
+}
+
+/// Returns the host name of `docker_host` with scheme `tcp`, `ssh`, `http` or `https`, unless
+/// it's the localhost.
+fn parse_remote_docker_host(docker_host: &str) -> Option<String> {
+    let url = Url::parse(docker_host).ok()?;
+    let host = url.host_str()?;
+    match (url.scheme(), host) {
+        (_, "localhost" | "127.0.0.1" | "[::1]") => None,
+        ("tcp" | "ssh" | "http" | "https", _) => Some(host.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::env::parse_remote_docker_host;
+
+    #[test]
+    fn test_parse_remote_docker_host() {
+        let parse = |docker_host| parse_remote_docker_host(docker_host);
+        assert_eq!(
+            parse("tcp://gpu-server:2376").as_deref(),
+            Some("gpu-server")
+        );
+        assert_eq!(parse("ssh://user@10.0.0.2").as_deref(), Some("10.0.0.2"));
+        assert_eq!(parse("tcp://[fd00::2]:2376").as_deref(), Some("[fd00::2]"));
+        assert_eq!(parse("tcp://127.0.0.1:2375"), None);
+        assert_eq!(parse("unix:///var/run/docker.sock"), None);
+    }
 }
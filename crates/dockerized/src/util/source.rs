@@ -0,0 +1,155 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use ere_prover_core::CommonError;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::{
+    util::{
+        docker::docker_image_label,
+        env::{base_image_build_args, runtime_image_build_args},
+        workspace_dir,
+    },
+    zkVMKind,
+};
+
+/// Label of the digest of the sources an image is built from, see [`is_source_stale`].
+pub const SOURCE_DIGEST_LABEL: &str = "ere.source-digest";
+
+/// Sources of `ere-server-{zkvm}` and `ere-compiler-{zkvm}` images, relative to the workspace.
+///
+/// `Cargo.lock` is included so dependency bumps also rebuild the images.
+const SOURCES: &[&str] = &["docker", "scripts", "crates", "Cargo.toml", "Cargo.lock"];
+
+/// Returns the digest of the sources of `ere-base`, which are its Dockerfile and the scripts.
+pub fn base_source_digest() -> Result<String, CommonError> {
+    digest(&["docker/Dockerfile.base", "scripts"])
+}
+
+/// Returns the digest of the sources of `ere-base-{zkvm_kind}`, which are the sources of
+/// `ere-base` and its Dockerfile, so SDK installation is not redone when only crate sources change.
+pub fn base_zkvm_source_digest(zkvm_kind: zkVMKind) -> Result<String, CommonError> {
+    let dockerfile = format!("docker/{}/Dockerfile.base", zkvm_kind.as_str());
+    digest(&["docker/Dockerfile.base", "scripts", &dockerfile])
+}
+
+/// Returns the digest of the Dockerfiles, scripts, crate sources and lockfile, computed once per
+/// process.
+pub fn source_digest() -> Result<&'static str, CommonError> {
+    static DIGEST: OnceLock<String> = OnceLock::new();
+    if let Some(digest) = DIGEST.get() {
+        return Ok(digest);
+    }
+    let digest = digest(SOURCES)?;
+    Ok(DIGEST.get_or_init(|| digest))
+}
+
+/// Returns `true` if the local `image` is built from sources other than `digest` (e.g. a
+/// Dockerfile is modified since), so it should be rebuilt.
+///
+/// Images without [`SOURCE_DIGEST_LABEL`] (e.g. built by older versions) are assumed to be
+/// up-to-date.
+pub fn is_source_stale(image: &str, digest: &str) -> Result<bool, CommonError> {
+    let Some(label) = docker_image_label(image, SOURCE_DIGEST_LABEL)? else {
+        return Ok(false);
+    };
+    let stale = label != digest;
+    if stale {
+        warn!("Image {image} is built from other sources ({label}), rebuilding");
+    }
+    Ok(stale)
+}
+
+/// Returns the digest of `sources`, and the overridden images `ere-base` is built on and the
+/// images run on if any, since all images are built on top of `ere-base`.
+fn digest(sources: &[&str]) -> Result<String, CommonError> {
+    let digest = digest_paths(&workspace_dir()?, sources)
+        .map_err(|err| CommonError::io("Failed to digest sources of images", err))?;
+    let images = [base_image_build_args()?, runtime_image_build_args()?].concat();
+    if images.is_empty() {
+        return Ok(digest);
+    }
+    let mut hasher = Sha256::new().chain_update(digest);
+    for (key, image) in images {
+        hasher.update(format!("{key}={image}\0"));
+    }
+    Ok(hex(hasher))
+}
+
+/// Returns the hex SHA-256 digest of the relative path and content of each file under `sources`
+/// in `root`, in sorted order. Hidden entries and `target` directories are skipped.
+fn digest_paths(root: &Path, sources: &[&str]) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    for source in sources {
+        collect_files(&root.join(source), &mut files)?;
+    }
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative = file.strip_prefix(root).unwrap_or(&file);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(fs::read(&file)?);
+        hasher.update([0]);
+    }
+    Ok(hex(hasher))
+}
+
+fn hex(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    if !path.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+        collect_files(&path, files)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use crate::util::source::digest_paths;
+
+    #[test]
+    fn test_digest_paths() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("docker/sp1")).unwrap();
+        fs::create_dir_all(dir.path().join("docker/target")).unwrap();
+        fs::write(dir.path().join("docker/sp1/Dockerfile.base"), "FROM a").unwrap();
+        fs::write(dir.path().join("docker/target/ignored"), "ignored").unwrap();
+
+        let digest = digest_paths(dir.path(), &["docker", "missing"]).unwrap();
+        assert_eq!(digest.len(), 64);
+
+        fs::write(dir.path().join("docker/target/ignored"), "changed").unwrap();
+        assert_eq!(digest_paths(dir.path(), &["docker"]).unwrap(), digest);
+
+        fs::write(dir.path().join("docker/sp1/Dockerfile.base"), "FROM b").unwrap();
+        assert_ne!(digest_paths(dir.path(), &["docker"]).unwrap(), digest);
+    }
+}
@@ -6,6 +6,6 @@ extern crate alloc;
 mod platform;
 
 pub use airbender;
-pub use ere_platform_core::Platform;
+pub use ere_platform_core::{Channels, Platform};
 
 pub use crate::platform::AirbenderPlatform;
@@ -0,0 +1,89 @@
+/// Named channels framed in the whole input, written by host via `Input::with_channel`.
+///
+/// Each channel is framed as:
+///
+/// - name length as u32 LE
+/// - name in UTF-8
+/// - data length as u32 LE
+/// - data
+///
+/// Channels are looked up by name, so the guest program doesn't depend on the order they are
+/// written by host.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let input = P::read_input();
+/// let channels = Channels::new(&input);
+/// let witness = channels.get("witness").expect("witness channel");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Channels<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Channels<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input }
+    }
+
+    /// Returns data of the first channel named `name`.
+    ///
+    /// Returns `None` if there is no such channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input is not validly framed.
+    pub fn get(&self, name: &str) -> Option<&'a [u8]> {
+        self.iter()
+            .find(|(channel, _)| *channel == name.as_bytes())
+            .map(|(_, data)| data)
+    }
+
+    /// Returns an iterator over name and data of the channels in written order.
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics if the input is not validly framed.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a [u8], &'a [u8])> {
+        let mut rest = self.input;
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let name = split_frame(&mut rest);
+            let data = split_frame(&mut rest);
+            Some((name, data))
+        })
+    }
+}
+
+fn split_frame<'a>(rest: &mut &'a [u8]) -> &'a [u8] {
+    let (len, tail) = rest
+        .split_first_chunk::<4>()
+        .expect("Invalid channel frame");
+    let len = u32::from_le_bytes(*len) as usize;
+    assert!(tail.len() >= len, "Invalid channel frame");
+    let (frame, tail) = tail.split_at(len);
+    *rest = tail;
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel::Channels;
+
+    #[test]
+    fn test_channels() {
+        #[rustfmt::skip]
+        let input = [
+            1, 0, 0, 0, b'a', 2, 0, 0, 0, 1, 2,
+            7, 0, 0, 0, b'w', b'i', b't', b'n', b'e', b's', b's', 0, 0, 0, 0,
+        ];
+        let channels = Channels::new(&input);
+        assert_eq!(channels.get("a"), Some([1, 2].as_slice()));
+        assert_eq!(channels.get("witness"), Some([].as_slice()));
+        assert_eq!(channels.get("b"), None);
+        assert_eq!(channels.iter().count(), 2);
+    }
+}
@@ -1,5 +1,6 @@
 #![no_std]
 
+mod channel;
 mod platform;
 
-pub use crate::platform::Platform;
+pub use crate::{channel::Channels, platform::Platform};
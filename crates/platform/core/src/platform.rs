@@ -35,11 +35,32 @@ pub trait Platform {
         unsafe { zkvm_io::write_output(output.as_ptr(), output.len()) };
     }
 
+    /// Reads the value of `key` from the key-value store set by host via `Input::with_kv_store`.
+    ///
+    /// Values are served on demand via the zkVM hint mechanism, so large stores (e.g. state
+    /// trie nodes) don't need to be decoded upfront. Values are unconstrained, the guest must
+    /// verify them against a commitment (e.g. trie root).
+    ///
+    /// Note that this function will return `None` if the platform doesn't support.
+    #[inline]
+    fn read_kv(_key: &[u8]) -> Option<impl Deref<Target = [u8]>> {
+        None::<&[u8]>
+    }
+
     /// Prints a message to the host environment.
     ///
     /// Note that this function will be a no-op if the platform doesn't support.
     fn print(message: &str);
 
+    /// Writes auxiliary output to host, which is not committed as public values.
+    ///
+    /// It's intended for debugging data (e.g. intermediate roots), and could be called multiple
+    /// times, the data will be concatenated in `ProgramExecutionReport::auxiliary_output`.
+    ///
+    /// Note that this function will be a no-op if the platform doesn't support.
+    #[inline]
+    fn write_auxiliary_output(_data: &[u8]) {}
+
     /// Returns the current cycle count.
     ///
     /// Note that this function will return `0` if the platform doesn't support.
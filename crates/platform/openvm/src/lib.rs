@@ -5,7 +5,7 @@ extern crate alloc;
 
 mod platform;
 
-pub use ere_platform_core::Platform;
+pub use ere_platform_core::{Channels, Platform};
 pub use openvm;
 
 pub use crate::platform::OpenVMPlatform;
@@ -22,6 +22,13 @@ impl Platform for OpenVMPlatform {
         openvm::io::reveal_bytes32(from_fn(|i| output.get(i).copied().unwrap_or(0)));
     }
 
+    /// Reads the value of `key` hinted by host. Note that OpenVM has no way to hint an absent
+    /// key, so the execution fails if `key` is not in the key-value store.
+    fn read_kv(key: &[u8]) -> Option<impl Deref<Target = [u8]>> {
+        openvm::io::hint_load_by_key(key);
+        Some(openvm::io::read_vec())
+    }
+
     fn print(message: &str) {
         openvm::io::print(message)
     }
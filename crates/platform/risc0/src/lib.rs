@@ -5,7 +5,7 @@ extern crate alloc;
 
 mod platform;
 
-pub use ere_platform_core::Platform;
+pub use ere_platform_core::{Channels, Platform};
 pub use risc0_zkvm;
 
-pub use crate::platform::Risc0Platform;
+pub use crate::platform::{AUXILIARY_OUTPUT_FD, Risc0Platform};
@@ -2,8 +2,15 @@ use alloc::vec;
 use core::ops::Deref;
 
 use ere_platform_core::Platform;
-use risc0_zkvm::guest::env::Write;
-use risc0_zkvm_platform as _;
+use risc0_zkvm::guest::env::{FdWriter, Write};
+use risc0_zkvm_platform::syscall::SyscallName;
+
+/// File descriptor the guest writes auxiliary output to, which `ere-prover-risc0` captures.
+pub const AUXILIARY_OUTPUT_FD: u32 = 10;
+
+/// Syscall for key-value store reads, must match the IO callback the host registers.
+const KV_READ: SyscallName =
+    unsafe { SyscallName::from_bytes_with_nul(c"ere_kv_read".as_ptr().cast()) };
 
 /// Risc0 [`Platform`] implementation.
 pub struct Risc0Platform;
@@ -20,14 +27,28 @@ impl Platform for Risc0Platform {
         input
     }
 
+    /// Commits `output` to the journal as-is, which is decoded on host by
+    /// `Risc0Verifier::verify_and_decode_journal` of `ere-verifier-risc0`.
     fn write_output(output: &[u8]) {
         risc0_zkvm::guest::env::commit_slice(output);
     }
 
+    fn read_kv(key: &[u8]) -> Option<impl Deref<Target = [u8]>> {
+        // Host responds with a leading byte of whether the key is present.
+        match risc0_zkvm::guest::env::send_recv_slice::<u8, u8>(KV_READ, key).split_first() {
+            Some((1, value)) => Some(value),
+            _ => None,
+        }
+    }
+
     fn print(message: &str) {
         risc0_zkvm::guest::env::stdout().write_slice(message.as_bytes());
     }
 
+    fn write_auxiliary_output(data: &[u8]) {
+        FdWriter::new(AUXILIARY_OUTPUT_FD, |_| {}).write_slice(data);
+    }
+
     fn cycle_count() -> u64 {
         risc0_zkvm::guest::env::cycle_count()
     }
@@ -3,7 +3,7 @@
 
 mod platform;
 
-pub use ere_platform_core::Platform;
+pub use ere_platform_core::{Channels, Platform};
 pub use ziskos;
 
-pub use crate::platform::ZiskPlatform;
+pub use crate::platform::{ZiskOutputHashedPlatform, ZiskPlatform};
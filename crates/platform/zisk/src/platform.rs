@@ -1,13 +1,17 @@
 #![allow(unexpected_cfgs)]
 
+use core::ops::Deref;
+
 use ere_platform_core::Platform;
+use sha2::{Digest, Sha256};
 
 /// ZisK [`Platform`] implementation.
 ///
 /// `read_input` and `write_output` are inherited from the trait's default
 /// implementation, which calls [zkvm-standards] FFI symbols exported by `ziskos`.
 ///
-/// Note that ZisK enforces a 256-byte output cap at the runtime level.
+/// Note that ZisK enforces a 256-byte output cap at the runtime level, see
+/// [`ZiskOutputHashedPlatform`] for larger outputs.
 ///
 /// [zkvm-standards]: https://github.com/eth-act/zkvm-standards
 pub struct ZiskPlatform;
@@ -50,6 +54,36 @@ impl Platform for ZiskPlatform {
     }
 }
 
+/// ZisK [`Platform`] implementation that commits the SHA-256 digest of the output instead of the
+/// output itself, so outputs of any size can be written regardless of the 256-byte cap.
+///
+/// The host checks the digest against the full output supplied out-of-band by
+/// `ZiskVerifier::verify_with_output`. Other methods are the same as [`ZiskPlatform`].
+pub struct ZiskOutputHashedPlatform;
+
+impl Platform for ZiskOutputHashedPlatform {
+    fn read_input() -> impl Deref<Target = [u8]> {
+        ZiskPlatform::read_input()
+    }
+
+    fn write_output(output: &[u8]) {
+        let digest = Sha256::digest(output);
+        ZiskPlatform::write_output(&digest);
+    }
+
+    fn print(message: &str) {
+        ZiskPlatform::print(message)
+    }
+
+    fn cycle_scope_start(name: &str) {
+        ZiskPlatform::cycle_scope_start(name)
+    }
+
+    fn cycle_scope_end(name: &str) {
+        ZiskPlatform::cycle_scope_end(name)
+    }
+}
+
 unsafe extern "C" {
     /// POSIX-style `write` syscall exported by `ziskos`.
     fn sys_write(fd: u32, write_ptr: *const u8, nbytes: usize);
@@ -1,5 +1,5 @@
 use airbender_host::HostError;
-use ere_prover_core::CommonError;
+use ere_prover_core::{CommonError, ErrorKind, zkVMError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -25,3 +25,17 @@ pub enum Error {
     #[error(transparent)]
     Verifier(#[from] ere_verifier_airbender::Error),
 }
+
+impl zkVMError for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommonError(err) => err.kind(),
+            Self::ExecutionDidNotTerminate | Self::ExecutePanic(_) => ErrorKind::guest_panic(self),
+            Self::Sdk(err) => ErrorKind::from_source(err, ErrorKind::ProverInfrastructure),
+            Self::CudaFeatureDisabled | Self::CpuProverNotAvailable => {
+                ErrorKind::ProverInfrastructure
+            }
+            Self::Verifier(_) => ErrorKind::VerificationFailed,
+        }
+    }
+}
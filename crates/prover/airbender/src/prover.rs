@@ -16,9 +16,11 @@ use airbender_host::{ExecutionResult, Runner, TranspilerRunner, TranspilerRunner
 use airbender_host::{GpuProver, GpuProverBuilder, Proof, ProveResult, Prover as _};
 use airbender_riscv_transpiler::cycle::IMStandardIsaConfigWithUnsignedMulDiv;
 use ere_compiler_core::Elf;
+#[cfg(feature = "cuda")]
+use ere_prover_core::ExecutionArtifact;
 use ere_prover_core::{
-    CommonError, Input, ProgramExecutionReport, ProgramProvingReport, ProverResource,
-    ProverResourceKind, PublicValues, zkVMProver,
+    CommonError, ExecuteOptions, ExecutionDebugInfo, Input, ProgramExecutionReport,
+    ProgramProvingReport, ProverResource, ProverResourceKind, PublicValues, zkVMProver,
 };
 use ere_verifier_airbender::{
     AirbenderProgramVk, AirbenderProof, AirbenderVerifier, UNROLLED_END_PARAMS, unified_end_params,
@@ -29,6 +31,14 @@ use tempfile::tempdir;
 
 use crate::error::Error;
 
+/// Maximum input length, since `AirbenderPlatform::read_input` collects stdin on the guest heap,
+/// which is `_heap_size` of 768 MiB by the linker script of `ere-compiler-airbender`. Input
+/// shorter could still run out of heap along with the other allocations of the program.
+const MAX_INPUT_LEN: usize = 768 << 20;
+
+/// Maximum output length, which is returned in the 8 registers `a0` to `a7`.
+const MAX_OUTPUT_LEN: usize = 32;
+
 pub struct AirbenderProver {
     verifier: AirbenderVerifier,
     resource: ProverResource,
@@ -79,12 +89,28 @@ impl zkVMProver for AirbenderProver {
         &self.verifier
     }
 
-    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+    fn max_input_len(&self) -> Option<usize> {
+        Some(MAX_INPUT_LEN)
+    }
+
+    fn max_output_len(&self) -> Option<usize> {
+        Some(MAX_OUTPUT_LEN)
+    }
+
+    fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        options.check_profile_unsupported()?;
         if input.proofs.is_some() {
             Err(CommonError::unsupported_input("no dedicated proofs stream"))?
         }
+        if input.kv_store.is_some() {
+            Err(CommonError::unsupported_input("no key-value store hint"))?
+        }
 
-        let input_words = input_to_words(input.stdin());
+        let input_words = input_to_words(input)?;
 
         let start = Instant::now();
         let ExecutionResult {
@@ -100,11 +126,14 @@ impl zkVMProver for AirbenderProver {
             return Err(Error::ExecutionDidNotTerminate);
         }
 
+        options.check_cycle_limit(cycles_executed as u64)?;
+
         Ok((
             words_to_le_bytes(receipt.output).into(),
             ProgramExecutionReport {
                 total_num_cycles: cycles_executed as u64,
                 execution_duration,
+                debug: Some(output_registers(&receipt.output)),
                 ..Default::default()
             },
         ))
@@ -138,16 +167,58 @@ impl zkVMProver for AirbenderProver {
         if input.proofs.is_some() {
             Err(CommonError::unsupported_input("no dedicated proofs stream"))?
         }
+        if input.kv_store.is_some() {
+            Err(CommonError::unsupported_input("no key-value store hint"))?
+        }
 
-        let gpu_prover = self.gpu_prover.as_ref().unwrap();
-        let input_words = input_to_words(input.stdin());
+        let input_words = input_to_words(input)?;
 
         // Pre-flight via the interpreter to avoid the gpu prover `panic_nounwind`.
         panic::catch_unwind(AssertUnwindSafe(|| self.runner.run(&input_words)))
             .map_err(|err| Error::ExecutePanic(panic_msg(err)))??;
 
+        self.prove_words(input, &input_words)
+    }
+
+    #[cfg(feature = "cuda")]
+    fn execute_for_proving(&self, input: &Input) -> Result<ExecutionArtifact, Error> {
+        let (public_values, report) = self.execute(input)?;
+        let input_words = input_to_words(input)?;
+        Ok(ExecutionArtifact::new(input.clone(), public_values, report)
+            .with_state(ExecutedInputWords(input_words)))
+    }
+
+    #[cfg(feature = "cuda")]
+    fn prove_from_execution(
+        &self,
+        artifact: &ExecutionArtifact,
+    ) -> Result<(PublicValues, AirbenderProof, ProgramProvingReport), Error> {
+        // The execution already served as the pre-flight, so prove directly.
+        match artifact.state::<ExecutedInputWords>() {
+            Some(ExecutedInputWords(input_words)) if self.resource == ProverResource::Gpu => {
+                self.prove_words(artifact.input(), input_words)
+            }
+            _ => self.prove(artifact.input()),
+        }
+    }
+}
+
+/// Input words of an [`ExecutionArtifact`] created by [`AirbenderProver::execute_for_proving`],
+/// whose execution is known to reach the end.
+#[cfg(feature = "cuda")]
+struct ExecutedInputWords(Vec<u32>);
+
+#[cfg(feature = "cuda")]
+impl AirbenderProver {
+    fn prove_words(
+        &self,
+        input: &Input,
+        input_words: &[u32],
+    ) -> Result<(PublicValues, AirbenderProof, ProgramProvingReport), Error> {
+        let gpu_prover = self.gpu_prover.as_ref().unwrap();
+
         let start = Instant::now();
-        let (proof, receipt, cycles) = match gpu_prover.prove(&input_words)? {
+        let (proof, receipt, cycles) = match gpu_prover.prove(input_words)? {
             ProveResult {
                 proof: Proof::Real(proof),
                 receipt,
@@ -167,11 +238,30 @@ impl zkVMProver for AirbenderProver {
             ProgramProvingReport {
                 proving_time,
                 total_num_cycles: Some(cycles),
-            },
+                ..Default::default()
+            }
+            .with_input_commitment(input),
         ))
     }
 }
 
+/// Returns the final values of registers `a0` to `a7`, which carry the output words on exit.
+///
+/// The SDK returns them as the 8 output words, which are registers `x10` to `x17` set by
+/// `exit_success`, so word `i` is named by its ABI name `a{i}` as documented by
+/// [`ExecutionDebugInfo::registers`]. The rest of the register file and the CSRs are not exposed
+/// by the execution result.
+fn output_registers(output: &[u32]) -> ExecutionDebugInfo {
+    ExecutionDebugInfo {
+        registers: output
+            .iter()
+            .enumerate()
+            .map(|(i, word)| (format!("a{i}"), *word as u64))
+            .collect(),
+        ..Default::default()
+    }
+}
+
 /// Compute the [`AirbenderProgramVk`] for the given guest binary.
 ///
 /// Computes the base-layer setup of the guest with [`compute_setup_for_machine_configuration`] and
@@ -267,14 +357,16 @@ fn cache_dir() -> PathBuf {
 ///
 /// The first word is the byte length, which `AirbenderPlatform::read_input` reads to size the
 /// payload.
-fn input_to_words(stdin: &[u8]) -> Vec<u32> {
-    core::iter::once(stdin.len() as u32)
+fn input_to_words(input: &Input) -> Result<Vec<u32>, CommonError> {
+    input.check_len(Some(MAX_INPUT_LEN))?;
+    let stdin = input.stdin()?;
+    Ok(core::iter::once(stdin.len() as u32)
         .chain(stdin.chunks(4).map(|chunk| {
             let mut padded = [0u8; 4];
             padded[..chunk.len()].copy_from_slice(chunk);
             u32::from_le_bytes(padded)
         }))
-        .collect()
+        .collect())
 }
 
 fn panic_msg(err: Box<dyn Any + Send + 'static>) -> String {
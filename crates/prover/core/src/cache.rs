@@ -0,0 +1,45 @@
+use std::{fs, io, path::Path};
+
+use tempfile::NamedTempFile;
+
+/// Writes an entry of an on-disk cache at `path` by `write`, which is given the path of a
+/// temporary file in the same directory to write into, then renamed to `path`, so concurrent
+/// readers never see a partial entry.
+///
+/// The parent directory is created if missing, and the temporary file is removed on failure.
+pub fn write_atomic<E: From<io::Error>>(
+    path: &Path,
+    write: impl FnOnce(&Path) -> Result<(), E>,
+) -> Result<(), E> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+    let file = NamedTempFile::new_in(dir)?;
+    write(file.path())?;
+    file.persist(path).map_err(io::Error::from)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::write_atomic;
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("entry.bin");
+
+        write_atomic(&path, |tmp_path| fs::write(tmp_path, b"entry")).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"entry");
+
+        let err = std::io::Error::other("failed");
+        let result = write_atomic(&path, |tmp_path| {
+            fs::write(tmp_path, b"partial")?;
+            Err(err)
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"entry");
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,525 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+use crate::{CommonError, ProverResource, ProverResourceKind};
+
+/// Env variable of the path to the configuration file.
+pub const ERE_CONFIG: &str = "ERE_CONFIG";
+
+/// Configuration file loaded from the current directory if `ERE_CONFIG` is not set.
+pub const DEFAULT_CONFIG_FILE: &str = "ere.toml";
+
+pub const ERE_PROVER_RESOURCE: &str = "ERE_PROVER_RESOURCE";
+pub const ERE_IMAGE_REGISTRY: &str = "ERE_IMAGE_REGISTRY";
+pub const ERE_FORCE_REBUILD_DOCKER_IMAGE: &str = "ERE_FORCE_REBUILD_DOCKER_IMAGE";
+pub const ERE_GPU_DEVICES: &str = "ERE_GPU_DEVICES";
+pub const ERE_DOCKER_NETWORK: &str = "ERE_DOCKER_NETWORK";
+pub const ERE_DOCKER_BUILD_PARALLELISM: &str = "ERE_DOCKER_BUILD_PARALLELISM";
+pub const ERE_CONTAINER_RUNTIME: &str = "ERE_CONTAINER_RUNTIME";
+pub const ERE_IMAGE_LAYOUT: &str = "ERE_IMAGE_LAYOUT";
+pub const ERE_BASE_IMAGE: &str = "ERE_BASE_IMAGE";
+pub const ERE_BASE_CUDA_IMAGE: &str = "ERE_BASE_CUDA_IMAGE";
+pub const ERE_RUNTIME_IMAGE: &str = "ERE_RUNTIME_IMAGE";
+pub const ERE_RUNTIME_CUDA_IMAGE: &str = "ERE_RUNTIME_CUDA_IMAGE";
+pub const ERE_CONTAINER_MEMORY_LIMIT: &str = "ERE_CONTAINER_MEMORY_LIMIT";
+pub const ERE_CONTAINER_CPUS: &str = "ERE_CONTAINER_CPUS";
+pub const ERE_CONTAINER_SHM_SIZE: &str = "ERE_CONTAINER_SHM_SIZE";
+pub const ERE_CONTAINER_USER: &str = "ERE_CONTAINER_USER";
+pub const ERE_CONTAINER_SECCOMP_PROFILE: &str = "ERE_CONTAINER_SECCOMP_PROFILE";
+pub const ERE_CONTAINER_APPARMOR_PROFILE: &str = "ERE_CONTAINER_APPARMOR_PROFILE";
+pub const ERE_CONTAINER_CAP_DROP: &str = "ERE_CONTAINER_CAP_DROP";
+pub const DOCKER_HOST: &str = "DOCKER_HOST";
+pub const DOCKER_TLS_VERIFY: &str = "DOCKER_TLS_VERIFY";
+pub const DOCKER_CERT_PATH: &str = "DOCKER_CERT_PATH";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    CommonError(#[from] CommonError),
+
+    #[error("Failed to parse config file {path:?}: {err}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        err: toml::de::Error,
+    },
+
+    #[error("Invalid env variable {key}={value:?}: {err}")]
+    InvalidEnv {
+        key: &'static str,
+        value: OsString,
+        err: String,
+    },
+}
+
+/// Configuration of Ere, layered from (in increasing precedence):
+///
+/// 1. Configuration file at `ERE_CONFIG`, or `ere.toml` in the current directory if exists
+/// 2. Environment variables
+/// 3. Programmatic overrides by `with_*` methods
+///
+/// The configuration used by `ere-dockerized` and the zkVM provers is [`Config::global`], which
+/// could be replaced by [`Config::install`] at startup.
+///
+/// # Example
+///
+/// ```toml
+/// [resource]
+/// kind = "gpu"
+///
+/// [docker]
+/// image_registry = "ghcr.io/eth-act/ere"
+/// network = "ere"
+///
+/// [container]
+/// memory_limit = "64g"
+/// ulimits = { nofile = "65536:65536" }
+///
+/// [tuning]
+/// ERE_RISC0_SEGMENT_PO2 = "21"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Prover resource of applications that don't take one explicitly, e.g.
+    /// `DockerizedzkVM::from_config` (`ERE_PROVER_RESOURCE`, which only selects `cpu` or `gpu`,
+    /// or the kind of the one in the file).
+    pub resource: ProverResource,
+    /// Docker images and containers of `ere-dockerized`.
+    pub docker: DockerConfig,
+    /// Resource limits and security options of the containers of `ere-dockerized`.
+    pub container: ContainerDefaults,
+    /// Tuning knobs of zkVMs keyed by their env variable (e.g. `ERE_RISC0_SEGMENT_PO2`), which
+    /// are overridden by the env variables themselves. See [`Config::var`].
+    pub tuning: BTreeMap<String, String>,
+    /// Tuning knobs set by [`Config::with_tuning`], which take precedence over env variables.
+    #[serde(skip)]
+    tuning_overrides: BTreeMap<String, String>,
+}
+
+/// Docker configuration, each field is overridden by the env variable in its doc.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DockerConfig {
+    /// Registry to pull images from (`ERE_IMAGE_REGISTRY`).
+    pub image_registry: Option<String>,
+    /// Rebuild images even if they exist (`ERE_FORCE_REBUILD_DOCKER_IMAGE`).
+    pub force_rebuild_image: bool,
+    /// GPU devices passed to `--gpus` (`ERE_GPU_DEVICES`).
+    pub gpu_devices: Option<String>,
+    /// Network the `ere-server` containers join (`ERE_DOCKER_NETWORK`).
+    pub network: Option<String>,
+    /// Maximum number of zkVMs to build images for concurrently (`ERE_DOCKER_BUILD_PARALLELISM`).
+    pub build_parallelism: Option<usize>,
+    /// Container runtime to run images with (`ERE_CONTAINER_RUNTIME`).
+    pub runtime: ContainerRuntime,
+    /// Layout of the zkVM base images (`ERE_IMAGE_LAYOUT`).
+    pub image_layout: ImageLayout,
+    /// Image to build `ere-base` on instead of `ubuntu:24.04` (`ERE_BASE_IMAGE`), e.g. an
+    /// internal hardened Ubuntu. It must be Debian based with `apt-get`.
+    pub base_image: Option<String>,
+    /// Image to build `ere-base` with GPU support on instead of
+    /// `nvidia/cuda:12.9.1-devel-ubuntu24.04` (`ERE_BASE_CUDA_IMAGE`), e.g. a CUDA version
+    /// matching the driver. It must be Debian based with `apt-get` and the CUDA toolkit.
+    pub base_cuda_image: Option<String>,
+    /// Image the `ere-server` and compiler images run on instead of `ubuntu:24.04`
+    /// (`ERE_RUNTIME_IMAGE`). It must be Debian based with `apt-get`.
+    pub runtime_image: Option<String>,
+    /// Image the `ere-server` images with GPU support run on instead of
+    /// `nvidia/cuda:12.9.1-runtime-ubuntu24.04` (`ERE_RUNTIME_CUDA_IMAGE`). It must be Debian
+    /// based with `apt-get` and the CUDA runtime.
+    pub runtime_cuda_image: Option<String>,
+    /// Docker daemon to connect to, e.g. `tcp://gpu-server:2376` (`DOCKER_HOST`).
+    pub host: Option<String>,
+    /// Verify the TLS certificate of the Docker daemon (`DOCKER_TLS_VERIFY`).
+    pub tls_verify: bool,
+    /// Directory of the TLS client certificates (`DOCKER_CERT_PATH`).
+    pub cert_path: Option<PathBuf>,
+}
+
+/// Resource limits and security options of the `ere-server` and compiler containers, each field
+/// overridden by the env variable in its doc, and by the `ContainerConfig` of `ere-dockerized`
+/// field by field.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContainerDefaults {
+    /// Memory limit passed to `--memory`, e.g. `64g` (`ERE_CONTAINER_MEMORY_LIMIT`).
+    pub memory_limit: Option<String>,
+    /// Number of CPUs passed to `--cpus`, e.g. `8.5` (`ERE_CONTAINER_CPUS`).
+    pub cpus: Option<String>,
+    /// Size of `/dev/shm` passed to `--shm-size`, e.g. `64g` (`ERE_CONTAINER_SHM_SIZE`).
+    pub shm_size: Option<String>,
+    /// Limits passed to `--ulimit` by name, e.g. `memlock` to `-1:-1`, only set by the file.
+    pub ulimits: BTreeMap<String, String>,
+    /// User to run the containers as, `host` for the UID and GID of the user running the process
+    /// (`ERE_CONTAINER_USER`).
+    pub user: Option<String>,
+    /// Seccomp profile passed to `--security-opt seccomp=` (`ERE_CONTAINER_SECCOMP_PROFILE`).
+    pub seccomp_profile: Option<String>,
+    /// AppArmor profile passed to `--security-opt apparmor=` (`ERE_CONTAINER_APPARMOR_PROFILE`).
+    pub apparmor_profile: Option<String>,
+    /// Capabilities passed to `--cap-drop`, comma-separated in `ERE_CONTAINER_CAP_DROP`.
+    pub cap_drop: Vec<String>,
+}
+
+/// Container runtime of `ere-dockerized`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumString,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    /// Daemonless runtime, which could run rootless where the Docker daemon is unavailable (e.g.
+    /// HPC clusters).
+    Podman,
+}
+
+/// Layout of the `ere-base-{zkvm}` images the compiler and server images are built from.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumString,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ImageLayout {
+    /// Compiler and server images are built from the same `ere-base-{zkvm}`, which has both the
+    /// compile-time and runtime dependencies.
+    #[default]
+    Shared,
+    /// Compiler images are built from `ere-base-{zkvm}-compiler` with only the compile-time
+    /// dependencies, which skips runtime ones like proving keys (e.g. ZisK and OpenVM), so hosts
+    /// that only compile build faster and take less disk. Tags of existing images are unchanged.
+    Split,
+}
+
+impl ContainerRuntime {
+    /// Returns the CLI binary of the runtime.
+    pub fn binary(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the configuration file and overrides it with env variables.
+    ///
+    /// Returns error if the file fails to parse, `ERE_CONFIG` is set but the file doesn't exist,
+    /// or an env variable is invalid.
+    pub fn load() -> Result<Self, ConfigError> {
+        let config = match env::var_os(ERE_CONFIG) {
+            Some(path) => Self::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Self::from_file(DEFAULT_CONFIG_FILE)?
+            }
+            None => Self::default(),
+        };
+        config.with_env_overrides()
+    }
+
+    /// Installs the configuration as [`Config::global`], or returns it back if one is already
+    /// installed or loaded by an earlier [`Config::global`].
+    pub fn install(self) -> Result<(), Box<Self>> {
+        GLOBAL.set(self).map_err(Box::new)
+    }
+
+    /// Returns the configuration [`Config::install`]ed, or [`Config::load`]ed once for the
+    /// process if none.
+    ///
+    /// Returns error if loading fails, which is retried by the next call.
+    pub fn global() -> Result<&'static Self, ConfigError> {
+        if let Some(config) = GLOBAL.get() {
+            return Ok(config);
+        }
+        let config = Self::load()?;
+        Ok(GLOBAL.get_or_init(|| config))
+    }
+
+    /// Parses the configuration file at `path` without env variable overrides.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content =
+            fs::read_to_string(path).map_err(|err| CommonError::read_file("config", path, err))?;
+        toml::from_str(&content).map_err(|err| ConfigError::Parse {
+            path: path.to_path_buf(),
+            err,
+        })
+    }
+
+    /// Overrides fields with the env variables that are set.
+    ///
+    /// Returns error if an env variable fails to parse.
+    pub fn with_env_overrides(self) -> Result<Self, ConfigError> {
+        self.with_overrides(|key| env::var_os(key))
+    }
+
+    /// Overrides fields with the variables returned by `var`.
+    fn with_overrides(
+        mut self,
+        var: impl Fn(&str) -> Option<OsString>,
+    ) -> Result<Self, ConfigError> {
+        if let Some(kind) = parse_var::<ProverResourceKind>(&var, ERE_PROVER_RESOURCE)? {
+            self.resource = match kind {
+                ProverResourceKind::Cpu => ProverResource::Cpu,
+                ProverResourceKind::Gpu => ProverResource::Gpu,
+                // The remote prover config is only set by the file.
+                kind if self.resource.kind() == kind => self.resource,
+                _ => {
+                    return Err(ConfigError::InvalidEnv {
+                        key: ERE_PROVER_RESOURCE,
+                        value: var(ERE_PROVER_RESOURCE).unwrap_or_default(),
+                        err: "remote prover config is not set by the file".to_string(),
+                    });
+                }
+            };
+        }
+
+        let docker = &mut self.docker;
+        if let Some(image_registry) = parse_var(&var, ERE_IMAGE_REGISTRY)? {
+            docker.image_registry = Some(image_registry);
+        }
+        if var(ERE_FORCE_REBUILD_DOCKER_IMAGE).is_some() {
+            docker.force_rebuild_image = true;
+        }
+        if let Some(gpu_devices) = parse_var(&var, ERE_GPU_DEVICES)? {
+            docker.gpu_devices = Some(gpu_devices);
+        }
+        if let Some(network) = parse_var(&var, ERE_DOCKER_NETWORK)? {
+            docker.network = Some(network);
+        }
+        if let Some(build_parallelism) = parse_var(&var, ERE_DOCKER_BUILD_PARALLELISM)? {
+            docker.build_parallelism = Some(build_parallelism);
+        }
+        if let Some(image_layout) = parse_var(&var, ERE_IMAGE_LAYOUT)? {
+            docker.image_layout = image_layout;
+        }
+        if let Some(runtime) = parse_var(&var, ERE_CONTAINER_RUNTIME)? {
+            docker.runtime = runtime;
+        }
+        if let Some(base_image) = parse_var(&var, ERE_BASE_IMAGE)? {
+            docker.base_image = Some(base_image);
+        }
+        if let Some(base_cuda_image) = parse_var(&var, ERE_BASE_CUDA_IMAGE)? {
+            docker.base_cuda_image = Some(base_cuda_image);
+        }
+        if let Some(runtime_image) = parse_var(&var, ERE_RUNTIME_IMAGE)? {
+            docker.runtime_image = Some(runtime_image);
+        }
+        if let Some(runtime_cuda_image) = parse_var(&var, ERE_RUNTIME_CUDA_IMAGE)? {
+            docker.runtime_cuda_image = Some(runtime_cuda_image);
+        }
+        if let Some(host) = parse_var(&var, DOCKER_HOST)? {
+            docker.host = Some(host);
+        }
+        // Docker treats any non-empty value as enabled.
+        if var(DOCKER_TLS_VERIFY).is_some_and(|value| !value.is_empty()) {
+            docker.tls_verify = true;
+        }
+        if let Some(cert_path) = var(DOCKER_CERT_PATH) {
+            docker.cert_path = Some(cert_path.into());
+        }
+
+        let container = &mut self.container;
+        if let Some(memory_limit) = parse_var(&var, ERE_CONTAINER_MEMORY_LIMIT)? {
+            container.memory_limit = Some(memory_limit);
+        }
+        if let Some(cpus) = parse_var::<f64>(&var, ERE_CONTAINER_CPUS)? {
+            container.cpus = Some(cpus.to_string());
+        }
+        if let Some(shm_size) = parse_var(&var, ERE_CONTAINER_SHM_SIZE)? {
+            container.shm_size = Some(shm_size);
+        }
+        if let Some(user) = parse_var(&var, ERE_CONTAINER_USER)? {
+            container.user = Some(user);
+        }
+        if let Some(profile) = parse_var(&var, ERE_CONTAINER_SECCOMP_PROFILE)? {
+            container.seccomp_profile = Some(profile);
+        }
+        if let Some(profile) = parse_var(&var, ERE_CONTAINER_APPARMOR_PROFILE)? {
+            container.apparmor_profile = Some(profile);
+        }
+        if let Some(cap_drop) = parse_var::<String>(&var, ERE_CONTAINER_CAP_DROP)? {
+            container.cap_drop = cap_drop
+                .split(',')
+                .map(str::trim)
+                .filter(|capability| !capability.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        Ok(self)
+    }
+
+    pub fn with_resource(mut self, resource: ProverResource) -> Self {
+        self.resource = resource;
+        self
+    }
+
+    pub fn with_docker(mut self, docker: DockerConfig) -> Self {
+        self.docker = docker;
+        self
+    }
+
+    pub fn with_container(mut self, container: ContainerDefaults) -> Self {
+        self.container = container;
+        self
+    }
+
+    /// Sets tuning knob `key` to `value`, which takes precedence over the env variable.
+    pub fn with_tuning(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tuning_overrides.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the tuning knob `key` set by [`Config::with_tuning`], or env variable `key`, or the
+    /// tuning knob `key` in the configuration file, in order.
+    pub fn var(&self, key: &str) -> Option<String> {
+        self.tuning_overrides
+            .get(key)
+            .cloned()
+            .or_else(|| env::var(key).ok())
+            .or_else(|| self.tuning.get(key).cloned())
+    }
+}
+
+static GLOBAL: OnceLock<Config> = OnceLock::new();
+
+impl From<ConfigError> for CommonError {
+    fn from(err: ConfigError) -> Self {
+        match err {
+            ConfigError::CommonError(err) => err,
+            err => Self::Config(Box::new(err)),
+        }
+    }
+}
+
+/// Parses variable `key` returned by `var` if set.
+fn parse_var<T: FromStr<Err: fmt::Display>>(
+    var: impl Fn(&str) -> Option<OsString>,
+    key: &'static str,
+) -> Result<Option<T>, ConfigError> {
+    let Some(value) = var(key) else {
+        return Ok(None);
+    };
+    let parsed = match value.to_str() {
+        Some(str) => str.parse().map_err(|err: T::Err| err.to_string()),
+        None => Err("not UTF-8".to_string()),
+    };
+    parsed
+        .map(Some)
+        .map_err(|err| ConfigError::InvalidEnv { key, value, err })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ProverResource, RemoteProverConfig,
+        config::{
+            Config, ConfigError, ContainerDefaults, ContainerRuntime, DockerConfig,
+            ERE_CONTAINER_CAP_DROP, ERE_CONTAINER_CPUS, ERE_CONTAINER_RUNTIME,
+            ERE_DOCKER_BUILD_PARALLELISM, ERE_PROVER_RESOURCE, ImageLayout,
+        },
+    };
+
+    #[test]
+    fn test_parse() {
+        let config: Config = toml::from_str(
+            r#"
+            [resource]
+            kind = "cluster"
+            endpoint = "http://cluster:7000"
+
+            [docker]
+            image_registry = "ghcr.io/eth-act/ere"
+            runtime = "podman"
+            image_layout = "split"
+
+            [container]
+            memory_limit = "64g"
+            ulimits = { nofile = "65536:65536" }
+
+            [tuning]
+            ERE_TEST_CONFIG_KNOB = "21"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                resource: ProverResource::Cluster(RemoteProverConfig {
+                    endpoint: "http://cluster:7000".to_string(),
+                    ..Default::default()
+                }),
+                docker: DockerConfig {
+                    image_registry: Some("ghcr.io/eth-act/ere".to_string()),
+                    runtime: ContainerRuntime::Podman,
+                    image_layout: ImageLayout::Split,
+                    ..Default::default()
+                },
+                container: ContainerDefaults {
+                    memory_limit: Some("64g".to_string()),
+                    ulimits: [("nofile".to_string(), "65536:65536".to_string())].into(),
+                    ..Default::default()
+                },
+                tuning: [("ERE_TEST_CONFIG_KNOB".to_string(), "21".to_string())].into(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(config.var("ERE_TEST_CONFIG_KNOB").as_deref(), Some("21"));
+        assert_eq!(config.var("ERE_TEST_CONFIG_MISSING"), None);
+
+        let config = config.with_tuning("ERE_TEST_CONFIG_KNOB", "22");
+        assert_eq!(config.var("ERE_TEST_CONFIG_KNOB").as_deref(), Some("22"));
+
+        assert!(toml::from_str::<Config>("unknown = 1").is_err());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let overrides = |vars: Vec<(&'static str, &'static str)>| {
+            Config::default().with_overrides(move |key| {
+                vars.iter().find(|(k, _)| *k == key).map(|(_, v)| v.into())
+            })
+        };
+
+        let config = overrides(vec![
+            (ERE_PROVER_RESOURCE, "gpu"),
+            (ERE_CONTAINER_RUNTIME, "podman"),
+            (ERE_DOCKER_BUILD_PARALLELISM, "2"),
+            (ERE_CONTAINER_CPUS, "8.5"),
+            (ERE_CONTAINER_CAP_DROP, "NET_RAW, SYS_ADMIN"),
+        ])
+        .unwrap();
+        assert_eq!(config.resource, ProverResource::Gpu);
+        assert_eq!(config.docker.runtime, ContainerRuntime::Podman);
+        assert_eq!(config.docker.build_parallelism, Some(2));
+        assert_eq!(config.container.cpus.as_deref(), Some("8.5"));
+        assert_eq!(config.container.cap_drop, ["NET_RAW", "SYS_ADMIN"]);
+
+        for (key, value) in [
+            (ERE_PROVER_RESOURCE, "network"),
+            (ERE_CONTAINER_RUNTIME, "dokcer"),
+            (ERE_DOCKER_BUILD_PARALLELISM, "two"),
+            (ERE_CONTAINER_CPUS, "many"),
+        ] {
+            let err = overrides(vec![(key, value)]).unwrap_err();
+            assert!(matches!(err, ConfigError::InvalidEnv { key: k, .. } if k == key));
+        }
+    }
+}
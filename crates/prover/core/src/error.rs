@@ -1,12 +1,18 @@
 use std::{
-    io,
+    collections::TryReserveError,
+    error::Error as StdError,
+    io, iter,
     path::Path,
     process::{Command, ExitStatus, Output},
 };
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::resource::ProverResourceKind;
+use crate::{
+    ProofKind,
+    resource::{ProverResourceKind, RemoteProverOption},
+};
 
 #[derive(Debug, Error)]
 pub enum CommonError {
@@ -53,11 +59,33 @@ pub enum CommonError {
     #[error("Unsupported input: {0}")]
     UnsupportedInput(String),
 
+    #[error("Input of {len} bytes exceeds the maximum {max_len} bytes")]
+    InputTooLarge { len: usize, max_len: usize },
+
+    #[error("Cycle limit {max_cycles} exceeded")]
+    CycleLimitExceeded { max_cycles: u64 },
+
     #[error("Unsupported prover resource kind {unsupported:?}, expect one of {supported:?}")]
     UnsupportedProverResourceKind {
         unsupported: ProverResourceKind,
         supported: Vec<ProverResourceKind>,
     },
+
+    #[error("Unsupported proof kind {unsupported}, expect one of {supported:?}")]
+    UnsupportedProofKind {
+        unsupported: ProofKind,
+        supported: Vec<ProofKind>,
+    },
+
+    #[error("Unsupported remote prover option {unsupported}, expect one of {supported:?}")]
+    UnsupportedRemoteProverOption {
+        unsupported: RemoteProverOption,
+        supported: Vec<RemoteProverOption>,
+    },
+
+    #[cfg(feature = "config")]
+    #[error("Invalid configuration: {0}")]
+    Config(#[source] Box<crate::config::ConfigError>),
 }
 
 impl CommonError {
@@ -143,6 +171,14 @@ impl CommonError {
         Self::UnsupportedInput(reason.as_ref().to_string())
     }
 
+    pub fn input_too_large(len: usize, max_len: usize) -> Self {
+        Self::InputTooLarge { len, max_len }
+    }
+
+    pub fn cycle_limit_exceeded(max_cycles: u64) -> Self {
+        Self::CycleLimitExceeded { max_cycles }
+    }
+
     pub fn unsupported_prover_resource_kind(
         unsupported: ProverResourceKind,
         supported: impl IntoIterator<Item = ProverResourceKind>,
@@ -152,4 +188,152 @@ impl CommonError {
             supported: supported.into_iter().collect(),
         }
     }
+
+    pub fn unsupported_proof_kind(
+        unsupported: ProofKind,
+        supported: impl IntoIterator<Item = ProofKind>,
+    ) -> Self {
+        Self::UnsupportedProofKind {
+            unsupported,
+            supported: supported.into_iter().collect(),
+        }
+    }
+
+    pub fn unsupported_remote_prover_option(
+        unsupported: RemoteProverOption,
+        supported: impl IntoIterator<Item = RemoteProverOption>,
+    ) -> Self {
+        Self::UnsupportedRemoteProverOption {
+            unsupported,
+            supported: supported.into_iter().collect(),
+        }
+    }
+}
+
+impl zkVMError for CommonError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::UnsupportedInput(_) | Self::InputTooLarge { .. } => ErrorKind::InvalidInput,
+            Self::CycleLimitExceeded { .. } => ErrorKind::CycleLimitExceeded,
+            _ => ErrorKind::ProverInfrastructure,
+        }
+    }
+}
+
+/// Category of a zkVM failure, which tells a failure of the guest program apart from a failure of
+/// the prover itself.
+///
+/// It's sent over the server API encoded by `bincode`, which encodes the variant index, so new
+/// variants are appended at the end.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorKind {
+    /// The guest program panicked or exited with a non-success status.
+    GuestPanic { message: String },
+    /// The prover ran out of memory.
+    OutOfMemory,
+    /// The prover failed for reasons unrelated to the guest program, e.g. setup, IO, GPU or
+    /// network failure.
+    ProverInfrastructure,
+    /// The input is malformed or not supported.
+    InvalidInput,
+    /// The proof failed to verify.
+    VerificationFailed,
+    /// The guest program exceeded the cycle limit.
+    CycleLimitExceeded,
+    /// The prover failed temporarily, e.g. RPC or connection failure, and might succeed on retry.
+    Transient,
+}
+
+impl ErrorKind {
+    /// Classifies an SDK error by the typed errors in its source chain.
+    ///
+    /// Returns [`ErrorKind::OutOfMemory`] if the chain has an [`io::Error`] of
+    /// [`io::ErrorKind::OutOfMemory`] or a [`TryReserveError`], otherwise `fallback`.
+    pub fn from_source(err: &(dyn StdError + 'static), fallback: Self) -> Self {
+        let out_of_memory = iter::successors(Some(err), |&err| err.source()).any(|err| {
+            err.is::<TryReserveError>()
+                || err
+                    .downcast_ref::<io::Error>()
+                    .is_some_and(|err| err.kind() == io::ErrorKind::OutOfMemory)
+        });
+        if out_of_memory {
+            Self::OutOfMemory
+        } else {
+            fallback
+        }
+    }
+
+    /// Returns [`ErrorKind::GuestPanic`] with `message`.
+    pub fn guest_panic(message: impl ToString) -> Self {
+        Self::GuestPanic {
+            message: message.to_string(),
+        }
+    }
+
+    /// Returns whether the failure is temporary and the operation might succeed on retry.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Transient)
+    }
+
+    /// Returns whether the failure is caused by the guest program or its input, rather than the
+    /// prover.
+    pub fn is_guest_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::GuestPanic { .. } | Self::CycleLimitExceeded | Self::InvalidInput
+        )
+    }
+}
+
+/// Error of a zkVM that can be classified into an [`ErrorKind`].
+#[allow(non_camel_case_types)]
+pub trait zkVMError: core::error::Error {
+    /// Returns the category of the error.
+    fn kind(&self) -> ErrorKind;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_from_source() {
+        let fallback = ErrorKind::ProverInfrastructure;
+
+        let err = anyhow::Error::new(io::Error::from(io::ErrorKind::OutOfMemory))
+            .context("failed to allocate trace");
+        assert_eq!(
+            ErrorKind::from_source(err.as_ref(), fallback.clone()),
+            ErrorKind::OutOfMemory
+        );
+
+        let err = Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err();
+        assert_eq!(
+            ErrorKind::from_source(&err, fallback.clone()),
+            ErrorKind::OutOfMemory
+        );
+
+        // Messages are not matched.
+        let err = io::Error::other("CUDA error: out of memory");
+        assert_eq!(ErrorKind::from_source(&err, fallback.clone()), fallback);
+    }
+
+    #[test]
+    fn test_bincode_variant_index() {
+        // Variant indices are kept for servers and clients of other versions.
+        for (kind, index) in [
+            (ErrorKind::guest_panic(""), 0),
+            (ErrorKind::OutOfMemory, 1),
+            (ErrorKind::ProverInfrastructure, 2),
+            (ErrorKind::InvalidInput, 3),
+            (ErrorKind::VerificationFailed, 4),
+            (ErrorKind::CycleLimitExceeded, 5),
+            (ErrorKind::Transient, 6),
+        ] {
+            let bytes = bincode::serde::encode_to_vec(&kind, bincode::config::legacy()).unwrap();
+            assert_eq!(bytes[..4], (index as u32).to_le_bytes());
+        }
+    }
 }
@@ -0,0 +1,91 @@
+use core::{any::Any, fmt};
+
+use crate::{Input, ProgramExecutionReport, PublicValues};
+
+/// Result of [`zkVMProver::execute_for_proving`], which can be consumed by
+/// [`zkVMProver::prove_from_execution`] to avoid executing the program again when proving.
+///
+/// Besides the input, public values and report, it carries opaque zkVM specific state (e.g.
+/// trace, segments or checkpoint) that only the prover that created it knows how to use.
+///
+/// [`zkVMProver::execute_for_proving`]: crate::zkVMProver::execute_for_proving
+/// [`zkVMProver::prove_from_execution`]: crate::zkVMProver::prove_from_execution
+pub struct ExecutionArtifact {
+    input: Input,
+    public_values: PublicValues,
+    report: ProgramExecutionReport,
+    state: Option<Box<dyn Any + Send + Sync>>,
+}
+
+impl ExecutionArtifact {
+    pub fn new(input: Input, public_values: PublicValues, report: ProgramExecutionReport) -> Self {
+        Self {
+            input,
+            public_values,
+            report,
+            state: None,
+        }
+    }
+
+    /// Attaches zkVM specific state and returns a new `ExecutionArtifact`.
+    pub fn with_state<T: Any + Send + Sync>(mut self, state: T) -> Self {
+        self.state = Some(Box::new(state));
+        self
+    }
+
+    /// Returns a reference to the input the program was executed with.
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Returns a reference to the public values of the execution.
+    pub fn public_values(&self) -> &PublicValues {
+        &self.public_values
+    }
+
+    /// Returns a reference to the report of the execution.
+    pub fn report(&self) -> &ProgramExecutionReport {
+        &self.report
+    }
+
+    /// Returns a reference to the zkVM specific state if present and of type `T`.
+    pub fn state<T: Any>(&self) -> Option<&T> {
+        self.state.as_ref()?.downcast_ref()
+    }
+
+    /// Consumes the artifact and returns the input, public values and report.
+    pub fn into_parts(self) -> (Input, PublicValues, ProgramExecutionReport) {
+        (self.input, self.public_values, self.report)
+    }
+}
+
+impl fmt::Debug for ExecutionArtifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExecutionArtifact")
+            .field("input", &self.input)
+            .field("public_values", &self.public_values)
+            .field("report", &self.report)
+            .field("state", &self.state.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Input, ProgramExecutionReport, PublicValues, execution::ExecutionArtifact};
+
+    #[test]
+    fn test_state() {
+        let artifact = ExecutionArtifact::new(
+            Input::new(),
+            PublicValues::default(),
+            ProgramExecutionReport::new(42),
+        );
+        assert_eq!(artifact.state::<Vec<u32>>(), None);
+
+        let artifact = artifact.with_state(vec![1u32, 2, 3]);
+        assert_eq!(artifact.state::<Vec<u32>>(), Some(&vec![1, 2, 3]));
+        assert_eq!(artifact.state::<Vec<u8>>(), None);
+        assert_eq!(artifact.report().total_num_cycles, 42);
+    }
+}
@@ -1,12 +1,71 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
 use bincode::error::{DecodeError, EncodeError};
 use serde::{Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+
+use crate::CommonError;
+
+/// Magic prefix of the file written by [`Input::record_to`], with the format version.
+const RECORD_MAGIC: &[u8] = b"ERE-INPUT-V2";
+
+/// Key-value store set via [`Input::with_kv_store`].
+pub type KvStore = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Compression of [`Input::stdin`] when transported to a remote prover (e.g. `ere-server`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    Zstd,
+}
+
+/// File stdin is read from on demand instead of being held in memory, see
+/// [`Input::with_stdin_file`].
+#[derive(Clone, Debug)]
+pub struct StdinFile {
+    path: PathBuf,
+    len: u64,
+    /// Hasher of [`Input::commitment`] already updated with the stdin length and content, so the
+    /// file is not read again to commit to it.
+    hasher: Sha256,
+}
+
+impl StdinFile {
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the length of the file when it was set as stdin.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns whether the file was empty when it was set as stdin.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
 
 /// Input for the prover to execute/prove a guest program.
 #[derive(Clone, Debug, Default)]
 pub struct Input {
     pub stdin: Vec<u8>,
+    /// File to read stdin from instead of `stdin`, set via [`Input::with_stdin_file`].
+    pub stdin_file: Option<StdinFile>,
     /// Serialized proofs to be verified in guest program for proof composition.
     pub proofs: Option<Vec<u8>>,
+    /// Compression of stdin for transport, which is transparent to the guest program.
+    pub compression: Compression,
+    /// Serialized key-value store the guest reads lazily via `Platform::read_kv`.
+    pub kv_store: Option<Vec<u8>>,
 }
 
 impl Input {
@@ -14,13 +73,31 @@ impl Input {
     pub fn new() -> Self {
         Self {
             stdin: Vec::new(),
+            stdin_file: None,
             proofs: None,
+            compression: Compression::None,
+            kv_store: None,
+        }
+    }
+
+    /// Returns the stdin as a byte slice, which is read from the file set via
+    /// [`Input::with_stdin_file`] if any.
+    pub fn stdin(&self) -> Result<Cow<'_, [u8]>, CommonError> {
+        match &self.stdin_file {
+            Some(file) => fs::read(&file.path)
+                .map(Cow::Owned)
+                .map_err(|err| CommonError::read_file("input stdin", &file.path, err)),
+            None => Ok(Cow::Borrowed(&self.stdin)),
         }
     }
 
-    /// Returns a reference to the stdin as a byte slice.
-    pub fn stdin(&self) -> &[u8] {
-        &self.stdin
+    /// Returns the length of stdin, without reading the file set via
+    /// [`Input::with_stdin_file`] if any.
+    pub fn stdin_len(&self) -> u64 {
+        match &self.stdin_file {
+            Some(file) => file.len,
+            None => self.stdin.len() as u64,
+        }
     }
 
     /// Deserializes and returns the proofs if present.
@@ -37,11 +114,194 @@ impl Input {
         })
     }
 
+    /// Deserializes and returns the key-value store if present.
+    ///
+    /// # Returns
+    ///
+    /// - `None` if no key-value store is set
+    /// - `Some(Ok(..))` if the key-value store was successfully deserialized
+    /// - `Some(Err(..))` if deserialization failed
+    pub fn kv_store(&self) -> Option<Result<KvStore, DecodeError>> {
+        self.kv_store.as_ref().map(|kv_store| {
+            bincode::serde::decode_from_slice(kv_store, bincode::config::legacy())
+                .map(|(kv_store, _)| kv_store)
+        })
+    }
+
+    /// Returns the SHA-256 commitment to the input, which links a proof to the exact input that
+    /// produced it, see [`ProgramProvingReport::input_commitment`].
+    ///
+    /// It's computed over the canonical serialization below, which is independent of how each
+    /// zkVM frames the input:
+    ///
+    /// - stdin length as u64 LE
+    /// - stdin
+    /// - if proofs are present, serialized proofs length as u64 LE
+    /// - if proofs are present, serialized proofs
+    /// - if key-value store is present but proofs are not, `u64::MAX` as u64 LE
+    /// - if key-value store is present, serialized key-value store length as u64 LE
+    /// - if key-value store is present, serialized key-value store
+    ///
+    /// Compression is not committed since it's transparent to the guest program. Stdin set via
+    /// [`Input::with_stdin_file`] is committed as read when it was set.
+    ///
+    /// [`ProgramProvingReport::input_commitment`]: crate::ProgramProvingReport::input_commitment
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut hasher = match &self.stdin_file {
+            Some(file) => file.hasher.clone(),
+            None => {
+                let mut hasher = Sha256::new();
+                hasher.update((self.stdin.len() as u64).to_le_bytes());
+                hasher.update(&self.stdin);
+                hasher
+            }
+        };
+        if let Some(proofs) = &self.proofs {
+            hasher.update((proofs.len() as u64).to_le_bytes());
+            hasher.update(proofs);
+        }
+        if let Some(kv_store) = &self.kv_store {
+            if self.proofs.is_none() {
+                hasher.update(u64::MAX.to_le_bytes());
+            }
+            hasher.update((kv_store.len() as u64).to_le_bytes());
+            hasher.update(kv_store);
+        }
+        hasher.finalize().into()
+    }
+
+    /// Returns [`CommonError::InputTooLarge`] if stdin exceeds `max_len` bytes, see
+    /// `zkVMProver::max_input_len`.
+    pub fn check_len(&self, max_len: Option<usize>) -> Result<(), CommonError> {
+        let len = usize::try_from(self.stdin_len()).unwrap_or(usize::MAX);
+        match max_len {
+            Some(max_len) if len > max_len => Err(CommonError::input_too_large(len, max_len)),
+            _ => Ok(()),
+        }
+    }
+
     /// Sets stdin and returns a new `Input`.
     ///
     /// The guest reads these bytes via `Platform::read_input`.
     pub fn with_stdin(mut self, stdin: Vec<u8>) -> Self {
         self.stdin = stdin;
+        self.stdin_file = None;
+        self
+    }
+
+    /// Sets stdin to all bytes of `reader` read until EOF and returns a new `Input`.
+    ///
+    /// The bytes are read into stdin directly instead of being buffered elsewhere first, but stdin
+    /// is still held in memory, use [`Input::with_stdin_file`] for input too large for that.
+    pub fn with_stdin_from_reader(mut self, mut reader: impl Read) -> io::Result<Self> {
+        self.stdin.clear();
+        self.stdin_file = None;
+        reader.read_to_end(&mut self.stdin)?;
+        Ok(self)
+    }
+
+    /// Sets stdin to the file at `path` and returns a new `Input`.
+    ///
+    /// The file is read once in chunks to commit to it (see [`Input::commitment`]), but not held
+    /// in memory. `zkVMClient` (and so `DockerizedzkVM`) copies it into its input directory or
+    /// streams it to the server, while zkVM provers running in-process read it when
+    /// executing/proving. The file must not be modified until then.
+    pub fn with_stdin_file(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = fs::File::open(&path)?;
+        let len = file.metadata()?.len();
+        let mut hasher = Sha256::new();
+        hasher.update(len.to_le_bytes());
+        let mut buf = vec![0; 1 << 16];
+        let mut read = 0;
+        loop {
+            let n = match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            hasher.update(&buf[..n]);
+            read += n as u64;
+        }
+        if read != len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Input stdin file was modified while being read",
+            ));
+        }
+        self.stdin = Vec::new();
+        self.stdin_file = Some(StdinFile { path, len, hasher });
+        Ok(self)
+    }
+
+    /// Appends a channel named `name` with `data` to stdin and returns a new `Input`.
+    ///
+    /// The guest reads the channel by name via `Channels::get` on the input returned by
+    /// `Platform::read_input`, regardless of the order channels are written. Stdin should only
+    /// consist of channels once this is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `name` or `data` exceeds `u32::MAX`, or if stdin is set via
+    /// [`Input::with_stdin_file`].
+    pub fn with_channel(mut self, name: impl AsRef<str>, data: impl AsRef<[u8]>) -> Self {
+        assert!(
+            self.stdin_file.is_none(),
+            "Channel can't be appended to stdin file"
+        );
+        for frame in [name.as_ref().as_bytes(), data.as_ref()] {
+            let len = u32::try_from(frame.len()).expect("Channel frame length exceeds u32::MAX");
+            self.stdin.extend(len.to_le_bytes());
+            self.stdin.extend(frame);
+        }
+        self
+    }
+
+    /// Records stdin, proofs and key-value store to the file at `path`, which can be replayed
+    /// bit-for-bit by [`Input::replay_from`] (e.g. to reproduce a failing proof on another
+    /// machine or backend).
+    ///
+    /// Compression is not recorded since it's transparent to the guest program.
+    pub fn record_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let stdin = self.stdin().map_err(io::Error::other)?;
+        let record = (
+            stdin.as_ref(),
+            self.proofs.as_deref(),
+            self.kv_store.as_deref(),
+        );
+        let mut bytes = RECORD_MAGIC.to_vec();
+        bincode::serde::encode_into_std_write(record, &mut bytes, bincode::config::legacy())
+            .map_err(io::Error::other)?;
+        fs::write(path, bytes)
+    }
+
+    /// Replays an `Input` recorded by [`Input::record_to`] from the file at `path`.
+    pub fn replay_from(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let Some(record) = bytes.strip_prefix(RECORD_MAGIC) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid input record",
+            ));
+        };
+        let ((stdin, proofs, kv_store), _) =
+            bincode::serde::decode_from_slice(record, bincode::config::legacy())
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Self {
+            stdin,
+            proofs,
+            kv_store,
+            ..Default::default()
+        })
+    }
+
+    /// Sets compression of stdin for transport and returns a new `Input`.
+    ///
+    /// Large stdin (e.g. block witness) compresses well, which reduces the transfer time to a
+    /// remote prover. It has no effect for zkVM provers running in-process.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
         self
     }
 
@@ -64,4 +324,154 @@ impl Input {
         self.proofs = Some(proofs);
         self
     }
+
+    /// Serializes the given key-value store and returns a new `Input` with it set.
+    ///
+    /// Unlike stdin, values are not read upfront, but served on demand when the guest calls
+    /// `Platform::read_kv`, via the zkVM hint mechanism. Values are unconstrained, so the guest
+    /// must verify them (e.g. against a trie root).
+    ///
+    /// Consumes `self` and returns an error if serialization fails.
+    pub fn with_kv_store(mut self, kv_store: &KvStore) -> Result<Self, EncodeError> {
+        self.kv_store = Some(bincode::serde::encode_to_vec(
+            kv_store,
+            bincode::config::legacy(),
+        )?);
+        Ok(self)
+    }
+
+    /// Sets serialized key-value store and returns a new `Input`.
+    ///
+    /// The key-value store must be a [`KvStore`] serialized using
+    /// [`bincode::serde`] with [`bincode::config::legacy`] configuration.
+    pub fn with_serialized_kv_store(mut self, kv_store: Vec<u8>) -> Self {
+        self.kv_store = Some(kv_store);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{
+        CommonError,
+        input::{Compression, Input},
+    };
+
+    #[test]
+    fn test_check_len() {
+        let input = Input::new().with_stdin(vec![0; 4]);
+        assert!(input.check_len(None).is_ok());
+        assert!(input.check_len(Some(4)).is_ok());
+        assert!(matches!(
+            input.check_len(Some(3)),
+            Err(CommonError::InputTooLarge { len: 4, max_len: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_commitment() {
+        let input = Input::new()
+            .with_stdin(vec![1, 2, 3])
+            .with_serialized_proofs(vec![4, 5]);
+        assert_eq!(
+            input.commitment(),
+            [
+                0x72, 0x28, 0x0a, 0x40, 0x47, 0x6d, 0xe9, 0x13, 0xf6, 0x92, 0xd1, 0x18, 0x0d, 0x31,
+                0xfa, 0x2d, 0x15, 0x84, 0xf9, 0x9c, 0xda, 0x45, 0xc5, 0xd0, 0x28, 0x98, 0x2a, 0x81,
+                0x1f, 0xf6, 0x64, 0x08,
+            ]
+        );
+        assert_eq!(
+            input.commitment(),
+            input
+                .clone()
+                .with_compression(Compression::Zstd)
+                .commitment()
+        );
+        assert_ne!(
+            Input::new().commitment(),
+            Input::new().with_serialized_proofs(vec![]).commitment()
+        );
+        assert_ne!(
+            Input::new().with_serialized_proofs(vec![]).commitment(),
+            Input::new().with_serialized_kv_store(vec![]).commitment()
+        );
+    }
+
+    #[test]
+    fn test_record_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.bin");
+
+        for input in [
+            Input::new(),
+            Input::new()
+                .with_stdin(vec![1, 2, 3])
+                .with_serialized_proofs(vec![4, 5])
+                .with_serialized_kv_store(vec![6])
+                .with_compression(Compression::Zstd),
+        ] {
+            input.record_to(&path).unwrap();
+            let replayed = Input::replay_from(&path).unwrap();
+            assert_eq!(replayed.stdin, input.stdin);
+            assert_eq!(replayed.proofs, input.proofs);
+            assert_eq!(replayed.kv_store, input.kv_store);
+            assert_eq!(replayed.compression, Compression::None);
+        }
+
+        std::fs::write(&path, [1, 2, 3]).unwrap();
+        assert!(Input::replay_from(&path).is_err());
+    }
+
+    #[test]
+    fn test_stdin_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stdin.bin");
+        std::fs::write(&path, [1, 2, 3]).unwrap();
+
+        let input = Input::new()
+            .with_stdin_file(&path)
+            .unwrap()
+            .with_serialized_proofs(vec![4, 5]);
+        let expected = Input::new()
+            .with_stdin(vec![1, 2, 3])
+            .with_serialized_proofs(vec![4, 5]);
+        assert!(input.stdin.is_empty());
+        assert_eq!(input.stdin_file.as_ref().unwrap().path(), path);
+        assert_eq!(input.stdin_len(), 3);
+        assert_eq!(*input.stdin().unwrap(), [1, 2, 3]);
+        assert_eq!(input.commitment(), expected.commitment());
+        assert!(matches!(
+            input.check_len(Some(2)),
+            Err(CommonError::InputTooLarge { len: 3, max_len: 2 })
+        ));
+
+        let record = dir.path().join("input.bin");
+        input.record_to(&record).unwrap();
+        assert_eq!(Input::replay_from(&record).unwrap().stdin, [1, 2, 3]);
+
+        let input = input.with_stdin(vec![6]);
+        assert!(input.stdin_file.is_none());
+        assert_eq!(*input.stdin().unwrap(), [6]);
+
+        assert!(
+            Input::new()
+                .with_stdin_file(dir.path().join("missing"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_kv_store() {
+        assert!(Input::new().kv_store().is_none());
+
+        let kv_store = BTreeMap::from([(vec![1], vec![2, 3]), (vec![4, 5], vec![])]);
+        let input = Input::new().with_kv_store(&kv_store).unwrap();
+        assert_eq!(input.kv_store().unwrap().unwrap(), kv_store);
+
+        let input = Input::new().with_serialized_kv_store(vec![1]);
+        assert!(input.kv_store().unwrap().is_err());
+    }
 }
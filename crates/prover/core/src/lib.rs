@@ -1,18 +1,39 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod cache;
+#[cfg(feature = "config")]
+pub mod config;
 mod error;
+mod execution;
 mod input;
+mod options;
+mod proof_kind;
 mod prover;
 mod report;
 mod resource;
+mod retry;
 
 pub use ere_codec as codec;
-pub use ere_verifier_core::{PublicValues, zkVMVerifier};
+pub use ere_verifier_core::{
+    PublicValues, PublicValuesDecodeError, PublicValuesDecoder, PublicValuesEncoding,
+    TypedVerifier, TypedVerifyError, zkVMVerifier,
+};
 
 pub use crate::{
-    error::CommonError,
-    input::Input,
+    cache::write_atomic,
+    error::{CommonError, ErrorKind, zkVMError},
+    execution::ExecutionArtifact,
+    input::{Compression, Input, KvStore, StdinFile},
+    options::ExecuteOptions,
+    proof_kind::{ProofKind, ProofKindObjective, ProofKindPolicy, ProofKindSample, ProofKindStats},
     prover::{ProgramVk, Proof, zkVMProver},
-    report::{ProgramExecutionReport, ProgramProvingReport},
-    resource::{ProverResource, ProverResourceKind, RemoteProverConfig},
+    report::{
+        ExecutionDebugInfo, ProgramExecutionReport, ProgramProvingReport, ReportEncoding,
+        SchemaVersion, SelfTestReport,
+    },
+    resource::{
+        FulfillmentStrategy, ProverResource, ProverResourceKind, RemoteProverConfig,
+        RemoteProverOption,
+    },
+    retry::{Backoff, RetryConfig},
 };
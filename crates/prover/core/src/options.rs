@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::CommonError;
+
+/// Options of [`zkVMProver::execute_with_options`].
+///
+/// [`zkVMProver::execute_with_options`]: crate::zkVMProver::execute_with_options
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecuteOptions {
+    /// Maximum number of cycles the guest is allowed to execute. Execution fails with
+    /// [`CommonError::CycleLimitExceeded`] once exceeded, which some zkVMs only check after the
+    /// execution, see [`ProgramExecutionReport::total_num_cycles`].
+    ///
+    /// [`ProgramExecutionReport::total_num_cycles`]: crate::ProgramExecutionReport::total_num_cycles
+    pub max_cycles: Option<u64>,
+    /// Whether to profile the guest, and write the profile to
+    /// [`ProgramExecutionReport::profile_path`]. zkVMs unable to profile return
+    /// [`CommonError::UnsupportedInput`].
+    ///
+    /// Only supported natively, since the profile is written on the host running the zkVM, so
+    /// the server client (and so `ere-dockerized`) returns [`CommonError::UnsupportedInput`] too.
+    ///
+    /// [`ProgramExecutionReport::profile_path`]: crate::ProgramExecutionReport::profile_path
+    #[serde(default)]
+    pub profile: bool,
+}
+
+impl ExecuteOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = Some(max_cycles);
+        self
+    }
+
+    pub fn with_profile(mut self, profile: bool) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Returns [`CommonError::CycleLimitExceeded`] if `total_num_cycles` exceeds `max_cycles`.
+    ///
+    /// For zkVMs whose SDK is unable to abort execution early, this is checked after execution.
+    pub fn check_cycle_limit(&self, total_num_cycles: u64) -> Result<(), CommonError> {
+        match self.max_cycles {
+            Some(max_cycles) if total_num_cycles > max_cycles => {
+                Err(CommonError::cycle_limit_exceeded(max_cycles))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns [`CommonError::UnsupportedInput`] if `profile` is set, for zkVMs unable to profile
+    /// the guest.
+    pub fn check_profile_unsupported(&self) -> Result<(), CommonError> {
+        match self.profile {
+            true => Err(CommonError::unsupported_input("no guest profiling")),
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CommonError, options::ExecuteOptions};
+
+    #[test]
+    fn test_check_cycle_limit() {
+        assert!(ExecuteOptions::new().check_cycle_limit(u64::MAX).is_ok());
+
+        let options = ExecuteOptions::new().with_max_cycles(100);
+        assert!(options.check_cycle_limit(100).is_ok());
+        assert!(matches!(
+            options.check_cycle_limit(101),
+            Err(CommonError::CycleLimitExceeded { max_cycles: 100 })
+        ));
+    }
+
+    #[test]
+    fn test_check_profile_unsupported() {
+        assert!(ExecuteOptions::new().check_profile_unsupported().is_ok());
+        assert!(matches!(
+            ExecuteOptions::new()
+                .with_profile(true)
+                .check_profile_unsupported(),
+            Err(CommonError::UnsupportedInput(_))
+        ));
+    }
+}
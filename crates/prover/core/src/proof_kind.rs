@@ -0,0 +1,240 @@
+use core::time::Duration;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use crate::ProgramProvingReport;
+
+/// Kind of proof produced by a zkVM prover.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumIter,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ProofKind {
+    /// Recursively compressed STARK proof, constant size but only verifiable off-chain.
+    #[default]
+    Compressed,
+    /// Groth16 SNARK wrapping a compressed proof.
+    Groth16,
+    /// Plonk SNARK wrapping a compressed proof.
+    Plonk,
+}
+
+impl ProofKind {
+    /// Returns whether the proof can be verified by an on-chain verifier.
+    pub fn is_onchain_verifiable(&self) -> bool {
+        !matches!(self, Self::Compressed)
+    }
+
+    /// Returns relative verification cost, lower is cheaper.
+    ///
+    /// Groth16 has the cheapest pairing check, Plonk is slightly more expensive, and a compressed
+    /// STARK is the most expensive to verify.
+    fn verification_cost_rank(&self) -> u8 {
+        match self {
+            Self::Groth16 => 0,
+            Self::Plonk => 1,
+            Self::Compressed => 2,
+        }
+    }
+}
+
+/// What a [`ProofKindPolicy`] optimizes for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofKindObjective {
+    /// Minimize proving time.
+    #[default]
+    Latency,
+    /// Minimize proof size.
+    Size,
+    /// Minimize verification cost.
+    VerificationCost,
+}
+
+/// Preferences used by [`ProofKindStats::recommend`] to pick a [`ProofKind`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofKindPolicy {
+    /// What to optimize for among the proof kinds satisfying the constraints.
+    pub objective: ProofKindObjective,
+    /// Only consider proof kinds verifiable on-chain.
+    pub onchain: bool,
+    /// Only consider proof kinds whose mean proving time is within this bound.
+    pub max_proving_time: Option<Duration>,
+    /// Only consider proof kinds whose mean proof size in bytes is within this bound.
+    pub max_proof_size: Option<usize>,
+}
+
+/// Aggregated measurements of a single [`ProofKind`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofKindSample {
+    /// Number of recorded proofs.
+    pub count: u32,
+    /// Mean proving time.
+    pub mean_proving_time: Duration,
+    /// Mean proof size in bytes.
+    pub mean_proof_size: usize,
+}
+
+/// Measurements of prior proofs per [`ProofKind`], used to recommend a proof kind for a
+/// [`ProofKindPolicy`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProofKindStats {
+    samples: IndexMap<ProofKind, ProofKindSample>,
+}
+
+impl ProofKindStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a proof of `kind` with its proving report and encoded size in bytes.
+    pub fn record(&mut self, kind: ProofKind, report: &ProgramProvingReport, proof_size: usize) {
+        let sample = self.samples.entry(kind).or_default();
+        let count = sample.count + 1;
+        let mean_proving_time =
+            (sample.mean_proving_time * sample.count + report.proving_time) / count;
+        let mean_proof_size =
+            (sample.mean_proof_size * sample.count as usize + proof_size) / count as usize;
+        *sample = ProofKindSample {
+            count,
+            mean_proving_time,
+            mean_proof_size,
+        };
+    }
+
+    /// Returns aggregated measurements of `kind`, if any proof of it has been recorded.
+    pub fn sample(&self, kind: ProofKind) -> Option<&ProofKindSample> {
+        self.samples.get(&kind)
+    }
+
+    /// Recommends the proof kind among `supported` that best satisfies `policy`.
+    ///
+    /// Only proof kinds with recorded measurements are considered. Returns `None` if none of them
+    /// satisfies the constraints of `policy`.
+    pub fn recommend(
+        &self,
+        policy: &ProofKindPolicy,
+        supported: &[ProofKind],
+    ) -> Option<ProofKind> {
+        supported
+            .iter()
+            .filter_map(|kind| Some((*kind, self.samples.get(kind)?)))
+            .filter(|(kind, _)| !policy.onchain || kind.is_onchain_verifiable())
+            .filter(|(_, sample)| {
+                policy
+                    .max_proving_time
+                    .is_none_or(|max| sample.mean_proving_time <= max)
+            })
+            .filter(|(_, sample)| {
+                policy
+                    .max_proof_size
+                    .is_none_or(|max| sample.mean_proof_size <= max)
+            })
+            .min_by_key(|(kind, sample)| {
+                let proving_time = sample.mean_proving_time.as_nanos();
+                let proof_size = sample.mean_proof_size as u128;
+                match policy.objective {
+                    ProofKindObjective::Latency => (proving_time, proof_size),
+                    ProofKindObjective::Size => (proof_size, proving_time),
+                    ProofKindObjective::VerificationCost => {
+                        (kind.verification_cost_rank() as u128, proving_time)
+                    }
+                }
+            })
+            .map(|(kind, _)| kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        ProgramProvingReport,
+        proof_kind::{ProofKind, ProofKindObjective, ProofKindPolicy, ProofKindStats},
+    };
+
+    const ALL: &[ProofKind] = &[ProofKind::Compressed, ProofKind::Groth16, ProofKind::Plonk];
+
+    fn stats() -> ProofKindStats {
+        let mut stats = ProofKindStats::new();
+        for (kind, secs, size) in [
+            (ProofKind::Compressed, 10, 1 << 20),
+            (ProofKind::Compressed, 20, 1 << 20),
+            (ProofKind::Groth16, 60, 260),
+            (ProofKind::Plonk, 50, 868),
+        ] {
+            let report = ProgramProvingReport::new(Duration::from_secs(secs));
+            stats.record(kind, &report, size);
+        }
+        stats
+    }
+
+    #[test]
+    fn test_record() {
+        let stats = stats();
+        let sample = stats.sample(ProofKind::Compressed).unwrap();
+        assert_eq!(sample.count, 2);
+        assert_eq!(sample.mean_proving_time, Duration::from_secs(15));
+        assert_eq!(sample.mean_proof_size, 1 << 20);
+    }
+
+    #[test]
+    fn test_recommend() {
+        let stats = stats();
+        let recommend = |policy: ProofKindPolicy| stats.recommend(&policy, ALL);
+
+        assert_eq!(
+            recommend(ProofKindPolicy::default()),
+            Some(ProofKind::Compressed)
+        );
+        assert_eq!(
+            recommend(ProofKindPolicy {
+                onchain: true,
+                ..Default::default()
+            }),
+            Some(ProofKind::Plonk)
+        );
+        assert_eq!(
+            recommend(ProofKindPolicy {
+                objective: ProofKindObjective::VerificationCost,
+                ..Default::default()
+            }),
+            Some(ProofKind::Groth16)
+        );
+        assert_eq!(
+            recommend(ProofKindPolicy {
+                objective: ProofKindObjective::Size,
+                max_proving_time: Some(Duration::from_secs(55)),
+                ..Default::default()
+            }),
+            Some(ProofKind::Plonk)
+        );
+        assert_eq!(
+            recommend(ProofKindPolicy {
+                onchain: true,
+                max_proving_time: Some(Duration::from_secs(30)),
+                ..Default::default()
+            }),
+            None
+        );
+        assert_eq!(
+            stats.recommend(&ProofKindPolicy::default(), &[ProofKind::Groth16]),
+            Some(ProofKind::Groth16)
+        );
+    }
+}
@@ -1,6 +1,10 @@
-use core::error::Error;
+use std::time::Instant;
 
-use crate::{Input, ProgramExecutionReport, ProgramProvingReport, PublicValues, zkVMVerifier};
+use crate::{
+    CommonError, ExecuteOptions, ExecutionArtifact, Input, ProgramExecutionReport,
+    ProgramProvingReport, ProofKind, ProofKindPolicy, ProofKindStats, PublicValues, RetryConfig,
+    SelfTestReport, zkVMError, zkVMVerifier,
+};
 
 /// zkVM prover trait to abstract away the differences between each zkVM.
 ///
@@ -15,14 +19,56 @@ use crate::{Input, ProgramExecutionReport, ProgramProvingReport, PublicValues, z
 #[auto_impl::auto_impl(&, Arc, Box)]
 pub trait zkVMProver {
     type Verifier: zkVMVerifier;
-    type Error: 'static + Send + Sync + Error + From<<Self::Verifier as zkVMVerifier>::Error>;
+    type Error: 'static
+        + Send
+        + Sync
+        + zkVMError
+        + From<CommonError>
+        + From<<Self::Verifier as zkVMVerifier>::Error>;
 
     /// Returns a reference to the verifier.
     fn verifier(&self) -> &Self::Verifier;
 
+    /// Eagerly performs one-time costs that are otherwise paid lazily on first proving (e.g.
+    /// downloading or building proving keys), to keep them off the proving critical path.
+    ///
+    /// Only ZisK implements it, by setting up the program ahead of the first proof, other zkVMs
+    /// keep the default no-op and pay such costs on construction or on first proving.
+    fn warmup(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Returns the maximum length in bytes of the input stdin, `None` if it's unlimited.
+    ///
+    /// Input exceeding it fails with [`CommonError::InputTooLarge`] before execution.
+    ///
+    /// [`CommonError::InputTooLarge`]: crate::CommonError::InputTooLarge
+    fn max_input_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the maximum length in bytes of the output written by the guest program via
+    /// `Platform::write_output`, `None` if it's unlimited.
+    ///
+    /// Output exceeding it fails in the guest program, which can't be checked before execution.
+    fn max_output_len(&self) -> Option<usize> {
+        None
+    }
+
     /// Executes the program with the given input.
-    fn execute(&self, input: &Input)
-    -> Result<(PublicValues, ProgramExecutionReport), Self::Error>;
+    fn execute(
+        &self,
+        input: &Input,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Self::Error> {
+        self.execute_with_options(input, &ExecuteOptions::default())
+    }
+
+    /// Executes the program with the given input and options.
+    fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Self::Error>;
 
     /// Creates a proof of the program execution with given input.
     fn prove(
@@ -30,6 +76,55 @@ pub trait zkVMProver {
         input: &Input,
     ) -> Result<(PublicValues, Proof<Self>, ProgramProvingReport), Self::Error>;
 
+    /// Creates a proof of `kind` among [`zkVMProver::proof_kinds`] of the program execution with
+    /// given input, [`zkVMProver::prove`] creates the default [`ProofKind::Compressed`].
+    ///
+    /// zkVMs supporting other proof kinds than [`ProofKind::Compressed`] override it.
+    fn prove_with_kind(
+        &self,
+        input: &Input,
+        kind: ProofKind,
+    ) -> Result<(PublicValues, Proof<Self>, ProgramProvingReport), Self::Error> {
+        if kind != ProofKind::Compressed {
+            Err(CommonError::unsupported_proof_kind(
+                kind,
+                self.proof_kinds().iter().copied(),
+            ))?
+        }
+        self.prove(input)
+    }
+
+    /// Same as [`zkVMProver::prove`] but retries on failures as configured by `config`, which by
+    /// default retries failures of [`ErrorKind::Transient`].
+    ///
+    /// [`ErrorKind::Transient`]: crate::ErrorKind::Transient
+    fn prove_with_retry(
+        &self,
+        input: &Input,
+        config: &RetryConfig,
+    ) -> Result<(PublicValues, Proof<Self>, ProgramProvingReport), Self::Error> {
+        config.run(|| self.prove(input))
+    }
+
+    /// Executes the program with the given input, and returns an [`ExecutionArtifact`] to be
+    /// passed to [`zkVMProver::prove_from_execution`].
+    fn execute_for_proving(&self, input: &Input) -> Result<ExecutionArtifact, Self::Error> {
+        let (public_values, report) = self.execute(input)?;
+        Ok(ExecutionArtifact::new(input.clone(), public_values, report))
+    }
+
+    /// Creates a proof of the execution returned by [`zkVMProver::execute_for_proving`] of this
+    /// zkVM prover instance.
+    ///
+    /// zkVMs able to reuse the execution skip executing the program again, otherwise this is the
+    /// same as [`zkVMProver::prove`] with the input of the execution.
+    fn prove_from_execution(
+        &self,
+        artifact: &ExecutionArtifact,
+    ) -> Result<(PublicValues, Proof<Self>, ProgramProvingReport), Self::Error> {
+        self.prove(artifact.input())
+    }
+
     /// Verifies a proof of the program used to create this zkVM prover instance, then
     /// returns the public values extracted from the proof.
     #[must_use = "Public values must be used"]
@@ -37,11 +132,52 @@ pub trait zkVMProver {
         Ok(self.verifier().verify(proof)?)
     }
 
+    /// Executes, proves and verifies the program with the given input as a self-test, to detect
+    /// degraded hardware or broken drivers before production jobs fail.
+    fn selftest(&self, input: &Input) -> Result<SelfTestReport, Self::Error> {
+        let start = Instant::now();
+        let (execution_public_values, execution_report) = self.execute(input)?;
+        let execution_duration = start.elapsed();
+
+        let start = Instant::now();
+        let (proving_public_values, proof, _) = self.prove(input)?;
+        let proving_duration = start.elapsed();
+
+        let start = Instant::now();
+        let verifying_public_values = self.verify(&proof)?;
+        let verifying_duration = start.elapsed();
+
+        Ok(SelfTestReport {
+            total_num_cycles: execution_report.total_num_cycles,
+            execution_duration,
+            proving_duration,
+            verifying_duration,
+            public_values_consistent: execution_public_values == proving_public_values
+                && proving_public_values == verifying_public_values,
+            ..Default::default()
+        })
+    }
+
     /// Returns the verifying key for the specific program.
     fn program_vk(&self) -> &ProgramVk<Self> {
         self.verifier().program_vk()
     }
 
+    /// Returns the proof kinds this prover can produce.
+    fn proof_kinds(&self) -> &'static [ProofKind] {
+        &[ProofKind::Compressed]
+    }
+
+    /// Recommends the proof kind among [`zkVMProver::proof_kinds`] that best satisfies `policy`,
+    /// based on measurements of prior proofs in `stats`.
+    fn recommend_proof_kind(
+        &self,
+        policy: &ProofKindPolicy,
+        stats: &ProofKindStats,
+    ) -> Option<ProofKind> {
+        stats.recommend(policy, self.proof_kinds())
+    }
+
     /// Returns the name of the zkVM.
     fn name(&self) -> &'static str {
         self.verifier().name()
@@ -1,19 +1,153 @@
 use core::time::Duration;
+use std::path::PathBuf;
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+use crate::{CommonError, Input};
+
+/// Schema version of the reports, which is serialized as an integer.
+///
+/// It's bumped on incompatible changes of any report, e.g. a field is renamed, removed or changes
+/// its meaning. Adding a field doesn't bump it, since reports deserialize from self-describing
+/// formats (e.g. JSON) with the missing fields defaulted and unknown fields ignored. Positional
+/// formats (e.g. `bincode`) don't tolerate either, see [`ReportEncoding`] for the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SchemaVersion(pub u32);
+
+impl SchemaVersion {
+    /// Version of reports serialized before the schema was versioned.
+    pub const UNVERSIONED: Self = Self(0);
+    /// Current version.
+    pub const CURRENT: Self = Self(1);
+
+    fn unversioned() -> Self {
+        Self::UNVERSIONED
+    }
+}
+
+impl Default for SchemaVersion {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+/// Encoding of reports sent between `zkVMClient` and `zkVMServer`, requested by the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ReportEncoding {
+    /// `bincode` with legacy config in the layout of reports before [`SchemaVersion`], which is
+    /// positional and only has the fields of that layout, the others are defaulted when decoded.
+    /// Spoken by servers and clients of protocol versions before reports were encoded in JSON.
+    #[default]
+    Legacy,
+    /// JSON, which tolerates fields added or unknown to the other side.
+    Json,
+}
+
+/// [`ProgramExecutionReport`] in the layout of [`ReportEncoding::Legacy`].
+#[derive(Serialize, Deserialize)]
+struct LegacyExecutionReport {
+    total_num_cycles: u64,
+    region_cycles: IndexMap<String, u64>,
+    execution_duration: Duration,
+}
+
+/// [`ProgramProvingReport`] in the layout of [`ReportEncoding::Legacy`].
+#[derive(Serialize, Deserialize)]
+struct LegacyProvingReport {
+    proving_time: Duration,
+    total_num_cycles: Option<u64>,
+}
+
+impl ReportEncoding {
+    fn encode<R: Serialize, L: Serialize>(
+        self,
+        report: &R,
+        legacy: impl FnOnce(&R) -> L,
+    ) -> Result<Vec<u8>, CommonError> {
+        match self {
+            Self::Legacy => {
+                bincode::serde::encode_to_vec(legacy(report), bincode::config::legacy())
+                    .map_err(|err| CommonError::serialize("report", "bincode", err))
+            }
+            Self::Json => serde_json::to_vec(report)
+                .map_err(|err| CommonError::serialize("report", "serde_json", err)),
+        }
+    }
+
+    fn decode<R: for<'de> Deserialize<'de>, L: for<'de> Deserialize<'de>>(
+        self,
+        bytes: &[u8],
+        legacy: impl FnOnce(L) -> R,
+    ) -> Result<R, CommonError> {
+        match self {
+            Self::Legacy => bincode::serde::decode_from_slice(bytes, bincode::config::legacy())
+                .map(|(report, _)| legacy(report))
+                .map_err(|err| CommonError::deserialize("report", "bincode", err)),
+            Self::Json => serde_json::from_slice(bytes)
+                .map_err(|err| CommonError::deserialize("report", "serde_json", err)),
+        }
+    }
+}
+
 /// ProgramExecutionReport produces information about a particular program
 /// execution.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ProgramExecutionReport {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     /// Total number of cycles for the entire workload execution.
+    ///
+    /// With [`ExecuteOptions::max_cycles`], Risc0 and ZisK abort the execution once the limit is
+    /// reached, while Airbender, OpenVM and SP1 run the guest to completion and compare this
+    /// against the limit afterwards, so an over-limit guest costs the full execution.
+    ///
+    /// [`ExecuteOptions::max_cycles`]: crate::ExecuteOptions::max_cycles
     pub total_num_cycles: u64,
     /// Region-specific cycles, mapping region names (e.g., "setup", "compute") to their cycle
     /// counts.
     pub region_cycles: IndexMap<String, u64>,
+    /// Precompile invocation counts, mapping precompile names (e.g., "keccak", "sha256") to the
+    /// number of times the guest invoked them. Empty if the zkVM doesn't report it.
+    pub precompile_counts: IndexMap<String, u64>,
+    /// Syscall invocation counts, mapping syscall names (e.g., "write", "keccak_permute") to the
+    /// number of times the guest invoked them, including the precompiles. Empty if the zkVM
+    /// doesn't report it.
+    pub syscall_counts: IndexMap<String, u64>,
+    /// Trace rows per chip, mapping chip names (e.g. "KeccakVmAir") to the number of rows it
+    /// takes in the trace across all segments, which for most instruction chips is the number of
+    /// executed instructions they handle. Empty if the zkVM doesn't report it.
+    pub chip_rows: IndexMap<String, u64>,
+    /// Operations sent over the bus to each state machine, mapping state machine names (e.g.
+    /// "Binary", "Arith") to the number of operations they handle, which drive the size of their
+    /// traces. Empty if the zkVM doesn't report it.
+    pub bus_ops: IndexMap<String, u64>,
     /// Execution duration.
     pub execution_duration: Duration,
+    /// Final machine state for debugging. `None` if the zkVM doesn't report it.
+    pub debug: Option<ExecutionDebugInfo>,
+    /// Auxiliary output written by the guest via `Platform::write_auxiliary_output`, which is not
+    /// part of the public values. Empty if the zkVM doesn't support.
+    pub auxiliary_output: Vec<u8>,
+    /// Messages printed by the guest via `Platform::print`. Only captured by Risc0, other zkVMs
+    /// leave it empty and print them to the host stdout.
+    pub guest_logs: String,
+    /// Path to the profile of the guest (e.g. pprof) written when executed with
+    /// [`ExecuteOptions::profile`]. `None` if not profiled.
+    ///
+    /// [`ExecuteOptions::profile`]: crate::ExecuteOptions::profile
+    pub profile_path: Option<PathBuf>,
+}
+
+/// Final machine state of a guest program execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionDebugInfo {
+    /// Final register values, mapping register ABI names (e.g., "a0", "sp") to their values.
+    pub registers: IndexMap<String, u64>,
+    /// Final CSR values, mapping CSR names (e.g., "mcause") to their values.
+    pub csrs: IndexMap<String, u64>,
 }
 
 impl ProgramExecutionReport {
@@ -26,6 +160,27 @@ impl ProgramExecutionReport {
     pub fn insert_region(&mut self, region_name: String, num_cycles: u64) {
         self.region_cycles.insert(region_name, num_cycles);
     }
+    pub fn insert_precompile(&mut self, precompile_name: String, count: u64) {
+        self.precompile_counts.insert(precompile_name, count);
+    }
+    /// Encodes the report to be sent to the other side, see [`ReportEncoding`].
+    pub fn encode(&self, encoding: ReportEncoding) -> Result<Vec<u8>, CommonError> {
+        encoding.encode(self, |report| LegacyExecutionReport {
+            total_num_cycles: report.total_num_cycles,
+            region_cycles: report.region_cycles.clone(),
+            execution_duration: report.execution_duration,
+        })
+    }
+    /// Decodes the report received from the other side, see [`ReportEncoding`].
+    pub fn decode(bytes: &[u8], encoding: ReportEncoding) -> Result<Self, CommonError> {
+        encoding.decode(bytes, |report: LegacyExecutionReport| Self {
+            schema_version: SchemaVersion::UNVERSIONED,
+            total_num_cycles: report.total_num_cycles,
+            region_cycles: report.region_cycles,
+            execution_duration: report.execution_duration,
+            ..Default::default()
+        })
+    }
 }
 
 /// ProgramProvingReport produces information about proving a particular
@@ -34,15 +189,185 @@ impl ProgramExecutionReport {
 /// Note: Execution is fused into the proving pipeline.
 /// To get separate execution metrics, call `execute()` before `prove()`.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ProgramProvingReport {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub proving_time: Duration,
     pub total_num_cycles: Option<u64>,
+    /// Phase-specific durations, mapping phase names (e.g., "execution", "compression") to their
+    /// durations, in the order they ran. Empty if the zkVM doesn't report it.
+    pub phase_durations: IndexMap<String, Duration>,
+    /// Commitment to the input the proof was created with, see [`Input::commitment`].
+    pub input_commitment: Option<[u8; 32]>,
+    /// Id of the request on the proving network that fulfilled the proof, `None` if not proved
+    /// on a proving network.
+    pub network_request_id: Option<String>,
 }
 impl ProgramProvingReport {
     pub fn new(proving_time: Duration) -> Self {
         Self {
             proving_time,
-            total_num_cycles: None,
+            ..Default::default()
         }
     }
+    pub fn insert_phase(&mut self, phase_name: String, duration: Duration) {
+        self.phase_durations.insert(phase_name, duration);
+    }
+    /// Sets the commitment to `input` and returns a new `ProgramProvingReport`.
+    pub fn with_input_commitment(mut self, input: &Input) -> Self {
+        self.input_commitment = Some(input.commitment());
+        self
+    }
+    /// Encodes the report to be sent to the other side, see [`ReportEncoding`].
+    pub fn encode(&self, encoding: ReportEncoding) -> Result<Vec<u8>, CommonError> {
+        encoding.encode(self, |report| LegacyProvingReport {
+            proving_time: report.proving_time,
+            total_num_cycles: report.total_num_cycles,
+        })
+    }
+    /// Decodes the report received from the other side, see [`ReportEncoding`].
+    pub fn decode(bytes: &[u8], encoding: ReportEncoding) -> Result<Self, CommonError> {
+        encoding.decode(bytes, |report: LegacyProvingReport| Self {
+            schema_version: SchemaVersion::UNVERSIONED,
+            proving_time: report.proving_time,
+            total_num_cycles: report.total_num_cycles,
+            ..Default::default()
+        })
+    }
+}
+
+/// SelfTestReport produces information about a self-test of a zkVM prover, see
+/// [`zkVMProver::selftest`].
+///
+/// [`zkVMProver::selftest`]: crate::zkVMProver::selftest
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelfTestReport {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub total_num_cycles: u64,
+    pub execution_duration: Duration,
+    pub proving_duration: Duration,
+    pub verifying_duration: Duration,
+    /// Whether the public values of execution, proving and verification are the same.
+    pub public_values_consistent: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::report::{
+        ProgramExecutionReport, ProgramProvingReport, ReportEncoding, SchemaVersion,
+    };
+
+    #[test]
+    fn test_schema_version() {
+        let report = ProgramExecutionReport::new(42);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["schema_version"], 1);
+
+        let report: ProgramExecutionReport = serde_json::from_value(json).unwrap();
+        assert_eq!(report.schema_version, SchemaVersion::CURRENT);
+        assert_eq!(report.total_num_cycles, 42);
+    }
+
+    #[test]
+    fn test_compatibility() {
+        // Unversioned report without fields added later.
+        let report: ProgramExecutionReport = serde_json::from_str(
+            r#"{
+                "total_num_cycles": 42,
+                "region_cycles": { "compute": 40 },
+                "execution_duration": { "secs": 1, "nanos": 0 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(report.schema_version, SchemaVersion::UNVERSIONED);
+        assert_eq!(report.total_num_cycles, 42);
+        assert_eq!(report.region_cycles["compute"], 40);
+        assert!(report.precompile_counts.is_empty());
+        assert!(report.chip_rows.is_empty());
+        assert!(report.bus_ops.is_empty());
+
+        // Report with fields unknown to this version.
+        let report: ProgramProvingReport = serde_json::from_str(
+            r#"{
+                "schema_version": 1,
+                "proving_time": { "secs": 2, "nanos": 0 },
+                "total_num_cycles": 42,
+                "unknown": true
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(report.schema_version, SchemaVersion::CURRENT);
+        assert_eq!(report.proving_time, Duration::from_secs(2));
+        assert_eq!(report.total_num_cycles, Some(42));
+        assert!(report.phase_durations.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_encoding() {
+        // Encoded by servers and clients before reports were versioned.
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct OldExecutionReport {
+            total_num_cycles: u64,
+            region_cycles: indexmap::IndexMap<String, u64>,
+            execution_duration: Duration,
+        }
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct OldProvingReport {
+            proving_time: Duration,
+            total_num_cycles: Option<u64>,
+        }
+        let legacy = bincode::config::legacy();
+
+        let old = OldExecutionReport {
+            total_num_cycles: 42,
+            region_cycles: [("compute".to_string(), 40)].into_iter().collect(),
+            execution_duration: Duration::from_secs(1),
+        };
+        let bytes = bincode::serde::encode_to_vec(&old, legacy).unwrap();
+        let report = ProgramExecutionReport::decode(&bytes, ReportEncoding::Legacy).unwrap();
+        assert_eq!(report.schema_version, SchemaVersion::UNVERSIONED);
+        assert_eq!(report.total_num_cycles, 42);
+        assert_eq!(report.region_cycles["compute"], 40);
+        assert_eq!(report.execution_duration, Duration::from_secs(1));
+
+        let mut report = report;
+        report.precompile_counts.insert("keccak".to_string(), 1);
+        let bytes = report.encode(ReportEncoding::Legacy).unwrap();
+        let (old, _): (OldExecutionReport, _) =
+            bincode::serde::decode_from_slice(&bytes, legacy).unwrap();
+        assert_eq!(old.total_num_cycles, 42);
+        assert_eq!(old.region_cycles["compute"], 40);
+
+        let old = OldProvingReport {
+            proving_time: Duration::from_secs(2),
+            total_num_cycles: Some(42),
+        };
+        let bytes = bincode::serde::encode_to_vec(&old, legacy).unwrap();
+        let report = ProgramProvingReport::decode(&bytes, ReportEncoding::Legacy).unwrap();
+        assert_eq!(report.proving_time, Duration::from_secs(2));
+        assert_eq!(report.total_num_cycles, Some(42));
+
+        let report = report.with_input_commitment(&Default::default());
+        let bytes = report.encode(ReportEncoding::Legacy).unwrap();
+        let (old, _): (OldProvingReport, _) =
+            bincode::serde::decode_from_slice(&bytes, legacy).unwrap();
+        assert_eq!(old.proving_time, Duration::from_secs(2));
+        assert_eq!(old.total_num_cycles, Some(42));
+    }
+
+    #[test]
+    fn test_json_encoding() {
+        let mut report = ProgramExecutionReport::new(42);
+        report.chip_rows.insert("KeccakVmAir".to_string(), 24);
+        let bytes = report.encode(ReportEncoding::Json).unwrap();
+        let decoded = ProgramExecutionReport::decode(&bytes, ReportEncoding::Json).unwrap();
+        assert_eq!(decoded.schema_version, SchemaVersion::CURRENT);
+        assert_eq!(decoded.total_num_cycles, 42);
+        assert_eq!(decoded.chip_rows, report.chip_rows);
+    }
 }
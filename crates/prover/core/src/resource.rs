@@ -1,7 +1,26 @@
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumDiscriminants, EnumIs, EnumIter, EnumString};
 
+use crate::CommonError;
+
+/// How requests on the proving network are fulfilled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum FulfillmentStrategy {
+    /// Fulfilled by the provers hosted by the network operator.
+    Hosted,
+    /// Fulfilled by the provers with capacity reserved for the requester.
+    Reserved,
+    /// Fulfilled by the prover winning the auction of the request.
+    Auction,
+}
+
 /// Configuration for remote proving
+///
+/// Options besides the endpoint and API key are rejected by the zkVMs that don't support them, see
+/// [`RemoteProverConfig::check_supported`].
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "clap", derive(clap::Args))]
 pub struct RemoteProverConfig {
@@ -11,15 +30,91 @@ pub struct RemoteProverConfig {
     /// Optional API key for authentication
     #[cfg_attr(feature = "clap", arg(long))]
     pub api_key: Option<String>,
+    /// Fulfillment strategy of proving network requests, the network default if `None`
+    #[cfg_attr(feature = "clap", arg(long))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fulfillment_strategy: Option<FulfillmentStrategy>,
+    /// Maximum price per prover gas unit of proving network requests, in the smallest unit of the
+    /// network token
+    #[cfg_attr(feature = "clap", arg(long))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_price_per_pgu: Option<u64>,
+    /// Timeout in seconds for proving network requests to be fulfilled
+    #[cfg_attr(feature = "clap", arg(long))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Maximum number of cycles of proving network requests, which are rejected if the execution
+    /// exceeds it
+    #[cfg_attr(feature = "clap", arg(long))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle_limit: Option<u64>,
+}
+
+/// Option of [`RemoteProverConfig`] besides the endpoint and API key, which not every remote
+/// prover supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum RemoteProverOption {
+    FulfillmentStrategy,
+    MaxPricePerPgu,
+    TimeoutSecs,
+    CycleLimit,
+}
+
+impl RemoteProverConfig {
+    /// Returns error if an option not in `supported` is set, instead of ignoring it silently.
+    pub fn check_supported(&self, supported: &[RemoteProverOption]) -> Result<(), CommonError> {
+        let options = [
+            (
+                RemoteProverOption::FulfillmentStrategy,
+                self.fulfillment_strategy.is_some(),
+            ),
+            (
+                RemoteProverOption::MaxPricePerPgu,
+                self.max_price_per_pgu.is_some(),
+            ),
+            (RemoteProverOption::TimeoutSecs, self.timeout_secs.is_some()),
+            (RemoteProverOption::CycleLimit, self.cycle_limit.is_some()),
+        ];
+        match options
+            .into_iter()
+            .find(|(option, set)| *set && !supported.contains(option))
+        {
+            Some((option, _)) => Err(CommonError::unsupported_remote_prover_option(
+                option,
+                supported.iter().copied(),
+            )),
+            None => Ok(()),
+        }
+    }
 }
 
 #[cfg(feature = "clap")]
 impl RemoteProverConfig {
-    pub fn to_args(&self) -> Vec<&str> {
-        core::iter::once(["--endpoint", self.endpoint.as_str()])
-            .chain(self.api_key.as_deref().map(|val| ["--api-key", val]))
-            .flatten()
-            .collect()
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec!["--endpoint".to_string(), self.endpoint.clone()];
+        let options = [
+            ("--api-key", self.api_key.clone()),
+            (
+                "--fulfillment-strategy",
+                self.fulfillment_strategy.map(|val| val.to_string()),
+            ),
+            (
+                "--max-price-per-pgu",
+                self.max_price_per_pgu.map(|val| val.to_string()),
+            ),
+            (
+                "--timeout-secs",
+                self.timeout_secs.map(|val| val.to_string()),
+            ),
+            ("--cycle-limit", self.cycle_limit.map(|val| val.to_string())),
+        ];
+        for (key, val) in options {
+            if let Some(val) = val {
+                args.extend([key.to_string(), val]);
+            }
+        }
+        args
     }
 }
 
@@ -53,14 +148,14 @@ impl ProverResource {
 
 #[cfg(feature = "clap")]
 impl ProverResource {
-    pub fn to_args(&self) -> Vec<&str> {
+    pub fn to_args(&self) -> Vec<String> {
         match self {
-            Self::Cpu => vec!["cpu"],
-            Self::Gpu => vec!["gpu"],
-            Self::Network(config) => core::iter::once("network")
+            Self::Cpu => vec!["cpu".to_string()],
+            Self::Gpu => vec!["gpu".to_string()],
+            Self::Network(config) => core::iter::once("network".to_string())
                 .chain(config.to_args())
                 .collect(),
-            Self::Cluster(config) => core::iter::once("cluster")
+            Self::Cluster(config) => core::iter::once("cluster".to_string())
                 .chain(config.to_args())
                 .collect(),
         }
@@ -73,7 +168,10 @@ mod tests {
 
     use serde::{Deserialize, Serialize};
 
-    use crate::resource::ProverResource;
+    use crate::{
+        CommonError,
+        resource::{ProverResource, RemoteProverConfig, RemoteProverOption},
+    };
 
     #[derive(Serialize, Deserialize)]
     struct Config {
@@ -151,4 +249,30 @@ resources:
 "#;
         test_round_trip(JSON, serde_json::to_string_pretty, serde_json::from_str);
     }
+
+    #[test]
+    fn test_check_supported() {
+        let config = RemoteProverConfig {
+            timeout_secs: Some(60),
+            ..Default::default()
+        };
+        config
+            .check_supported(&[RemoteProverOption::TimeoutSecs])
+            .unwrap();
+        let err = config
+            .check_supported(&[RemoteProverOption::CycleLimit])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CommonError::UnsupportedRemoteProverOption {
+                unsupported: RemoteProverOption::TimeoutSecs,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Unsupported remote prover option timeout_secs, expect one of [CycleLimit]"
+        );
+        RemoteProverConfig::default().check_supported(&[]).unwrap();
+    }
 }
@@ -0,0 +1,158 @@
+use core::time::Duration;
+use std::thread::sleep;
+
+use crate::{ErrorKind, zkVMError};
+
+/// Exponential backoff between retry attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay before the first retry.
+    pub initial: Duration,
+    /// Factor the delay is multiplied by after each retry.
+    pub multiplier: u32,
+    /// Upper bound of the delay.
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            multiplier: 2,
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns the delay before the `retry`-th retry (starting from 0).
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(retry);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+/// Configuration of [`zkVMProver::prove_with_retry`].
+///
+/// [`zkVMProver::prove_with_retry`]: crate::zkVMProver::prove_with_retry
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Backoff between attempts.
+    pub backoff: Backoff,
+    /// Returns whether an error of the [`ErrorKind`] should be retried.
+    pub retry_on: fn(&ErrorKind) -> bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Backoff::default(),
+            retry_on: ErrorKind::is_transient,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_retry_on(mut self, retry_on: fn(&ErrorKind) -> bool) -> Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    /// Runs `f` until it succeeds, fails with an error not to be retried, or `max_attempts` is
+    /// reached, sleeping for the backoff delay between attempts.
+    pub fn run<T, E: zkVMError>(&self, mut f: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+        let mut retry = 0;
+        loop {
+            match f() {
+                Err(err) if retry + 1 < self.max_attempts && (self.retry_on)(&err.kind()) => {
+                    sleep(self.backoff.delay(retry));
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        CommonError, ErrorKind,
+        retry::{Backoff, RetryConfig},
+        zkVMError,
+    };
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("{0:?}")]
+    struct TestError(ErrorKind);
+
+    impl zkVMError for TestError {
+        fn kind(&self) -> ErrorKind {
+            self.0.clone()
+        }
+    }
+
+    const NO_BACKOFF: Backoff = Backoff {
+        initial: Duration::ZERO,
+        multiplier: 1,
+        max: Duration::ZERO,
+    };
+
+    #[test]
+    fn test_backoff() {
+        let backoff = Backoff::default();
+        let delays = (0..7).map(|retry| backoff.delay(retry).as_secs());
+        assert_eq!(delays.collect::<Vec<_>>(), [1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(backoff.delay(u32::MAX), backoff.max);
+    }
+
+    #[test]
+    fn test_run() {
+        let config = RetryConfig::new().with_backoff(NO_BACKOFF);
+
+        let mut attempts = 0;
+        let result = config.run(|| {
+            attempts += 1;
+            match attempts {
+                1 | 2 => Err(TestError(ErrorKind::Transient)),
+                _ => Ok(attempts),
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut attempts = 0;
+        let result = config.run(|| {
+            attempts += 1;
+            Err::<(), _>(TestError(ErrorKind::Transient))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+
+        let mut attempts = 0;
+        let result = config.run(|| {
+            attempts += 1;
+            Err::<(), _>(CommonError::unsupported_input("test"))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
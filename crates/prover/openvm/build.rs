@@ -0,0 +1,22 @@
+use std::env;
+
+use ere_util_build::{cargo_lock_path, detect_dep_version};
+use ere_util_cuda::build_cuda_archs;
+
+fn main() {
+    let version = detect_dep_version("ere-prover-openvm", "openvm-sdk");
+    println!("cargo:rustc-env=OPENVM_SDK_VERSION={version}");
+
+    // The CUDA architectures the kernels are built for by `openvm-cuda-builder`.
+    if env::var_os("CARGO_FEATURE_CUDA").is_some() {
+        let cuda_archs = build_cuda_archs("CUDA_ARCH")
+            .iter()
+            .map(|arch| arch.to_string())
+            .collect::<Vec<_>>();
+        println!("cargo:rustc-env=OPENVM_CUDA_ARCH={}", cuda_archs.join(","));
+    }
+
+    if let Some(cargo_lock) = cargo_lock_path() {
+        println!("cargo:rerun-if-changed={}", cargo_lock.display());
+    }
+}
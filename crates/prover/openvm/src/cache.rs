@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use ere_prover_core::{CommonError, config::Config, write_atomic};
+use openvm_sdk::{
+    commit::AppExecutionCommit,
+    config::{AppConfig, SdkVmConfig},
+    fs::{read_object_from_file, write_object_to_file},
+    keygen::AppProvingKey,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::{error::Error, prover::openvm_dir};
+
+/// Version of `openvm-sdk` the keys are generated by.
+const OPENVM_SDK_VERSION: &str = env!("OPENVM_SDK_VERSION");
+
+/// On-disk cache of the app proving key and the app commit of programs, which is set by
+/// `ERE_OPENVM_KEY_CACHE_DIR`, defaults to `~/.openvm/cache`.
+///
+/// Entries are addressed by the digest of the `openvm-sdk` version and the app config (and the
+/// ELF for the app commit), so they never go stale and can be shared by concurrent processes.
+///
+/// Failing to read or write an entry only falls back to generating it.
+pub(crate) struct KeyCache {
+    dir: PathBuf,
+    app_config_digest: String,
+}
+
+impl KeyCache {
+    pub(crate) fn from_env(app_config: &AppConfig<SdkVmConfig>) -> Result<Self, Error> {
+        let dir = match Config::global()
+            .map_err(CommonError::from)?
+            .var("ERE_OPENVM_KEY_CACHE_DIR")
+        {
+            Some(dir) => PathBuf::from(dir),
+            None => openvm_dir().join("cache"),
+        };
+        let app_config = toml::to_string(app_config).map_err(Error::SerializeAppConfig)?;
+        Ok(Self {
+            dir,
+            app_config_digest: hex_digest([OPENVM_SDK_VERSION.as_bytes(), app_config.as_bytes()]),
+        })
+    }
+
+    /// Returns the cached app proving key, or generates and caches it by `keygen`.
+    pub(crate) fn app_pk(
+        &self,
+        keygen: impl FnOnce() -> AppProvingKey<SdkVmConfig>,
+    ) -> Result<AppProvingKey<SdkVmConfig>, Error> {
+        let path = self
+            .dir
+            .join(format!("app_pk-{}.bin", self.app_config_digest));
+        self.get_or_insert(&path, || Ok(keygen()))
+    }
+
+    /// Returns the cached app commit of `elf`, or computes and caches it by `commit`.
+    pub(crate) fn app_commit(
+        &self,
+        elf: &[u8],
+        commit: impl FnOnce() -> Result<AppExecutionCommit, Error>,
+    ) -> Result<AppExecutionCommit, Error> {
+        let digest = hex_digest([self.app_config_digest.as_bytes(), elf]);
+        let path = self.dir.join(format!("app_commit-{digest}.bin"));
+        self.get_or_insert(&path, commit)
+    }
+
+    fn get_or_insert<T: Serialize + DeserializeOwned>(
+        &self,
+        path: &Path,
+        generate: impl FnOnce() -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        if path.exists() {
+            match read_object_from_file(path) {
+                Ok(value) => {
+                    info!("Read cached {}", path.display());
+                    return Ok(value);
+                }
+                Err(err) => warn!("Failed to read cached {}: {err}", path.display()),
+            }
+        }
+
+        let value = generate()?;
+
+        if let Err(err) = write_atomic(path, |tmp_path| write_object_to_file(tmp_path, &value)) {
+            warn!("Failed to cache {}: {err}", path.display());
+        }
+
+        Ok(value)
+    }
+}
+
+fn hex_digest<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::KeyCache;
+
+    #[test]
+    fn test_get_or_insert() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = KeyCache {
+            dir: dir.path().join("cache"),
+            app_config_digest: String::new(),
+        };
+        let path = cache.dir.join("value.bin");
+
+        assert_eq!(cache.get_or_insert(&path, || Ok(42u64)).unwrap(), 42);
+        assert!(path.exists());
+        assert_eq!(
+            cache
+                .get_or_insert::<u64>(&path, || unreachable!("should be cached"))
+                .unwrap(),
+            42
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use ere_util_cuda::{covers_cuda_archs, detect_compute_caps, inexact_cuda_archs, parse_cuda_archs};
+use tracing::{info, warn};
+
+use crate::error::Error;
+
+/// CUDA architectures the GPU kernels are compiled for, which is env `CUDA_ARCH` at build time,
+/// or the compute capabilities of the GPUs of the build machine if unset or `native`.
+///
+/// Empty if neither is available, in which case `openvm-cuda-builder` picks its default.
+const OPENVM_CUDA_ARCH: &str = env!("OPENVM_CUDA_ARCH");
+
+/// Returns the CUDA architectures the GPU kernels are compiled for (e.g. `[89, 120]`), empty if
+/// unknown.
+pub fn cuda_archs() -> Vec<u32> {
+    parse_cuda_archs(OPENVM_CUDA_ARCH)
+}
+
+/// Returns error if the GPU kernels can't run on all visible GPUs, which would otherwise fail at
+/// the first kernel launch (e.g. compiled for `sm_90` but running on `sm_89`), and warns if some
+/// GPUs run kernels compiled for a lower minor version.
+///
+/// Skipped if the compiled architectures are unknown or `nvidia-smi` is not available.
+pub(crate) fn check_cuda_archs() -> Result<(), Error> {
+    let built = cuda_archs();
+    let detected = detect_compute_caps();
+    info!("GPU kernels compiled for CUDA_ARCH {built:?}, detected GPUs {detected:?}");
+    if built.is_empty() || detected.is_empty() {
+        warn!("Skipping checking CUDA architectures of the GPU kernels");
+        return Ok(());
+    }
+    if !covers_cuda_archs(&built, &detected) {
+        return Err(Error::CudaArchMismatch { built, detected });
+    }
+    let inexact = inexact_cuda_archs(&built, &detected);
+    if !inexact.is_empty() {
+        warn!(
+            "GPU kernels are not compiled for GPUs {inexact:?} but compatible ones, rebuild with \
+             env `CUDA_ARCH` covering them for the best performance"
+        );
+    }
+    Ok(())
+}
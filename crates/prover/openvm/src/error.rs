@@ -1,4 +1,5 @@
-use ere_prover_core::CommonError;
+use ere_prover_core::{CommonError, ErrorKind, zkVMError};
+use openvm_circuit::arch::ExecutionError;
 use openvm_sdk::{SdkError, commit::AppExecutionCommit};
 use thiserror::Error;
 
@@ -11,12 +12,33 @@ pub enum Error {
     #[error("Enable `cuda` feature to enable `ProverResource::Gpu`")]
     CudaFeatureDisabled,
 
+    #[error(
+        "GPU kernels are compiled for CUDA_ARCH {built:?}, but GPUs {detected:?} are detected, \
+         rebuild with env `CUDA_ARCH` covering them"
+    )]
+    CudaArchMismatch { built: Vec<u32>, detected: Vec<u32> },
+
+    #[error("Invalid env variable {key}, got {value}")]
+    InvalidEnvVar { key: &'static str, value: String },
+
+    #[error("Parse app config failed: {0}")]
+    ParseAppConfig(toml::de::Error),
+
+    #[error("Serialize app config failed: {0}")]
+    SerializeAppConfig(toml::ser::Error),
+
+    #[error("Invalid app config: {0}")]
+    InvalidAppConfig(SdkError),
+
     #[error("Transpile elf failed: {0}")]
     Transpile(SdkError),
 
     #[error("Read aggregation key failed: {0}")]
     ReadAggKeyFailed(eyre::Error),
 
+    #[error("Read halo2 proving key failed: {0}")]
+    ReadHalo2PkFailed(eyre::Error),
+
     #[error("Initialize prover failed: {0}")]
     ProverInit(SdkError),
 
@@ -24,6 +46,12 @@ pub enum Error {
     #[error("OpenVM execution failed: {0}")]
     Execute(#[source] SdkError),
 
+    #[error("Guest program exit with non-success code {exit_code}")]
+    ExecutionFailedWithExitCode { exit_code: u32 },
+
+    #[error("Guest program failed at pc {pc:#x}: {msg}")]
+    ExecutionFailedAtPc { pc: u32, msg: String },
+
     // Prove
     #[error("OpenVM proving failed: {0}")]
     Prove(#[source] SdkError),
@@ -37,3 +65,45 @@ pub enum Error {
     #[error(transparent)]
     Verifier(#[from] ere_verifier_openvm::Error),
 }
+
+impl Error {
+    /// Returns [`Error::ExecutionFailedWithExitCode`] or [`Error::ExecutionFailedAtPc`] if `err` is
+    /// termination of the guest program, otherwise [`Error::Execute`].
+    pub fn execute(err: SdkError) -> Self {
+        match err {
+            SdkError::Execution(ExecutionError::FailedWithExitCode(exit_code)) => {
+                Self::ExecutionFailedWithExitCode { exit_code }
+            }
+            SdkError::Execution(ExecutionError::Fail { pc, msg }) => Self::ExecutionFailedAtPc {
+                pc,
+                msg: msg.to_string(),
+            },
+            err => Self::Execute(err),
+        }
+    }
+}
+
+impl zkVMError for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommonError(err) => err.kind(),
+            Self::Execute(err) => ErrorKind::from_source(err, ErrorKind::guest_panic(err)),
+            Self::ExecutionFailedWithExitCode { .. } | Self::ExecutionFailedAtPc { .. } => {
+                ErrorKind::guest_panic(self)
+            }
+            Self::Prove(err) => ErrorKind::from_source(err, ErrorKind::ProverInfrastructure),
+            Self::CudaFeatureDisabled
+            | Self::CudaArchMismatch { .. }
+            | Self::InvalidEnvVar { .. }
+            | Self::ParseAppConfig(_)
+            | Self::SerializeAppConfig(_)
+            | Self::InvalidAppConfig(_)
+            | Self::Transpile(_)
+            | Self::ReadAggKeyFailed(_)
+            | Self::ReadHalo2PkFailed(_)
+            | Self::ProverInit(_)
+            | Self::UnexpectedAppCommit { .. } => ErrorKind::ProverInfrastructure,
+            Self::Verifier(_) => ErrorKind::VerificationFailed,
+        }
+    }
+}
@@ -6,12 +6,19 @@
 //! repository at the same git revision as your `ere-prover-openvm` dependency.
 //!
 //! To use with GPU proving support, make sure CUDA 12.9 is installed, and turn
-//! on the `cuda` feature.
+//! on the `cuda` feature. The GPU kernels are compiled for the CUDA architectures set by env
+//! `CUDA_ARCH` at build time (e.g. `89,120`), or for the GPUs of the build machine if unset or
+//! `native`. The compiled architectures are returned by `cuda_archs`, and `OpenVMProver::new` with
+//! `ProverResource::Gpu` returns error if they don't cover the visible GPUs, i.e. none is of the
+//! same major version and an equal or lower minor version of a GPU.
+//!
+//! To prove into EVM verifiable proofs by `OpenVMProver::prove_evm`, turn on the `evm` feature.
 //!
 //! ## `zkVMProver` requirements
 //!
 //! - `cargo-openvm`
-//! - Setup via `cargo openvm setup` - Setup aggregation keys used by `zkVMProver::prove`
+//! - Setup via `cargo openvm setup` - Setup aggregation keys used by `zkVMProver::prove`, and halo2
+//!   keys and EVM verifier used by `OpenVMProver::prove_evm` unless `--skip-halo2`
 //!
 //! # `Compiler` implementation
 //!
@@ -28,14 +35,42 @@
 //! | `Network` |    No     |
 //! | `Cluster` |    No     |
 //!
+//! ## Execution
+//!
+//! `zkVMProver::execute` runs the metered execution of the SDK, which tracks the trace heights of
+//! every chip to split the execution into segments as proving would, to report the cycles and
+//! trace rows per chip. It's slower than the plain execution of the guest, and can't stop at
+//! `ExecuteOptions::max_cycles`, which is checked after the execution finishes instead.
+//!
+//! ## Environment variables
+//!
+//! | Variable                              | Type  | Default           | Description                                                         |
+//! | ------------------------------------- | ----- | ----------------- | ------------------------------------------------------------------- |
+//! | `ERE_OPENVM_APP_CONFIG`               | Value |                   | App config in the format of `openvm.toml`, see [`parse_app_config`] |
+//! | `ERE_OPENVM_KEY_CACHE_DIR`            | Value | `~/.openvm/cache` | Directory to cache the app proving key and app commits              |
+//! | `ERE_OPENVM_SEGMENT_MAX_TRACE_HEIGHT` | Value |                   | Maximum rows of any chip per segment, see [`OpenVMSegmentOpts`]     |
+//! | `ERE_OPENVM_SEGMENT_MAX_CELLS`        | Value |                   | Maximum trace cells per segment, see [`OpenVMSegmentOpts`]          |
+//! | `ERE_OPENVM_SEGMENT_MAX_INTERACTIONS` | Value |                   | Maximum interactions per segment, see [`OpenVMSegmentOpts`]         |
+//!
 //! [`install_openvm_sdk.sh`]: https://github.com/eth-act/ere/blob/master/scripts/sdk_installers/install_openvm_sdk.sh
 
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod cache;
+#[cfg(feature = "cuda")]
+mod cuda;
 mod error;
+mod opts;
 mod prover;
 
 pub use ere_prover_core::*;
 pub use ere_verifier_openvm::*;
+pub use openvm_sdk::config::{AppConfig, SdkVmConfig};
 
-pub use crate::{error::Error, prover::OpenVMProver};
+#[cfg(feature = "cuda")]
+pub use crate::cuda::cuda_archs;
+pub use crate::{
+    error::Error,
+    opts::OpenVMSegmentOpts,
+    prover::{OpenVMProver, parse_app_config},
+};
@@ -0,0 +1,60 @@
+use ere_prover_core::{CommonError, config::Config};
+use openvm_sdk::config::{AppConfig, SdkVmConfig};
+
+use crate::error::Error;
+
+/// Segmentation limits of the OpenVM executor overriding the ones of the app config, which are set
+/// by `ERE_OPENVM_SEGMENT_*` by default, see [`OpenVMSegmentOpts::from_env`].
+///
+/// A new segment is started once any chip would exceed `max_trace_height` rows, or the segment
+/// would exceed `max_cells` trace cells or `max_interactions` interactions. Each segment is proven
+/// separately, so lower limits lower the peak memory of proving (e.g. to avoid GPU OOM on large
+/// blocks), at the cost of more segments to prove and aggregate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpenVMSegmentOpts {
+    /// Maximum number of rows of any chip per segment, kept as is if `None`.
+    pub max_trace_height: Option<u32>,
+    /// Maximum number of trace cells of all chips per segment, kept as is if `None`.
+    pub max_cells: Option<usize>,
+    /// Maximum number of interactions of all chips per segment, kept as is if `None`.
+    pub max_interactions: Option<usize>,
+}
+
+impl OpenVMSegmentOpts {
+    /// Reads `ERE_OPENVM_SEGMENT_MAX_TRACE_HEIGHT`, `ERE_OPENVM_SEGMENT_MAX_CELLS` and
+    /// `ERE_OPENVM_SEGMENT_MAX_INTERACTIONS`.
+    pub fn from_env() -> Result<Self, Error> {
+        fn parse_limit<T: std::str::FromStr + Default + PartialEq>(
+            key: &'static str,
+        ) -> Result<Option<T>, Error> {
+            Config::global()
+                .map_err(CommonError::from)?
+                .var(key)
+                .map(|value| match value.parse() {
+                    Ok(limit) if limit != T::default() => Ok(limit),
+                    _ => Err(Error::InvalidEnvVar { key, value }),
+                })
+                .transpose()
+        }
+
+        Ok(Self {
+            max_trace_height: parse_limit("ERE_OPENVM_SEGMENT_MAX_TRACE_HEIGHT")?,
+            max_cells: parse_limit("ERE_OPENVM_SEGMENT_MAX_CELLS")?,
+            max_interactions: parse_limit("ERE_OPENVM_SEGMENT_MAX_INTERACTIONS")?,
+        })
+    }
+
+    /// Overrides the segmentation limits of `app_config` with the ones set.
+    pub fn apply(&self, app_config: &mut AppConfig<SdkVmConfig>) {
+        let limits = &mut app_config.app_vm_config.system.config.segmentation_limits;
+        if let Some(max_trace_height) = self.max_trace_height {
+            limits.max_trace_height = max_trace_height;
+        }
+        if let Some(max_cells) = self.max_cells {
+            limits.max_cells = max_cells;
+        }
+        if let Some(max_interactions) = self.max_interactions {
+            limits.max_interactions = max_interactions;
+        }
+    }
+}
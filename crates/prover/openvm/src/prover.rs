@@ -2,22 +2,24 @@ use std::{path::PathBuf, sync::Arc, time::Instant};
 
 use ere_compiler_core::Elf;
 use ere_prover_core::{
-    CommonError, Input, ProgramExecutionReport, ProgramProvingReport, ProverResource,
-    ProverResourceKind, PublicValues, zkVMProver, zkVMVerifier,
+    CommonError, ExecuteOptions, Input, ProgramExecutionReport, ProgramProvingReport,
+    ProverResource, ProverResourceKind, PublicValues, config::Config, zkVMProver, zkVMVerifier,
 };
 use ere_verifier_openvm::{OpenVMProgramVk, OpenVMProof, OpenVMVerifier};
-use openvm_circuit::arch::instructions::exe::VmExe;
+use indexmap::IndexMap;
+use openvm_circuit::arch::{execution_mode::Segment, instructions::exe::VmExe};
 use openvm_sdk::{
     CpuSdk, F, StdIn,
     commit::AppExecutionCommit,
-    config::SdkVmConfig,
+    config::{AppConfig, SdkVmConfig},
     fs::read_object_from_file,
     keygen::{AggProvingKey, AppProvingKey},
 };
 
-use crate::error::Error;
+use crate::{cache::KeyCache, error::Error, opts::OpenVMSegmentOpts};
 
 pub struct OpenVMProver {
+    app_config: AppConfig<SdkVmConfig>,
     app_exe: Arc<VmExe<F>>,
     app_pk: AppProvingKey<SdkVmConfig>,
     agg_pk: AggProvingKey,
@@ -27,7 +29,30 @@ pub struct OpenVMProver {
 }
 
 impl OpenVMProver {
+    /// Creates the prover with the app config set by `ERE_OPENVM_APP_CONFIG` in the format of
+    /// `openvm.toml`, defaults to the standard one with all extensions enabled, and its
+    /// segmentation limits overridden by [`OpenVMSegmentOpts::from_env`].
     pub fn new(elf: Elf, resource: ProverResource) -> Result<Self, Error> {
+        let mut app_config = match Config::global()
+            .map_err(CommonError::from)?
+            .var("ERE_OPENVM_APP_CONFIG")
+        {
+            Some(app_config) => parse_app_config(&app_config)?,
+            None => AppConfig::standard(),
+        };
+        OpenVMSegmentOpts::from_env()?.apply(&mut app_config);
+        Self::new_with_app_config(elf, resource, app_config)
+    }
+
+    /// Same as [`OpenVMProver::new`] but with `app_config`, which replaces the `openvm.toml` of
+    /// the guest to select the extensions, segmentation limits and memory config.
+    ///
+    /// Segmentation limits can be overridden by [`OpenVMSegmentOpts::apply`] beforehand.
+    pub fn new_with_app_config(
+        elf: Elf,
+        resource: ProverResource,
+        app_config: AppConfig<SdkVmConfig>,
+    ) -> Result<Self, Error> {
         if !matches!(resource, ProverResource::Cpu | ProverResource::Gpu) {
             Err(CommonError::unsupported_prover_resource_kind(
                 resource.kind(),
@@ -35,21 +60,34 @@ impl OpenVMProver {
             ))?;
         }
 
-        let sdk = CpuSdk::standard();
+        #[cfg(feature = "cuda")]
+        if resource.is_gpu() {
+            crate::cuda::check_cuda_archs()?;
+        }
+
+        let cache = KeyCache::from_env(&app_config)?;
+
+        let sdk = CpuSdk::new(app_config.clone()).map_err(Error::InvalidAppConfig)?;
+
+        let app_exe = sdk
+            .convert_to_exe(elf.0.clone())
+            .map_err(Error::Transpile)?;
 
-        let app_exe = sdk.convert_to_exe(elf.0).map_err(Error::Transpile)?;
+        let app_pk = cache.app_pk(|| sdk.app_keygen().0)?;
 
-        let (app_pk, _) = sdk.app_keygen();
+        let _ = sdk.set_app_pk(app_pk.clone());
 
         let agg_pk = read_object_from_file::<AggProvingKey, _>(agg_pk_path())
             .map_err(Error::ReadAggKeyFailed)?;
 
         let _ = sdk.set_agg_pk(agg_pk.clone());
 
-        let app_commit = sdk
-            .prover(app_exe.clone())
-            .map_err(Error::ProverInit)?
-            .app_commit();
+        let app_commit = cache.app_commit(&elf.0, || {
+            Ok(sdk
+                .prover(app_exe.clone())
+                .map_err(Error::ProverInit)?
+                .app_commit())
+        })?;
 
         let verifier = OpenVMVerifier::new(OpenVMProgramVk::new(
             app_commit.app_exe_commit.as_slice(),
@@ -57,6 +95,7 @@ impl OpenVMProver {
         ));
 
         Ok(Self {
+            app_config,
             app_exe,
             app_pk,
             agg_pk,
@@ -67,15 +106,33 @@ impl OpenVMProver {
     }
 
     fn cpu_sdk(&self) -> Result<CpuSdk, Error> {
-        let sdk = CpuSdk::standard();
+        let sdk = CpuSdk::new(self.app_config.clone()).map_err(Error::InvalidAppConfig)?;
         let _ = sdk.set_app_pk(self.app_pk.clone());
         let _ = sdk.set_agg_pk(self.agg_pk.clone());
         Ok(sdk)
     }
 
+    /// Returns the trace rows of each chip summed over `segments`, keyed by the AIR names of the
+    /// app proving key, which index the trace heights of segments. Chips without any rows (e.g.
+    /// extensions not used by the guest) are skipped.
+    fn chip_rows(&self, segments: &[Segment]) -> IndexMap<String, u64> {
+        let mut chip_rows = IndexMap::new();
+        for (air_idx, pk) in self.app_pk.app_vm_pk.vm_pk.per_air.iter().enumerate() {
+            let rows = segments
+                .iter()
+                .map(|segment| segment.trace_heights.get(air_idx).copied().unwrap_or(0) as u64)
+                .sum::<u64>();
+            if rows > 0 {
+                *chip_rows.entry(pk.air_name.clone()).or_default() += rows;
+            }
+        }
+        chip_rows
+    }
+
     #[cfg(feature = "cuda")]
     fn gpu_sdk(&self) -> Result<openvm_sdk::GpuSdk, Error> {
-        let sdk = openvm_sdk::GpuSdk::standard();
+        let sdk =
+            openvm_sdk::GpuSdk::new(self.app_config.clone()).map_err(Error::InvalidAppConfig)?;
         let _ = sdk.set_app_pk(self.app_pk.clone());
         let _ = sdk.set_agg_pk(self.agg_pk.clone());
         Ok(sdk)
@@ -90,24 +147,34 @@ impl zkVMProver for OpenVMProver {
         &self.verifier
     }
 
-    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport), Error> {
-        if input.proofs.is_some() {
-            Err(CommonError::unsupported_input("no dedicated proofs stream"))?
-        }
+    fn max_output_len(&self) -> Option<usize> {
+        self.verifier.public_values_padded_len()
+    }
 
-        let mut stdin = StdIn::default();
-        stdin.write_bytes(input.stdin());
+    fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        options.check_profile_unsupported()?;
+        let stdin = input_to_stdin(input)?;
 
         let start = Instant::now();
-        let public_values = self
+        let (public_values, segments) = self
             .cpu_sdk()?
-            .execute(self.app_exe.clone(), stdin)
-            .map_err(Error::Execute)?;
+            .execute_metered(self.app_exe.clone(), stdin)
+            .map_err(Error::execute)?;
         let execution_duration = start.elapsed();
 
+        // Metered execution can't be interrupted at a cycle limit, so it's checked afterwards.
+        let total_num_cycles = segments.iter().map(|segment| segment.num_insns).sum();
+        options.check_cycle_limit(total_num_cycles)?;
+
         Ok((
             public_values.into(),
             ProgramExecutionReport {
+                total_num_cycles,
+                chip_rows: self.chip_rows(&segments),
                 execution_duration,
                 ..Default::default()
             },
@@ -118,12 +185,7 @@ impl zkVMProver for OpenVMProver {
         &self,
         input: &Input,
     ) -> Result<(PublicValues, OpenVMProof, ProgramProvingReport), Error> {
-        if input.proofs.is_some() {
-            Err(CommonError::unsupported_input("no dedicated proofs stream"))?
-        }
-
-        let mut stdin = StdIn::default();
-        stdin.write_bytes(input.stdin());
+        let stdin = input_to_stdin(input)?;
 
         let start = Instant::now();
         let (proof, app_commit) = match self.resource {
@@ -157,14 +219,153 @@ impl zkVMProver for OpenVMProver {
         Ok((
             public_values,
             proof,
-            ProgramProvingReport::new(proving_time),
+            ProgramProvingReport::new(proving_time).with_input_commitment(input),
         ))
     }
 }
 
+/// Parses `app_config` in the format of `openvm.toml`.
+pub fn parse_app_config(app_config: &str) -> Result<AppConfig<SdkVmConfig>, Error> {
+    toml::from_str(app_config).map_err(Error::ParseAppConfig)
+}
+
+#[cfg(feature = "evm")]
+impl OpenVMProver {
+    /// Proves and wraps the proof by the halo2 aggregation into a proof verifiable on EVM by the
+    /// contract exported by [`OpenVMProver::export_evm_verifier`].
+    ///
+    /// The halo2 proving key and KZG params are read from `~/.openvm`, which requires
+    /// `cargo openvm setup` without `--skip-halo2`.
+    ///
+    /// It's not a [`ProofKind`] of [`zkVMProver::prove_with_kind`], since [`OpenVMProof`] only
+    /// holds STARK proofs.
+    ///
+    /// [`ProofKind`]: ere_prover_core::ProofKind
+    pub fn prove_evm(
+        &self,
+        input: &Input,
+    ) -> Result<
+        (
+            PublicValues,
+            openvm_sdk::types::EvmProof,
+            ProgramProvingReport,
+        ),
+        Error,
+    > {
+        // The EVM proof is not verified locally, so the execution is checked beforehand.
+        self.execute(input)?;
+
+        let stdin = input_to_stdin(input)?;
+
+        let halo2_pk = read_object_from_file(openvm_dir().join("agg_halo2.pk"))
+            .map_err(Error::ReadHalo2PkFailed)?;
+
+        let start = Instant::now();
+        let proof = match self.resource {
+            ProverResource::Cpu => {
+                let sdk = self.cpu_sdk()?;
+                let _ = sdk.set_halo2_pk(halo2_pk);
+                sdk.prove_evm(self.app_exe.clone(), stdin)
+            }
+            #[cfg(feature = "cuda")]
+            ProverResource::Gpu => {
+                let sdk = self.gpu_sdk()?;
+                let _ = sdk.set_halo2_pk(halo2_pk);
+                sdk.prove_evm(self.app_exe.clone(), stdin)
+            }
+            #[cfg(not(feature = "cuda"))]
+            ProverResource::Gpu => return Err(Error::CudaFeatureDisabled),
+            _ => {
+                return Err(CommonError::unsupported_prover_resource_kind(
+                    self.resource.kind(),
+                    [ProverResourceKind::Cpu, ProverResourceKind::Gpu],
+                ))?;
+            }
+        }
+        .map_err(Error::Prove)?;
+        let proving_time = start.elapsed();
+
+        if proof.app_commit != self.app_commit {
+            return Err(Error::UnexpectedAppCommit {
+                preprocessed: self.app_commit.into(),
+                proved: proof.app_commit.into(),
+            });
+        }
+
+        Ok((
+            proof.user_public_values.as_slice().into(),
+            proof,
+            ProgramProvingReport::new(proving_time).with_input_commitment(input),
+        ))
+    }
+
+    /// Copies the EVM verifier artifacts set up by `cargo openvm setup` (Solidity contracts of
+    /// the halo2 and OpenVM verifiers, and the bytecode of the halo2 verifier) into `dir`.
+    pub fn export_evm_verifier(dir: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        copy_dir(&openvm_dir().join("halo2"), dir.as_ref())
+    }
+}
+
+/// Copies the files in `src` into `dst` recursively.
+#[cfg(feature = "evm")]
+fn copy_dir(src: &std::path::Path, dst: &std::path::Path) -> Result<(), Error> {
+    use std::fs;
+
+    fs::create_dir_all(dst).map_err(|err| CommonError::create_dir("evm_verifier", dst, err))?;
+    let entries = fs::read_dir(src)
+        .map_err(|err| CommonError::io(format!("Failed to read {}", src.display()), err))?;
+    for entry in entries {
+        let entry = entry
+            .map_err(|err| CommonError::io(format!("Failed to read {}", src.display()), err))?;
+        let (src, dst) = (entry.path(), dst.join(entry.file_name()));
+        if src.is_dir() {
+            copy_dir(&src, &dst)?;
+        } else {
+            fs::copy(&src, &dst)
+                .map_err(|err| CommonError::io(format!("Failed to copy {}", src.display()), err))?;
+        }
+    }
+    Ok(())
+}
+
+/// Converts `Input` to `StdIn`, with each value of the key-value store hinted by its key for
+/// `OpenVMPlatform::read_kv`.
+fn input_to_stdin(input: &Input) -> Result<StdIn, Error> {
+    if input.proofs.is_some() {
+        Err(CommonError::unsupported_input("no dedicated proofs stream"))?
+    }
+
+    let mut stdin = StdIn::default();
+    stdin.write_bytes(&input.stdin()?);
+    if let Some(kv_store) = input.kv_store() {
+        let kv_store =
+            kv_store.map_err(|err| CommonError::deserialize("key-value store", "bincode", err))?;
+        for (key, value) in kv_store {
+            stdin.add_key_value(key, kv_value(&value));
+        }
+    }
+    Ok(stdin)
+}
+
+/// Encodes `value` in the layout the `hint_load_by_key` instruction decodes and pushes onto the
+/// input stream, which is a `u32` LE number of vectors followed by each vector as a `u32` LE length
+/// and `u32` LE field elements. Here it's a single vector with a byte of `value` per element, the
+/// same as [`StdIn::write_bytes`], for the guest to read by `openvm::io::read_vec`.
+fn kv_value(value: &[u8]) -> Vec<u8> {
+    [1, value.len() as u32]
+        .into_iter()
+        .chain(value.iter().map(|byte| *byte as u32))
+        .flat_map(u32::to_le_bytes)
+        .collect()
+}
+
+/// Directory `cargo openvm setup` writes the keys and artifacts into.
+pub(crate) fn openvm_dir() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").expect("env `$HOME` should be set")).join(".openvm")
+}
+
 fn agg_pk_path() -> PathBuf {
-    PathBuf::from(std::env::var("HOME").expect("env `$HOME` should be set"))
-        .join(".openvm/agg_stark.pk")
+    openvm_dir().join("agg_stark.pk")
 }
 
 #[cfg(test)]
@@ -180,7 +381,10 @@ mod tests {
         program::basic::BasicProgram,
     };
 
-    use crate::prover::OpenVMProver;
+    use crate::{
+        opts::OpenVMSegmentOpts,
+        prover::{OpenVMProver, parse_app_config},
+    };
 
     fn basic_elf() -> Elf {
         static ELF: OnceLock<Elf> = OnceLock::new();
@@ -199,6 +403,52 @@ mod tests {
 
         let test_case = BasicProgram::<BincodeLegacy>::valid_test_case().into_output_sha256();
         run_zkvm_execute(&zkvm, &test_case);
+
+        let (_, report) = zkvm.execute(&test_case.input()).unwrap();
+        assert!(report.total_num_cycles > 0);
+        assert!(!report.chip_rows.is_empty());
+    }
+
+    #[test]
+    fn test_execute_app_config() {
+        let elf = basic_elf();
+        let app_config = parse_app_config(
+            "[app_vm_config.rv32i]\n[app_vm_config.rv32m]\n[app_vm_config.io]\n[app_vm_config.sha256]\n",
+        )
+        .unwrap();
+        let zkvm = OpenVMProver::new_with_app_config(elf, ProverResource::Cpu, app_config).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case().into_output_sha256();
+        run_zkvm_execute(&zkvm, &test_case);
+
+        assert!(parse_app_config("[app_vm_config.unknown]").is_err());
+    }
+
+    #[test]
+    fn test_execute_segment_opts() {
+        let elf = basic_elf();
+        let mut app_config = parse_app_config(
+            "[app_vm_config.rv32i]\n[app_vm_config.rv32m]\n[app_vm_config.io]\n[app_vm_config.sha256]\n",
+        )
+        .unwrap();
+        let opts = OpenVMSegmentOpts {
+            max_trace_height: Some(1 << 16),
+            ..Default::default()
+        };
+        opts.apply(&mut app_config);
+        assert_eq!(
+            app_config
+                .app_vm_config
+                .system
+                .config
+                .segmentation_limits
+                .max_trace_height,
+            1 << 16
+        );
+        let zkvm = OpenVMProver::new_with_app_config(elf, ProverResource::Cpu, app_config).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case().into_output_sha256();
+        run_zkvm_execute(&zkvm, &test_case);
     }
 
     #[test]
@@ -267,4 +517,20 @@ mod tests {
         let test_case = BasicProgram::<BincodeLegacy>::valid_test_case().into_output_sha256();
         run_zkvm_prove(&zkvm, &test_case);
     }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_prove_evm() {
+        let elf = basic_elf();
+        let zkvm = OpenVMProver::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case().into_output_sha256();
+        let (public_values, proof, _) = zkvm.prove_evm(&test_case.input()).unwrap();
+        assert_eq!(public_values, zkvm.execute(&test_case.input()).unwrap().0);
+        assert_eq!(proof.user_public_values, *public_values);
+
+        let dir = tempfile::tempdir().unwrap();
+        OpenVMProver::export_evm_verifier(dir.path()).unwrap();
+        assert!(dir.path().read_dir().unwrap().next().is_some());
+    }
 }
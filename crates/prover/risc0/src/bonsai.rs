@@ -0,0 +1,370 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use bonsai_sdk::{SdkErr, blocking::Client};
+use ere_prover_core::{
+    CommonError, ProofKind, RemoteProverConfig, RemoteProverOption, config::Config,
+};
+use risc0_zkvm::{Digest, Receipt, VERSION};
+
+use crate::error::Error;
+
+/// Default interval to poll the status of Bonsai sessions and snarks.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Status of a Bonsai session or snark still in progress.
+const STATUS_RUNNING: &str = "RUNNING";
+
+/// Status of a Bonsai session or snark that succeeded.
+const STATUS_SUCCEEDED: &str = "SUCCEEDED";
+
+/// HTTP headers of the API key and the `risc0-zkvm` version, same as the ones set by `bonsai-sdk`.
+const API_KEY_HEADER: &str = "x-api-key";
+const VERSION_HEADER: &str = "x-risc0-version";
+
+/// Client of [Bonsai] for [`ProverResource::Network`], with the endpoint and API key from
+/// [`RemoteProverConfig`] and the polling interval set by `ERE_RISC0_BONSAI_POLL_INTERVAL_SECS`.
+///
+/// [Bonsai]: https://dev.risczero.com/api/generating-proofs/remote-proving
+/// [`ProverResource::Network`]: ere_prover_core::ProverResource::Network
+pub(crate) struct BonsaiClient {
+    client: Client,
+    /// Client to check the API key with, since `bonsai-sdk` drops the HTTP status of failed
+    /// requests, which is needed to tell a rejected request from a transient server error.
+    http: reqwest::blocking::Client,
+    endpoint: String,
+    api_key: String,
+    poll_interval: Duration,
+    timeout: Option<Duration>,
+}
+
+/// Receipt proven by Bonsai.
+pub(crate) struct BonsaiReceipt {
+    pub(crate) receipt: Receipt,
+    pub(crate) total_cycles: Option<u64>,
+    pub(crate) session_id: String,
+}
+
+impl BonsaiClient {
+    pub(crate) fn new(config: &RemoteProverConfig) -> Result<Self, Error> {
+        // Bonsai has no fulfillment options or cycle limit of requests.
+        config.check_supported(&[RemoteProverOption::TimeoutSecs])?;
+        let api_key = config.api_key.clone().ok_or(Error::BonsaiApiKeyMissing)?;
+        let client = Client::from_parts(config.endpoint.clone(), api_key.clone(), VERSION)
+            .map_err(Error::bonsai("Failed to create client"))?;
+
+        let poll_interval = match Config::global()
+            .map_err(CommonError::from)?
+            .var("ERE_RISC0_BONSAI_POLL_INTERVAL_SECS")
+        {
+            Some(value) => match value.parse() {
+                Ok(secs) if secs > 0 => Duration::from_secs(secs),
+                _ => Err(Error::InvalidEnvVar {
+                    key: "ERE_RISC0_BONSAI_POLL_INTERVAL_SECS",
+                    value,
+                })?,
+            },
+            None => DEFAULT_POLL_INTERVAL,
+        };
+
+        Ok(Self {
+            client,
+            http: reqwest::blocking::Client::new(),
+            endpoint: config.endpoint.trim_end_matches('/').to_string(),
+            api_key,
+            poll_interval,
+            timeout: config.timeout_secs.map(Duration::from_secs),
+        })
+    }
+
+    /// Proves `program` with `stdin` into a succinct receipt for [`ProofKind::Compressed`], or a
+    /// Groth16 receipt wrapping it for [`ProofKind::Groth16`].
+    pub(crate) fn prove(
+        &self,
+        program: &[u8],
+        image_id: Digest,
+        stdin: Vec<u8>,
+        assumptions: &[Receipt],
+        kind: ProofKind,
+    ) -> Result<BonsaiReceipt, Error> {
+        let start = Instant::now();
+
+        self.check_api_key()?;
+
+        let image_id = image_id.to_string();
+        self.client
+            .upload_img(&image_id, program.to_vec())
+            .map_err(Error::bonsai("Failed to upload program"))?;
+        let input_id = self
+            .client
+            .upload_input(stdin)
+            .map_err(Error::bonsai("Failed to upload input"))?;
+        let assumption_ids = assumptions
+            .iter()
+            .map(|receipt| {
+                let bytes = bincode::serde::encode_to_vec(receipt, bincode::config::legacy())
+                    .map_err(|err| CommonError::serialize("assumption", "bincode", err))?;
+                self.client
+                    .upload_receipt(bytes)
+                    .map_err(Error::bonsai("Failed to upload assumption"))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let session = self
+            .client
+            .create_session(image_id, input_id, assumption_ids, false)
+            .map_err(Error::bonsai("Failed to create session"))?;
+        let (receipt_url, total_cycles) = loop {
+            let res = session
+                .status(&self.client)
+                .map_err(Error::bonsai("Failed to get session status"))?;
+            if res.status == STATUS_RUNNING {
+                self.wait(start, &session.uuid)?;
+                continue;
+            }
+            match (res.status.as_str(), res.receipt_url) {
+                (STATUS_SUCCEEDED, Some(receipt_url)) => {
+                    break (receipt_url, res.stats.map(|stats| stats.total_cycles));
+                }
+                (status, _) => Err(Error::BonsaiFailed {
+                    id: session.uuid.clone(),
+                    status: status.to_string(),
+                    error_msg: res.error_msg.unwrap_or_default(),
+                })?,
+            }
+        };
+
+        let receipt_url = match kind {
+            ProofKind::Compressed => receipt_url,
+            ProofKind::Groth16 => {
+                let snark = self
+                    .client
+                    .create_snark(session.uuid.clone())
+                    .map_err(Error::bonsai("Failed to create snark"))?;
+                loop {
+                    let res = snark
+                        .status(&self.client)
+                        .map_err(Error::bonsai("Failed to get snark status"))?;
+                    if res.status == STATUS_RUNNING {
+                        self.wait(start, &snark.uuid)?;
+                        continue;
+                    }
+                    match (res.status.as_str(), res.output) {
+                        (STATUS_SUCCEEDED, Some(output_url)) => break output_url,
+                        (status, _) => Err(Error::BonsaiFailed {
+                            id: snark.uuid.clone(),
+                            status: status.to_string(),
+                            error_msg: res.error_msg.unwrap_or_default(),
+                        })?,
+                    }
+                }
+            }
+            ProofKind::Plonk => Err(CommonError::unsupported_proof_kind(
+                kind,
+                [ProofKind::Compressed, ProofKind::Groth16],
+            ))?,
+        };
+
+        let bytes = self
+            .client
+            .download(&receipt_url)
+            .map_err(Error::bonsai("Failed to download receipt"))?;
+        let (receipt, _) = bincode::serde::decode_from_slice(&bytes, bincode::config::legacy())
+            .map_err(|err| CommonError::deserialize("receipt", "bincode", err))?;
+
+        Ok(BonsaiReceipt {
+            receipt,
+            total_cycles,
+            session_id: session.uuid,
+        })
+    }
+
+    /// Checks the API key is accepted by Bonsai by querying the user quotas, returns
+    /// [`Error::BonsaiRejected`] with the HTTP status otherwise.
+    fn check_api_key(&self) -> Result<(), Error> {
+        let res = self
+            .http
+            .get(format!("{}/user/quotas", self.endpoint))
+            .header(API_KEY_HEADER, &self.api_key)
+            .header(VERSION_HEADER, VERSION)
+            .send()
+            .map_err(|err| Error::bonsai("Failed to check API key")(SdkErr::HttpErr(err)))?;
+        let status = res.status();
+        if !status.is_success() {
+            return Err(Error::BonsaiRejected {
+                status: status.as_u16(),
+                body: res.text().unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Sleeps for the polling interval, returns error if the timeout since `start` is exceeded.
+    fn wait(&self, start: Instant, id: &str) -> Result<(), Error> {
+        if let Some(timeout) = self.timeout
+            && start.elapsed() > timeout
+        {
+            return Err(Error::BonsaiTimeout {
+                id: id.to_string(),
+                timeout,
+            });
+        }
+        thread::sleep(self.poll_interval);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use bonsai_sdk::blocking::Client;
+    use ere_prover_core::{ErrorKind, ProofKind, zkVMError};
+    use httpmock::{
+        Method::{GET, POST, PUT},
+        Mock, MockServer,
+    };
+    use risc0_zkvm::{Digest, FakeReceipt, InnerReceipt, Receipt, ReceiptClaim, VERSION};
+
+    use crate::{bonsai::BonsaiClient, error::Error};
+
+    const API_KEY: &str = "api-key";
+
+    fn client(server: &MockServer) -> BonsaiClient {
+        BonsaiClient {
+            client: Client::from_parts(server.base_url(), API_KEY.to_string(), VERSION).unwrap(),
+            http: reqwest::blocking::Client::new(),
+            endpoint: server.base_url(),
+            api_key: API_KEY.to_string(),
+            poll_interval: Duration::from_millis(10),
+            timeout: None,
+        }
+    }
+
+    /// Mocks `GET {path}` to respond `body` in JSON.
+    fn mock_get<'a>(server: &'a MockServer, path: &str, body: &str) -> Mock<'a> {
+        server.mock(|when, then| {
+            when.method(GET).path(path).header("x-api-key", API_KEY);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(body);
+        })
+    }
+
+    /// Waits until `mock` is hit `hits` times.
+    fn wait_hits(mock: &Mock, hits: usize) {
+        while mock.hits() < hits {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_prove_polling() {
+        let server = MockServer::start();
+        let receipt = Receipt::new(
+            InnerReceipt::Fake(FakeReceipt::new(ReceiptClaim::ok(
+                Digest::ZERO,
+                b"journal".to_vec(),
+            ))),
+            b"journal".to_vec(),
+        );
+        let receipt = bincode::serde::encode_to_vec(&receipt, bincode::config::legacy()).unwrap();
+
+        mock_get(&server, "/user/quotas", "{}");
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/images/upload/{}", Digest::ZERO));
+            then.status(204);
+        });
+        let upload_url = server.url("/upload/input");
+        mock_get(
+            &server,
+            "/inputs/upload",
+            &format!(r#"{{"url":"{upload_url}","uuid":"input"}}"#),
+        );
+        server.mock(|when, then| {
+            when.method(PUT).path("/upload/input");
+            then.status(200);
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sessions/create");
+            then.status(200).body(r#"{"uuid":"session"}"#);
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/snark/create");
+            then.status(200).body(r#"{"uuid":"snark"}"#);
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/receipt");
+            then.status(200).body(receipt);
+        });
+
+        // Mocks matching the same request respond in the order of creation, so the status is
+        // `RUNNING` until the mock is deleted.
+        let mut session_running = mock_get(
+            &server,
+            "/sessions/status/session",
+            r#"{"status":"RUNNING"}"#,
+        );
+        let receipt_url = server.url("/receipt");
+        let stats = r#"{"segments":1,"total_cycles":65536,"cycles":1024}"#;
+        mock_get(
+            &server,
+            "/sessions/status/session",
+            &format!(r#"{{"status":"SUCCEEDED","receipt_url":"{receipt_url}","stats":{stats}}}"#),
+        );
+        let mut snark_running = mock_get(&server, "/snark/status/snark", r#"{"status":"RUNNING"}"#);
+        mock_get(
+            &server,
+            "/snark/status/snark",
+            &format!(r#"{{"status":"SUCCEEDED","output":"{receipt_url}"}}"#),
+        );
+
+        thread::scope(|scope| {
+            let client = client(&server);
+            let handle = scope.spawn(move || {
+                client.prove(
+                    b"program",
+                    Digest::ZERO,
+                    Vec::new(),
+                    &[],
+                    ProofKind::Groth16,
+                )
+            });
+
+            wait_hits(&session_running, 2);
+            session_running.delete();
+            wait_hits(&snark_running, 2);
+            snark_running.delete();
+
+            let proved = handle.join().unwrap().unwrap();
+            assert_eq!(proved.receipt.journal.bytes, b"journal");
+            assert_eq!(proved.total_cycles, Some(65536));
+            assert_eq!(proved.session_id, "session");
+        });
+    }
+
+    #[test]
+    fn test_prove_rejected() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/user/quotas");
+            then.status(403).body(r#"{"message":"Forbidden"}"#);
+        });
+
+        let err = client(&server)
+            .prove(
+                b"program",
+                Digest::ZERO,
+                Vec::new(),
+                &[],
+                ProofKind::Compressed,
+            )
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::BonsaiRejected { status: 403, .. }));
+        assert_eq!(err.kind(), ErrorKind::ProverInfrastructure);
+    }
+}
@@ -1,6 +1,8 @@
 use core::ops::RangeInclusive;
+use std::{io, time::Duration};
 
-use ere_prover_core::CommonError;
+use bonsai_sdk::SdkErr;
+use ere_prover_core::{CommonError, ErrorKind, zkVMError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -11,6 +13,9 @@ pub enum Error {
     #[error("Deserialize proofs in Input failed: {0:?}")]
     DeserializeInputProofs(bincode::error::DecodeError),
 
+    #[error("Deserialize key-value store in Input failed: {0:?}")]
+    DeserializeInputKvStore(bincode::error::DecodeError),
+
     #[error("Unsupported power of 2 value {val} of {key}, expected in range {range:?}")]
     UnsupportedPo2Value {
         key: String,
@@ -18,6 +23,9 @@ pub enum Error {
         range: RangeInclusive<usize>,
     },
 
+    #[error("Invalid env variable {key}, got {value}")]
+    InvalidEnvVar { key: &'static str, value: String },
+
     #[error("Failed to compute image ID: {0}")]
     ComputeImageId(anyhow::Error),
 
@@ -32,10 +40,94 @@ pub enum Error {
     #[error("Failed to initialize cuda prover: {0}")]
     InitializeCudaProver(anyhow::Error),
 
+    #[error("r0vm server at {addr} is unreachable: {err}")]
+    R0vmServerUnreachable {
+        addr: String,
+        #[source]
+        err: io::Error,
+    },
+
     #[error("Failed to prove: {0}")]
     Prove(anyhow::Error),
 
+    #[error("API key of Bonsai is required in `RemoteProverConfig`")]
+    BonsaiApiKeyMissing,
+
+    #[error("{ctx} on Bonsai: {err}")]
+    Bonsai {
+        ctx: &'static str,
+        #[source]
+        err: SdkErr,
+    },
+
+    #[error("Bonsai {id} failed with status {status}: {error_msg}")]
+    BonsaiFailed {
+        id: String,
+        status: String,
+        error_msg: String,
+    },
+
+    #[error("Bonsai {id} timed out after {timeout:?}")]
+    BonsaiTimeout { id: String, timeout: Duration },
+
+    #[error("Bonsai rejected the request with HTTP status {status}: {body}")]
+    BonsaiRejected { status: u16, body: String },
+
     // Verify
     #[error(transparent)]
     Verifier(#[from] ere_verifier_risc0::Error),
 }
+
+impl Error {
+    pub(crate) fn bonsai(ctx: &'static str) -> impl FnOnce(SdkErr) -> Self {
+        move |err| Self::Bonsai { ctx, err }
+    }
+
+    /// Returns whether the HTTP `status` is a client error that won't succeed by retrying, which
+    /// excludes `429 Too Many Requests`.
+    fn is_rejected(status: u16) -> bool {
+        (400..500).contains(&status) && status != 429
+    }
+}
+
+impl zkVMError for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommonError(err) => err.kind(),
+            Self::DeserializeInputProofs(_) | Self::DeserializeInputKvStore(_) => {
+                ErrorKind::InvalidInput
+            }
+            Self::Execute(err) => ErrorKind::from_source(err.as_ref(), ErrorKind::guest_panic(err)),
+            Self::Prove(err) => {
+                ErrorKind::from_source(err.as_ref(), ErrorKind::ProverInfrastructure)
+            }
+            // Bonsai only reports a message, which is not matched.
+            Self::UnsupportedPo2Value { .. }
+            | Self::BonsaiFailed { .. }
+            | Self::InvalidEnvVar { .. }
+            | Self::BonsaiApiKeyMissing
+            | Self::ComputeImageId(_)
+            | Self::BuildExecutorEnv(_)
+            | Self::InitializeCudaProver(_) => ErrorKind::ProverInfrastructure,
+            Self::Bonsai { err, .. } => match err {
+                // `bonsai-sdk` drops the HTTP status of failed requests, so they are assumed to be
+                // server errors, and a rejected API key is found by `BonsaiRejected` beforehand.
+                SdkErr::InternalServerErr(_) => ErrorKind::Transient,
+                SdkErr::HttpErr(err) => match err.status() {
+                    Some(status) if Self::is_rejected(status.as_u16()) => {
+                        ErrorKind::ProverInfrastructure
+                    }
+                    _ => ErrorKind::Transient,
+                },
+                _ => ErrorKind::ProverInfrastructure,
+            },
+            Self::BonsaiRejected { status, .. } if Self::is_rejected(*status) => {
+                ErrorKind::ProverInfrastructure
+            }
+            Self::R0vmServerUnreachable { .. }
+            | Self::BonsaiRejected { .. }
+            | Self::BonsaiTimeout { .. } => ErrorKind::Transient,
+            Self::Verifier(_) => ErrorKind::VerificationFailed,
+        }
+    }
+}
@@ -13,6 +13,10 @@
 //! - [`rzup`]
 //! - Installation via `rzup install`
 //! - `r0vm-cuda` - Used by `zkVMProver::prove` if `ProverResource::Gpu` is selected
+//! - `docker` - Used by `zkVMProver::prove_with_kind` with `ProofKind::Groth16` if
+//!   `ProverResource::Cpu` is selected, so `ere-server` (which proves only `ProofKind::Compressed`)
+//!   can't prove Groth16 receipts in a container without access to a Docker daemon
+//! - Bonsai API key - Used by `zkVMProver::prove` if `ProverResource::Network` is selected
 //!
 //! # `Compiler` implementation
 //!
@@ -26,18 +30,42 @@
 //! | --------- | :-------: |
 //! | `Cpu`     |    Yes    |
 //! | `Gpu`     |    Yes    |
-//! | `Network` |    No     |
+//! | `Network` |    Yes    |
 //! | `Cluster` |    No     |
 //!
+//! ## Auxiliary output
+//!
+//! Auxiliary output the guest writes via `Platform::write_auxiliary_output` is captured by
+//! `zkVMProver::execute` and discarded when proving locally or with the r0vm server. Bonsai has
+//! no way to register its file descriptor, so guests writing it can't be proved on Bonsai.
+//!
+//! ## Environment variables
+//!
+//! | Variable                              | Type  | Default | Description                                                  |
+//! | ------------------------------------- | ----- | ------- | ------------------------------------------------------------ |
+//! | `ERE_RISC0_SEGMENT_PO2`               | Value | `20`    | Logarithmic segment size, see [`Risc0ProverOpts`]            |
+//! | `ERE_RISC0_KECCAK_PO2`                | Value | `17`    | Logarithmic keccak segment size                              |
+//! | `ERE_RISC0_MAX_SEGMENTS`              | Value |         | Maximum number of segments                                   |
+//! | `ERE_RISC0_SESSION_LIMIT`             | Value |         | Maximum number of cycles of a session                        |
+//! | `ERE_RISC0_R0VM_ADDR`                 | Value |         | `{host}:{port}` of a running r0vm server, see [`R0vmServer`] |
+//! | `ERE_RISC0_BONSAI_POLL_INTERVAL_SECS` | Value | `5`     | Interval to poll Bonsai for `ProverResource::Network`        |
+//!
+//! In `ere-dockerized`, a loopback host of `ERE_RISC0_R0VM_ADDR` is replaced by
+//! `host.docker.internal` unless the container uses the host network, so the r0vm server on the
+//! host has to listen on the Docker bridge interface too, e.g. on `0.0.0.0`.
+//!
 //! [`install_risc0_sdk.sh`]: https://github.com/eth-act/ere/blob/master/scripts/sdk_installers/install_risc0_sdk.sh
 //! [`rzup`]: https://risczero.com/install
 
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod bonsai;
 mod error;
+mod opts;
 mod prover;
+mod r0vm;
 
 pub use ere_prover_core::*;
 pub use ere_verifier_risc0::*;
 
-pub use crate::{error::Error, prover::Risc0Prover};
+pub use crate::{error::Error, opts::Risc0ProverOpts, prover::Risc0Prover, r0vm::R0vmServer};
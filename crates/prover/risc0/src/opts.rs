@@ -0,0 +1,133 @@
+use core::ops::RangeInclusive;
+
+use ere_prover_core::{CommonError, config::Config};
+use risc0_zkvm::DEFAULT_MAX_PO2;
+
+use crate::error::Error;
+
+/// Default logarithmic segment size from [`DEFAULT_SEGMENT_LIMIT_PO2`].
+///
+/// [`DEFAULT_SEGMENT_LIMIT_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/rv32im/src/execute/mod.rs#L39.
+const DEFAULT_SEGMENT_PO2: usize = 20;
+
+/// Supported range of logarithmic segment size.
+///
+/// The minimum is by [`MIN_LIFT_PO2`] to be lifted.
+///
+/// The maximum is by [`DEFAULT_MAX_PO2`], although the real maximum is `24`,
+/// but it requires us to set the `control_ids` manually in the `ProverOpts`.
+///
+/// [`MIN_LIFT_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/recursion/src/control_id.rs#L19
+/// [`DEFAULT_MAX_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/zkvm/src/receipt.rs#L898
+const SEGMENT_PO2_RANGE: RangeInclusive<usize> = 14..=DEFAULT_MAX_PO2;
+
+/// Default logarithmic keccak size from [`KECCAK_DEFAULT_PO2`].
+///
+/// [`KECCAK_DEFAULT_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/keccak/src/lib.rs#L27.
+const DEFAULT_KECCAK_PO2: usize = 17;
+
+/// Supported range of logarithmic keccak size from [`KECCAK_PO2_RANGE`].
+///
+/// [`KECCAK_PO2_RANGE`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/keccak/src/lib.rs#L29.
+const KECCAK_PO2_RANGE: RangeInclusive<usize> = 14..=18;
+
+/// Segmentation and limits of the Risc0 executor, which are set by `ERE_RISC0_*` by default, see
+/// [`Risc0ProverOpts::from_env`].
+///
+/// Each segment is proven separately, so smaller segments lower the peak memory of proving, at the
+/// cost of more segments to prove and join.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Risc0ProverOpts {
+    /// Logarithmic maximum number of cycles per segment, in range `14..=22`.
+    pub segment_po2: usize,
+    /// Logarithmic maximum number of cycles per keccak segment, in range `14..=18`.
+    pub keccak_po2: usize,
+    /// Maximum number of segments, unbounded if `None`. Enforced as a session limit of
+    /// `max_segments * 2^segment_po2` cycles, since segments are cut by the cycles.
+    pub max_segments: Option<u64>,
+    /// Maximum number of cycles of a session, unbounded if `None`.
+    pub session_limit: Option<u64>,
+}
+
+impl Default for Risc0ProverOpts {
+    fn default() -> Self {
+        Self {
+            segment_po2: DEFAULT_SEGMENT_PO2,
+            keccak_po2: DEFAULT_KECCAK_PO2,
+            max_segments: None,
+            session_limit: None,
+        }
+    }
+}
+
+impl Risc0ProverOpts {
+    /// Reads `ERE_RISC0_SEGMENT_PO2`, `ERE_RISC0_KECCAK_PO2`, `ERE_RISC0_MAX_SEGMENTS` and
+    /// `ERE_RISC0_SESSION_LIMIT`.
+    pub fn from_env() -> Result<Self, Error> {
+        let config = Config::global().map_err(CommonError::from)?;
+        let parse_po2 = |key: &str, default: usize, range: RangeInclusive<usize>| {
+            let Some(val) = config.var(key) else {
+                return Ok(default);
+            };
+
+            match val.parse() {
+                Ok(val) if range.contains(&val) => Ok(val),
+                _ => Err(Error::UnsupportedPo2Value {
+                    key: key.to_string(),
+                    val,
+                    range,
+                }),
+            }
+        };
+        let parse_limit = |key: &'static str| {
+            config
+                .var(key)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| Error::InvalidEnvVar { key, value })
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            segment_po2: parse_po2(
+                "ERE_RISC0_SEGMENT_PO2",
+                DEFAULT_SEGMENT_PO2,
+                SEGMENT_PO2_RANGE,
+            )?,
+            keccak_po2: parse_po2("ERE_RISC0_KECCAK_PO2", DEFAULT_KECCAK_PO2, KECCAK_PO2_RANGE)?,
+            max_segments: parse_limit("ERE_RISC0_MAX_SEGMENTS")?,
+            session_limit: parse_limit("ERE_RISC0_SESSION_LIMIT")?,
+        })
+    }
+
+    /// Returns error if `segment_po2` or `keccak_po2` is out of the supported range.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for (key, val, range) in [
+            ("segment_po2", self.segment_po2, SEGMENT_PO2_RANGE),
+            ("keccak_po2", self.keccak_po2, KECCAK_PO2_RANGE),
+        ] {
+            if !range.contains(&val) {
+                return Err(Error::UnsupportedPo2Value {
+                    key: key.to_string(),
+                    val: val.to_string(),
+                    range,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the session limit bounding both the number of cycles and segments, where each
+    /// segment has at most `2^segment_po2` cycles.
+    pub(crate) fn cycle_limit(&self) -> Option<u64> {
+        let segments_limit = self
+            .max_segments
+            .map(|max_segments| max_segments.saturating_mul(1 << self.segment_po2));
+        match (self.session_limit, segments_limit) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
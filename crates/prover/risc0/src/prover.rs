@@ -1,95 +1,101 @@
-use core::ops::RangeInclusive;
-use std::{env, rc::Rc, time::Instant};
+use std::{
+    io,
+    path::Path,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use ere_compiler_core::Elf;
+use ere_platform_risc0::AUXILIARY_OUTPUT_FD;
 use ere_prover_core::{
-    CommonError, Input, ProgramExecutionReport, ProgramProvingReport, ProverResource,
-    ProverResourceKind, PublicValues, zkVMProver,
+    CommonError, ExecuteOptions, Input, ProgramExecutionReport, ProgramProvingReport, ProofKind,
+    ProverResource, ProverResourceKind, PublicValues, zkVMProver,
 };
 use ere_verifier_risc0::{Risc0ProgramVk, Risc0Proof, Risc0Verifier};
 use risc0_zkvm::{
-    AssumptionReceipt, DEFAULT_MAX_PO2, DefaultProver, ExecutorEnv, ExternalProver, ProverOpts,
-    default_executor, default_prover,
+    Asset, AssumptionReceipt, Bytes, DefaultProver, ExecutorEnv, ExternalProver, GUEST_MAX_MEM,
+    ProverOpts, Receipt, default_executor, default_prover,
+};
+
+use crate::{
+    bonsai::{BonsaiClient, BonsaiReceipt},
+    error::Error,
+    opts::Risc0ProverOpts,
+    r0vm::R0vmServer,
 };
 
-use crate::error::Error;
-
-/// Default logarithmic segment size from [`DEFAULT_SEGMENT_LIMIT_PO2`].
-///
-/// [`DEFAULT_SEGMENT_LIMIT_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/rv32im/src/execute/mod.rs#L39.
-const DEFAULT_SEGMENT_PO2: usize = 20;
-
-/// Supported range of logarithmic segment size.
-///
-/// The minimum is by [`MIN_LIFT_PO2`] to be lifted.
-///
-/// The maximum is by [`DEFAULT_MAX_PO2`], although the real maximum is `24`,
-/// but it requires us to set the `control_ids` manually in the `ProverOpts`.
-///
-/// [`MIN_LIFT_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/recursion/src/control_id.rs#L19
-/// [`DEFAULT_MAX_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/zkvm/src/receipt.rs#L898
-const SEGMENT_PO2_RANGE: RangeInclusive<usize> = 14..=DEFAULT_MAX_PO2;
-
-/// Default logarithmic keccak size from [`KECCAK_DEFAULT_PO2`].
-///
-/// [`KECCAK_DEFAULT_PO2`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/keccak/src/lib.rs#L27.
-const DEFAULT_KECCAK_PO2: usize = 17;
-
-/// Supported range of logarithmic keccak size from [`KECCAK_PO2_RANGE`].
-///
-/// [`KECCAK_PO2_RANGE`]: https://github.com/risc0/risc0/blob/v3.0.5/risc0/circuit/keccak/src/lib.rs#L29.
-const KECCAK_PO2_RANGE: RangeInclusive<usize> = 14..=18;
+/// IO callback channel the guest reads key-value store from via `Platform::read_kv`.
+const KV_READ_CHANNEL: &str = "ere_kv_read";
+
+/// Maximum input length, since `Risc0Platform::read_input` reads stdin into the guest memory of
+/// `GUEST_MAX_MEM` bytes in whole. Input shorter could still run out of guest memory along with
+/// the program and its other allocations.
+const MAX_INPUT_LEN: usize = GUEST_MAX_MEM;
+
+/// Error message of executor when session limit is exceeded, followed by
+/// `{user_cycles} >= {session_limit}`.
+const SESSION_LIMIT_EXCEEDED: &str = "Session limit exceeded: ";
 
 pub struct Risc0Prover {
     elf: Elf,
     verifier: Risc0Verifier,
     resource: ProverResource,
-    segment_po2: usize,
-    keccak_po2: usize,
+    opts: Risc0ProverOpts,
+    r0vm_server: R0vmServer,
+    /// Set for [`ProverResource::Network`].
+    bonsai: Option<BonsaiClient>,
 }
 
 impl Risc0Prover {
+    /// Creates the prover with [`Risc0ProverOpts::from_env`] and [`R0vmServer::from_env`].
+    ///
+    /// [`ProverResource::Network`] proves on Bonsai, with the endpoint and API key from the
+    /// [`RemoteProverConfig`], while execution still runs locally.
+    ///
+    /// [`RemoteProverConfig`]: ere_prover_core::RemoteProverConfig
     pub fn new(elf: Elf, resource: ProverResource) -> Result<Self, Error> {
-        if !matches!(resource, ProverResource::Cpu | ProverResource::Gpu) {
-            Err(CommonError::unsupported_prover_resource_kind(
+        let bonsai = match &resource {
+            ProverResource::Cpu | ProverResource::Gpu => None,
+            ProverResource::Network(config) => Some(BonsaiClient::new(config)?),
+            _ => Err(CommonError::unsupported_prover_resource_kind(
                 resource.kind(),
-                [ProverResourceKind::Cpu, ProverResourceKind::Gpu],
-            ))?;
-        }
+                [
+                    ProverResourceKind::Cpu,
+                    ProverResourceKind::Gpu,
+                    ProverResourceKind::Network,
+                ],
+            ))?,
+        };
 
         let image_id = risc0_binfmt::compute_image_id(&elf).map_err(Error::ComputeImageId)?;
         let verifier = Risc0Verifier::new(Risc0ProgramVk(image_id));
 
-        let parse_env = |key: &str, default: usize, range: RangeInclusive<usize>| {
-            let Ok(val) = env::var(key) else {
-                return Ok(default);
-            };
-
-            match val.parse() {
-                Ok(val) if range.contains(&val) => Ok(val),
-                _ => Err(Error::UnsupportedPo2Value {
-                    key: key.to_string(),
-                    val,
-                    range,
-                }),
-            }
-        };
-
-        let segment_po2 = parse_env(
-            "ERE_RISC0_SEGMENT_PO2",
-            DEFAULT_SEGMENT_PO2,
-            SEGMENT_PO2_RANGE,
-        )?;
-        let keccak_po2 = parse_env("ERE_RISC0_KECCAK_PO2", DEFAULT_KECCAK_PO2, KECCAK_PO2_RANGE)?;
+        let r0vm_server = R0vmServer::from_env()?;
+        r0vm_server.check_health()?;
 
         Ok(Self {
             elf,
             verifier,
             resource,
-            segment_po2,
-            keccak_po2,
+            opts: Risc0ProverOpts::from_env()?,
+            r0vm_server,
+            bonsai,
         })
     }
+
+    /// Returns error if `segment_po2` or `keccak_po2` of `opts` is out of the supported range.
+    pub fn with_prover_opts(mut self, opts: Risc0ProverOpts) -> Result<Self, Error> {
+        opts.validate()?;
+        self.opts = opts;
+        Ok(self)
+    }
+
+    /// Returns error if `r0vm_server` is unreachable.
+    pub fn with_r0vm_server(mut self, r0vm_server: R0vmServer) -> Result<Self, Error> {
+        r0vm_server.check_health()?;
+        self.r0vm_server = r0vm_server;
+        Ok(self)
+    }
 }
 
 impl zkVMProver for Risc0Prover {
@@ -100,13 +106,46 @@ impl zkVMProver for Risc0Prover {
         &self.verifier
     }
 
-    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport), Error> {
-        let env = self.input_to_env(input)?;
+    fn max_input_len(&self) -> Option<usize> {
+        Some(MAX_INPUT_LEN)
+    }
+
+    fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        let max_cycles = match (options.max_cycles, self.opts.cycle_limit()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        // Kept after execution for the caller to inspect, e.g. by `go tool pprof`.
+        let profile_path = options
+            .profile
+            .then(|| {
+                tempfile::Builder::new()
+                    .prefix("ere-risc0-")
+                    .suffix(".pb")
+                    .tempfile()
+                    .and_then(|file| file.into_temp_path().keep().map_err(Into::into))
+                    .map_err(|err| CommonError::io("Failed to create profile file", err))
+            })
+            .transpose()?;
+
+        let mut captured = CapturedOutput::default();
+        let env = self.input_to_env(
+            input,
+            max_cycles,
+            Some(&mut captured),
+            profile_path.as_deref(),
+        )?;
 
         let executor = default_executor();
 
         let start = Instant::now();
-        let session_info = executor.execute(env, &self.elf).map_err(Error::Execute)?;
+        let session_info = executor
+            .execute(env, &self.elf)
+            .map_err(|err| session_error(err, max_cycles, Error::Execute))?;
         let execution_duration = start.elapsed();
 
         Ok((
@@ -114,6 +153,9 @@ impl zkVMProver for Risc0Prover {
             ProgramExecutionReport {
                 total_num_cycles: session_info.cycles() as u64,
                 execution_duration,
+                auxiliary_output: captured.auxiliary_output,
+                guest_logs: String::from_utf8_lossy(&captured.stdout).into_owned(),
+                profile_path,
                 ..Default::default()
             },
         ))
@@ -123,7 +165,73 @@ impl zkVMProver for Risc0Prover {
         &self,
         input: &Input,
     ) -> Result<(PublicValues, Risc0Proof, ProgramProvingReport), Error> {
-        let env = self.input_to_env(input)?;
+        self.prove_with_kind(input, ProofKind::Compressed)
+    }
+
+    /// Proves into a succinct receipt for [`ProofKind::Compressed`], or a Groth16 receipt wrapping
+    /// it for [`ProofKind::Groth16`], which runs the STARK-to-SNARK circuit in docker unless
+    /// proving with `r0vm-cuda`.
+    fn prove_with_kind(
+        &self,
+        input: &Input,
+        kind: ProofKind,
+    ) -> Result<(PublicValues, Risc0Proof, ProgramProvingReport), Error> {
+        let opts = match kind {
+            ProofKind::Compressed => ProverOpts::succinct(),
+            ProofKind::Groth16 => ProverOpts::groth16(),
+            ProofKind::Plonk => Err(CommonError::unsupported_proof_kind(
+                kind,
+                self.proof_kinds().iter().copied(),
+            ))?,
+        };
+
+        if let Some(bonsai) = &self.bonsai {
+            if input.kv_store.is_some() {
+                Err(CommonError::unsupported_input(
+                    "no key-value store hint on Bonsai",
+                ))?
+            }
+            let assumptions: Vec<Receipt> = input
+                .proofs()
+                .transpose()
+                .map_err(Error::DeserializeInputProofs)?
+                .unwrap_or_default();
+
+            let start = Instant::now();
+            let BonsaiReceipt {
+                receipt,
+                total_cycles,
+                session_id,
+            } = bonsai.prove(
+                &self.elf.0,
+                self.program_vk().0,
+                stdin_bytes(input)?,
+                &assumptions,
+                kind,
+            )?;
+            let (public_values, proof, mut report) =
+                proving_result(input, receipt, total_cycles, start.elapsed());
+            report.network_request_id = Some(session_id);
+            return Ok((public_values, proof, report));
+        }
+
+        let max_cycles = self.opts.cycle_limit();
+        let env = self.input_to_env(input, max_cycles, None, None)?;
+
+        if let Some(client) = self.r0vm_server.client() {
+            self.r0vm_server.check_health()?;
+            let start = Instant::now();
+            let binary = Asset::Inline(self.elf.0.clone().into());
+            let prove_info = client
+                .prove(&env, &opts, binary)
+                .map_err(|err| session_error(err, max_cycles, Error::Prove))?;
+            return Ok(proving_result(
+                input,
+                prove_info.receipt,
+                Some(prove_info.stats.total_cycles),
+                start.elapsed(),
+            ));
+        }
 
         let prover = match self.resource {
             ProverResource::Cpu => Rc::new(ExternalProver::new("ipc", "r0vm")),
@@ -149,42 +257,115 @@ impl zkVMProver for Risc0Prover {
             }
         };
 
-        let opts = ProverOpts::succinct();
-
         let start = Instant::now();
         let prove_info = prover
             .prove_with_opts(env, &self.elf, &opts)
-            .map_err(Error::Prove)?;
-        let proving_time = start.elapsed();
+            .map_err(|err| session_error(err, max_cycles, Error::Prove))?;
+        Ok(proving_result(
+            input,
+            prove_info.receipt,
+            Some(prove_info.stats.total_cycles),
+            start.elapsed(),
+        ))
+    }
 
-        let public_values = prove_info.receipt.journal.bytes.as_slice().into();
-        let proof = Risc0Proof(prove_info.receipt);
+    fn proof_kinds(&self) -> &'static [ProofKind] {
+        &[ProofKind::Compressed, ProofKind::Groth16]
+    }
+}
 
-        Ok((
-            public_values,
-            proof,
-            ProgramProvingReport {
-                proving_time,
-                total_num_cycles: Some(prove_info.stats.total_cycles),
-            },
-        ))
+/// Returns [`CommonError::CycleLimitExceeded`] if `err` is from the session reaching
+/// `max_cycles` user cycles, otherwise `err` wrapped by `wrap`.
+fn session_error(
+    err: anyhow::Error,
+    max_cycles: Option<u64>,
+    wrap: fn(anyhow::Error) -> Error,
+) -> Error {
+    match (max_cycles, session_limit_cycles(&err)) {
+        (Some(max_cycles), Some((user_cycles, session_limit)))
+            if session_limit == max_cycles && user_cycles >= max_cycles =>
+        {
+            CommonError::cycle_limit_exceeded(max_cycles).into()
+        }
+        _ => wrap(err),
     }
 }
 
+/// Returns the user cycles and the session limit of the session stopped by the executor on
+/// reaching the limit, which it reports only by the error instead of returning the session.
+fn session_limit_cycles(err: &anyhow::Error) -> Option<(u64, u64)> {
+    let msg = format!("{err:#}");
+    let (_, cycles) = msg.split_once(SESSION_LIMIT_EXCEEDED)?;
+    let (user_cycles, session_limit) = cycles.split_once(" >= ")?;
+    let session_limit = session_limit.split(|c: char| !c.is_ascii_digit()).next()?;
+    Some((user_cycles.parse().ok()?, session_limit.parse().ok()?))
+}
+
+fn proving_result(
+    input: &Input,
+    receipt: Receipt,
+    total_num_cycles: Option<u64>,
+    proving_time: Duration,
+) -> (PublicValues, Risc0Proof, ProgramProvingReport) {
+    let public_values = receipt.journal.bytes.as_slice().into();
+    let proof = Risc0Proof(receipt);
+
+    (
+        public_values,
+        proof,
+        ProgramProvingReport {
+            proving_time,
+            total_num_cycles,
+            ..Default::default()
+        }
+        .with_input_commitment(input),
+    )
+}
+
+/// Returns stdin prefixed with its u32 LE byte length, which `Risc0Platform::read_input` reads to
+/// size the payload.
+fn stdin_bytes(input: &Input) -> Result<Vec<u8>, Error> {
+    input.check_len(Some(MAX_INPUT_LEN))?;
+    let stdin = input.stdin()?;
+    Ok([(stdin.len() as u32).to_le_bytes().as_slice(), &stdin].concat())
+}
+
+/// Output written by the guest besides public values, captured during execution.
+#[derive(Default)]
+struct CapturedOutput {
+    /// Written via `Platform::print`.
+    stdout: Vec<u8>,
+    /// Written via `Platform::write_auxiliary_output`.
+    auxiliary_output: Vec<u8>,
+}
+
 impl Risc0Prover {
-    /// Converts `Input` to `ExecutorEnv`.
+    /// Converts `Input` to `ExecutorEnv`, with session limit set to `max_cycles`.
+    ///
+    /// Stdin is framed by [`stdin_bytes`].
     ///
-    /// Stdin is prefixed with its u32 LE byte length, which `Risc0Platform::read_input` reads to
-    /// size the payload.
-    fn input_to_env(&self, input: &Input) -> Result<ExecutorEnv<'static>, Error> {
+    /// If `captured` is given, the guest stdout and auxiliary output are written into it instead
+    /// of the host stdout, otherwise the auxiliary output is discarded.
+    ///
+    /// If `profile_path` is given, the guest is profiled and the pprof profile is written to it.
+    fn input_to_env<'a>(
+        &self,
+        input: &Input,
+        max_cycles: Option<u64>,
+        captured: Option<&'a mut CapturedOutput>,
+        profile_path: Option<&Path>,
+    ) -> Result<ExecutorEnv<'a>, Error> {
         let mut env = ExecutorEnv::builder();
-        env.segment_limit_po2(self.segment_po2 as _)
-            .keccak_max_po2(self.keccak_po2 as _)
+        env.session_limit(max_cycles)
+            .segment_limit_po2(self.opts.segment_po2 as _)
+            .keccak_max_po2(self.opts.keccak_po2 as _)
             .expect("keccak_po2 in valid range");
 
-        let stdin = input.stdin();
-        env.write_slice(&(stdin.len() as u32).to_le_bytes());
-        env.write_slice(stdin);
+        if let Some(profile_path) = profile_path {
+            env.enable_profiler(profile_path);
+        }
+
+        env.write_slice(&stdin_bytes(input)?);
 
         if let Some(receipts) = input.proofs() {
             for receipt in receipts.map_err(Error::DeserializeInputProofs)? {
@@ -192,6 +373,28 @@ impl Risc0Prover {
             }
         }
 
+        if let Some(kv_store) = input.kv_store() {
+            let kv_store = kv_store.map_err(Error::DeserializeInputKvStore)?;
+            env.io_callback(KV_READ_CHANNEL, move |key: Bytes| {
+                let response = match kv_store.get(key.as_ref()) {
+                    Some(value) => [[1].as_slice(), value].concat(),
+                    None => vec![0],
+                };
+                Ok(response.into())
+            });
+        }
+
+        match captured {
+            Some(CapturedOutput {
+                stdout,
+                auxiliary_output,
+            }) => env
+                .stdout(stdout)
+                .write_fd(AUXILIARY_OUTPUT_FD, auxiliary_output),
+            // Writes to a file descriptor without a writer fail, so discard it when proving.
+            None => env.write_fd(AUXILIARY_OUTPUT_FD, io::sink()),
+        };
+
         env.build().map_err(Error::BuildExecutorEnv)
     }
 }
@@ -202,14 +405,23 @@ mod tests {
 
     use ere_compiler_core::{Compiler, Elf};
     use ere_compiler_risc0::Risc0RustRv32imaCustomized;
-    use ere_prover_core::{Input, ProverResource, zkVMProver};
+    use ere_prover_core::{
+        CommonError, ExecuteOptions, Input, ProofKind, ProverResource, RemoteProverConfig,
+        zkVMProver,
+    };
     use ere_util_test::{
         codec::BincodeLegacy,
         host::{TestCase, run_zkvm_execute, run_zkvm_prove, testing_guest_directory},
         program::basic::BasicProgram,
     };
+    use risc0_zkvm::InnerReceipt;
 
-    use crate::prover::Risc0Prover;
+    use crate::{
+        error::Error,
+        opts::Risc0ProverOpts,
+        prover::{Risc0Prover, session_error},
+        r0vm::R0vmServer,
+    };
 
     fn basic_elf() -> Elf {
         static ELF: OnceLock<Elf> = OnceLock::new();
@@ -243,6 +455,137 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execute_cycle_limit() {
+        let elf = basic_elf();
+        let zkvm = Risc0Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let options = ExecuteOptions::new().with_max_cycles(1);
+        let err = zkvm
+            .execute_with_options(&test_case.input(), &options)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::CommonError(CommonError::CycleLimitExceeded { max_cycles: 1 })
+            ),
+            "Expect error variant `CycleLimitExceeded`, got {err:?}",
+        );
+    }
+
+    #[test]
+    fn test_session_error() {
+        let limit_err = || anyhow::anyhow!("Session limit exceeded: 5 >= 4");
+        for (err, max_cycles, exceeded) in [
+            (limit_err(), Some(4), true),
+            (limit_err().context("execution failed"), Some(4), true),
+            (limit_err(), Some(8), false),
+            (limit_err(), None, false),
+            (
+                anyhow::anyhow!("Session limit exceeded: 3 >= 4"),
+                Some(4),
+                false,
+            ),
+            (anyhow::anyhow!("Session limit exceeded"), Some(4), false),
+            (anyhow::anyhow!("Guest panicked"), Some(4), false),
+        ] {
+            let err = session_error(err, max_cycles, Error::Execute);
+            assert_eq!(
+                matches!(
+                    err,
+                    Error::CommonError(CommonError::CycleLimitExceeded { max_cycles: 4 })
+                ),
+                exceeded,
+                "Unexpected mapping to {err:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_profile() {
+        let elf = basic_elf();
+        let zkvm = Risc0Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let options = ExecuteOptions::new().with_profile(true);
+        let (_, report) = zkvm
+            .execute_with_options(&test_case.input(), &options)
+            .unwrap();
+        let profile_path = report.profile_path.unwrap();
+        assert!(profile_path.is_file());
+        std::fs::remove_file(profile_path).unwrap();
+    }
+
+    #[test]
+    fn test_execute_prover_opts() {
+        let elf = basic_elf();
+        let zkvm = Risc0Prover::new(elf.clone(), ProverResource::Cpu)
+            .unwrap()
+            .with_prover_opts(Risc0ProverOpts {
+                session_limit: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let err = zkvm.execute(&test_case.input()).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::CommonError(CommonError::CycleLimitExceeded { max_cycles: 1 })
+            ),
+            "Expect error variant `CycleLimitExceeded`, got {err:?}",
+        );
+
+        // Proving hits the session limit the same way.
+        let err = zkvm.prove(&test_case.input()).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::CommonError(CommonError::CycleLimitExceeded { max_cycles: 1 })
+            ),
+            "Expect error variant `CycleLimitExceeded`, got {err:?}",
+        );
+
+        let err = Risc0Prover::new(elf, ProverResource::Cpu)
+            .unwrap()
+            .with_prover_opts(Risc0ProverOpts {
+                segment_po2: 13,
+                ..Default::default()
+            })
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::UnsupportedPo2Value { .. }));
+    }
+
+    #[test]
+    fn test_r0vm_server_unreachable() {
+        let elf = basic_elf();
+        let err = Risc0Prover::new(elf, ProverResource::Cpu)
+            .unwrap()
+            .with_r0vm_server(R0vmServer::Tcp {
+                host: "127.0.0.1".to_string(),
+                port: 1,
+            })
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::R0vmServerUnreachable { .. }));
+    }
+
+    #[test]
+    fn test_network_api_key_missing() {
+        let elf = basic_elf();
+        let config = RemoteProverConfig {
+            endpoint: "https://api.bonsai.xyz".to_string(),
+            ..Default::default()
+        };
+        let err = Risc0Prover::new(elf, ProverResource::Network(config))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::BonsaiApiKeyMissing));
+    }
+
     #[test]
     fn test_prove() {
         let elf = basic_elf();
@@ -252,6 +595,35 @@ mod tests {
         run_zkvm_prove(&zkvm, &test_case);
     }
 
+    #[test]
+    #[ignore = "Requires docker to wrap into Groth16 by the STARK-to-SNARK circuit"]
+    fn test_prove_groth16() {
+        let elf = basic_elf();
+        let zkvm = Risc0Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let (public_values, proof, _) = zkvm
+            .prove_with_kind(&test_case.input(), ProofKind::Groth16)
+            .unwrap();
+        assert!(matches!(proof.0.inner, InnerReceipt::Groth16(_)));
+        assert_eq!(zkvm.verify(&proof).unwrap(), public_values);
+    }
+
+    #[test]
+    fn test_prove_plonk_unsupported() {
+        let elf = basic_elf();
+        let zkvm = Risc0Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let err = zkvm
+            .prove_with_kind(&test_case.input(), ProofKind::Plonk)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::CommonError(CommonError::UnsupportedProofKind { .. })
+        ));
+    }
+
     #[test]
     fn test_prove_invalid_test_case() {
         let elf = basic_elf();
@@ -0,0 +1,129 @@
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use ere_prover_core::{CommonError, config::Config};
+use risc0_zkvm::{ApiClient, TcpConnector};
+
+use crate::error::Error;
+
+/// Timeout to connect to the [`R0vmServer::Tcp`] server when checking its health.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `r0vm` server the prover sends the proving requests to, which is set by `ERE_RISC0_R0VM_ADDR`
+/// (`{host}:{port}`, or `[{ipv6}]:{port}`) by default, see [`R0vmServer::from_env`].
+///
+/// Unix sockets are not supported, since the client of Risc0 only connects over TCP.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum R0vmServer {
+    /// Spawns `r0vm` (or `r0vm-cuda` for [`ProverResource::Gpu`]) for each proving request.
+    ///
+    /// [`ProverResource::Gpu`]: ere_prover_core::ProverResource::Gpu
+    #[default]
+    Spawn,
+    /// Connects to an already running `r0vm` or `r0vm-cuda` server, e.g. a GPU prover daemon
+    /// shared by multiple provers on the host.
+    ///
+    /// The `host` is a hostname or an IP address, without brackets for IPv6.
+    Tcp { host: String, port: u16 },
+}
+
+impl R0vmServer {
+    /// Reads `ERE_RISC0_R0VM_ADDR`.
+    pub fn from_env() -> Result<Self, Error> {
+        let Some(value) = Config::global()
+            .map_err(CommonError::from)?
+            .var("ERE_RISC0_R0VM_ADDR")
+        else {
+            return Ok(Self::Spawn);
+        };
+        Self::parse_addr(&value).ok_or(Error::InvalidEnvVar {
+            key: "ERE_RISC0_R0VM_ADDR",
+            value,
+        })
+    }
+
+    /// Parses `{host}:{port}` or `[{ipv6}]:{port}` into [`R0vmServer::Tcp`].
+    fn parse_addr(value: &str) -> Option<Self> {
+        let (host, port) = value.rsplit_once(':')?;
+        let host = match host.strip_prefix('[') {
+            Some(host) => host.strip_suffix(']')?,
+            None if host.contains(':') => return None,
+            None => host,
+        };
+        Some(Self::Tcp {
+            host: host.to_string(),
+            port: port.parse().ok()?,
+        })
+    }
+
+    /// Returns the address of the [`R0vmServer::Tcp`] server in format of `{host}:{port}`, with
+    /// IPv6 `host` in brackets.
+    fn addr(host: &str, port: u16) -> String {
+        match host.contains(':') {
+            true => format!("[{host}]:{port}"),
+            false => format!("{host}:{port}"),
+        }
+    }
+
+    /// Returns error if the [`R0vmServer::Tcp`] server doesn't accept connections.
+    pub fn check_health(&self) -> Result<(), Error> {
+        let Self::Tcp { host, port } = self else {
+            return Ok(());
+        };
+        let addr = Self::addr(host, *port);
+        let unreachable = |err| Error::R0vmServerUnreachable {
+            addr: addr.clone(),
+            err,
+        };
+        let socket_addrs = (host.as_str(), *port)
+            .to_socket_addrs()
+            .map_err(unreachable)?;
+        let mut last_err = None;
+        for socket_addr in socket_addrs {
+            match TcpStream::connect_timeout(&socket_addr, HEALTH_CHECK_TIMEOUT) {
+                Ok(_) => return Ok(()),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(unreachable(last_err.unwrap_or_else(|| {
+            std::io::Error::other("no address resolved")
+        })))
+    }
+
+    /// Returns the client connected to the [`R0vmServer::Tcp`] server, `None` for
+    /// [`R0vmServer::Spawn`].
+    pub(crate) fn client(&self) -> Option<ApiClient> {
+        let Self::Tcp { host, port } = self else {
+            return None;
+        };
+        let connector = TcpConnector::new(&Self::addr(host, *port));
+        Some(ApiClient::with_connector(Box::new(connector)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::r0vm::R0vmServer;
+
+    #[test]
+    fn test_parse_addr() {
+        let tcp = |host: &str, port| {
+            Some(R0vmServer::Tcp {
+                host: host.to_string(),
+                port,
+            })
+        };
+        assert_eq!(
+            R0vmServer::parse_addr("127.0.0.1:9000"),
+            tcp("127.0.0.1", 9000)
+        );
+        assert_eq!(R0vmServer::parse_addr("r0vm:9000"), tcp("r0vm", 9000));
+        assert_eq!(R0vmServer::parse_addr("[::1]:9000"), tcp("::1", 9000));
+        assert_eq!(R0vmServer::addr("::1", 9000), "[::1]:9000");
+        assert_eq!(R0vmServer::parse_addr("::1:9000"), None);
+        assert_eq!(R0vmServer::parse_addr("r0vm"), None);
+        assert_eq!(R0vmServer::parse_addr("r0vm:port"), None);
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use ere_prover_core::{CommonError, Input, PublicValues};
+use ere_verifier_sp1::SP1ProgramVk;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Execution of a program by [`SP1Prover::execute_to_artifact`], which can be serialized and
+/// handed to [`SP1Prover::prove_from_artifact`] of a prover of the same program on another
+/// machine, e.g. to execute on cheaper CPU machines and prove only the valid executions on GPU
+/// machines.
+///
+/// SP1 SDK doesn't accept execution checkpoints from outside for proving, so the prover still
+/// generates the trace from the input, but skips the separate execution it otherwise runs to
+/// report the cycles and execution duration.
+///
+/// [`SP1Prover::execute_to_artifact`]: crate::SP1Prover::execute_to_artifact
+/// [`SP1Prover::prove_from_artifact`]: crate::SP1Prover::prove_from_artifact
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SP1ExecutionArtifact {
+    pub(crate) program_vk: SP1ProgramVk,
+    pub(crate) stdin: Vec<u8>,
+    pub(crate) proofs: Option<Vec<u8>>,
+    pub(crate) public_values: Vec<u8>,
+    pub(crate) total_num_cycles: u64,
+    pub(crate) execution_duration: Duration,
+}
+
+impl SP1ExecutionArtifact {
+    /// Returns the program vk the artifact was executed with.
+    pub fn program_vk(&self) -> &SP1ProgramVk {
+        &self.program_vk
+    }
+
+    /// Returns the commitment to the input the program was executed with, see
+    /// [`Input::commitment`].
+    ///
+    /// [`Input::commitment`]: ere_prover_core::Input::commitment
+    pub fn input_commitment(&self) -> [u8; 32] {
+        self.input().commitment()
+    }
+
+    /// Returns the input the program was executed with.
+    pub(crate) fn input(&self) -> Input {
+        Input {
+            stdin: self.stdin.clone(),
+            proofs: self.proofs.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the public values of the execution.
+    pub fn public_values(&self) -> PublicValues {
+        self.public_values.as_slice().into()
+    }
+
+    /// Returns the total number of cycles of the execution.
+    pub fn total_num_cycles(&self) -> u64 {
+        self.total_num_cycles
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        bincode::serde::encode_to_vec(self, bincode::config::legacy())
+            .map_err(|err| CommonError::serialize("SP1 execution artifact", "bincode", err).into())
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        bincode::serde::decode_from_slice(bytes, bincode::config::legacy())
+            .map(|(artifact, _)| artifact)
+            .map_err(|err| {
+                CommonError::deserialize("SP1 execution artifact", "bincode", err).into()
+            })
+    }
+}
@@ -1,4 +1,5 @@
-use ere_prover_core::CommonError;
+use ere_prover_core::{CommonError, ErrorKind, zkVMError};
+use ere_verifier_sp1::SP1ProgramVk;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -15,6 +16,9 @@ pub enum Error {
     #[error("Missing `api_key` in `RemoteProverConfig`")]
     MissingApiKey,
 
+    #[error("Invalid env variable {key}, got {value}")]
+    InvalidEnvVar { key: &'static str, value: String },
+
     // Execute
     #[error("SP1 execution failed: {0}")]
     Execute(#[source] anyhow::Error),
@@ -29,6 +33,12 @@ pub enum Error {
     #[error("Failed to extract exit code from proof")]
     ExitCodeExtractionFailed,
 
+    #[error("Execution artifact of program {got:?} doesn't match program {expected:?}")]
+    ArtifactProgramMismatch {
+        expected: SP1ProgramVk,
+        got: SP1ProgramVk,
+    },
+
     // Verify
     #[error(transparent)]
     Verifier(#[from] ere_verifier_sp1::Error),
@@ -43,3 +53,25 @@ impl Error {
         Self::Prove(err.into())
     }
 }
+
+impl zkVMError for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommonError(err) => err.kind(),
+            Self::DeserializeInputProofs(_) | Self::ArtifactProgramMismatch { .. } => {
+                ErrorKind::InvalidInput
+            }
+            Self::ExecutionFailed(exit_code) => {
+                ErrorKind::guest_panic(format!("exit with non-success code {exit_code}"))
+            }
+            Self::Execute(err) => ErrorKind::from_source(err.as_ref(), ErrorKind::guest_panic(err)),
+            Self::Setup(err) | Self::Prove(err) => {
+                ErrorKind::from_source(err.as_ref(), ErrorKind::ProverInfrastructure)
+            }
+            Self::MissingApiKey | Self::InvalidEnvVar { .. } | Self::ExitCodeExtractionFailed => {
+                ErrorKind::ProverInfrastructure
+            }
+            Self::Verifier(_) => ErrorKind::VerificationFailed,
+        }
+    }
+}
@@ -0,0 +1,201 @@
+#[cfg(feature = "cuda")]
+use std::{
+    net::TcpStream,
+    process::Command,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "cuda")]
+use ere_prover_core::config::{ContainerRuntime, DockerConfig};
+use ere_prover_core::{CommonError, config::Config};
+#[cfg(feature = "cuda")]
+use sp1_sdk::SP1_CIRCUIT_VERSION;
+#[cfg(feature = "cuda")]
+use tracing::{info, warn};
+
+use crate::error::Error;
+
+/// Settings of the Moongate GPU prover container of [`ProverResource::Gpu`], which are set by
+/// `ERE_SP1_GPU_*` by default, see [`SP1GpuConfig::from_env`].
+///
+/// When [`SP1GpuConfig::endpoint`] is set, the prover connects to that already running Moongate
+/// server (e.g. the one `ere-dockerized` starts next to the `ere-server` container) and the other
+/// fields are ignored. Otherwise, when all fields are unset, the SP1 SDK starts the container with
+/// its defaults, else the container is started with the settings and removed when the prover is
+/// dropped, so multiple provers on a host can use distinct ports and GPUs. The container is run
+/// by the container runtime and daemon of [`Config::docker`] (e.g. `ERE_CONTAINER_RUNTIME`).
+///
+/// [`ProverResource::Gpu`]: ere_prover_core::ProverResource::Gpu
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SP1GpuConfig {
+    /// Endpoint of a running Moongate server (e.g. `http://ere-sp1-moongate:3000`), in which case
+    /// no container is started.
+    pub endpoint: Option<String>,
+    /// Moongate image, defaults to `public.ecr.aws/succinct-labs/moongate:{circuit_version}` of
+    /// the SP1 SDK.
+    pub image: Option<String>,
+    /// Host port the Moongate server is published on, defaults to `3000`.
+    pub port: Option<u16>,
+    /// GPU devices passed to `--gpus device=` (e.g. `0` or `0,1`), all GPUs if `None`.
+    pub device: Option<String>,
+    /// Memory limit passed to `--memory` (e.g. `64g`), unbounded if `None`.
+    pub memory_limit: Option<String>,
+}
+
+impl SP1GpuConfig {
+    /// Reads `ERE_SP1_GPU_ENDPOINT`, `ERE_SP1_GPU_IMAGE`, `ERE_SP1_GPU_PORT`,
+    /// `ERE_SP1_GPU_DEVICE` and `ERE_SP1_GPU_MEMORY_LIMIT`.
+    pub fn from_env() -> Result<Self, Error> {
+        let config = Config::global().map_err(CommonError::from)?;
+        let port = config
+            .var("ERE_SP1_GPU_PORT")
+            .map(|value| {
+                value.parse().map_err(|_| Error::InvalidEnvVar {
+                    key: "ERE_SP1_GPU_PORT",
+                    value,
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            endpoint: config.var("ERE_SP1_GPU_ENDPOINT"),
+            image: config.var("ERE_SP1_GPU_IMAGE"),
+            port,
+            device: config.var("ERE_SP1_GPU_DEVICE"),
+            memory_limit: config.var("ERE_SP1_GPU_MEMORY_LIMIT"),
+        })
+    }
+
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Timeout to wait for the Moongate server to listen.
+#[cfg(feature = "cuda")]
+const READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Waits until the Moongate server at `endpoint` (e.g. `http://127.0.0.1:3000`) listens.
+#[cfg(feature = "cuda")]
+pub fn wait_until_ready(endpoint: &str) -> Result<(), Error> {
+    let addr = endpoint
+        .split_once("://")
+        .map_or(endpoint, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    let start = Instant::now();
+    while TcpStream::connect(addr).is_err() {
+        if start.elapsed() > READY_TIMEOUT {
+            return Err(Error::setup(anyhow::anyhow!(
+                "Moongate server at {endpoint} not ready after {READY_TIMEOUT:?}"
+            )));
+        }
+        sleep(Duration::from_millis(500));
+    }
+    Ok(())
+}
+
+/// Returns the command of the container runtime configured in `docker`, connecting to the daemon
+/// configured in it, which falls back to the `DOCKER_*` env variables read by `docker` itself.
+#[cfg(feature = "cuda")]
+fn docker_cmd(docker: &DockerConfig) -> Command {
+    let mut cmd = Command::new(docker.runtime.binary());
+    if let Some(host) = &docker.host {
+        cmd.env("DOCKER_HOST", host);
+    }
+    if docker.tls_verify {
+        cmd.env("DOCKER_TLS_VERIFY", "1");
+    }
+    if let Some(cert_path) = &docker.cert_path {
+        cmd.env("DOCKER_CERT_PATH", cert_path);
+    }
+    cmd
+}
+
+/// Moongate container started on the host running the prover for a [`SP1GpuConfig`], removed on
+/// drop.
+#[cfg(feature = "cuda")]
+pub struct MoongateContainer {
+    name: String,
+    endpoint: String,
+    /// Container runtime and daemon the container is started by, see [`Config::docker`].
+    docker: &'static DockerConfig,
+}
+
+#[cfg(feature = "cuda")]
+impl MoongateContainer {
+    /// Port the Moongate server listens on in the container.
+    const CONTAINER_PORT: u16 = 3000;
+
+    pub fn start(config: &SP1GpuConfig) -> Result<Self, Error> {
+        let port = config.port.unwrap_or(Self::CONTAINER_PORT);
+        // Unique per process, so a prover never replaces the container of another one.
+        let name = format!("ere-sp1-moongate-{}-{port}", std::process::id());
+        let image = config.image.clone().unwrap_or_else(|| {
+            format!("public.ecr.aws/succinct-labs/moongate:{SP1_CIRCUIT_VERSION}")
+        });
+
+        let docker = &Config::global().map_err(CommonError::from)?.docker;
+        let mut cmd = docker_cmd(docker);
+        cmd.args(["run", "--detach", "--rm", "--name", &name]);
+        match docker.runtime {
+            ContainerRuntime::Docker => {
+                cmd.arg("--gpus").arg(match &config.device {
+                    // Quoted for docker to take the comma-separated devices as one value.
+                    Some(device) => format!("\"device={device}\""),
+                    None => "all".to_string(),
+                });
+            }
+            // Podman passes through GPUs by CDI devices generated by `nvidia-ctk cdi generate`.
+            ContainerRuntime::Podman => {
+                for id in config.device.as_deref().unwrap_or("all").split(',') {
+                    cmd.arg("--device")
+                        .arg(format!("nvidia.com/gpu={}", id.trim()));
+                }
+            }
+        }
+        cmd.arg("--publish")
+            .arg(format!("127.0.0.1:{port}:{}", Self::CONTAINER_PORT));
+        if let Some(memory_limit) = &config.memory_limit {
+            cmd.args(["--memory", memory_limit]);
+        }
+        cmd.arg(&image);
+
+        info!("Starting Moongate container {name} of {image}...");
+        let output = cmd
+            .output()
+            .map_err(|err| CommonError::command(&cmd, err))?;
+        if !output.status.success() {
+            Err(CommonError::command_exit_non_zero(
+                &cmd,
+                output.status,
+                Some(&output),
+            ))?
+        }
+
+        let container = Self {
+            name,
+            endpoint: format!("http://127.0.0.1:{port}"),
+            docker,
+        };
+        wait_until_ready(&container.endpoint)?;
+        Ok(container)
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+#[cfg(feature = "cuda")]
+impl Drop for MoongateContainer {
+    fn drop(&mut self) {
+        if let Err(err) = docker_cmd(self.docker)
+            .args(["rm", "-f", &self.name])
+            .output()
+        {
+            warn!("Failed to remove Moongate container {}: {err}", self.name);
+        }
+    }
+}
@@ -7,7 +7,9 @@
 //!
 //! ## `zkVMProver` requirements
 //!
-//! - `docker` - Used by `zkVMProver::prove` if `ProverResource::Gpu` is selected
+//! - `docker` - Used by `zkVMProver::prove` if `ProverResource::Gpu` is selected without
+//!   `ERE_SP1_GPU_ENDPOINT`, or `zkVMProver::prove_with_kind` with `ProofKind::Groth16` or
+//!   `ProofKind::Plonk`
 //!
 //! # `Compiler` implementation
 //!
@@ -24,15 +26,39 @@
 //! | `Network` |    Yes    |
 //! | `Cluster` |    No     |
 //!
+//! ## Environment variables
+//!
+//! | Variable                          | Type  | Default | Description                                                    |
+//! | --------------------------------- | ----- | ------- | -------------------------------------------------------------- |
+//! | `ERE_SP1_CLIENT_REUSE_POLICY`     | Value | `reuse` | When to rebuild the SDK client, see [`ClientReusePolicy`]      |
+//! | `ERE_SP1_GPU_ENDPOINT`            | Value |         | Running Moongate server to use instead of starting a container |
+//! | `ERE_SP1_GPU_IMAGE`               | Value |         | Moongate image of the GPU prover, see [`SP1GpuConfig`]         |
+//! | `ERE_SP1_GPU_PORT`                | Value | `3000`  | Host port of the Moongate GPU prover                           |
+//! | `ERE_SP1_GPU_DEVICE`              | Value |         | GPU devices of the Moongate GPU prover (e.g. `0,1`)            |
+//! | `ERE_SP1_GPU_MEMORY_LIMIT`        | Value |         | Memory limit of the Moongate GPU prover container (e.g. `64g`) |
+//! | `ERE_SP1_SHARD_SIZE`              | Value |         | Cycles per shard of the CPU prover, see [`SP1ProverOpts`]      |
+//! | `ERE_SP1_SHARD_BATCH_SIZE`        | Value |         | Shards generated and committed at once by the CPU prover       |
+//! | `ERE_SP1_RECONSTRUCT_COMMITMENTS` | Value |         | Whether the CPU prover regenerates traces to open commitments  |
+//! | `ERE_SP1_REPORT_EXECUTION`        | Value | `false` | Whether to execute before proving to report cycles             |
+//!
 //! [`install_sp1_sdk.sh`]: https://github.com/eth-act/ere/blob/master/scripts/sdk_installers/install_sp1_sdk.sh
 
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod artifact;
 mod error;
+mod gpu;
+mod opts;
 mod prover;
 mod sdk;
 
 pub use ere_prover_core::*;
 pub use ere_verifier_sp1::*;
 
-pub use crate::{error::Error, prover::SP1Prover};
+pub use crate::{
+    artifact::SP1ExecutionArtifact,
+    error::Error,
+    gpu::SP1GpuConfig,
+    opts::SP1ProverOpts,
+    prover::{ClientReusePolicy, SP1Prover},
+};
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+use ere_prover_core::{CommonError, config::Config};
+use sp1_sdk::SP1CoreOpts;
+
+use crate::error::Error;
+
+/// Options of the SP1 prover for [`ProverResource::Cpu`], which are set by `ERE_SP1_*` by
+/// default, see [`SP1ProverOpts::from_env`]. Unset fields keep the SP1 SDK defaults.
+///
+/// Smaller shards and shard batches lower the peak memory of proving at the cost of more shards
+/// to prove and compress, which is the main lever to fit large programs into the available RAM.
+///
+/// [`ProverResource::Cpu`]: ere_prover_core::ProverResource::Cpu
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SP1ProverOpts {
+    /// Number of cycles per shard.
+    pub shard_size: Option<usize>,
+    /// Number of shards generated and committed at once.
+    pub shard_batch_size: Option<usize>,
+    /// Whether to regenerate the traces of shards when opening their commitments, instead of
+    /// keeping them in memory.
+    pub reconstruct_commitments: Option<bool>,
+}
+
+impl SP1ProverOpts {
+    /// Reads `ERE_SP1_SHARD_SIZE`, `ERE_SP1_SHARD_BATCH_SIZE` and
+    /// `ERE_SP1_RECONSTRUCT_COMMITMENTS` (`true` or `false`).
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Self {
+            shard_size: parse_env("ERE_SP1_SHARD_SIZE")?,
+            shard_batch_size: parse_env("ERE_SP1_SHARD_BATCH_SIZE")?,
+            reconstruct_commitments: parse_env("ERE_SP1_RECONSTRUCT_COMMITMENTS")?,
+        })
+    }
+
+    pub(crate) fn core_opts(&self) -> SP1CoreOpts {
+        let mut opts = SP1CoreOpts::default();
+        if let Some(shard_size) = self.shard_size {
+            opts.shard_size = shard_size;
+        }
+        if let Some(shard_batch_size) = self.shard_batch_size {
+            opts.shard_batch_size = shard_batch_size;
+        }
+        if let Some(reconstruct_commitments) = self.reconstruct_commitments {
+            opts.reconstruct_commitments = reconstruct_commitments;
+        }
+        opts
+    }
+}
+
+fn parse_env<T: FromStr>(key: &'static str) -> Result<Option<T>, Error> {
+    Config::global()
+        .map_err(CommonError::from)?
+        .var(key)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| Error::InvalidEnvVar { key, value })
+        })
+        .transpose()
+}
@@ -1,80 +1,375 @@
-use std::time::Instant;
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
 use ere_compiler_core::Elf;
 use ere_prover_core::{
-    Input, ProgramExecutionReport, ProgramProvingReport, ProverResource, PublicValues, zkVMProver,
+    CommonError, ExecuteOptions, ExecutionArtifact, Input, ProgramExecutionReport,
+    ProgramProvingReport, ProofKind, ProverResource, PublicValues, config::Config, zkVMError,
+    zkVMProver,
 };
 use ere_util_tokio::block_on;
 use ere_verifier_sp1::{SP1ProgramVk, SP1Proof, SP1Verifier};
-use sp1_sdk::{HashableKey, SP1Stdin};
-use tracing::info;
+use indexmap::IndexMap;
+use sp1_sdk::{ExecutionReport, HashableKey, SP1PublicValues, SP1Stdin};
+use tracing::{info, warn};
+
+use crate::{
+    artifact::SP1ExecutionArtifact, error::Error, gpu::SP1GpuConfig, opts::SP1ProverOpts,
+    sdk::SP1Sdk,
+};
+
+/// When to rebuild the SP1 client, which for [`ProverResource::Gpu`] tears down and starts the
+/// Moongate GPU prover container again, taking tens of seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClientReusePolicy {
+    /// Reuses the client for all calls.
+    #[default]
+    Reuse,
+    /// Rebuilds the client on the call following one that failed for reasons other than the
+    /// guest program, to recover from a broken client (e.g. crashed GPU prover).
+    RebuildOnError,
+    /// Rebuilds the client for every call.
+    AlwaysRebuild,
+}
 
-use crate::{error::Error, sdk::SP1Sdk};
+impl FromStr for ClientReusePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reuse" => Ok(Self::Reuse),
+            "rebuild-on-error" => Ok(Self::RebuildOnError),
+            "always-rebuild" => Ok(Self::AlwaysRebuild),
+            _ => Err(s.to_string()),
+        }
+    }
+}
 
 pub struct SP1Prover {
-    sdk: SP1Sdk,
+    elf: Elf,
+    resource: ProverResource,
+    gpu_config: SP1GpuConfig,
+    client_reuse_policy: ClientReusePolicy,
+    prover_opts: SP1ProverOpts,
+    /// Whether to execute before proving to report the cycles and execution duration, see
+    /// [`SP1Prover::with_report_execution`].
+    report_execution: bool,
+    /// Client to reuse, `None` if dropped to be rebuilt.
+    sdk: Mutex<Option<Arc<SP1Sdk>>>,
     verifier: SP1Verifier,
 }
 
 impl SP1Prover {
+    /// Creates the prover with [`SP1GpuConfig::from_env`], [`SP1ProverOpts::from_env`], and
+    /// [`ClientReusePolicy`] set by `ERE_SP1_CLIENT_REUSE_POLICY` (`reuse`, `rebuild-on-error` or
+    /// `always-rebuild`), defaults to [`ClientReusePolicy::Reuse`], and
+    /// [`SP1Prover::with_report_execution`] set by `ERE_SP1_REPORT_EXECUTION` (`true` or `false`),
+    /// defaults to `false`.
     pub fn new(elf: Elf, resource: ProverResource) -> Result<Self, Error> {
-        let sdk = block_on(SP1Sdk::new(elf.0, &resource))?;
+        Self::new_with_gpu_config(elf, resource, SP1GpuConfig::from_env()?)
+    }
+
+    /// Same as [`SP1Prover::new`] but with `gpu_config` for [`ProverResource::Gpu`].
+    pub fn new_with_gpu_config(
+        elf: Elf,
+        resource: ProverResource,
+        gpu_config: SP1GpuConfig,
+    ) -> Result<Self, Error> {
+        let config = Config::global().map_err(CommonError::from)?;
+        let client_reuse_policy = match config.var("ERE_SP1_CLIENT_REUSE_POLICY") {
+            Some(value) => value.parse().map_err(|value| Error::InvalidEnvVar {
+                key: "ERE_SP1_CLIENT_REUSE_POLICY",
+                value,
+            })?,
+            None => ClientReusePolicy::default(),
+        };
+        let report_execution = match config.var("ERE_SP1_REPORT_EXECUTION") {
+            Some(value) => value.parse().map_err(|_| Error::InvalidEnvVar {
+                key: "ERE_SP1_REPORT_EXECUTION",
+                value,
+            })?,
+            None => false,
+        };
+        let prover_opts = SP1ProverOpts::from_env()?;
+        let sdk = block_on(SP1Sdk::new(elf.0.clone(), &resource, &gpu_config))?;
         let program_vk = SP1ProgramVk(sdk.vk().hash_koalabear());
         let verifier = SP1Verifier::new(program_vk);
-        Ok(Self { sdk, verifier })
+        Ok(Self {
+            elf,
+            resource,
+            gpu_config,
+            client_reuse_policy,
+            prover_opts,
+            report_execution,
+            sdk: Mutex::new(Some(Arc::new(sdk))),
+            verifier,
+        })
     }
-}
 
-impl zkVMProver for SP1Prover {
-    type Verifier = SP1Verifier;
-    type Error = Error;
+    pub fn with_client_reuse_policy(mut self, client_reuse_policy: ClientReusePolicy) -> Self {
+        self.client_reuse_policy = client_reuse_policy;
+        self
+    }
 
-    fn verifier(&self) -> &SP1Verifier {
-        &self.verifier
+    pub fn with_prover_opts(mut self, prover_opts: SP1ProverOpts) -> Self {
+        self.prover_opts = prover_opts;
+        self
     }
 
-    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport), Error> {
-        let stdin = input_to_stdin(input)?;
+    /// Sets whether to execute the program before proving it (disabled by default), to report
+    /// the total number of cycles and the execution (trace generation) duration, which SP1
+    /// doesn't report of its proving pipeline.
+    ///
+    /// The execution takes as long as executing the program once more, and is skipped for the
+    /// network prover and proves from an execution artifact, which already have them.
+    pub fn with_report_execution(mut self, report_execution: bool) -> Self {
+        self.report_execution = report_execution;
+        self
+    }
+
+    /// Runs `f` with the client, rebuilt as configured by [`ClientReusePolicy`].
+    fn with_sdk<T>(&self, f: impl FnOnce(&SP1Sdk) -> Result<T, Error>) -> Result<T, Error> {
+        let always_rebuild = self.client_reuse_policy == ClientReusePolicy::AlwaysRebuild;
+        let sdk = {
+            let mut current = self.sdk.lock().unwrap_or_else(PoisonError::into_inner);
+            match current.clone().filter(|_| !always_rebuild) {
+                Some(sdk) => sdk,
+                None => {
+                    // Drops the previous client first to release its GPU prover container.
+                    *current = None;
+                    info!("Building SP1 client...");
+                    let sdk = Arc::new(block_on(SP1Sdk::new(
+                        self.elf.0.clone(),
+                        &self.resource,
+                        &self.gpu_config,
+                    ))?);
+                    if !always_rebuild {
+                        *current = Some(sdk.clone());
+                    }
+                    sdk
+                }
+            }
+        };
+
+        let result = f(&sdk);
+        if let Err(err) = &result
+            && self.client_reuse_policy == ClientReusePolicy::RebuildOnError
+            && !err.kind().is_guest_failure()
+        {
+            warn!("SP1 client failed, rebuilding it on the next call");
+            let mut current = self.sdk.lock().unwrap_or_else(PoisonError::into_inner);
+            if current
+                .as_ref()
+                .is_some_and(|current| Arc::ptr_eq(current, &sdk))
+            {
+                *current = None;
+            }
+        }
+        result
+    }
+
+    /// Executes the program with `input`, and returns the artifact to be proved by
+    /// [`SP1Prover::prove_from_artifact`] of a prover of the same program.
+    pub fn execute_to_artifact(&self, input: &Input) -> Result<SP1ExecutionArtifact, Error> {
+        self.execute_with_artifact(input)
+            .map(|(artifact, _)| artifact)
+    }
+
+    /// Creates a compressed proof of the execution returned by
+    /// [`SP1Prover::execute_to_artifact`], without executing the program separately again.
+    ///
+    /// The input commitment of the report is derived from the input in the artifact, while the
+    /// total number of cycles and execution duration are the ones claimed by the artifact, since
+    /// deriving them would take the execution this skips.
+    pub fn prove_from_artifact(
+        &self,
+        artifact: &SP1ExecutionArtifact,
+    ) -> Result<(PublicValues, SP1Proof, ProgramProvingReport), Error> {
+        if artifact.program_vk != *self.program_vk() {
+            return Err(Error::ArtifactProgramMismatch {
+                expected: *self.program_vk(),
+                got: artifact.program_vk,
+            });
+        }
+        let input = artifact.input();
+        self.prove_stdin(
+            input_to_stdin(&input)?,
+            input.commitment(),
+            ProofKind::Compressed,
+            Some((artifact.total_num_cycles, artifact.execution_duration)),
+        )
+    }
+
+    fn execute_with_artifact(
+        &self,
+        input: &Input,
+    ) -> Result<(SP1ExecutionArtifact, ProgramExecutionReport), Error> {
+        let (public_values, report) = self.execute_stdin(input_to_stdin(input)?)?;
+        let artifact = SP1ExecutionArtifact {
+            program_vk: *self.program_vk(),
+            stdin: input.stdin()?.into_owned(),
+            proofs: input.proofs.clone(),
+            public_values: public_values.to_vec(),
+            total_num_cycles: report.total_num_cycles,
+            execution_duration: report.execution_duration,
+        };
+        Ok((artifact, report))
+    }
 
+    fn execute_stdin(
+        &self,
+        stdin: SP1Stdin,
+    ) -> Result<(SP1PublicValues, ProgramExecutionReport), Error> {
         let start = Instant::now();
-        let (public_values, exec_report) = block_on(self.sdk.execute(stdin))?;
+        let (public_values, exec_report) = self.with_sdk(|sdk| block_on(sdk.execute(stdin)))?;
         let execution_duration = start.elapsed();
 
+        let (syscall_counts, precompile_counts) = syscall_counts(&exec_report);
         Ok((
-            public_values.as_slice().into(),
+            public_values,
             ProgramExecutionReport {
                 total_num_cycles: exec_report.total_instruction_count(),
                 region_cycles: exec_report.cycle_tracker.into_iter().collect(),
+                precompile_counts,
+                syscall_counts,
                 execution_duration,
+                ..Default::default()
             },
         ))
     }
 
+    /// Proves in `kind`, with the total number of cycles and execution duration of `execution` if
+    /// already executed.
+    fn prove_stdin(
+        &self,
+        stdin: SP1Stdin,
+        input_commitment: [u8; 32],
+        kind: ProofKind,
+        execution: Option<(u64, Duration)>,
+    ) -> Result<(PublicValues, SP1Proof, ProgramProvingReport), Error> {
+        info!("Generating {kind} proof...");
+
+        let (execution, (proof, network_request_id), proving_time) = self.with_sdk(|sdk| {
+            // SP1 doesn't report durations of its proving pipeline, so execute separately if
+            // enabled to report the total number of cycles and the execution (trace generation)
+            // duration, which is not counted in the proving time. Skipped for the network prover
+            // since it proves remotely, and if already executed.
+            let execution = if sdk.is_network() {
+                None
+            } else if execution.is_some() || !self.report_execution {
+                execution
+            } else {
+                let start = Instant::now();
+                let (_, exec_report) = block_on(sdk.execute(stdin.clone()))?;
+                Some((exec_report.total_instruction_count(), start.elapsed()))
+            };
+
+            let start = Instant::now();
+            let proof = block_on(sdk.prove(stdin, kind, &self.prover_opts))?;
+            Ok((execution, proof, start.elapsed()))
+        })?;
+
+        let public_values = proof.public_values.as_slice().into();
+
+        let mut report = ProgramProvingReport::new(proving_time);
+        report.input_commitment = Some(input_commitment);
+        if let Some((total_num_cycles, execution_duration)) = execution {
+            report.total_num_cycles = Some(total_num_cycles);
+            report.insert_phase("execution".to_string(), execution_duration);
+        }
+        report.insert_phase("proving".to_string(), proving_time);
+        report.network_request_id = network_request_id;
+
+        Ok((public_values, SP1Proof(proof), report))
+    }
+}
+
+impl zkVMProver for SP1Prover {
+    type Verifier = SP1Verifier;
+    type Error = Error;
+
+    fn verifier(&self) -> &SP1Verifier {
+        &self.verifier
+    }
+
+    fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        options.check_profile_unsupported()?;
+        let stdin = input_to_stdin(input)?;
+        let (public_values, report) = self.execute_stdin(stdin)?;
+        options.check_cycle_limit(report.total_num_cycles)?;
+        Ok((public_values.as_slice().into(), report))
+    }
+
     fn prove(
         &self,
         input: &Input,
     ) -> Result<(PublicValues, SP1Proof, ProgramProvingReport), Error> {
-        info!("Generating proof...");
+        self.prove_with_kind(input, ProofKind::Compressed)
+    }
 
+    fn prove_with_kind(
+        &self,
+        input: &Input,
+        kind: ProofKind,
+    ) -> Result<(PublicValues, SP1Proof, ProgramProvingReport), Error> {
         let stdin = input_to_stdin(input)?;
+        self.prove_stdin(stdin, input.commitment(), kind, None)
+    }
 
-        let start = Instant::now();
-        let proof = block_on(self.sdk.prove(stdin))?;
-        let proving_time = start.elapsed();
+    fn execute_for_proving(&self, input: &Input) -> Result<ExecutionArtifact, Error> {
+        let (artifact, report) = self.execute_with_artifact(input)?;
+        Ok(
+            ExecutionArtifact::new(input.clone(), artifact.public_values(), report)
+                .with_state(artifact),
+        )
+    }
 
-        let public_values = proof.public_values.as_slice().into();
+    fn prove_from_execution(
+        &self,
+        artifact: &ExecutionArtifact,
+    ) -> Result<(PublicValues, SP1Proof, ProgramProvingReport), Error> {
+        match artifact.state::<SP1ExecutionArtifact>() {
+            Some(artifact) => self.prove_from_artifact(artifact),
+            None => self.prove(artifact.input()),
+        }
+    }
 
-        Ok((
-            public_values,
-            SP1Proof(proof),
-            ProgramProvingReport::new(proving_time),
-        ))
+    fn proof_kinds(&self) -> &'static [ProofKind] {
+        &[ProofKind::Compressed, ProofKind::Groth16, ProofKind::Plonk]
+    }
+}
+
+/// Returns invocation counts of all syscalls and of the precompiles among them, which are the
+/// syscalls proven by dedicated chips (e.g. keccak_permute, bn254_add and secp256k1_add).
+fn syscall_counts(exec_report: &ExecutionReport) -> (IndexMap<String, u64>, IndexMap<String, u64>) {
+    let mut syscall_counts = IndexMap::new();
+    let mut precompile_counts = IndexMap::new();
+    for (code, count) in exec_report.syscall_counts.iter() {
+        if *count == 0 {
+            continue;
+        }
+        let name = format!("{code:?}").to_lowercase();
+        if code.should_send() != 0 {
+            precompile_counts.insert(name.clone(), *count);
+        }
+        syscall_counts.insert(name, *count);
     }
+    (syscall_counts, precompile_counts)
 }
 
 fn input_to_stdin(input: &Input) -> Result<SP1Stdin, Error> {
+    if input.kv_store.is_some() {
+        Err(CommonError::unsupported_input("no key-value store hint"))?
+    }
     let mut stdin = SP1Stdin::new();
-    stdin.write_slice(input.stdin());
+    stdin.write_slice(&input.stdin()?);
     if let Some(proofs) = input.proofs() {
         for (proof, vk) in proofs.map_err(Error::DeserializeInputProofs)? {
             stdin.write_proof(proof, vk);
@@ -89,14 +384,24 @@ mod tests {
 
     use ere_compiler_core::{Compiler, Elf};
     use ere_compiler_sp1::SP1RustRv64imaCustomized;
-    use ere_prover_core::{Input, ProverResource, RemoteProverConfig, zkVMProver};
+    use ere_prover_core::{
+        CommonError, ExecuteOptions, Input, ProofKind, ProverResource, RemoteProverConfig,
+        zkVMProver,
+    };
     use ere_util_test::{
         codec::BincodeLegacy,
         host::{TestCase, run_zkvm_execute, run_zkvm_prove, testing_guest_directory},
         program::basic::BasicProgram,
     };
 
-    use crate::prover::SP1Prover;
+    #[cfg(feature = "cuda")]
+    use crate::gpu::SP1GpuConfig;
+    use crate::{
+        artifact::SP1ExecutionArtifact,
+        error::Error,
+        opts::SP1ProverOpts,
+        prover::{ClientReusePolicy, SP1Prover},
+    };
 
     fn basic_elf() -> Elf {
         static ELF: OnceLock<Elf> = OnceLock::new();
@@ -115,6 +420,11 @@ mod tests {
 
         let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
         run_zkvm_execute(&zkvm, &test_case);
+
+        let (_, report) = zkvm.execute(&test_case.input()).unwrap();
+        // The guest reads the input and commits the output by syscalls, without precompiles.
+        assert!(report.syscall_counts.contains_key("write"));
+        assert!(report.precompile_counts.is_empty());
     }
 
     #[test]
@@ -130,6 +440,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execute_cycle_limit() {
+        let elf = basic_elf();
+        let zkvm = SP1Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let options = ExecuteOptions::new().with_max_cycles(1);
+        let err = zkvm
+            .execute_with_options(&test_case.input(), &options)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::CommonError(CommonError::CycleLimitExceeded { max_cycles: 1 })
+            ),
+            "Expect error variant `CycleLimitExceeded`, got {err:?}",
+        );
+    }
+
     #[test]
     fn test_prove() {
         let elf = basic_elf();
@@ -156,6 +485,81 @@ mod tests {
         run_zkvm_prove(&zkvm, &test_case);
     }
 
+    #[test]
+    fn test_prove_always_rebuild() {
+        let elf = basic_elf();
+        let zkvm = SP1Prover::new(elf, ProverResource::Cpu)
+            .unwrap()
+            .with_client_reuse_policy(ClientReusePolicy::AlwaysRebuild);
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        run_zkvm_execute(&zkvm, &test_case);
+        run_zkvm_prove(&zkvm, &test_case);
+    }
+
+    #[test]
+    fn test_prove_from_artifact() {
+        let elf = basic_elf();
+        let zkvm = SP1Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let artifact = zkvm.execute_to_artifact(&test_case.input()).unwrap();
+        let artifact = SP1ExecutionArtifact::from_bytes(&artifact.to_bytes().unwrap()).unwrap();
+
+        let (public_values, proof, report) = zkvm.prove_from_artifact(&artifact).unwrap();
+        assert_eq!(public_values, artifact.public_values());
+        assert_eq!(report.total_num_cycles, Some(artifact.total_num_cycles()));
+        assert_eq!(
+            report.input_commitment,
+            Some(test_case.input().commitment())
+        );
+        assert_eq!(zkvm.verify(&proof).unwrap(), public_values);
+    }
+
+    #[test]
+    fn test_prove_report_execution() {
+        let elf = basic_elf();
+        let zkvm = SP1Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let (_, _, report) = zkvm.prove(&test_case.input()).unwrap();
+        assert_eq!(report.total_num_cycles, None);
+
+        let zkvm = zkvm.with_report_execution(true);
+        let (_, exec_report) = zkvm.execute(&test_case.input()).unwrap();
+        let (_, _, report) = zkvm.prove(&test_case.input()).unwrap();
+        assert_eq!(report.total_num_cycles, Some(exec_report.total_num_cycles));
+    }
+
+    #[test]
+    fn test_prove_prover_opts() {
+        let elf = basic_elf();
+        let zkvm = SP1Prover::new(elf, ProverResource::Cpu)
+            .unwrap()
+            .with_prover_opts(SP1ProverOpts {
+                shard_size: Some(1 << 16),
+                shard_batch_size: Some(1),
+                reconstruct_commitments: Some(true),
+            });
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        run_zkvm_prove(&zkvm, &test_case);
+    }
+
+    #[test]
+    #[ignore = "Requires docker to wrap into SNARK by gnark"]
+    fn test_prove_snark() {
+        let elf = basic_elf();
+        let zkvm = SP1Prover::new(elf, ProverResource::Cpu).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        for kind in [ProofKind::Groth16, ProofKind::Plonk] {
+            let (public_values, proof, _) = zkvm.prove_with_kind(&test_case.input(), kind).unwrap();
+            assert_eq!(zkvm.verify(&proof).unwrap(), public_values);
+            test_case.assert_output(&public_values);
+        }
+    }
+
     #[cfg(feature = "cuda")]
     #[tokio::test(flavor = "multi_thread")]
     async fn test_prove_gpu() {
@@ -166,6 +570,21 @@ mod tests {
         run_zkvm_prove(&zkvm, &test_case);
     }
 
+    #[cfg(feature = "cuda")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prove_gpu_config() {
+        let elf = basic_elf();
+        let gpu_config = SP1GpuConfig {
+            port: Some(3001),
+            device: Some("0".to_string()),
+            ..Default::default()
+        };
+        let zkvm = SP1Prover::new_with_gpu_config(elf, ProverResource::Gpu, gpu_config).unwrap();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        run_zkvm_prove(&zkvm, &test_case);
+    }
+
     #[cfg(feature = "cuda")]
     #[tokio::test(flavor = "multi_thread")]
     async fn test_prove_invalid_test_case_gpu() {
@@ -197,6 +616,7 @@ mod tests {
         let config = RemoteProverConfig {
             endpoint: std::env::var("NETWORK_RPC_URL").unwrap_or_default(),
             api_key: std::env::var("NETWORK_PRIVATE_KEY").ok(),
+            ..Default::default()
         };
         let elf = basic_elf();
         let zkvm = SP1Prover::new(elf, ProverResource::Network(config)).unwrap();
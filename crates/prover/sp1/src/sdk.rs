@@ -1,6 +1,9 @@
-use std::{borrow::Borrow, env, sync::Arc};
+use std::{borrow::Borrow, env, sync::Arc, time::Duration};
 
-use ere_prover_core::{CommonError, ProverResource, ProverResourceKind, RemoteProverConfig};
+use ere_prover_core::{
+    CommonError, FulfillmentStrategy, ProofKind, ProverResource, ProverResourceKind,
+    RemoteProverConfig,
+};
 #[cfg(feature = "cuda")]
 use sp1_cuda::CudaProvingKey;
 use sp1_hypercube::PrimeField32;
@@ -11,10 +14,13 @@ use sp1_sdk::{
     CpuProver, Elf, ExecutionReport, NetworkProver, ProofFromNetwork, ProveRequest,
     Prover as SP1Prover, ProverClient, ProvingKey as SP1ProvingKeyTrait, SP1Proof, SP1ProofMode,
     SP1ProofWithPublicValues, SP1ProvingKey as CpuProvingKey, SP1PublicValues, SP1Stdin,
-    SP1VerifyingKey, StatusCode,
+    SP1VerifyingKey, StatusCode, network::FulfillmentStrategy as NetworkFulfillmentStrategy,
 };
+use tracing::info;
 
-use crate::error::Error;
+#[cfg(feature = "cuda")]
+use crate::gpu::MoongateContainer;
+use crate::{error::Error, gpu::SP1GpuConfig, opts::SP1ProverOpts};
 
 pub enum SP1Sdk {
     Cpu {
@@ -25,15 +31,22 @@ pub enum SP1Sdk {
     Gpu {
         prover: CudaProver,
         pk: CudaProvingKey,
+        /// Dropped after the prover.
+        _moongate: Option<MoongateContainer>,
     },
     Network {
         prover: Box<NetworkProver>,
         pk: CpuProvingKey,
+        config: RemoteProverConfig,
     },
 }
 
 impl SP1Sdk {
-    pub async fn new(elf: Vec<u8>, resource: &ProverResource) -> Result<Self, Error> {
+    pub async fn new(
+        elf: Vec<u8>,
+        resource: &ProverResource,
+        gpu_config: &SP1GpuConfig,
+    ) -> Result<Self, Error> {
         let elf = Elf::Dynamic(Arc::from(elf));
         Ok(match resource {
             ProverResource::Cpu => {
@@ -43,9 +56,27 @@ impl SP1Sdk {
             }
             #[cfg(feature = "cuda")]
             ProverResource::Gpu => {
-                let prover = ProverClient::builder().cuda().build().await;
+                let builder = ProverClient::builder().cuda();
+                let (prover, moongate) = match &gpu_config.endpoint {
+                    Some(endpoint) => {
+                        crate::gpu::wait_until_ready(endpoint)?;
+                        (builder.server(endpoint).build().await, None)
+                    }
+                    None if gpu_config.is_default() => (builder.build().await, None),
+                    None => {
+                        let moongate = MoongateContainer::start(gpu_config)?;
+                        (
+                            builder.server(moongate.endpoint()).build().await,
+                            Some(moongate),
+                        )
+                    }
+                };
                 let pk = prover.setup(elf).await.map_err(Error::setup)?;
-                Self::Gpu { prover, pk }
+                Self::Gpu {
+                    prover,
+                    pk,
+                    _moongate: moongate,
+                }
             }
             ProverResource::Network(config) => {
                 let prover = build_network_prover(config).await?;
@@ -53,6 +84,7 @@ impl SP1Sdk {
                 Self::Network {
                     prover: Box::new(prover),
                     pk,
+                    config: config.clone(),
                 }
             }
             _ => Err(CommonError::unsupported_prover_resource_kind(
@@ -76,6 +108,11 @@ impl SP1Sdk {
         }
     }
 
+    /// Returns whether proving happens remotely on the SP1 network.
+    pub fn is_network(&self) -> bool {
+        matches!(self, Self::Network { .. })
+    }
+
     pub async fn execute(
         &self,
         input: SP1Stdin,
@@ -83,8 +120,8 @@ impl SP1Sdk {
         let (public_values, exec_report) = match self {
             Self::Cpu { prover, pk } => prover.execute(pk.elf().clone(), input).await,
             #[cfg(feature = "cuda")]
-            Self::Gpu { prover, pk } => prover.execute(pk.elf().clone(), input).await,
-            Self::Network { prover, pk } => prover.execute(pk.elf().clone(), input).await,
+            Self::Gpu { prover, pk, .. } => prover.execute(pk.elf().clone(), input).await,
+            Self::Network { prover, pk, .. } => prover.execute(pk.elf().clone(), input).await,
         }
         .map_err(|e| Error::Execute(e.into()))?;
 
@@ -96,33 +133,79 @@ impl SP1Sdk {
         Ok((public_values, exec_report))
     }
 
-    pub async fn prove(&self, input: SP1Stdin) -> Result<ProofFromNetwork, Error> {
-        let proof = match self {
+    /// Proves in `kind`, where Groth16 and Plonk wrap the compressed proof into a SNARK by gnark,
+    /// which runs in docker unless SP1 SDK is built with native gnark.
+    ///
+    /// Returns the proof along with the id of the request if proved on the SP1 network.
+    ///
+    /// `opts` applies only to the CPU prover, since the others shard on their own.
+    pub async fn prove(
+        &self,
+        input: SP1Stdin,
+        kind: ProofKind,
+        opts: &SP1ProverOpts,
+    ) -> Result<(ProofFromNetwork, Option<String>), Error> {
+        let mode = match kind {
+            ProofKind::Compressed => SP1ProofMode::Compressed,
+            ProofKind::Groth16 => SP1ProofMode::Groth16,
+            ProofKind::Plonk => SP1ProofMode::Plonk,
+        };
+        let (proof, request_id) = match self {
             Self::Cpu { prover, pk } => {
-                let req = prover.prove(pk, input).compressed();
-                req.await.map_err(Error::prove)
+                let req = prover
+                    .prove(pk, input)
+                    .mode(mode)
+                    .core_opts(opts.core_opts());
+                (req.await.map_err(Error::prove)?, None)
             }
             #[cfg(feature = "cuda")]
-            Self::Gpu { prover, pk } => {
-                let req = prover.prove(pk, input).compressed();
-                req.await.map_err(Error::prove)
+            Self::Gpu { prover, pk, .. } => {
+                let req = prover.prove(pk, input).mode(mode);
+                (req.await.map_err(Error::prove)?, None)
             }
-            Self::Network { prover, pk } => {
-                let req = prover.prove(pk, input).compressed();
-                req.await.map_err(Error::prove)
+            Self::Network { prover, pk, config } => {
+                let mut req = prover.prove(pk, input).mode(mode);
+                if let Some(strategy) = config.fulfillment_strategy {
+                    req = req.strategy(match strategy {
+                        FulfillmentStrategy::Hosted => NetworkFulfillmentStrategy::Hosted,
+                        FulfillmentStrategy::Reserved => NetworkFulfillmentStrategy::Reserved,
+                        FulfillmentStrategy::Auction => NetworkFulfillmentStrategy::Auction,
+                    });
+                }
+                if let Some(max_price_per_pgu) = config.max_price_per_pgu {
+                    req = req.max_price_per_pgu(max_price_per_pgu);
+                }
+                let timeout = config.timeout_secs.map(Duration::from_secs);
+                if let Some(timeout) = timeout {
+                    req = req.timeout(timeout);
+                }
+                if let Some(cycle_limit) = config.cycle_limit {
+                    req = req.cycle_limit(cycle_limit);
+                }
+                // Requests and waits separately to report the request id.
+                let request_id = req.request().await.map_err(Error::prove)?;
+                info!("Requested proof {request_id} on the SP1 network");
+                let proof: SP1ProofWithPublicValues = prover
+                    .wait_proof(request_id, timeout)
+                    .await
+                    .map_err(Error::prove)?;
+                (proof, Some(request_id.to_string()))
             }
-        }?;
+        };
 
         let exit_code = extract_exit_code(&proof)?;
         if exit_code != StatusCode::SUCCESS.as_u32() {
             return Err(Error::ExecutionFailed(exit_code));
         }
 
-        Ok(ProofFromNetwork {
-            proof: proof.proof,
-            public_values: proof.public_values,
-            sp1_version: proof.sp1_version,
-        })
+        Ok((
+            ProofFromNetwork {
+                proof: proof.proof,
+                public_values: proof.public_values,
+                sp1_version: proof.sp1_version,
+            },
+            request_id,
+        ))
     }
 }
 
@@ -148,17 +231,31 @@ async fn build_network_prover(config: &RemoteProverConfig) -> Result<NetworkProv
 
 /// Extracts the exit code from an public values of proof.
 ///
-/// The `exit_code` field is extracted from the public values struct of proof,
-/// mirroring the approach used in `verify_proof` of `sp1_sdk`.
+/// The `exit_code` field is extracted from the public values struct of compressed proof, or the
+/// 3rd public input of SNARK proof, mirroring the approach used in `verify_proof` of `sp1_sdk`.
 fn extract_exit_code(proof: &SP1ProofWithPublicValues) -> Result<u32, Error> {
-    let SP1Proof::Compressed(proof) = &proof.proof else {
-        let proof_mode = SP1ProofMode::from(&proof.proof);
-        return Err(ere_verifier_sp1::Error::UnexpectedProofKind(proof_mode).into());
-    };
-    (proof.proof.public_values.len() == RECURSIVE_PROOF_NUM_PV_ELTS)
-        .then(|| {
-            let pv: &RecursionPublicValues<_> = proof.proof.public_values.as_slice().borrow();
-            pv.exit_code.as_canonical_u32()
-        })
+    match &proof.proof {
+        SP1Proof::Compressed(proof) => (proof.proof.public_values.len()
+            == RECURSIVE_PROOF_NUM_PV_ELTS)
+            .then(|| {
+                let pv: &RecursionPublicValues<_> = proof.proof.public_values.as_slice().borrow();
+                pv.exit_code.as_canonical_u32()
+            })
+            .ok_or(Error::ExitCodeExtractionFailed),
+        SP1Proof::Groth16(proof) => snark_exit_code(&proof.public_inputs),
+        SP1Proof::Plonk(proof) => snark_exit_code(&proof.public_inputs),
+        proof => {
+            let proof_mode = SP1ProofMode::from(proof);
+            Err(ere_verifier_sp1::Error::UnexpectedProofKind(proof_mode).into())
+        }
+    }
+}
+
+/// Parses the exit code from public inputs of SNARK proof, which are `[vkey_hash,
+/// committed_values_digest, exit_code, ...]` in decimal.
+fn snark_exit_code(public_inputs: &[String]) -> Result<u32, Error> {
+    public_inputs
+        .get(2)
+        .and_then(|exit_code| exit_code.parse().ok())
         .ok_or(Error::ExitCodeExtractionFailed)
 }
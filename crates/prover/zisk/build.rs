@@ -4,12 +4,23 @@ use std::{
     process::{self, Command},
 };
 
+use ere_util_cuda::build_cuda_archs;
+
 fn main() {
     println!("cargo:rerun-if-env-changed=PATH");
 
-    if env::var("CARGO_FEATURE_CUDA").is_ok() && !nvcc_exists() {
-        eprintln!("`cuda` feature requires `nvcc` at /usr/local/cuda/bin/nvcc or on PATH.");
-        process::exit(1);
+    if env::var_os("CARGO_FEATURE_CUDA").is_some() {
+        if !nvcc_exists() {
+            eprintln!("`cuda` feature requires `nvcc` at /usr/local/cuda/bin/nvcc or on PATH.");
+            process::exit(1);
+        }
+
+        // The CUDA architectures the GPU kernels of ZisK are built for.
+        let cuda_archs = build_cuda_archs("CUDA_ARCHS")
+            .iter()
+            .map(|arch| arch.to_string())
+            .collect::<Vec<_>>();
+        println!("cargo:rustc-env=ZISK_CUDA_ARCHS={}", cuda_archs.join(","));
     }
 }
 
@@ -0,0 +1,153 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ere_compiler_core::Elf;
+use ere_prover_core::{CommonError, ProverResource, write_atomic};
+use ere_verifier_zisk::ZiskProgramVk;
+use tracing::warn;
+use zisk_common::ZiskPaths;
+use zisk_prover_backend::GuestProgram;
+use zisk_rom_setup::get_elf_bin_file_path_with_hash;
+
+use crate::{error::Error, sdk::local::compute_program_vk};
+
+/// Returns the directory the ROM setup of programs is cached in, which is shared by all
+/// processes of the same user, `~/.zisk/cache` by default.
+///
+/// Entries are named by the hash of the ELF, so the setup of an identical ELF is reused by new
+/// provers instead of repeated.
+pub fn setup_cache_dir() -> PathBuf {
+    ZiskPaths::global().cache.clone()
+}
+
+/// Computes and caches the ROM setup and program vk of `elf` ahead of creating the prover, and
+/// returns the program vk.
+///
+/// The assembly of the program is generated when the prover is set up, see
+/// `zkVMProver::warmup`.
+pub fn prewarm_setup_cache(elf: &Elf) -> Result<ZiskProgramVk, Error> {
+    let program = GuestProgram::from_bytes("guest", elf.0.clone());
+    program_vk(&program, || {
+        compute_program_vk(&ProverResource::Cpu, &program)
+    })
+}
+
+/// Removes all cached entries of `elf` from [`setup_cache_dir`], returns the number of removed
+/// entries.
+///
+/// Entries of a program are the ROM setups and the program vk at their known paths, and the
+/// other files ZisK names with the hash of the ELF as prefix (e.g. the generated assembly).
+pub fn evict_setup_cache(elf: &Elf) -> Result<usize, Error> {
+    let program = GuestProgram::from_bytes("guest", elf.0.clone());
+    let hash = program.hash().to_string();
+    let cache_dir = setup_cache_dir();
+    if !cache_dir.exists() {
+        return Ok(0);
+    }
+
+    let known_paths = [
+        elf_bin_path(&program, &cache_dir, false)?,
+        elf_bin_path(&program, &cache_dir, true)?,
+        program_vk_path(&program, &cache_dir),
+    ];
+    let is_program_entry = |path: &PathBuf| {
+        known_paths.contains(path)
+            || path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&hash))
+                .is_some_and(|rest| rest.starts_with(['.', '-', '_']))
+    };
+
+    let entries = fs::read_dir(&cache_dir)
+        .map_err(|err| CommonError::io("Failed to read setup cache", err))?;
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if is_program_entry(&path) && path.is_file() {
+            fs::remove_file(&path)
+                .map_err(|err| CommonError::io("Failed to remove cached setup", err))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Returns the cached program vk of `program`, or computes it by `compute` and caches it.
+///
+/// The cached program vk is only used if the ROM setup written by `compute` is also cached, since
+/// the prover setup reads it.
+pub(crate) fn program_vk(
+    program: &GuestProgram,
+    compute: impl FnOnce() -> Result<ZiskProgramVk, Error>,
+) -> Result<ZiskProgramVk, Error> {
+    let cache_dir = setup_cache_dir();
+    let elf_bin_path = elf_bin_path(program, &cache_dir, false)?;
+    let program_vk_path = program_vk_path(program, &cache_dir);
+
+    if elf_bin_path.exists()
+        && let Ok(bytes) = fs::read(&program_vk_path)
+    {
+        match ZiskProgramVk::try_from(bytes.as_slice()) {
+            Ok(program_vk) => return Ok(program_vk),
+            Err(err) => warn!("Failed to read cached {}: {err}", program_vk_path.display()),
+        }
+    }
+
+    let program_vk = compute()?;
+
+    let bytes = Vec::from(program_vk);
+    if let Err(err) = write_atomic(&program_vk_path, |tmp_path| fs::write(tmp_path, &bytes)) {
+        warn!("Failed to cache {}: {err}", program_vk_path.display());
+    }
+
+    Ok(program_vk)
+}
+
+/// Returns the path of the ROM setup of `program` in `cache_dir`, for GPU proving if `gpu`.
+pub(crate) fn elf_bin_path(
+    program: &GuestProgram,
+    cache_dir: &Path,
+    gpu: bool,
+) -> Result<PathBuf, Error> {
+    get_elf_bin_file_path_with_hash(program.hash(), cache_dir, gpu)
+        .map_err(|err| Error::Setup(anyhow::anyhow!("Failed to get ROM setup path: {err}")))
+}
+
+fn program_vk_path(program: &GuestProgram, cache_dir: &Path) -> PathBuf {
+    cache_dir.join(format!("{}.program_vk.bin", program.hash()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ere_compiler_core::Compiler;
+    use ere_compiler_zisk::ZiskRustRv64imaCustomized;
+    use ere_prover_core::zkVMProver;
+    use ere_util_test::host::testing_guest_directory;
+
+    use crate::{
+        cache::{evict_setup_cache, prewarm_setup_cache},
+        prover::tests::{basic_elf, basic_elf_zkvm},
+    };
+
+    #[test]
+    fn test_prewarm_setup_cache() {
+        let program_vk = prewarm_setup_cache(&basic_elf()).unwrap();
+        assert_eq!(*basic_elf_zkvm().program_vk(), program_vk);
+    }
+
+    #[test]
+    fn test_evict_setup_cache() {
+        // Not the basic ELF shared by other tests, whose setup would be evicted concurrently.
+        let elf = ZiskRustRv64imaCustomized
+            .compile(testing_guest_directory("zisk", "primitives"), &[])
+            .unwrap();
+
+        let program_vk = prewarm_setup_cache(&elf).unwrap();
+        assert!(evict_setup_cache(&elf).unwrap() >= 2);
+        assert_eq!(evict_setup_cache(&elf).unwrap(), 0);
+        assert_eq!(prewarm_setup_cache(&elf).unwrap(), program_vk);
+    }
+}
@@ -1,4 +1,4 @@
-use ere_prover_core::CommonError;
+use ere_prover_core::{CommonError, ErrorKind, zkVMError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -39,6 +39,12 @@ pub enum Error {
     #[error("Enable `cuda` feature to use `ProverResource::Gpu`")]
     CudaFeatureDisabled,
 
+    #[error(
+        "GPU kernels are compiled for CUDA_ARCHS {built:?}, but proving on GPUs {device:?}, \
+         rebuild with env `CUDA_ARCHS` covering them"
+    )]
+    CudaArchMismatch { built: Vec<u32>, device: Vec<u32> },
+
     // Cluster
     #[error(transparent)]
     Cluster(#[from] ere_cluster_client_zisk::Error),
@@ -47,3 +53,26 @@ pub enum Error {
     #[error(transparent)]
     Verifier(#[from] ere_verifier_zisk::Error),
 }
+
+impl zkVMError for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommonError(err) => err.kind(),
+            Self::EmulatorNotTerminated | Self::EmulatorError | Self::EmulatorPanic(_) => {
+                ErrorKind::guest_panic(self)
+            }
+            Self::Prove(err) => {
+                ErrorKind::from_source(err.as_ref(), ErrorKind::ProverInfrastructure)
+            }
+            Self::InvalidEnvVar { .. }
+            | Self::Riscv2zisk(_)
+            | Self::BuildProver(_)
+            | Self::Setup(_)
+            | Self::ProvePanic(_)
+            | Self::CudaFeatureDisabled
+            | Self::CudaArchMismatch { .. }
+            | Self::Cluster(_) => ErrorKind::ProverInfrastructure,
+            Self::Verifier(_) => ErrorKind::VerificationFailed,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use ere_prover_core::{CommonError, config::Config};
+use ere_util_cuda::{
+    covers_cuda_archs, detect_compute_caps, inexact_cuda_archs, parse_cuda_arch, parse_cuda_archs,
+};
+use tracing::{info, warn};
+
+use crate::error::Error;
+
+/// CUDA architectures the GPU kernels are compiled for, which is env `CUDA_ARCHS` at build time,
+/// or the compute capabilities of the GPUs of the build machine if unset or `native`.
+///
+/// Unset without the `cuda` feature, and empty if neither is available.
+const ZISK_CUDA_ARCHS: Option<&str> = option_env!("ZISK_CUDA_ARCHS");
+
+/// Config of the local prover for [`ProverResource::Gpu`], which is set by `ERE_ZISK_GPU_*` by
+/// default, see [`ZiskGpuConfig::from_env`].
+///
+/// The GPU kernels are linked into the prover, so they can only run on GPUs of the same major
+/// version and an equal or higher minor version of the CUDA architectures they are compiled for.
+/// They are checked against the GPUs on initialization, to fail with an actionable error instead
+/// of a CUDA fault in the middle of proving.
+///
+/// The witness computation is linked into the prover along with the kernels instead of loaded
+/// from a separate library, so there is no library path to configure, and rebuilding with env
+/// `CUDA_ARCHS` is the way to target other GPUs.
+///
+/// [`ProverResource::Gpu`]: ere_prover_core::ProverResource::Gpu
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ZiskGpuConfig {
+    /// Compute capability of the GPUs to prove on (e.g. `89` for `sm_89`), overriding the ones
+    /// detected by `nvidia-smi`, e.g. for containers without `nvidia-smi` or heterogeneous
+    /// clusters where the GPUs are assigned later.
+    pub sm_arch: Option<u32>,
+}
+
+impl ZiskGpuConfig {
+    /// Reads `ERE_ZISK_GPU_SM_ARCH`, which accepts `89`, `8.9` or `sm_89`.
+    pub fn from_env() -> Result<Self, Error> {
+        let sm_arch = Config::global()
+            .map_err(CommonError::from)?
+            .var("ERE_ZISK_GPU_SM_ARCH")
+            .map(|value| {
+                parse_cuda_arch(&value).ok_or(Error::InvalidEnvVar {
+                    key: "ERE_ZISK_GPU_SM_ARCH",
+                    value,
+                })
+            })
+            .transpose()?;
+        Ok(Self { sm_arch })
+    }
+
+    /// Returns error if the GPU kernels can't run on [`ZiskGpuConfig::sm_arch`] or all visible
+    /// GPUs, and warns if some run kernels compiled for a lower minor version.
+    ///
+    /// Skipped if the compiled architectures or the GPUs are unknown.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        let built = cuda_archs();
+        let device = match self.sm_arch {
+            Some(sm_arch) => vec![sm_arch],
+            None => detect_compute_caps(),
+        };
+        info!("GPU kernels compiled for CUDA_ARCHS {built:?}, proving on GPUs {device:?}");
+        if built.is_empty() || device.is_empty() {
+            return Ok(());
+        }
+        if !covers_cuda_archs(&built, &device) {
+            return Err(Error::CudaArchMismatch { built, device });
+        }
+        let inexact = inexact_cuda_archs(&built, &device);
+        if !inexact.is_empty() {
+            warn!(
+                "GPU kernels are not compiled for GPUs {inexact:?} but compatible ones, rebuild \
+                 with env `CUDA_ARCHS` covering them for the best performance"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns the CUDA architectures the GPU kernels are compiled for (e.g. `[89, 120]`), empty if
+/// unknown or without the `cuda` feature.
+pub fn cuda_archs() -> Vec<u32> {
+    ZISK_CUDA_ARCHS.map(parse_cuda_archs).unwrap_or_default()
+}
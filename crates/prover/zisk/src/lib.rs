@@ -5,7 +5,10 @@
 //! To install all requirements, run [`install_zisk_sdk.sh`] from the Ere
 //! repository at the same git revision as your `ere-prover-zisk` dependency.
 //!
-//! GPU proving requires the `cuda` Cargo feature and CUDA 12.9 installed.
+//! GPU proving requires the `cuda` Cargo feature and CUDA 12.9 installed. The GPU kernels are
+//! compiled for the CUDA architectures set by env `CUDA_ARCHS` at build time (e.g. `89,120`), or
+//! for the GPUs of the build machine if unset, and are checked against the GPUs on initialization,
+//! see `ZiskGpuConfig`.
 //!
 //! ## `zkVMProver` requirements
 //!
@@ -26,28 +29,55 @@
 //! | `Network` |    No     |
 //! | `Cluster` |    Yes    |
 //!
+//! ## Setup cache
+//!
+//! The ROM setup and program vk are cached in `setup_cache_dir` by the hash of the ELF, so
+//! creating a prover of an ELF already set up skips the setup. The cache of an ELF can be prewarmed
+//! by `prewarm_setup_cache` and evicted by `evict_setup_cache`.
+//!
+//! ## Distributed proving
+//!
+//! Proving across multiple processes or machines is done by `ProverResource::Cluster` with the
+//! endpoint of a ZisK coordinator, which splits each proof across the `zisk-worker`s registered
+//! to it (e.g. one worker per GPU, on any number of hosts), see the example
+//! [`docker-compose.cluster.yml`]. The workers and hosts are managed by the coordinator, so the
+//! prover only needs its endpoint. To make use of a single large machine, the coordinator and
+//! the workers run on the same host, as in the example.
+//!
+//! The local prover of `ProverResource::Cpu` and `ProverResource::Gpu` is shared in the process
+//! and doesn't spawn MPI processes itself.
+//!
 //! ## Environment variables
 //!
-//! | Variable                               | Type  | Default | Description                                                            |
-//! | -------------------------------------- | ----- | ------- | ---------------------------------------------------------------------- |
-//! | `ERE_ZISK_SETUP_ON_INIT`               | Flag  |         | Setup local prover on initialization instead of lazily                 |
-//! | `ERE_ZISK_UNLOCK_MAPPED_MEMORY`        | Flag  |         | Configure the prover to unlock mapped memory                           |
-//! | `ERE_ZISK_MINIMAL_MEMORY`              | Flag  |         | Configure the prover to use minimal memory                             |
-//! | `ERE_ZISK_MAX_STREAMS`                 | Value |         | Configure the prover max streams                                       |
-//! | `ERE_ZISK_NUMBER_THREADS_WITNESS`      | Value |         | Configure the prover number of witness threads                         |
-//! | `ERE_ZISK_MAX_WITNESS_STORED`          | Value |         | Configure the prover max witness stored                                |
-//! | `ERE_ZISK_CLUSTER_PROVE_TIMEOUT_SECS`  | Value |         | Timeout for the cluster client prove job                               |
+//! | Variable                              | Type  | Default | Description                                                           |
+//! | ------------------------------------- | ----- | ------- | --------------------------------------------------------------------- |
+//! | `ERE_ZISK_SETUP_ON_INIT`              | Flag  |         | Setup local prover on initialization instead of lazily or on `warmup` |
+//! | `ERE_ZISK_UNLOCK_MAPPED_MEMORY`       | Flag  |         | Configure the prover to unlock mapped memory                          |
+//! | `ERE_ZISK_MINIMAL_MEMORY`             | Flag  |         | Configure the prover to use minimal memory                            |
+//! | `ERE_ZISK_MAX_STREAMS`                | Value |         | Configure the prover max streams                                      |
+//! | `ERE_ZISK_NUMBER_THREADS_WITNESS`     | Value |         | Configure the prover number of witness threads                        |
+//! | `ERE_ZISK_MAX_WITNESS_STORED`         | Value |         | Configure the prover max witness stored                               |
+//! | `ERE_ZISK_CLUSTER_PROVE_TIMEOUT_SECS` | Value |         | Timeout for the cluster client prove job                              |
+//! | `ERE_ZISK_GPU_SM_ARCH`                | Value |         | Compute capability of the GPUs, see [`ZiskGpuConfig`]                 |
 //!
 //! [`install_zisk_sdk.sh`]: https://github.com/eth-act/ere/blob/master/scripts/sdk_installers/install_zisk_sdk.sh
+//! [`docker-compose.cluster.yml`]: https://github.com/eth-act/ere/blob/master/examples/zisk/docker-compose.cluster.yml
 //! [`ziskup`]: https://raw.githubusercontent.com/0xPolygonHermez/zisk/main/ziskup/install.sh
 
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod cache;
 mod error;
+mod gpu;
 mod prover;
 mod sdk;
 
 pub use ere_prover_core::*;
 pub use ere_verifier_zisk::*;
 
-pub use crate::{error::Error, prover::ZiskProver};
+pub use crate::{
+    cache::{evict_setup_cache, prewarm_setup_cache, setup_cache_dir},
+    error::Error,
+    gpu::{ZiskGpuConfig, cuda_archs},
+    prover::ZiskProver,
+};
@@ -2,12 +2,12 @@ use std::time::Instant;
 
 use ere_compiler_core::Elf;
 use ere_prover_core::{
-    CommonError, Input, ProgramExecutionReport, ProgramProvingReport, ProverResource, PublicValues,
-    zkVMProver,
+    CommonError, ExecuteOptions, Input, ProgramExecutionReport, ProgramProvingReport,
+    ProverResource, PublicValues, zkVMProver,
 };
 use ere_verifier_zisk::{ZiskProof, ZiskVerifier};
 
-use crate::{error::Error, sdk::ZiskSdk};
+use crate::{error::Error, gpu::ZiskGpuConfig, sdk::ZiskSdk};
 
 pub struct ZiskProver {
     sdk: ZiskSdk,
@@ -15,8 +15,18 @@ pub struct ZiskProver {
 }
 
 impl ZiskProver {
+    /// Creates the prover with [`ZiskGpuConfig::from_env`].
     pub fn new(elf: Elf, resource: ProverResource) -> Result<Self, Error> {
-        let sdk = ZiskSdk::new(elf, resource)?;
+        Self::new_with_gpu_config(elf, resource, ZiskGpuConfig::from_env()?)
+    }
+
+    /// Same as [`ZiskProver::new`] but with `gpu_config` for [`ProverResource::Gpu`].
+    pub fn new_with_gpu_config(
+        elf: Elf,
+        resource: ProverResource,
+        gpu_config: ZiskGpuConfig,
+    ) -> Result<Self, Error> {
+        let sdk = ZiskSdk::new(elf, resource, &gpu_config)?;
         let verifier = ZiskVerifier::new(sdk.program_vk());
         Ok(Self { sdk, verifier })
     }
@@ -30,21 +40,37 @@ impl zkVMProver for ZiskProver {
         &self.verifier
     }
 
-    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+    fn max_output_len(&self) -> Option<usize> {
+        // Capped by the ZisK runtime.
+        Some(256)
+    }
+
+    fn warmup(&self) -> Result<(), Error> {
+        self.sdk.warmup()
+    }
+
+    fn execute_with_options(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        options.check_profile_unsupported()?;
         if input.proofs.is_some() {
             Err(CommonError::unsupported_input("no dedicated proofs stream"))?
         }
+        if input.kv_store.is_some() {
+            Err(CommonError::unsupported_input("no key-value store hint"))?
+        }
 
         let start = Instant::now();
-        let (public_values, total_num_cycles) = self.sdk.execute(input)?;
+        let (public_values, report) = self.sdk.execute(input, options)?;
         let execution_duration = start.elapsed();
 
         Ok((
             public_values,
             ProgramExecutionReport {
-                total_num_cycles,
                 execution_duration,
-                ..Default::default()
+                ..report
             },
         ))
     }
@@ -56,13 +82,16 @@ impl zkVMProver for ZiskProver {
         if input.proofs.is_some() {
             Err(CommonError::unsupported_input("no dedicated proofs stream"))?
         }
+        if input.kv_store.is_some() {
+            Err(CommonError::unsupported_input("no key-value store hint"))?
+        }
 
         let (public_values, proof, proving_time) = self.sdk.prove(input)?;
 
         Ok((
             public_values,
             proof,
-            ProgramProvingReport::new(proving_time),
+            ProgramProvingReport::new(proving_time).with_input_commitment(input),
         ))
     }
 }
@@ -73,14 +102,17 @@ pub(crate) mod tests {
 
     use ere_compiler_core::{Compiler, Elf};
     use ere_compiler_zisk::ZiskRustRv64imaCustomized;
-    use ere_prover_core::{Input, ProverResource, RemoteProverConfig, zkVMProver};
+    use ere_prover_core::{
+        CommonError, ExecuteOptions, Input, ProverResource, RemoteProverConfig, zkVMProver,
+    };
     use ere_util_test::{
         codec::BincodeLegacy,
         host::{TestCase, run_zkvm_execute, run_zkvm_prove, testing_guest_directory},
         program::basic::BasicProgram,
     };
+    use ere_verifier_zisk::{Error as VerifierError, ZiskVerifier};
 
-    use crate::prover::ZiskProver;
+    use crate::{error::Error, prover::ZiskProver};
 
     pub(crate) fn basic_elf() -> Elf {
         static ELF: OnceLock<Elf> = OnceLock::new();
@@ -92,18 +124,19 @@ pub(crate) mod tests {
         .clone()
     }
 
+    fn resource() -> ProverResource {
+        if cfg!(feature = "cuda") {
+            ProverResource::Gpu
+        } else {
+            ProverResource::Cpu
+        }
+    }
+
     pub(crate) fn basic_elf_zkvm() -> MutexGuard<'static, ZiskProver> {
         static ZKVM: OnceLock<Mutex<ZiskProver>> = OnceLock::new();
-        ZKVM.get_or_init(|| {
-            let resource = if cfg!(feature = "cuda") {
-                ProverResource::Gpu
-            } else {
-                ProverResource::Cpu
-            };
-            Mutex::new(ZiskProver::new(basic_elf(), resource).unwrap())
-        })
-        .lock()
-        .unwrap()
+        ZKVM.get_or_init(|| Mutex::new(ZiskProver::new(basic_elf(), resource()).unwrap()))
+            .lock()
+            .unwrap()
     }
 
     #[test]
@@ -112,6 +145,28 @@ pub(crate) mod tests {
 
         let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
         run_zkvm_execute(&zkvm, &test_case);
+
+        let (_, report) = zkvm.execute(&test_case.input()).unwrap();
+        assert_eq!(report.chip_rows["Main"], report.total_num_cycles);
+        assert!(report.bus_ops.contains_key("Binary"));
+    }
+
+    #[test]
+    fn test_execute_cycle_limit() {
+        let zkvm = &*basic_elf_zkvm();
+
+        let test_case = BasicProgram::<BincodeLegacy>::valid_test_case();
+        let options = ExecuteOptions::new().with_max_cycles(1);
+        let err = zkvm
+            .execute_with_options(&test_case.input(), &options)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::CommonError(CommonError::CycleLimitExceeded { max_cycles: 1 })
+            ),
+            "Expect error variant `CycleLimitExceeded`, got {err:?}",
+        );
     }
 
     #[test]
@@ -150,6 +205,30 @@ pub(crate) mod tests {
         run_zkvm_prove(&zkvm, &test_case);
     }
 
+    #[test]
+    fn test_verify_with_output() {
+        let elf = ZiskRustRv64imaCustomized
+            .compile(testing_guest_directory("zisk", "output_hashed"), &[])
+            .unwrap();
+        // The local prover is shared by all programs, so proves while holding the basic one.
+        let _basic_zkvm = basic_elf_zkvm();
+        let zkvm = ZiskProver::new(elf, resource()).unwrap();
+
+        let stdin = b"output larger than public values".to_vec();
+        let output = stdin.iter().cycle().take(1024).copied().collect::<Vec<_>>();
+        let (_, proof, _) = zkvm.prove(&Input::new().with_stdin(stdin)).unwrap();
+
+        let verifier = ZiskVerifier::new(*zkvm.program_vk());
+        verifier.verify_with_output(&proof, &output).unwrap();
+
+        let mut tampered = output;
+        tampered[0] ^= 1;
+        assert!(matches!(
+            verifier.verify_with_output(&proof, &tampered).unwrap_err(),
+            VerifierError::OutputDigestMismatch { .. }
+        ));
+    }
+
     #[test]
     #[ignore = "Requires ZisK cluster running"]
     fn test_cluster_prove() {
@@ -1,22 +1,25 @@
 use std::{
     any::Any,
-    env,
     panic::{self, AssertUnwindSafe},
     time::Duration,
 };
 
 use ere_cluster_client_zisk::ZiskClusterClient;
 use ere_compiler_core::Elf;
-use ere_prover_core::{CommonError, Input, ProverResource, ProverResourceKind, PublicValues};
+use ere_prover_core::{
+    CommonError, ExecuteOptions, Input, ProgramExecutionReport, ProverResource, ProverResourceKind,
+    PublicValues, config::Config,
+};
 use ere_util_tokio::block_on;
 use ere_verifier_zisk::{ZiskProgramVk, ZiskProof, ensure_program_vk_matches};
+use indexmap::IndexMap;
 use tokio::time::Instant;
-use zisk_core::{Riscv2zisk, ZiskRom};
-use ziskemu::{Emu, EmuOptions};
+use zisk_core::{Riscv2zisk, ZiskOperationType, ZiskRom, zisk_ops::ZiskOp};
+use ziskemu::{Emu, EmuOptions, Stats};
 
-use crate::{error::Error, sdk::local::LocalProver};
+use crate::{error::Error, gpu::ZiskGpuConfig, sdk::local::LocalProver};
 
-mod local;
+pub(crate) mod local;
 
 /// Default ZisK cluster prove timeout seconds.
 const DEFAULT_ZISK_CLUSTER_PROVE_TIMEOUT_SECS: u64 = 600;
@@ -37,7 +40,11 @@ pub struct ZiskSdk {
 }
 
 impl ZiskSdk {
-    pub fn new(elf: Elf, resource: ProverResource) -> Result<Self, Error> {
+    pub fn new(
+        elf: Elf,
+        resource: ProverResource,
+        gpu_config: &ZiskGpuConfig,
+    ) -> Result<Self, Error> {
         // Convert ELF to ZisK ROM
         let rom = Riscv2zisk::new(&elf)
             .run()
@@ -46,13 +53,19 @@ impl ZiskSdk {
         // Initialize prover
         let backend = match &resource {
             ProverResource::Cpu | ProverResource::Gpu => {
+                if cfg!(feature = "cuda") && resource.is_gpu() {
+                    gpu_config.validate()?;
+                }
                 Backend::Local(LocalProver::new(elf, &resource)?)
             }
             ProverResource::Cluster(config) => {
+                // The cluster proves with the timeout set by `ERE_ZISK_CLUSTER_PROVE_TIMEOUT_SECS`.
+                config.check_supported(&[])?;
                 let client = block_on(ZiskClusterClient::new(config, elf))?;
                 let prove_timeout = Duration::from_secs(
-                    env::var("ERE_ZISK_CLUSTER_PROVE_TIMEOUT_SECS")
-                        .ok()
+                    Config::global()
+                        .map_err(CommonError::from)?
+                        .var("ERE_ZISK_CLUSTER_PROVE_TIMEOUT_SECS")
                         .and_then(|val| val.parse::<u64>().ok())
                         .unwrap_or(DEFAULT_ZISK_CLUSTER_PROVE_TIMEOUT_SECS),
                 );
@@ -88,16 +101,36 @@ impl ZiskSdk {
         }
     }
 
-    /// Execute the ELF with the given `stdin`.
-    pub fn execute(&self, input: &Input) -> Result<(PublicValues, u64), Error> {
-        let stdin = framed_stdin(input.stdin());
+    /// Execute the ELF with the given `stdin`, returns public values and the report of the
+    /// statistics collected by the emulator, without the execution duration.
+    ///
+    /// The emulator stops after `max_cycles` steps if specified in `options`.
+    pub fn execute(
+        &self,
+        input: &Input,
+        options: &ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        let mut emu_options = EmuOptions {
+            stats: true,
+            ..Default::default()
+        };
+        if let Some(max_cycles) = options.max_cycles {
+            emu_options.max_steps = emu_options.max_steps.min(max_cycles);
+        }
+
+        let stdin = framed_stdin(&input.stdin()?);
         let mut emu = Emu::new(&self.rom);
-        emu.ctx = emu.create_emu_context(stdin, &EmuOptions::default());
+        emu.ctx = emu.create_emu_context(stdin, &emu_options);
 
-        panic::catch_unwind(AssertUnwindSafe(|| emu.run_fast(&EmuOptions::default())))
+        panic::catch_unwind(AssertUnwindSafe(|| emu.run_fast(&emu_options)))
             .map_err(|err| Error::EmulatorPanic(panic_msg(err)))?;
 
         if !emu.ctx.inst_ctx.end {
+            if let Some(max_cycles) = options.max_cycles
+                && emu.number_of_steps() >= max_cycles
+            {
+                return Err(CommonError::cycle_limit_exceeded(max_cycles).into());
+            }
             return Err(Error::EmulatorNotTerminated);
         }
 
@@ -107,8 +140,31 @@ impl ZiskSdk {
 
         let public_values = emu.get_output_8().into();
         let total_num_cycles = emu.number_of_steps();
+        let (precompile_counts, bus_ops) = op_counts(&emu.ctx.stats);
+
+        Ok((
+            public_values,
+            ProgramExecutionReport {
+                total_num_cycles,
+                precompile_counts,
+                // The main state machine takes a row per step.
+                chip_rows: IndexMap::from_iter([("Main".to_string(), total_num_cycles)]),
+                bus_ops,
+                ..Default::default()
+            },
+        ))
+    }
 
-        Ok((public_values, total_num_cycles))
+    /// Sets up the local prover, no-op for the cluster.
+    pub fn warmup(&self) -> Result<(), Error> {
+        if cfg!(not(feature = "cuda")) && self.resource == ProverResource::Gpu {
+            return Err(Error::CudaFeatureDisabled);
+        }
+
+        match &self.backend {
+            Backend::Local(local) => local.setup().map(|_| ()),
+            Backend::Cluster { .. } => Ok(()),
+        }
     }
 
     pub fn prove(&self, input: &Input) -> Result<(PublicValues, ZiskProof, Duration), Error> {
@@ -147,6 +203,61 @@ fn framed_stdin(data: &[u8]) -> Vec<u8> {
     buf
 }
 
+/// Returns invocation counts of precompiles, which are the ZisK operations executed by dedicated
+/// state machines (e.g. keccakf, sha256f and arith256), and the number of operations sent over
+/// the bus to each state machine, collected by the emulator.
+fn op_counts(stats: &Stats) -> (IndexMap<String, u64>, IndexMap<String, u64>) {
+    let mut precompile_counts = IndexMap::new();
+    let mut bus_ops = IndexMap::<String, u64>::new();
+    for (code, count) in stats.ops.iter().enumerate() {
+        if *count == 0 {
+            continue;
+        }
+        let Ok(op) = ZiskOp::try_from_code(code as u8) else {
+            continue;
+        };
+        let op_type = op.op_type();
+        // Operations of these types are executed by the main state machine.
+        if matches!(
+            op_type,
+            ZiskOperationType::None | ZiskOperationType::Internal
+        ) {
+            continue;
+        }
+        *bus_ops.entry(op_type_name(op_type)).or_default() += *count;
+        let is_precompile = !matches!(
+            op_type,
+            ZiskOperationType::Arith
+                | ZiskOperationType::Binary
+                | ZiskOperationType::BinaryE
+                | ZiskOperationType::PubOut
+        );
+        if is_precompile {
+            precompile_counts.insert(op.name().to_string(), *count);
+        }
+    }
+    (precompile_counts, bus_ops)
+}
+
+/// Returns the name of the state machine handling operations of `op_type`, as the key of
+/// `ProgramExecutionReport::bus_ops`.
+///
+/// Names are mapped explicitly to stay stable across upstream changes of `ZiskOperationType`.
+fn op_type_name(op_type: ZiskOperationType) -> String {
+    let name = match op_type {
+        ZiskOperationType::Arith => "Arith",
+        ZiskOperationType::ArithEq => "ArithEq",
+        ZiskOperationType::Binary => "Binary",
+        ZiskOperationType::BinaryE => "BinaryExtension",
+        ZiskOperationType::Keccak => "Keccakf",
+        ZiskOperationType::Sha256 => "Sha256f",
+        ZiskOperationType::PubOut => "PubOut",
+        // Types added upstream are keyed by discriminant until named here.
+        op_type => return format!("OpType{}", op_type as u32),
+    };
+    name.to_string()
+}
+
 fn panic_msg(err: Box<dyn Any + Send + 'static>) -> String {
     None.or_else(|| err.downcast_ref::<String>().cloned())
         .or_else(|| err.downcast_ref::<&'static str>().map(ToString::to_string))
@@ -161,7 +272,19 @@ mod tests {
     use ere_verifier_zisk::ZiskProgramVk;
     use tempfile::tempdir;
 
-    use crate::prover::tests::{basic_elf, basic_elf_zkvm};
+    use zisk_core::ZiskOperationType;
+
+    use crate::{
+        prover::tests::{basic_elf, basic_elf_zkvm},
+        sdk::op_type_name,
+    };
+
+    #[test]
+    fn test_op_type_name() {
+        assert_eq!(op_type_name(ZiskOperationType::Binary), "Binary");
+        assert_eq!(op_type_name(ZiskOperationType::BinaryE), "BinaryExtension");
+        assert_eq!(op_type_name(ZiskOperationType::Keccak), "Keccakf");
+    }
 
     #[test]
     fn program_vk_matches_cargo_zisk_program_setup() {
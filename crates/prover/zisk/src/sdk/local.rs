@@ -1,10 +1,10 @@
 use std::{
-    env, fs,
+    fs,
     time::{Duration, Instant},
 };
 
 use ere_compiler_core::Elf;
-use ere_prover_core::{CommonError, Input, ProverResource};
+use ere_prover_core::{CommonError, Input, ProverResource, config::Config as EreConfig};
 use ere_verifier_zisk::{ZiskProgramVk, ZiskProof};
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
@@ -16,10 +16,10 @@ use zisk_pil::RomRomTrace;
 use zisk_prover_backend::{
     Asm, AsmOptions, BackendProverOpts, GuestProgram, ProverClientBuilder, ZiskProver,
 };
-use zisk_rom_setup::{ROM_BLOWUP_FACTOR, ROM_MERKLE_TREE_ARITY, get_elf_bin_file_path_with_hash};
+use zisk_rom_setup::{ROM_BLOWUP_FACTOR, ROM_MERKLE_TREE_ARITY};
 use zisk_sm_rom::RomSM;
 
-use crate::{error::Error, sdk::framed_stdin};
+use crate::{cache, error::Error, sdk::framed_stdin};
 
 // Use a shared prover instance to avoid `MpiCtx` get initialized twice, to support multiple
 // `ZiskProver` instances creation (e.g. testing different ELFs).
@@ -37,9 +37,10 @@ struct Config {
 
 impl Config {
     fn from_env() -> Result<Self, Error> {
+        let config = EreConfig::global().map_err(CommonError::from)?;
         let parse_usize = |key| {
-            env::var(key)
-                .ok()
+            config
+                .var(key)
                 .map(|value| {
                     value
                         .parse()
@@ -48,9 +49,9 @@ impl Config {
                 .transpose()
         };
         Ok(Self {
-            setup_on_init: env::var_os("ERE_ZISK_SETUP_ON_INIT").is_some(),
-            unlock_mapped_memory: env::var_os("ERE_ZISK_UNLOCK_MAPPED_MEMORY").is_some(),
-            minimal_memory: env::var_os("ERE_ZISK_MINIMAL_MEMORY").is_some(),
+            setup_on_init: config.var("ERE_ZISK_SETUP_ON_INIT").is_some(),
+            unlock_mapped_memory: config.var("ERE_ZISK_UNLOCK_MAPPED_MEMORY").is_some(),
+            minimal_memory: config.var("ERE_ZISK_MINIMAL_MEMORY").is_some(),
             max_streams: parse_usize("ERE_ZISK_MAX_STREAMS")?,
             number_threads_witness: parse_usize("ERE_ZISK_NUMBER_THREADS_WITNESS")?,
             max_witness_stored: parse_usize("ERE_ZISK_MAX_WITNESS_STORED")?,
@@ -71,27 +72,29 @@ impl LocalProver {
         let config = Config::from_env()?;
 
         let program = GuestProgram::from_bytes("guest", elf.0);
-        let program_vk = compute_program_vk(resource, &program)?;
+        let program_vk = cache::program_vk(&program, || compute_program_vk(resource, &program))?;
 
-        if config.setup_on_init {
-            let prover = LOCAL_PROVER.get_or_try_init(|| build_prover(&config, resource))?;
-            prover.setup(&program).run().map_err(Error::Setup)?;
-        }
-
-        Ok(Self {
+        let local = Self {
             resource: resource.clone(),
             config,
             program,
             program_vk,
-            initialized: Mutex::new(config.setup_on_init),
-        })
+            initialized: Mutex::new(false),
+        };
+
+        if config.setup_on_init {
+            local.setup()?;
+        }
+
+        Ok(local)
     }
 
     pub fn program_vk(&self) -> ZiskProgramVk {
         self.program_vk
     }
 
-    pub fn prove(&self, input: &Input) -> Result<(ZiskProof, Duration), Error> {
+    /// Builds the shared prover and sets up the program if not yet done, then returns the prover.
+    pub fn setup(&self) -> Result<&'static ZiskProver<Asm>, Error> {
         let prover = LOCAL_PROVER.get_or_try_init(|| build_prover(&self.config, &self.resource))?;
 
         let mut initialized = self.initialized.lock();
@@ -100,7 +103,13 @@ impl LocalProver {
             *initialized = true;
         }
 
-        let stdin = ZiskStdin::from_vec(framed_stdin(input.stdin()));
+        Ok(prover)
+    }
+
+    pub fn prove(&self, input: &Input) -> Result<(ZiskProof, Duration), Error> {
+        let prover = self.setup()?;
+
+        let stdin = ZiskStdin::from_vec(framed_stdin(&input.stdin()?));
 
         let started = Instant::now();
         let output = prover
@@ -152,7 +161,7 @@ fn build_prover(config: &Config, resource: &ProverResource) -> Result<ZiskProver
 
 /// Vendored from [`zisk_rom_setup::rom_merkle_setup`] to do program setup withuot creating
 /// `ProofCtx` or generating assembly, which can only be created once due to mpi initialization.
-fn compute_program_vk(
+pub(crate) fn compute_program_vk(
     resource: &ProverResource,
     program: &GuestProgram,
 ) -> Result<ZiskProgramVk, Error> {
@@ -187,8 +196,7 @@ fn compute_program_vk(
     let cache_dir = &ZiskPaths::global().cache;
     fs::create_dir_all(cache_dir)
         .map_err(|err| CommonError::create_dir("cache", cache_dir, err))?;
-    let elf_bin_path =
-        get_elf_bin_file_path_with_hash(program.hash(), cache_dir, false).expect("infallable");
+    let elf_bin_path = cache::elf_bin_path(program, cache_dir, false)?;
 
     proofman_starks_lib_c::write_custom_commit_c(
         root.as_mut_ptr() as *mut u8,
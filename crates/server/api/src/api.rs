@@ -1,15 +1,49 @@
 // This file is @generated by prost-build.
 #[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct HandshakeRequest {
+    #[prost(uint32, tag = "1")]
+    pub protocol_version: u32,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct HandshakeResponse {
+    #[prost(uint32, tag = "1")]
+    pub protocol_version: u32,
+    #[prost(string, tag = "2")]
+    pub crate_version: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub zkvm_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub zkvm_sdk_version: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ExecuteRequest {
     #[prost(bytes = "vec", tag = "1")]
     pub input_stdin: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", optional, tag = "2")]
     pub input_proofs: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(uint64, optional, tag = "3")]
+    pub max_cycles: ::core::option::Option<u64>,
+    #[prost(enumeration = "Compression", tag = "4")]
+    pub input_stdin_compression: i32,
+    #[prost(bytes = "vec", optional, tag = "5")]
+    pub input_kv_store: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub program_digest: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, optional, tag = "7")]
+    pub input_upload_id: ::core::option::Option<u64>,
+    #[prost(string, optional, tag = "8")]
+    pub input_stdin_file: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(enumeration = "ReportEncoding", tag = "9")]
+    pub report_encoding: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ExecuteResponse {
+    #[prost(message, optional, tag = "3")]
+    pub error: ::core::option::Option<Error>,
     #[prost(oneof = "execute_response::Result", tags = "1, 2")]
     pub result: ::core::option::Option<execute_response::Result>,
 }
@@ -39,10 +73,26 @@ pub struct ProveRequest {
     pub input_stdin: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", optional, tag = "2")]
     pub input_proofs: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(enumeration = "Compression", tag = "3")]
+    pub input_stdin_compression: i32,
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub input_kv_store: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub program_digest: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, optional, tag = "6")]
+    pub input_upload_id: ::core::option::Option<u64>,
+    #[prost(string, optional, tag = "7")]
+    pub input_stdin_file: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(enumeration = "ReportEncoding", tag = "8")]
+    pub report_encoding: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ProveResponse {
+    #[prost(uint64, tag = "3")]
+    pub job_id: u64,
+    #[prost(message, optional, tag = "4")]
+    pub error: ::core::option::Option<Error>,
     #[prost(oneof = "prove_response::Result", tags = "1, 2")]
     pub result: ::core::option::Option<prove_response::Result>,
 }
@@ -68,14 +118,54 @@ pub struct ProveOk {
     pub report: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SubmitProveResponse {
+    #[prost(uint64, tag = "1")]
+    pub job_id: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ResumeProveRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub input_commitment: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, optional, tag = "2")]
+    pub job_id: ::core::option::Option<u64>,
+    #[prost(bool, tag = "3")]
+    pub omit_proof: bool,
+    #[prost(enumeration = "ReportEncoding", tag = "4")]
+    pub report_encoding: i32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub program_digest: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct JobStatusRequest {
+    #[prost(uint64, tag = "1")]
+    pub job_id: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct JobStatusResponse {
+    #[prost(enumeration = "JobState", tag = "1")]
+    pub state: i32,
+    #[prost(string, optional, tag = "2")]
+    pub err: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag = "3")]
+    pub error: ::core::option::Option<Error>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct VerifyRequest {
     #[prost(bytes = "vec", tag = "1")]
     pub proof: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub program_digest: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct VerifyResponse {
+    #[prost(message, optional, tag = "3")]
+    pub error: ::core::option::Option<Error>,
     #[prost(oneof = "verify_response::Result", tags = "1, 2")]
     pub result: ::core::option::Option<verify_response::Result>,
 }
@@ -97,11 +187,16 @@ pub struct VerifyOk {
     pub public_values: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
-pub struct ProgramVkRequest {}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ProgramVkRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub program_digest: ::prost::alloc::vec::Vec<u8>,
+}
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ProgramVkResponse {
+    #[prost(message, optional, tag = "3")]
+    pub error: ::core::option::Option<Error>,
     #[prost(oneof = "program_vk_response::Result", tags = "1, 2")]
     pub result: ::core::option::Option<program_vk_response::Result>,
 }
@@ -122,9 +217,141 @@ pub struct ProgramVkOk {
     #[prost(bytes = "vec", tag = "1")]
     pub program_vk: ::prost::alloc::vec::Vec<u8>,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct UploadProgramRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub elf: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct UploadProgramResponse {
+    #[prost(message, optional, tag = "3")]
+    pub error: ::core::option::Option<Error>,
+    #[prost(oneof = "upload_program_response::Result", tags = "1, 2")]
+    pub result: ::core::option::Option<upload_program_response::Result>,
+}
+/// Nested message and enum types in `UploadProgramResponse`.
+pub mod upload_program_response {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag = "1")]
+        Ok(super::UploadProgramOk),
+        #[prost(string, tag = "2")]
+        Err(::prost::alloc::string::String),
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct UploadProgramOk {
+    #[prost(bytes = "vec", tag = "1")]
+    pub program_digest: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub program_vk: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct Error {
+    #[prost(string, tag = "1")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub kind: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Compression {
+    None = 0,
+    Zstd = 1,
+}
+impl Compression {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::None => "COMPRESSION_NONE",
+            Self::Zstd => "COMPRESSION_ZSTD",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "COMPRESSION_NONE" => Some(Self::None),
+            "COMPRESSION_ZSTD" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ReportEncoding {
+    Legacy = 0,
+    Json = 1,
+}
+impl ReportEncoding {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Legacy => "REPORT_ENCODING_LEGACY",
+            Self::Json => "REPORT_ENCODING_JSON",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "REPORT_ENCODING_LEGACY" => Some(Self::Legacy),
+            "REPORT_ENCODING_JSON" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum JobState {
+    Queued = 0,
+    Proving = 1,
+    Done = 2,
+    Failed = 3,
+}
+impl JobState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Queued => "JOB_STATE_QUEUED",
+            Self::Proving => "JOB_STATE_PROVING",
+            Self::Done => "JOB_STATE_DONE",
+            Self::Failed => "JOB_STATE_FAILED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "JOB_STATE_QUEUED" => Some(Self::Queued),
+            "JOB_STATE_PROVING" => Some(Self::Proving),
+            "JOB_STATE_DONE" => Some(Self::Done),
+            "JOB_STATE_FAILED" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
 pub use twirp;
 #[twirp::async_trait::async_trait]
 pub trait ZkvmService: Send + Sync {
+    async fn handshake(
+        &self,
+        req: twirp::Request<HandshakeRequest>,
+    ) -> twirp::Result<twirp::Response<HandshakeResponse>>;
     async fn execute(
         &self,
         req: twirp::Request<ExecuteRequest>,
@@ -133,6 +360,18 @@ pub trait ZkvmService: Send + Sync {
         &self,
         req: twirp::Request<ProveRequest>,
     ) -> twirp::Result<twirp::Response<ProveResponse>>;
+    async fn submit_prove(
+        &self,
+        req: twirp::Request<ProveRequest>,
+    ) -> twirp::Result<twirp::Response<SubmitProveResponse>>;
+    async fn resume_prove(
+        &self,
+        req: twirp::Request<ResumeProveRequest>,
+    ) -> twirp::Result<twirp::Response<ProveResponse>>;
+    async fn job_status(
+        &self,
+        req: twirp::Request<JobStatusRequest>,
+    ) -> twirp::Result<twirp::Response<JobStatusResponse>>;
     async fn verify(
         &self,
         req: twirp::Request<VerifyRequest>,
@@ -141,12 +380,22 @@ pub trait ZkvmService: Send + Sync {
         &self,
         req: twirp::Request<ProgramVkRequest>,
     ) -> twirp::Result<twirp::Response<ProgramVkResponse>>;
+    async fn upload_program(
+        &self,
+        req: twirp::Request<UploadProgramRequest>,
+    ) -> twirp::Result<twirp::Response<UploadProgramResponse>>;
 }
 #[twirp::async_trait::async_trait]
 impl<T> ZkvmService for std::sync::Arc<T>
 where
     T: ZkvmService + Sync + Send,
 {
+    async fn handshake(
+        &self,
+        req: twirp::Request<HandshakeRequest>,
+    ) -> twirp::Result<twirp::Response<HandshakeResponse>> {
+        T::handshake(&*self, req).await
+    }
     async fn execute(
         &self,
         req: twirp::Request<ExecuteRequest>,
@@ -159,6 +408,24 @@ where
     ) -> twirp::Result<twirp::Response<ProveResponse>> {
         T::prove(&*self, req).await
     }
+    async fn submit_prove(
+        &self,
+        req: twirp::Request<ProveRequest>,
+    ) -> twirp::Result<twirp::Response<SubmitProveResponse>> {
+        T::submit_prove(&*self, req).await
+    }
+    async fn resume_prove(
+        &self,
+        req: twirp::Request<ResumeProveRequest>,
+    ) -> twirp::Result<twirp::Response<ProveResponse>> {
+        T::resume_prove(&*self, req).await
+    }
+    async fn job_status(
+        &self,
+        req: twirp::Request<JobStatusRequest>,
+    ) -> twirp::Result<twirp::Response<JobStatusResponse>> {
+        T::job_status(&*self, req).await
+    }
     async fn verify(
         &self,
         req: twirp::Request<VerifyRequest>,
@@ -171,12 +438,24 @@ where
     ) -> twirp::Result<twirp::Response<ProgramVkResponse>> {
         T::program_vk(&*self, req).await
     }
+    async fn upload_program(
+        &self,
+        req: twirp::Request<UploadProgramRequest>,
+    ) -> twirp::Result<twirp::Response<UploadProgramResponse>> {
+        T::upload_program(&*self, req).await
+    }
 }
 pub fn router<T>(api: T) -> twirp::Router
 where
     T: ZkvmService + Clone + Send + Sync + 'static,
 {
     twirp::details::TwirpRouterBuilder::new("/api.ZkvmService", api)
+        .route(
+            "/Handshake",
+            |api: T, req: twirp::Request<HandshakeRequest>| async move {
+                api.handshake(req).await
+            },
+        )
         .route(
             "/Execute",
             |api: T, req: twirp::Request<ExecuteRequest>| async move {
@@ -189,6 +468,24 @@ where
                 api.prove(req).await
             },
         )
+        .route(
+            "/SubmitProve",
+            |api: T, req: twirp::Request<ProveRequest>| async move {
+                api.submit_prove(req).await
+            },
+        )
+        .route(
+            "/ResumeProve",
+            |api: T, req: twirp::Request<ResumeProveRequest>| async move {
+                api.resume_prove(req).await
+            },
+        )
+        .route(
+            "/JobStatus",
+            |api: T, req: twirp::Request<JobStatusRequest>| async move {
+                api.job_status(req).await
+            },
+        )
         .route(
             "/Verify",
             |api: T, req: twirp::Request<VerifyRequest>| async move {
@@ -201,10 +498,22 @@ where
                 api.program_vk(req).await
             },
         )
+        .route(
+            "/UploadProgram",
+            |api: T, req: twirp::Request<UploadProgramRequest>| async move {
+                api.upload_program(req).await
+            },
+        )
         .build()
 }
 #[twirp::async_trait::async_trait]
 impl ZkvmService for twirp::client::Client {
+    async fn handshake(
+        &self,
+        req: twirp::Request<HandshakeRequest>,
+    ) -> twirp::Result<twirp::Response<HandshakeResponse>> {
+        self.request("api.ZkvmService/Handshake", req).await
+    }
     async fn execute(
         &self,
         req: twirp::Request<ExecuteRequest>,
@@ -217,6 +526,24 @@ impl ZkvmService for twirp::client::Client {
     ) -> twirp::Result<twirp::Response<ProveResponse>> {
         self.request("api.ZkvmService/Prove", req).await
     }
+    async fn submit_prove(
+        &self,
+        req: twirp::Request<ProveRequest>,
+    ) -> twirp::Result<twirp::Response<SubmitProveResponse>> {
+        self.request("api.ZkvmService/SubmitProve", req).await
+    }
+    async fn resume_prove(
+        &self,
+        req: twirp::Request<ResumeProveRequest>,
+    ) -> twirp::Result<twirp::Response<ProveResponse>> {
+        self.request("api.ZkvmService/ResumeProve", req).await
+    }
+    async fn job_status(
+        &self,
+        req: twirp::Request<JobStatusRequest>,
+    ) -> twirp::Result<twirp::Response<JobStatusResponse>> {
+        self.request("api.ZkvmService/JobStatus", req).await
+    }
     async fn verify(
         &self,
         req: twirp::Request<VerifyRequest>,
@@ -229,6 +556,12 @@ impl ZkvmService for twirp::client::Client {
     ) -> twirp::Result<twirp::Response<ProgramVkResponse>> {
         self.request("api.ZkvmService/ProgramVk", req).await
     }
+    async fn upload_program(
+        &self,
+        req: twirp::Request<UploadProgramRequest>,
+    ) -> twirp::Result<twirp::Response<UploadProgramResponse>> {
+        self.request("api.ZkvmService/UploadProgram", req).await
+    }
 }
 #[allow(dead_code)]
 pub mod handler {
@@ -255,6 +588,14 @@ pub mod handler {
             req: twirp::reqwest::Request,
         ) -> twirp::Result<twirp::reqwest::Response> {
             match method {
+                "Handshake" => {
+                    twirp::details::encode_response(
+                        self
+                            .inner
+                            .handshake(twirp::details::decode_request(req).await?)
+                            .await?,
+                    )
+                }
                 "Execute" => {
                     twirp::details::encode_response(
                         self
@@ -271,6 +612,30 @@ pub mod handler {
                             .await?,
                     )
                 }
+                "SubmitProve" => {
+                    twirp::details::encode_response(
+                        self
+                            .inner
+                            .submit_prove(twirp::details::decode_request(req).await?)
+                            .await?,
+                    )
+                }
+                "ResumeProve" => {
+                    twirp::details::encode_response(
+                        self
+                            .inner
+                            .resume_prove(twirp::details::decode_request(req).await?)
+                            .await?,
+                    )
+                }
+                "JobStatus" => {
+                    twirp::details::encode_response(
+                        self
+                            .inner
+                            .job_status(twirp::details::decode_request(req).await?)
+                            .await?,
+                    )
+                }
                 "Verify" => {
                     twirp::details::encode_response(
                         self
@@ -287,6 +652,14 @@ pub mod handler {
                             .await?,
                     )
                 }
+                "UploadProgram" => {
+                    twirp::details::encode_response(
+                        self
+                            .inner
+                            .upload_program(twirp::details::decode_request(req).await?)
+                            .await?,
+                    )
+                }
                 _ => {
                     Err(
                         twirp::bad_route(
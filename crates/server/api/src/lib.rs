@@ -1,3 +1,45 @@
+//! # Ere Server API
+//!
+//! Wire contract of `ere-server` defined in `proto/api.proto`, served by [Twirp] over HTTP at
+//! `POST /twirp/api.ZkvmService/{Method}`, with body encoded in protobuf
+//! (`Content-Type: application/protobuf`) or JSON (`Content-Type: application/json`).
+//!
+//! A server serves the program given on start (by `--elf-path`, `--elf-url` or stdin), and the
+//! ones uploaded by `UploadProgram`, selected by the `program_digest` (SHA-256 of the ELF) of
+//! requests, which defaults to the former when empty. A client usually:
+//!
+//! 1. Calls `Handshake` to negotiate the [`PROTOCOL_VERSION`], which also returns the versions of
+//!    the server crate and zkVM SDK to check compatibility with (e.g. of encoded proofs).
+//! 2. Calls `SubmitProve` with the input to get a job id, or `Prove` to wait for the proof, which
+//!    aborts the job if the client disconnects before it finishes.
+//! 3. Polls `JobStatus` until the job is done or failed.
+//! 4. Calls `ResumeProve` with the job id to fetch the proof.
+//!
+//! Inputs and proofs too large to be buffered in a request or response (e.g. of a mainnet block)
+//! can be streamed in chunks instead:
+//!
+//! - `POST /inputs` with the stdin as body (`Content-Encoding: zstd` if compressed) responds with
+//!   an upload id in plain text, to be given as `input_upload_id` of `Execute`, `Prove` or
+//!   `SubmitProve` instead of `input_stdin`. An upload is consumed by the request it's given to.
+//! - `ResumeProve` with `omit_proof` responds without the proof, which is then fetched by `GET
+//!   /proofs/{job_id}` as `application/octet-stream`.
+//!
+//! A client sharing a directory with the server (e.g. bind-mounted into its container) can also
+//! write the stdin to a file there, and give its name as `input_stdin_file` for the server started
+//! with `--input-dir` of the directory to read it directly. The file is removed once read.
+//!
+//! Errors of the zkVM are returned as the message in `err` of responses (as by servers predating
+//! `ErrorKind`), along with the `error` carrying the encoded `ErrorKind`, while errors of the
+//! request (e.g. not found job) are returned as Twirp errors. Besides the API, `GET /health`
+//! reports 503 when the server is unhealthy, and `GET /metrics` serves Prometheus metrics, also on
+//! `--metrics-port` if set.
+//!
+//! When started with `--auth-token` or `--auth-token-file`, API requests (including `/inputs` and
+//! `/proofs`) must carry `Authorization: Bearer {token}`, otherwise they fail as
+//! `unauthenticated`. `/health` and `/metrics` are not protected.
+//!
+//! [Twirp]: https://twitchtv.github.io/twirp/docs/spec_v7.html
+
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
 #[rustfmt::skip]
@@ -7,3 +49,20 @@ mod api;
 mod test;
 
 pub use api::*;
+
+/// Version of the crates of `zkVMClient` and `zkVMServer`, which are released together.
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Version of the protocol between `zkVMClient` and `zkVMServer`, bumped on changes that the
+/// other side can't ignore. Both sides use the lower version negotiated by `Handshake`.
+///
+/// - `0`: One-shot `Prove` and `ResumeProve` by input commitment, without `Handshake`.
+/// - `1`: Prove jobs with ids, `SubmitProve`, `JobStatus` and `ResumeProve` by job id, and
+///   `max_cycles`, `input_stdin_compression` and `input_kv_store` of requests.
+/// - `2`: Programs uploaded by `UploadProgram` and selected by `program_digest` of requests.
+/// - `3`: Stdin streamed by `POST /inputs` and proofs by `GET /proofs/{job_id}`.
+/// - `4`: Stdin read from a file in `--input-dir` by `input_stdin_file` of requests.
+/// - `5`: Reports encoded in JSON if requested by `report_encoding` of requests, otherwise in
+///   `bincode` in the layout of version `0`, so fields can be added to reports without breaking
+///   the other side.
+pub const PROTOCOL_VERSION: u32 = 5;
@@ -0,0 +1,113 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, Error};
+use parking_lot::RwLock;
+use tokio::signal::unix::{SignalKind, signal};
+use tracing::{info, warn};
+use twirp::{
+    axum::{
+        extract::{Request, State},
+        http::header::AUTHORIZATION,
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    unauthenticated,
+};
+
+/// Bearer tokens accepted by the API, either a fixed one or ones read from a file (one per line),
+/// which is re-read on `SIGHUP` to rotate tokens without restarting.
+///
+/// A file can list both the old and new tokens while clients switch over.
+#[derive(Clone)]
+pub struct AuthTokens {
+    tokens: Arc<RwLock<Vec<String>>>,
+    path: Option<PathBuf>,
+}
+
+impl AuthTokens {
+    pub fn new(token: String) -> Self {
+        Self {
+            tokens: Arc::new(RwLock::new(vec![token])),
+            path: None,
+        }
+    }
+
+    pub fn from_file(path: PathBuf) -> Result<Self, Error> {
+        let tokens = read_tokens(&path)?;
+        Ok(Self {
+            tokens: Arc::new(RwLock::new(tokens)),
+            path: Some(path),
+        })
+    }
+
+    /// Re-reads the tokens from the file, and keeps the current ones if it fails.
+    fn reload(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        *self.tokens.write() = read_tokens(path)?;
+        Ok(())
+    }
+
+    /// Returns `true` if `token` equals any of the tokens, compared in constant time.
+    fn accepts(&self, token: &str) -> bool {
+        self.tokens.read().iter().fold(false, |accepted, expected| {
+            accepted | constant_time_eq(token.as_bytes(), expected.as_bytes())
+        })
+    }
+}
+
+fn read_tokens(path: &Path) -> Result<Vec<String>, Error> {
+    let tokens = fs::read_to_string(path)
+        .with_context(|| format!("failed to read auth tokens from {}", path.display()))?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    anyhow::ensure!(!tokens.is_empty(), "no auth token in {}", path.display());
+    Ok(tokens)
+}
+
+/// Compares without short-circuiting on the first differing byte, so the time taken doesn't
+/// reveal how much of a guessed token is correct.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+pub fn spawn_reload_on_sighup(tokens: AuthTokens) {
+    if tokens.path.is_none() {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut sighup = signal(SignalKind::hangup()).expect("SIGHUP should be enabled");
+        while sighup.recv().await.is_some() {
+            match tokens.reload() {
+                Ok(()) => info!("reloaded auth tokens"),
+                Err(err) => warn!("failed to reload auth tokens, keep the current ones: {err:#}"),
+            }
+        }
+    });
+}
+
+/// Rejects requests without `Authorization: Bearer {token}` of an accepted token.
+pub async fn middleware(
+    State(tokens): State<AuthTokens>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| tokens.accepts(token));
+    if !authorized {
+        return unauthenticated("missing or invalid bearer token").into_response();
+    }
+    next.run(request).await
+}
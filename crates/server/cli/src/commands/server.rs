@@ -1,58 +1,176 @@
 use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    ffi::OsStr,
+    fs,
+    io::{self, Read, Write},
     net::{Ipv4Addr, SocketAddr},
-    sync::Arc,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
     time::{Duration, Instant},
 };
 
 use anyhow::{Context, Error};
 use ere_compiler_core::Elf;
 use ere_prover_core::{
-    Input, ProgramExecutionReport, ProgramProvingReport, Proof, ProverResource, PublicValues,
+    CommonError, ErrorKind, ExecuteOptions, Input, ProgramExecutionReport, ProgramProvingReport,
+    Proof, ProverResource, PublicValues, ReportEncoding, SelfTestReport,
     codec::{Decode, Encode},
-    zkVMProver,
+    zkVMError, zkVMProver,
 };
 use ere_server_api::{
-    ExecuteOk, ExecuteRequest, ExecuteResponse, ProgramVkOk, ProgramVkRequest, ProgramVkResponse,
-    ProveOk, ProveRequest, ProveResponse, VerifyOk, VerifyRequest, VerifyResponse, ZkvmService,
-    execute_response::Result as ExecuteResult, program_vk_response::Result as ProgramVkResult,
-    prove_response::Result as ProveResult, router, verify_response::Result as VerifyResult,
+    CRATE_VERSION, Compression as ApiCompression, Error as ApiError, ExecuteOk, ExecuteRequest,
+    ExecuteResponse, HandshakeRequest, HandshakeResponse, JobState, JobStatusRequest,
+    JobStatusResponse, PROTOCOL_VERSION, ProgramVkOk, ProgramVkRequest, ProgramVkResponse, ProveOk,
+    ProveRequest, ProveResponse, ReportEncoding as ApiReportEncoding, ResumeProveRequest,
+    SubmitProveResponse, UploadProgramOk, UploadProgramRequest, UploadProgramResponse, VerifyOk,
+    VerifyRequest, VerifyResponse, ZkvmService, execute_response::Result as ExecuteResult,
+    program_vk_response::Result as ProgramVkResult, prove_response::Result as ProveResult, router,
+    upload_program_response::Result as UploadProgramResult,
+    verify_response::Result as VerifyResult,
 };
-use parking_lot::Mutex;
+use futures_util::{
+    StreamExt,
+    future::{AbortHandle, Abortable, Aborted},
+    stream,
+};
+use parking_lot::{Mutex, RwLock};
 use tokio::{
     net::TcpListener,
     signal::unix::{SignalKind, signal},
-    sync::Semaphore,
+    sync::{Semaphore, watch},
 };
 use tower::ServiceBuilder;
 use tower_http::{catch_panic::CatchPanicLayer, trace::TraceLayer};
-use tracing::info;
+use tracing::{Instrument, Span, info, info_span, warn};
 use twirp::{
     Request, Response, Router, TwirpErrorResponse,
     async_trait::async_trait,
-    axum::{self, extract::State, middleware, routing::get},
-    internal, invalid_argument,
+    axum::{
+        self,
+        body::{Body, Bytes},
+        extract::{self, State},
+        http::{
+            HeaderMap,
+            header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE},
+        },
+        middleware,
+        response::IntoResponse,
+        routing::{get, post},
+    },
+    failed_precondition, internal, invalid_argument, not_found,
     reqwest::StatusCode,
+    resource_exhausted,
     server::not_found_handler,
 };
 
-use crate::{metrics, otel};
+use crate::{
+    auth::{self, AuthTokens},
+    job_store::{JobRecord, JobStore},
+    metrics, otel,
+};
+
+/// Configuration of the server besides the program and the prover resource.
+pub struct ServerConfig {
+    pub port: u16,
+    /// Port to serve `/metrics` on in addition to `port`, e.g. to be published for scraping
+    /// without exposing the API.
+    pub metrics_port: Option<u16>,
+    /// Tokens required by the API, which is open to anyone if `None`.
+    pub auth_tokens: Option<AuthTokens>,
+    /// Directory to persist prove jobs in, to resume them after the server restarts.
+    pub job_dir: Option<PathBuf>,
+    /// Directory to read the stdin written by clients as files from.
+    pub input_dir: Option<PathBuf>,
+    pub prove_timeout: Option<Duration>,
+    pub verify_threads: NonZeroUsize,
+    pub max_prove_jobs: NonZeroUsize,
+    /// Maximum number of programs served, including the one the server started with.
+    pub max_programs: NonZeroUsize,
+    pub warmup: bool,
+    pub selftest: Option<SelfTestConfig>,
+}
+
+pub async fn run(elf: Elf, resource: ProverResource, config: ServerConfig) -> Result<(), Error> {
+    let ServerConfig {
+        port,
+        metrics_port,
+        auth_tokens,
+        job_dir,
+        input_dir,
+        prove_timeout,
+        verify_threads,
+        max_prove_jobs,
+        max_programs,
+        warmup,
+        selftest,
+    } = config;
 
-pub async fn run(
-    port: u16,
-    elf: Elf,
-    resource: ProverResource,
-    prove_timeout: Option<Duration>,
-) -> Result<(), Error> {
     let resource_kind = resource.kind();
-    let zkvm = crate::construct_zkvm(elf, resource)?;
+    let gpu = resource.is_gpu();
+    let job_store = job_dir.map(JobStore::open).transpose()?;
+    let program_digest = elf.digest();
+    let zkvm = crate::construct_zkvm(elf, resource.clone())?;
     info!("initialized zkVMProver with {resource_kind} prover");
 
+    if warmup {
+        let start = Instant::now();
+        zkvm.warmup().context("failed to warm up zkVMProver")?;
+        info!("warmed up zkVMProver in {:?}", start.elapsed());
+    }
+
     let metrics_handle = metrics::init(zkvm.name(), zkvm.sdk_version())
         .context("failed to install metrics recorder")?;
     metrics::spawn_upkeep(metrics_handle.clone());
+    if gpu {
+        metrics::spawn_gpu_memory_sampler();
+    }
 
     let prove_state = Arc::new(ProveState::new(prove_timeout));
-    let server = Arc::new(zkVMServer::new(zkvm, Arc::clone(&prove_state)));
+    let programs = Programs::new(
+        program_digest,
+        zkvm,
+        max_programs,
+        Arc::new(move |elf: Elf| crate::construct_zkvm(elf, resource.clone())),
+    );
+    let server = Arc::new(zkVMServer::new(
+        programs,
+        Arc::clone(&prove_state),
+        verify_threads,
+        max_prove_jobs,
+        job_store,
+        input_dir,
+    ));
+    info!("verifying up to {verify_threads} proofs concurrently");
+    info!("queueing up to {max_prove_jobs} prove jobs");
+    info!("serving up to {max_programs} programs");
+    server
+        .restore_prove_jobs(program_digest)
+        .context("failed to restore prove jobs")?;
+
+    if let Some(selftest) = selftest {
+        info!("running self-test every {:?}", selftest.interval);
+        spawn_selftest(Arc::clone(&server), Arc::clone(&prove_state), selftest);
+    }
+
+    if let Some(metrics_port) = metrics_port {
+        let metrics_app = Router::new().route(
+            "/metrics",
+            get(metrics::handler).with_state(metrics_handle.clone()),
+        );
+        let addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), metrics_port);
+        let tcp_listener = TcpListener::bind(addr).await?;
+        info!("serving metrics on {}", addr);
+        tokio::spawn(async move {
+            if let Err(err) = axum::serve(tcp_listener, metrics_app).await {
+                warn!("metrics listener failed: {err}");
+            }
+        });
+    }
 
     let api_middleware = ServiceBuilder::new()
         .layer(
@@ -66,8 +184,17 @@ pub async fn run(
         .layer(middleware::from_fn(metrics::middleware))
         .layer(CatchPanicLayer::new());
 
-    let app = Router::new()
-        .nest("/twirp", router(server))
+    let api = api_router(server);
+    let api = match auth_tokens {
+        Some(tokens) => {
+            info!("requiring bearer token for the API");
+            auth::spawn_reload_on_sighup(tokens.clone());
+            api.layer(middleware::from_fn_with_state(tokens, auth::middleware))
+        }
+        None => api,
+    };
+
+    let app = api
         .fallback(not_found_handler)
         .layer(api_middleware)
         .route("/metrics", get(metrics::handler).with_state(metrics_handle))
@@ -87,12 +214,21 @@ pub async fn run(
     Ok(())
 }
 
+/// Configuration of the periodic self-test, see [`zkVMProver::selftest`].
+pub struct SelfTestConfig {
+    pub interval: Duration,
+    pub input: Input,
+}
+
 /// Shared state for the prove endpoint. Holds when the currently-running prove started and the
 /// prove timeout above which `/health` reports the server unhealthy. A `None` started timestamp
 /// means no prove is in flight. `is_timeout` is always `false` when no timeout is configured.
+///
+/// It also holds whether the last self-test failed, which reports the server unhealthy as well.
 pub struct ProveState {
     started_at: Mutex<Option<Instant>>,
     prove_timeout: Option<Duration>,
+    selftest_failed: AtomicBool,
 }
 
 impl ProveState {
@@ -100,9 +236,15 @@ impl ProveState {
         Self {
             started_at: Mutex::new(None),
             prove_timeout,
+            selftest_failed: AtomicBool::new(false),
         }
     }
 
+    /// Returns `true` if the last self-test failed.
+    pub fn is_selftest_failed(&self) -> bool {
+        self.selftest_failed.load(Ordering::Relaxed)
+    }
+
     /// Returns `true` if a prove has been running longer than the configured timeout.
     pub fn is_timeout(&self) -> bool {
         let Some(timeout) = self.prove_timeout else {
@@ -133,70 +275,601 @@ impl Drop for ProveInFlight {
     }
 }
 
+/// Receiver of the result of a [`ProveJob`], which is `None` until the job finishes.
+type ProveResultReceiver = watch::Receiver<Option<twirp::Result<Result<ProveOk, ApiError>>>>;
+
+/// Aborts a [`ProveJob`] on drop, which ties the job to the request waiting for it.
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Prove job. A job submitted by `SubmitProve` keeps running after the requesting client
+/// disconnects (e.g. the host process crashed), so a new client can collect the result via
+/// `ResumeProve` instead of proving again. A job requested by `Prove` is aborted when its client
+/// disconnects before it finishes.
+///
+/// Jobs are identified by ids unique within the server process, and by the input commitment for
+/// clients of protocol version 0.
+struct ProveJob {
+    id: u64,
+    program_digest: [u8; 32],
+    input_commitment: [u8; 32],
+    started: Arc<AtomicBool>,
+    result: ProveResultReceiver,
+}
+
+impl ProveJob {
+    fn is_finished(&self) -> bool {
+        self.result.borrow().is_some()
+    }
+
+    /// Returns the state of the job, and the error if it failed.
+    fn state<T: zkVMProver>(&self) -> twirp::Result<(JobState, Option<ApiError>)> {
+        Ok(match &*self.result.borrow() {
+            None if self.started.load(Ordering::Relaxed) => (JobState::Proving, None),
+            None => (JobState::Queued, None),
+            Some(Ok(Ok(_))) => (JobState::Done, None),
+            Some(Ok(Err(err))) => (JobState::Failed, Some(err.clone())),
+            Some(Err(err)) => (
+                JobState::Failed,
+                Some(api_error::<T>(anyhow::anyhow!("{err}"))?),
+            ),
+        })
+    }
+}
+
+/// Maximum number of times a persisted prove job is started, after which it's failed instead of
+/// restored again, e.g. when proving it crashes the server.
+const MAX_PROVE_ATTEMPTS: u32 = 3;
+
+/// Size of the chunks a proof is streamed in by `GET /proofs/{job_id}`.
+const PROOF_CHUNK_SIZE: usize = 1 << 20;
+
+/// Maximum length of a stdin once decompressed, which bounds the memory a request takes, e.g. one
+/// with a small zstd frame decompressing to far more bytes.
+const MAX_STDIN_LEN: usize = 4 << 30;
+
+/// Constructs the [`zkVMProver`] of an uploaded program.
+type ConstructZkvm<T> = Arc<dyn Fn(Elf) -> Result<T, Error> + Send + Sync>;
+
+/// Programs served, keyed by the digest of their ELF (see [`Elf::digest`]). The default one is
+/// the program the server started with, which requests select by an empty digest.
+pub struct Programs<T> {
+    default: [u8; 32],
+    zkvms: RwLock<HashMap<[u8; 32], Arc<T>>>,
+    max_programs: usize,
+    construct: ConstructZkvm<T>,
+}
+
+impl<T: 'static + zkVMProver + Send + Sync> Programs<T> {
+    pub fn new(
+        default: [u8; 32],
+        zkvm: T,
+        max_programs: NonZeroUsize,
+        construct: ConstructZkvm<T>,
+    ) -> Self {
+        Self {
+            default,
+            zkvms: RwLock::new(HashMap::from([(default, Arc::new(zkvm))])),
+            max_programs: max_programs.get(),
+            construct,
+        }
+    }
+
+    fn default_zkvm(&self) -> Arc<T> {
+        Arc::clone(&self.zkvms.read()[&self.default])
+    }
+
+    /// Returns the digest and the zkVM of the program selected by `program_digest` of a request.
+    fn get(&self, program_digest: &[u8]) -> twirp::Result<([u8; 32], Arc<T>)> {
+        let digest = if program_digest.is_empty() {
+            self.default
+        } else {
+            program_digest
+                .try_into()
+                .map_err(|_| invalid_argument("program digest should be 32 bytes"))?
+        };
+        let zkvm = self
+            .zkvms
+            .read()
+            .get(&digest)
+            .cloned()
+            .ok_or_else(|| not_found("no such program, upload it first"))?;
+        Ok((digest, zkvm))
+    }
+}
+
 /// zkVMProver server that handles the request by forwarding to the underlying [`zkVMProver`]
 /// implementation methods.
 ///
+/// Besides the program it started with, programs uploaded by `UploadProgram` are served, up to
+/// `max_programs`, and each request selects one by its digest. All programs share the gates
+/// below, e.g. only one prove runs at a time regardless of the program.
+///
 /// `prove` is gated by a binary [`Semaphore`] so only one prove runs at a time. Requests queue in
-/// FIFO order, dropping a request future before the permit is acquired removes that waiter from
-/// the queue.
+/// FIFO order, so a client can pipeline the next prove (and execute, which is not gated) while the
+/// previous prove runs. Each prove runs as a [`ProveJob`] with its own id and result, which can be
+/// submitted by `SubmitProve` without waiting, polled by `JobStatus` and collected by
+/// `ResumeProve`. A job of `Prove` is aborted when its client disconnects, and any job fails once
+/// proving takes longer than the prove timeout. At most `max_prove_jobs` unfinished jobs are
+/// accepted, further prove requests are rejected as resource exhausted. Up to `max_prove_jobs` jobs
+/// are kept to be resumed, finished ones are evicted oldest first.
+///
+/// Stdins streamed by `POST /inputs` are kept until consumed by the request given their upload id,
+/// up to `max_prove_jobs` of them, older ones are evicted first.
+///
+/// `verify` is gated by a [`Semaphore`] with `verify_threads` permits, so at most that many
+/// verifications run concurrently on the blocking thread pool and the rest queue in FIFO order.
 ///
 /// `execute` and `verify` are assumed concurrent-safe for the underlying implementation.
 #[allow(non_camel_case_types)]
 pub struct zkVMServer<T> {
-    zkvm: Arc<T>,
+    programs: Arc<Programs<T>>,
     prove_sem: Arc<Semaphore>,
     prove_state: Arc<ProveState>,
+    prove_jobs: Arc<Mutex<VecDeque<ProveJob>>>,
+    max_prove_jobs: usize,
+    next_job_id: Arc<AtomicU64>,
+    job_store: Option<Arc<JobStore>>,
+    input_dir: Option<PathBuf>,
+    uploaded_inputs: Arc<Mutex<VecDeque<(u64, Vec<u8>)>>>,
+    next_upload_id: Arc<AtomicU64>,
+    verify_sem: Arc<Semaphore>,
+}
+
+impl<T> Clone for zkVMServer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            programs: Arc::clone(&self.programs),
+            prove_sem: Arc::clone(&self.prove_sem),
+            prove_state: Arc::clone(&self.prove_state),
+            prove_jobs: Arc::clone(&self.prove_jobs),
+            max_prove_jobs: self.max_prove_jobs,
+            next_job_id: Arc::clone(&self.next_job_id),
+            job_store: self.job_store.clone(),
+            input_dir: self.input_dir.clone(),
+            uploaded_inputs: Arc::clone(&self.uploaded_inputs),
+            next_upload_id: Arc::clone(&self.next_upload_id),
+            verify_sem: Arc::clone(&self.verify_sem),
+        }
+    }
 }
 
 impl<T: 'static + zkVMProver + Send + Sync> zkVMServer<T> {
-    pub fn new(zkvm: T, prove_state: Arc<ProveState>) -> Self {
+    pub fn new(
+        programs: Programs<T>,
+        prove_state: Arc<ProveState>,
+        verify_threads: NonZeroUsize,
+        max_prove_jobs: NonZeroUsize,
+        job_store: Option<JobStore>,
+        input_dir: Option<PathBuf>,
+    ) -> Self {
         Self {
-            zkvm: Arc::new(zkvm),
+            programs: Arc::new(programs),
             prove_sem: Arc::new(Semaphore::new(1)),
             prove_state,
+            prove_jobs: Arc::new(Mutex::new(VecDeque::new())),
+            max_prove_jobs: max_prove_jobs.get(),
+            next_job_id: Arc::new(AtomicU64::new(1)),
+            job_store: job_store.map(Arc::new),
+            input_dir,
+            uploaded_inputs: Arc::new(Mutex::new(VecDeque::new())),
+            next_upload_id: Arc::new(AtomicU64::new(1)),
+            verify_sem: Arc::new(Semaphore::new(verify_threads.get())),
         }
     }
 
     async fn execute(
         &self,
+        zkvm: Arc<T>,
         input: Input,
+        options: ExecuteOptions,
     ) -> anyhow::Result<(PublicValues, ProgramExecutionReport)> {
-        let zkvm = Arc::clone(&self.zkvm);
-        tokio::task::spawn_blocking(move || Ok(zkvm.execute(&input)?))
+        tokio::task::spawn_blocking(move || Ok(zkvm.execute_with_options(&input, &options)?))
             .await
             .context("execute panicked")?
     }
 
+    /// Proves once the prove [`Semaphore`] is acquired, which is when `started` is set, and fails
+    /// if proving takes longer than the prove timeout if any.
+    ///
+    /// The zkVM can't be interrupted, so when this is dropped (the job is aborted) or times out
+    /// while proving, the prove keeps the [`Semaphore`] until the zkVM returns and its result is
+    /// discarded. Dropped while queued, the [`Semaphore`] is never acquired.
     async fn prove(
         &self,
+        zkvm: Arc<T>,
         input: Input,
+        started: Arc<AtomicBool>,
     ) -> anyhow::Result<(PublicValues, Proof<T>, ProgramProvingReport)> {
+        let queued = Instant::now();
         let permit = Arc::clone(&self.prove_sem)
             .acquire_owned()
             .await
             .context("prove semaphore closed unexpectedly")?;
+        metrics::record_prove_queue_duration(queued.elapsed());
 
-        let zkvm = Arc::clone(&self.zkvm);
         let prove_state = Arc::clone(&self.prove_state);
-        tokio::task::spawn_blocking(move || {
+        let prove_timeout = prove_state.prove_timeout;
+        // Entered on the blocking thread, so the logs of the zkVM carry the job.
+        let span = Span::current();
+        let start = Instant::now();
+        let task = tokio::task::spawn_blocking(move || {
+            let _span = span.enter();
             let _permit = permit;
             let _in_flight = ProveInFlight::new(prove_state);
+            started.store(true, Ordering::Relaxed);
+            metrics::record_prove_started();
             Ok(zkvm.prove(&input)?)
+        });
+        let result = match prove_timeout {
+            Some(prove_timeout) => tokio::time::timeout(prove_timeout, task)
+                .await
+                .map_err(|_| anyhow::anyhow!("prove timed out after {prove_timeout:?}"))
+                .and_then(|result| result.context("prove panicked")),
+            None => task.await.context("prove panicked"),
+        }
+        .and_then(|result| result);
+        metrics::record_prove(&result, start.elapsed());
+        result
+    }
+
+    async fn verify(&self, zkvm: Arc<T>, proof: Proof<T>) -> anyhow::Result<PublicValues> {
+        let permit = {
+            let _queued = metrics::VerifyQueuedGuard::new();
+            let start = Instant::now();
+            let permit = Arc::clone(&self.verify_sem)
+                .acquire_owned()
+                .await
+                .context("verify semaphore closed unexpectedly")?;
+            metrics::record_verify_queue_duration(start.elapsed());
+            permit
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            Ok(zkvm.verify(&proof)?)
         })
         .await
-        .context("prove panicked")?
+        .context("verify panicked")?
+    }
+
+    /// Accepts the prove job if there are less than `max_prove_jobs` unfinished jobs, and evicts
+    /// the oldest finished jobs to keep at most `max_prove_jobs` jobs.
+    fn push_prove_job(&self, job: ProveJob) -> twirp::Result<()> {
+        let mut jobs = self.prove_jobs.lock();
+        let unfinished = jobs.iter().filter(|job| !job.is_finished()).count();
+        if !job.is_finished() && unfinished >= self.max_prove_jobs {
+            return Err(resource_exhausted(format!(
+                "{unfinished} prove jobs are queued or running, retry later"
+            )));
+        }
+        jobs.push_back(job);
+        while jobs.len() > self.max_prove_jobs {
+            let Some(idx) = jobs.iter().position(ProveJob::is_finished) else {
+                break;
+            };
+            if let (Some(job), Some(store)) = (jobs.remove(idx), &self.job_store) {
+                store.remove(&job.program_digest, job.id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps the stdin streamed by `POST /inputs` until consumed, and returns its upload id.
+    fn push_uploaded_input(&self, stdin: Vec<u8>) -> u64 {
+        let id = self.next_upload_id.fetch_add(1, Ordering::Relaxed);
+        let mut inputs = self.uploaded_inputs.lock();
+        inputs.push_back((id, stdin));
+        while inputs.len() > self.max_prove_jobs {
+            inputs.pop_front();
+        }
+        id
+    }
+
+    /// Removes and returns the stdin uploaded with `upload_id`.
+    fn take_uploaded_input(&self, upload_id: u64) -> twirp::Result<Vec<u8>> {
+        let mut inputs = self.uploaded_inputs.lock();
+        let idx = inputs
+            .iter()
+            .position(|(id, _)| *id == upload_id)
+            .ok_or_else(|| not_found("no such uploaded input, upload it again"))?;
+        Ok(inputs.remove(idx).expect("index is valid").1)
     }
 
-    async fn verify(&self, proof: Proof<T>) -> anyhow::Result<PublicValues> {
-        let zkvm = Arc::clone(&self.zkvm);
-        tokio::task::spawn_blocking(move || Ok(zkvm.verify(&proof)?))
+    /// Reads and removes the stdin written by the client as `file` in the input directory.
+    async fn read_input_file(&self, file: String) -> twirp::Result<Vec<u8>> {
+        let dir = self
+            .input_dir
+            .as_ref()
+            .ok_or_else(|| failed_precondition("server is started without --input-dir"))?;
+        if Path::new(&file).file_name() != Some(OsStr::new(&file)) {
+            return Err(invalid_argument(
+                "input stdin file should be a file name in the input directory",
+            ));
+        }
+        let path = dir.join(file);
+        tokio::task::spawn_blocking(move || {
+            let stdin = fs::read(&path);
+            if let Err(err) = fs::remove_file(&path) {
+                warn!("failed to remove input file {}: {err}", path.display());
+            }
+            stdin
+        })
+        .await
+        .map_err(|err| internal(format!("reading input file panicked: {err}")))?
+        .map_err(|err| invalid_argument(format!("failed to read input stdin file: {err}")))
+    }
+
+    /// Replaces the stdin of the request by the one in its `input_stdin_file` if any.
+    async fn read_prove_input_file(
+        &self,
+        mut request: ProveRequest,
+    ) -> twirp::Result<ProveRequest> {
+        if let Some(file) = request.input_stdin_file.take() {
+            request.input_stdin = self.read_input_file(file).await?;
+            request.input_stdin_compression = ApiCompression::None.into();
+        }
+        Ok(request)
+    }
+
+    /// Spawns a [`ProveJob`] of the request, and returns its id, result and abort handle.
+    fn spawn_prove_job(
+        &self,
+        request: ProveRequest,
+    ) -> twirp::Result<(u64, ProveResultReceiver, AbortHandle)> {
+        let id = self.next_job_id.fetch_add(1, Ordering::Relaxed);
+        let (result, abort) = self.start_prove_job(id, request, 1)?;
+        Ok((id, result, abort))
+    }
+
+    /// Starts the [`ProveJob`] `id` of the request for the `attempts`-th time, which is persisted
+    /// in the [`JobStore`] if any, and returns its result and the handle to abort it, which fails
+    /// the job as cancelled.
+    fn start_prove_job(
+        &self,
+        id: u64,
+        mut request: ProveRequest,
+        attempts: u32,
+    ) -> twirp::Result<(ProveResultReceiver, AbortHandle)> {
+        let (program_digest, zkvm) = self.programs.get(&request.program_digest)?;
+        request.program_digest = program_digest.to_vec();
+        // Persisted with the stdin so the job can be restored after the upload is gone.
+        if let Some(upload_id) = request.input_upload_id.take() {
+            request.input_stdin = self.take_uploaded_input(upload_id)?;
+            request.input_stdin_compression = ApiCompression::None.into();
+        }
+        let record = self.job_store.as_ref().map(|_| JobRecord {
+            id,
+            program_digest,
+            request: request.clone(),
+            result: None,
+            attempts,
+        });
+
+        let input = prove_input(request)?;
+        metrics::record_input_bytes("prove", &input);
+
+        let started = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = watch::channel(None);
+        self.push_prove_job(ProveJob {
+            id,
+            program_digest,
+            input_commitment: input.commitment(),
+            started: Arc::clone(&started),
+            result: rx.clone(),
+        })?;
+        self.persist_prove_job(record.as_ref());
+
+        let (abort, registration) = AbortHandle::new_pair();
+        let server = self.clone();
+        let span = info_span!("prove_job", job = id);
+        let job = async move {
+            let prove = server.prove(zkvm, input, started);
+            let result = Abortable::new(prove, registration)
+                .await
+                .unwrap_or_else(|Aborted| Err(anyhow::anyhow!("prove job cancelled")));
+            let result = encode_prove_result::<T>(result);
+            if let Some(mut record) = record {
+                record.result = persisted_prove_result::<T>(&result).ok();
+                server.persist_prove_job(Some(&record));
+            }
+            tx.send_replace(Some(result));
+        };
+        tokio::spawn(job.instrument(span));
+
+        Ok((rx, abort))
+    }
+
+    fn persist_prove_job(&self, record: Option<&JobRecord>) {
+        let (Some(store), Some(record)) = (&self.job_store, record) else {
+            return;
+        };
+        if let Err(err) = store.save(record) {
+            warn!("failed to persist prove job {}: {err:#}", record.id);
+        }
+    }
+
+    /// Restores the prove jobs of the program persisted in the [`JobStore`] if any. Finished jobs
+    /// are kept to be collected, and unfinished ones (queued or proving when the server stopped)
+    /// are proved again in the order submitted, with their ids unchanged. Jobs started
+    /// [`MAX_PROVE_ATTEMPTS`] times already, or failing to be restored, are failed instead.
+    ///
+    /// New jobs are assigned ids after all persisted ones, including jobs of programs not yet
+    /// uploaded again.
+    fn restore_prove_jobs(&self, program_digest: [u8; 32]) -> Result<(), Error> {
+        let Some(store) = &self.job_store else {
+            return Ok(());
+        };
+        let mut records = store.load()?;
+        for record in &records {
+            self.next_job_id.fetch_max(record.id + 1, Ordering::Relaxed);
+        }
+        records.retain(|record| record.program_digest == program_digest);
+        let restored = records.len();
+        let mut resumed = 0;
+        for mut record in records {
+            let id = record.id;
+            let outcome = match record.result.take() {
+                Some(result) => self.push_finished_prove_job(&record, result),
+                None if record.attempts >= MAX_PROVE_ATTEMPTS => Err(internal(format!(
+                    "server stopped before the job finished {} times",
+                    record.attempts
+                ))),
+                None => self
+                    .start_prove_job(id, record.request.clone(), record.attempts + 1)
+                    .map(|_| resumed += 1),
+            };
+            if let Err(err) = outcome {
+                warn!("failed to restore prove job {id}, failing it: {err}");
+                // Kept failed to be reported by `JobStatus`, instead of the job being not found.
+                let Ok(err) = api_error::<T>(anyhow::anyhow!("failed to restore prove job: {err}"))
+                else {
+                    continue;
+                };
+                let result = Err(err);
+                record.result = Some(result.clone());
+                if let Err(err) = self.push_finished_prove_job(&record, result) {
+                    warn!("failed to keep failed prove job {id}: {err}");
+                }
+                self.persist_prove_job(Some(&record));
+            }
+        }
+        info!("restored {restored} prove jobs, {resumed} of them to be proved again");
+        Ok(())
+    }
+
+    /// Keeps the restored job of `record` finished with `result`, to be collected.
+    fn push_finished_prove_job(
+        &self,
+        record: &JobRecord,
+        result: Result<ProveOk, ApiError>,
+    ) -> twirp::Result<()> {
+        // The input of a job failed to be restored might be invalid, in which case it can only be
+        // found by id.
+        let input_commitment = prove_input(record.request.clone())
+            .map(|input| input.commitment())
+            .unwrap_or_default();
+        let (_, rx) = watch::channel(Some(Ok(result)));
+        self.push_prove_job(ProveJob {
+            id: record.id,
+            program_digest: record.program_digest,
+            input_commitment,
+            started: Arc::new(AtomicBool::new(true)),
+            result: rx,
+        })
+    }
+
+    /// Serves the program of `elf` besides the ones served, and restores its persisted prove jobs.
+    /// Returns the zkVM already constructed if the program is already served.
+    async fn register_program(
+        &self,
+        elf: Elf,
+    ) -> twirp::Result<anyhow::Result<([u8; 32], Arc<T>)>> {
+        let digest = elf.digest();
+        {
+            let zkvms = self.programs.zkvms.read();
+            if let Some(zkvm) = zkvms.get(&digest) {
+                return Ok(Ok((digest, Arc::clone(zkvm))));
+            }
+            if zkvms.len() >= self.programs.max_programs {
+                return Err(programs_exhausted(zkvms.len()));
+            }
+        }
+
+        let construct = Arc::clone(&self.programs.construct);
+        let zkvm = match tokio::task::spawn_blocking(move || construct(elf)).await {
+            Ok(Ok(zkvm)) => Arc::new(zkvm),
+            Ok(Err(err)) => return Ok(Err(err)),
+            Err(err) => {
+                return Ok(Err(anyhow::anyhow!(
+                    "constructing zkVMProver panicked: {err}"
+                )));
+            }
+        };
+        {
+            // Checked again since other programs might be uploaded concurrently.
+            let mut zkvms = self.programs.zkvms.write();
+            if let Some(zkvm) = zkvms.get(&digest) {
+                return Ok(Ok((digest, Arc::clone(zkvm))));
+            }
+            if zkvms.len() >= self.programs.max_programs {
+                return Err(programs_exhausted(zkvms.len()));
+            }
+            zkvms.insert(digest, Arc::clone(&zkvm));
+        }
+        info!("serving uploaded program");
+
+        if let Err(err) = self.restore_prove_jobs(digest) {
+            warn!("failed to restore prove jobs of uploaded program: {err:#}");
+        }
+        Ok(Ok((digest, zkvm)))
+    }
+
+    /// Runs [`zkVMProver::selftest`], which is gated by the prove [`Semaphore`] to not run
+    /// concurrently with prove.
+    async fn selftest(&self, input: Input) -> anyhow::Result<SelfTestReport> {
+        let permit = Arc::clone(&self.prove_sem)
+            .acquire_owned()
             .await
-            .context("verify panicked")?
+            .context("prove semaphore closed unexpectedly")?;
+
+        let zkvm = self.programs.default_zkvm();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let report = zkvm.selftest(&input)?;
+            anyhow::ensure!(
+                report.public_values_consistent,
+                "inconsistent public values between execute, prove and verify"
+            );
+            Ok(report)
+        })
+        .await
+        .context("selftest panicked")?
     }
 }
 
+fn spawn_selftest<T: 'static + zkVMProver + Send + Sync>(
+    server: Arc<zkVMServer<T>>,
+    prove_state: Arc<ProveState>,
+    config: SelfTestConfig,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(config.interval).await;
+            let result = server.selftest(config.input.clone()).await;
+            metrics::record_selftest(&result);
+            match &result {
+                Ok(report) => info!("self-test passed: {report:?}"),
+                Err(err) => warn!("self-test failed: {err:#}"),
+            }
+            prove_state
+                .selftest_failed
+                .store(result.is_err(), Ordering::Relaxed);
+        }
+    });
+}
+
 #[async_trait]
 impl<T: 'static + zkVMProver + Send + Sync> ZkvmService for zkVMServer<T> {
+    async fn handshake(
+        &self,
+        _: Request<HandshakeRequest>,
+    ) -> twirp::Result<Response<HandshakeResponse>> {
+        // The client takes the lower version, so any client version is accepted.
+        let zkvm = self.programs.default_zkvm();
+        Ok(Response::new(HandshakeResponse {
+            protocol_version: PROTOCOL_VERSION,
+            crate_version: CRATE_VERSION.to_string(),
+            zkvm_name: zkvm.name().to_string(),
+            zkvm_sdk_version: zkvm.sdk_version().to_string(),
+        }))
+    }
+
     async fn execute(
         &self,
         request: Request<ExecuteRequest>,
@@ -204,61 +877,124 @@ impl<T: 'static + zkVMProver + Send + Sync> ZkvmService for zkVMServer<T> {
         let ExecuteRequest {
             input_stdin: stdin,
             input_proofs: proofs,
+            max_cycles,
+            input_stdin_compression,
+            input_kv_store: kv_store,
+            program_digest,
+            input_upload_id,
+            input_stdin_file,
+            report_encoding: encoding,
         } = request.into_body();
 
-        let input = Input { stdin, proofs };
+        let encoding = report_encoding(encoding)?;
+        let (_, zkvm) = self.programs.get(&program_digest)?;
+        let stdin = match (input_upload_id, input_stdin_file) {
+            (Some(upload_id), _) => self.take_uploaded_input(upload_id)?,
+            (None, Some(file)) => self.read_input_file(file).await?,
+            (None, None) => decompress_stdin(stdin, input_stdin_compression, MAX_STDIN_LEN)?,
+        };
+        let input = Input {
+            stdin,
+            proofs,
+            kv_store,
+            ..Default::default()
+        };
+        metrics::record_input_bytes("execute", &input);
+        // Profiling is native only, see `ExecuteOptions::profile`.
+        let options = ExecuteOptions {
+            max_cycles,
+            ..Default::default()
+        };
 
         let start = Instant::now();
-        let result = self.execute(input).await;
+        let result = self.execute(zkvm, input, options).await;
         metrics::record_execute(&result, start.elapsed());
 
         let result = match result {
-            Ok((public_values, report)) => ExecuteResult::Ok(ExecuteOk {
+            Ok((public_values, report)) => Ok(ExecuteOk {
                 public_values: public_values.into(),
-                report: bincode::serde::encode_to_vec(&report, bincode::config::legacy())
-                    .map_err(serialize_report_err)?,
+                report: report.encode(encoding).map_err(serialize_report_err)?,
             }),
-            Err(err) => ExecuteResult::Err(err.to_string()),
+            Err(err) => Err(api_error::<T>(err)?),
         };
+        let (result, error) = response_result(result, ExecuteResult::Ok, ExecuteResult::Err);
 
-        Ok(Response::new(ExecuteResponse {
-            result: Some(result),
-        }))
+        Ok(Response::new(ExecuteResponse { result, error }))
     }
 
     async fn prove(
         &self,
         request: Request<ProveRequest>,
     ) -> twirp::Result<Response<ProveResponse>> {
-        let ProveRequest {
-            input_stdin: stdin,
-            input_proofs: proofs,
-        } = request.into_body();
+        let request = self.read_prove_input_file(request.into_body()).await?;
+        let encoding = report_encoding(request.report_encoding)?;
+        let (job_id, result, abort) = self.spawn_prove_job(request)?;
+        // Dropped with this future when the client disconnects before the job finishes.
+        let _abort = AbortOnDrop(abort);
+        wait_prove_result(job_id, result, false, encoding).await
+    }
 
-        let input = Input { stdin, proofs };
+    async fn submit_prove(
+        &self,
+        request: Request<ProveRequest>,
+    ) -> twirp::Result<Response<SubmitProveResponse>> {
+        let request = self.read_prove_input_file(request.into_body()).await?;
+        let (job_id, ..) = self.spawn_prove_job(request)?;
+        Ok(Response::new(SubmitProveResponse { job_id }))
+    }
 
-        let start = Instant::now();
-        let result = self.prove(input).await;
-        metrics::record_prove(&result, start.elapsed());
+    async fn resume_prove(
+        &self,
+        request: Request<ResumeProveRequest>,
+    ) -> twirp::Result<Response<ProveResponse>> {
+        let ResumeProveRequest {
+            input_commitment,
+            job_id,
+            omit_proof,
+            report_encoding: encoding,
+            program_digest,
+        } = request.into_body();
 
-        let result = match result {
-            Ok((public_values, proof, report)) => {
-                let proof = proof
-                    .encode_to_vec()
-                    .map_err(|err| internal(format!("failed to encode proof: {err:?}")))?;
-                metrics::record_prove_proof_bytes(proof.len());
-                ProveResult::Ok(ProveOk {
-                    public_values: public_values.into(),
-                    proof,
-                    report: bincode::serde::encode_to_vec(&report, bincode::config::legacy())
-                        .map_err(serialize_report_err)?,
-                })
-            }
-            Err(err) => ProveResult::Err(err.to_string()),
+        let encoding = report_encoding(encoding)?;
+        // Jobs of other programs might have the same input.
+        let program_digest = match job_id {
+            Some(_) => None,
+            None => Some(self.programs.get(&program_digest)?.0),
+        };
+        let (job_id, result) = {
+            let jobs = self.prove_jobs.lock();
+            let job = match job_id {
+                Some(job_id) => jobs.iter().find(|job| job.id == job_id),
+                None => jobs.iter().rev().find(|job| {
+                    Some(job.program_digest) == program_digest
+                        && job.input_commitment[..] == input_commitment[..]
+                }),
+            };
+            let job = job.ok_or_else(|| not_found("no such prove job"))?;
+            (job.id, job.result.clone())
         };
 
-        Ok(Response::new(ProveResponse {
-            result: Some(result),
+        wait_prove_result(job_id, result, omit_proof, encoding).await
+    }
+
+    async fn job_status(
+        &self,
+        request: Request<JobStatusRequest>,
+    ) -> twirp::Result<Response<JobStatusResponse>> {
+        let JobStatusRequest { job_id } = request.into_body();
+
+        let (state, err) = self
+            .prove_jobs
+            .lock()
+            .iter()
+            .find(|job| job.id == job_id)
+            .ok_or_else(|| not_found("no such prove job"))?
+            .state::<T>()?;
+
+        Ok(Response::new(JobStatusResponse {
+            state: state.into(),
+            err: err.as_ref().map(|err| err.message.clone()),
+            error: err,
         }))
     }
 
@@ -268,42 +1004,73 @@ impl<T: 'static + zkVMProver + Send + Sync> ZkvmService for zkVMServer<T> {
     ) -> twirp::Result<Response<VerifyResponse>> {
         let request = request.into_body();
 
+        let (_, zkvm) = self.programs.get(&request.program_digest)?;
         let proof = Proof::<T>::decode_from_slice(&request.proof)
             .map_err(|err| invalid_argument(format!("failed to decode proof: {err:?}")))?;
 
         let start = Instant::now();
-        let result = self.verify(proof).await;
+        let result = self.verify(zkvm, proof).await;
         metrics::record_verify(&result, start.elapsed());
 
         let result = match result {
-            Ok(public_values) => VerifyResult::Ok(VerifyOk {
+            Ok(public_values) => Ok(VerifyOk {
                 public_values: public_values.into(),
             }),
-            Err(err) => VerifyResult::Err(err.to_string()),
+            Err(err) => Err(api_error::<T>(err)?),
         };
+        let (result, error) = response_result(result, VerifyResult::Ok, VerifyResult::Err);
 
-        Ok(Response::new(VerifyResponse {
-            result: Some(result),
-        }))
+        Ok(Response::new(VerifyResponse { result, error }))
     }
 
     async fn program_vk(
         &self,
-        _: Request<ProgramVkRequest>,
+        request: Request<ProgramVkRequest>,
     ) -> twirp::Result<Response<ProgramVkResponse>> {
-        let result = match self.zkvm.program_vk().encode_to_vec() {
-            Ok(program_vk) => ProgramVkResult::Ok(ProgramVkOk { program_vk }),
-            Err(err) => ProgramVkResult::Err(format!("failed to encode program_vk: {err:?}")),
+        let ProgramVkRequest { program_digest } = request.into_body();
+
+        let (_, zkvm) = self.programs.get(&program_digest)?;
+        let result = match zkvm.program_vk().encode_to_vec() {
+            Ok(program_vk) => Ok(ProgramVkOk { program_vk }),
+            Err(err) => Err(api_error::<T>(anyhow::anyhow!(
+                "failed to encode program_vk: {err:?}"
+            ))?),
         };
+        let (result, error) = response_result(result, ProgramVkResult::Ok, ProgramVkResult::Err);
 
-        Ok(Response::new(ProgramVkResponse {
-            result: Some(result),
-        }))
+        Ok(Response::new(ProgramVkResponse { result, error }))
+    }
+
+    async fn upload_program(
+        &self,
+        request: Request<UploadProgramRequest>,
+    ) -> twirp::Result<Response<UploadProgramResponse>> {
+        let UploadProgramRequest { elf } = request.into_body();
+
+        let elf = Elf::from_maybe_compressed(elf)
+            .map_err(|err| invalid_argument(format!("failed to decompress ELF: {err}")))?;
+
+        let result = match self.register_program(elf).await? {
+            Ok((program_digest, zkvm)) => match zkvm.program_vk().encode_to_vec() {
+                Ok(program_vk) => Ok(UploadProgramOk {
+                    program_digest: program_digest.to_vec(),
+                    program_vk,
+                }),
+                Err(err) => Err(api_error::<T>(anyhow::anyhow!(
+                    "failed to encode program_vk: {err:?}"
+                ))?),
+            },
+            Err(err) => Err(api_error::<T>(err)?),
+        };
+        let (result, error) =
+            response_result(result, UploadProgramResult::Ok, UploadProgramResult::Err);
+
+        Ok(Response::new(UploadProgramResponse { result, error }))
     }
 }
 
 async fn health_handler(State(state): State<Arc<ProveState>>) -> StatusCode {
-    if state.is_timeout() {
+    if state.is_timeout() || state.is_selftest_failed() {
         StatusCode::SERVICE_UNAVAILABLE
     } else {
         StatusCode::OK
@@ -319,6 +1086,631 @@ async fn shutdown_signal() {
     }
 }
 
-fn serialize_report_err(err: bincode::error::EncodeError) -> TwirpErrorResponse {
+/// Converts the error into [`ApiError`] with its [`ErrorKind`], which is taken from the
+/// underlying [`zkVMProver::Error`] if any, otherwise [`ErrorKind::ProverInfrastructure`].
+fn api_error<T: zkVMProver>(err: Error) -> twirp::Result<ApiError> {
+    let kind = err
+        .downcast_ref::<T::Error>()
+        .map(zkVMError::kind)
+        .unwrap_or(ErrorKind::ProverInfrastructure);
+    Ok(ApiError {
+        message: err.to_string(),
+        kind: bincode::serde::encode_to_vec(&kind, bincode::config::legacy())
+            .map_err(|err| internal(format!("failed to serialize error kind: {err}")))?,
+    })
+}
+
+/// Returns the `result` of a response with `ok` or `err`, and its `error`. The message is kept in
+/// `err` for clients predating [`ApiError`], which ignore `error`.
+fn response_result<O, R>(
+    result: Result<O, ApiError>,
+    ok: impl FnOnce(O) -> R,
+    err: impl FnOnce(String) -> R,
+) -> (Option<R>, Option<ApiError>) {
+    match result {
+        Ok(result) => (Some(ok(result)), None),
+        Err(error) => (Some(err(error.message.clone())), Some(error)),
+    }
+}
+
+fn encode_prove_result<T: zkVMProver>(
+    result: anyhow::Result<(PublicValues, Proof<T>, ProgramProvingReport)>,
+) -> twirp::Result<Result<ProveOk, ApiError>> {
+    Ok(match result {
+        Ok((public_values, proof, report)) => {
+            let proof = proof
+                .encode_to_vec()
+                .map_err(|err| internal(format!("failed to encode proof: {err:?}")))?;
+            metrics::record_prove_proof_bytes(proof.len());
+            Ok(ProveOk {
+                public_values: public_values.into(),
+                proof,
+                // Kept self-describing, and encoded as requested when it's sent.
+                report: report
+                    .encode(ReportEncoding::Json)
+                    .map_err(serialize_report_err)?,
+            })
+        }
+        Err(err) => Err(api_error::<T>(err)?),
+    })
+}
+
+/// Returns the result of a prove job to persist, with Twirp errors converted to [`ApiError`].
+fn persisted_prove_result<T: zkVMProver>(
+    result: &twirp::Result<Result<ProveOk, ApiError>>,
+) -> twirp::Result<Result<ProveOk, ApiError>> {
+    match result {
+        Ok(result) => Ok(result.clone()),
+        Err(err) => Ok(Err(api_error::<T>(anyhow::anyhow!("{err}"))?)),
+    }
+}
+
+/// Waits until the prove job finishes and returns its result, without the proof if `omit_proof`
+/// (to be streamed by `GET /proofs/{job_id}` instead).
+async fn wait_prove_result(
+    job_id: u64,
+    mut result: ProveResultReceiver,
+    omit_proof: bool,
+    encoding: ReportEncoding,
+) -> twirp::Result<Response<ProveResponse>> {
+    let result = result
+        .wait_for(Option::is_some)
+        .await
+        .map_err(|_| internal("prove job dropped unexpectedly"))?;
+    let result = match result.as_ref().expect("result is some") {
+        Ok(Ok(ok)) => Ok(Ok(ProveOk {
+            public_values: ok.public_values.clone(),
+            proof: if omit_proof {
+                Vec::new()
+            } else {
+                ok.proof.clone()
+            },
+            report: match encoding {
+                ReportEncoding::Json => ok.report.clone(),
+                _ => ProgramProvingReport::decode(&ok.report, ReportEncoding::Json)
+                    .and_then(|report| report.encode(encoding))
+                    .map_err(serialize_report_err)?,
+            },
+        })),
+        result => result.clone(),
+    };
+    let (result, error) = response_result(result?, ProveResult::Ok, ProveResult::Err);
+    Ok(Response::new(ProveResponse {
+        job_id,
+        result,
+        error,
+    }))
+}
+
+/// Routes of the API, which are the Twirp service and the streaming endpoints.
+fn api_router<T: 'static + zkVMProver + Send + Sync>(server: Arc<zkVMServer<T>>) -> Router {
+    Router::new()
+        .nest("/twirp", router(Arc::clone(&server)))
+        .route("/inputs", post(upload_input_handler::<T>))
+        .route("/proofs/{job_id}", get(download_proof_handler::<T>))
+        .with_state(server)
+}
+
+/// Sink of the stdin streamed by `POST /inputs`, which decompresses chunks as they arrive instead
+/// of buffering the whole compressed body.
+enum UploadedStdin {
+    None(BoundedStdin),
+    Zstd(zstd::stream::write::Decoder<'static, BoundedStdin>),
+}
+
+impl UploadedStdin {
+    fn write(&mut self, chunk: &[u8]) -> io::Result<()> {
+        match self {
+            Self::None(stdin) => stdin.write_all(chunk),
+            Self::Zstd(decoder) => decoder.write_all(chunk),
+        }
+    }
+
+    fn finish(self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None(stdin) => Ok(stdin.stdin),
+            Self::Zstd(mut decoder) => {
+                decoder.flush()?;
+                Ok(decoder.into_inner().stdin)
+            }
+        }
+    }
+}
+
+/// Stdin failing to be written over `max_len` bytes, so a decompressed upload is bounded as it
+/// arrives.
+struct BoundedStdin {
+    stdin: Vec<u8>,
+    max_len: usize,
+}
+
+impl BoundedStdin {
+    fn new(max_len: usize) -> Self {
+        Self {
+            stdin: Vec::new(),
+            max_len,
+        }
+    }
+}
+
+impl Write for BoundedStdin {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.stdin.len() + buf.len() > self.max_len {
+            return Err(io::Error::other(format!(
+                "input stdin exceeds {} bytes once decompressed",
+                self.max_len
+            )));
+        }
+        self.stdin.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Receives a stdin streamed in chunks, and responds with its upload id to be given as
+/// `input_upload_id` of `Execute` or `SubmitProve`.
+async fn upload_input_handler<T: 'static + zkVMProver + Send + Sync>(
+    State(server): State<Arc<zkVMServer<T>>>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<String, TwirpErrorResponse> {
+    let mut stdin = match headers.get(CONTENT_ENCODING) {
+        None => UploadedStdin::None(BoundedStdin::new(MAX_STDIN_LEN)),
+        Some(encoding) if encoding == "zstd" => UploadedStdin::Zstd(
+            zstd::stream::write::Decoder::new(BoundedStdin::new(MAX_STDIN_LEN))
+                .map_err(|err| internal(format!("failed to create zstd decoder: {err}")))?,
+        ),
+        Some(encoding) => {
+            return Err(invalid_argument(format!(
+                "unsupported content encoding: {encoding:?}"
+            )));
+        }
+    };
+
+    let mut chunks = body.into_data_stream();
+    while let Some(chunk) = chunks.next().await {
+        let chunk =
+            chunk.map_err(|err| invalid_argument(format!("failed to receive input: {err}")))?;
+        stdin
+            .write(&chunk)
+            .map_err(|err| invalid_argument(format!("failed to receive input stdin: {err}")))?;
+    }
+    let stdin = stdin
+        .finish()
+        .map_err(|err| invalid_argument(format!("failed to decompress input stdin: {err}")))?;
+
+    Ok(server.push_uploaded_input(stdin).to_string())
+}
+
+/// Streams the proof of the finished prove job in chunks, for clients that collected the rest by
+/// `ResumeProve` with `omit_proof`.
+async fn download_proof_handler<T: 'static + zkVMProver + Send + Sync>(
+    State(server): State<Arc<zkVMServer<T>>>,
+    extract::Path(job_id): extract::Path<u64>,
+) -> Result<axum::response::Response, TwirpErrorResponse> {
+    let result = server
+        .prove_jobs
+        .lock()
+        .iter()
+        .find(|job| job.id == job_id)
+        .map(|job| job.result.clone())
+        .ok_or_else(|| not_found("no such prove job"))?;
+    let proof_len = match &*result.borrow() {
+        Some(Ok(Ok(ok))) => ok.proof.len(),
+        _ => {
+            return Err(failed_precondition(
+                "prove job has not finished with a proof",
+            ));
+        }
+    };
+
+    // Chunks are copied from the job's result as they are sent, instead of the whole proof.
+    let chunks = stream::unfold((result, 0), |(result, offset)| async move {
+        let chunk = match &*result.borrow() {
+            Some(Ok(Ok(ok))) if offset < ok.proof.len() => {
+                let end = ok.proof.len().min(offset + PROOF_CHUNK_SIZE);
+                Bytes::copy_from_slice(&ok.proof[offset..end])
+            }
+            _ => return None,
+        };
+        let offset = offset + chunk.len();
+        Some((Ok::<_, Infallible>(chunk), (result, offset)))
+    });
+
+    Ok((
+        [
+            (CONTENT_TYPE, "application/octet-stream".to_string()),
+            (CONTENT_LENGTH, proof_len.to_string()),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+fn prove_input(request: ProveRequest) -> twirp::Result<Input> {
+    let ProveRequest {
+        input_stdin: stdin,
+        input_proofs: proofs,
+        input_stdin_compression,
+        input_kv_store: kv_store,
+        program_digest: _,
+        input_upload_id: _,
+        input_stdin_file: _,
+        report_encoding: _,
+    } = request;
+
+    let stdin = decompress_stdin(stdin, input_stdin_compression, MAX_STDIN_LEN)?;
+    Ok(Input {
+        stdin,
+        proofs,
+        kv_store,
+        ..Default::default()
+    })
+}
+
+/// Decompresses `stdin` of a request according to its `input_stdin_compression`, failing if it
+/// exceeds `max_len` bytes once decompressed.
+fn decompress_stdin(stdin: Vec<u8>, compression: i32, max_len: usize) -> twirp::Result<Vec<u8>> {
+    match ApiCompression::try_from(compression) {
+        Ok(ApiCompression::None) => Ok(stdin),
+        Ok(ApiCompression::Zstd) => {
+            // Reads one byte over `max_len` to tell an exceeding stdin apart.
+            let mut decompressed = Vec::new();
+            zstd::stream::Decoder::new(stdin.as_slice())
+                .and_then(|decoder| {
+                    decoder
+                        .take(max_len as u64 + 1)
+                        .read_to_end(&mut decompressed)
+                })
+                .map_err(|err| {
+                    invalid_argument(format!("failed to decompress input stdin: {err}"))
+                })?;
+            if decompressed.len() > max_len {
+                return Err(stdin_too_large(max_len));
+            }
+            Ok(decompressed)
+        }
+        Err(_) => Err(invalid_argument(format!(
+            "unknown input stdin compression: {compression}"
+        ))),
+    }
+}
+
+fn stdin_too_large(max_len: usize) -> TwirpErrorResponse {
+    invalid_argument(format!(
+        "input stdin exceeds {max_len} bytes once decompressed"
+    ))
+}
+
+/// Converts `report_encoding` of a request into [`ReportEncoding`].
+fn report_encoding(encoding: i32) -> twirp::Result<ReportEncoding> {
+    match ApiReportEncoding::try_from(encoding) {
+        Ok(ApiReportEncoding::Legacy) => Ok(ReportEncoding::Legacy),
+        Ok(ApiReportEncoding::Json) => Ok(ReportEncoding::Json),
+        Err(_) => Err(invalid_argument(format!(
+            "unknown report encoding: {encoding}"
+        ))),
+    }
+}
+
+fn programs_exhausted(programs: usize) -> TwirpErrorResponse {
+    resource_exhausted(format!(
+        "{programs} programs are served, no more can be uploaded"
+    ))
+}
+
+fn serialize_report_err(err: CommonError) -> TwirpErrorResponse {
     internal(format!("failed to serialize report: {err}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        num::NonZeroUsize,
+        sync::{Arc, mpsc},
+        time::Duration,
+    };
+
+    use ere_compiler_core::Elf;
+    use ere_prover_core::{
+        CommonError, ExecuteOptions, Input, ProgramExecutionReport, ProgramProvingReport,
+        PublicValues, zkVMProver, zkVMVerifier,
+    };
+    use ere_server_api::{
+        Compression as ApiCompression, JobState, ProveRequest, ZkvmService,
+        prove_response::Result as ProveResult,
+    };
+    use parking_lot::Mutex;
+    use twirp::Request;
+
+    use crate::{
+        commands::server::{
+            BoundedStdin, MAX_PROVE_ATTEMPTS, Programs, ProveState, decompress_stdin, zkVMServer,
+        },
+        job_store::{JobRecord, JobStore},
+    };
+
+    #[derive(Clone, Copy, Debug)]
+    struct TestVerifier;
+
+    impl zkVMVerifier for TestVerifier {
+        type ProgramVk = ();
+        type Proof = Vec<u8>;
+        type Error = CommonError;
+
+        fn verify(&self, proof: &Vec<u8>) -> Result<PublicValues, CommonError> {
+            Ok(proof.clone().into())
+        }
+
+        fn program_vk(&self) -> &() {
+            &()
+        }
+
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        fn sdk_version(&self) -> &'static str {
+            "0.0.0"
+        }
+    }
+
+    /// Prover whose prove blocks until released by the sender returned by [`TestProver::new`].
+    struct TestProver(Mutex<mpsc::Receiver<()>>);
+
+    impl TestProver {
+        fn new() -> (mpsc::Sender<()>, Self) {
+            let (release, rx) = mpsc::channel();
+            (release, Self(Mutex::new(rx)))
+        }
+    }
+
+    impl zkVMProver for TestProver {
+        type Verifier = TestVerifier;
+        type Error = CommonError;
+
+        fn verifier(&self) -> &TestVerifier {
+            &TestVerifier
+        }
+
+        fn execute_with_options(
+            &self,
+            _: &Input,
+            _: &ExecuteOptions,
+        ) -> Result<(PublicValues, ProgramExecutionReport), CommonError> {
+            Ok(Default::default())
+        }
+
+        fn prove(
+            &self,
+            _: &Input,
+        ) -> Result<(PublicValues, Vec<u8>, ProgramProvingReport), CommonError> {
+            let _ = self.0.lock().recv();
+            Ok(Default::default())
+        }
+    }
+
+    fn test_server(
+        prover: TestProver,
+        prove_timeout: Option<Duration>,
+        job_store: Option<JobStore>,
+    ) -> zkVMServer<TestProver> {
+        let one = NonZeroUsize::new(1).unwrap();
+        let programs = Programs::new(
+            [0; 32],
+            prover,
+            one,
+            Arc::new(|_: Elf| -> anyhow::Result<TestProver> {
+                anyhow::bail!("uploading is not supported")
+            }),
+        );
+        let prove_state = Arc::new(ProveState::new(prove_timeout));
+        zkVMServer::new(
+            programs,
+            prove_state,
+            one,
+            NonZeroUsize::new(4).unwrap(),
+            job_store,
+            None,
+        )
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("condition should hold in time");
+    }
+
+    fn job_state(
+        server: &zkVMServer<TestProver>,
+        idx: usize,
+    ) -> Option<(JobState, Option<String>)> {
+        let jobs = server.prove_jobs.lock();
+        let (state, err) = jobs.get(idx)?.state::<TestProver>().unwrap();
+        Some((state, err.map(|err| err.message)))
+    }
+
+    fn spawn_prove(server: &zkVMServer<TestProver>) -> tokio::task::JoinHandle<()> {
+        let server = server.clone();
+        tokio::spawn(async move {
+            let _ = ZkvmService::prove(&server, Request::new(ProveRequest::default())).await;
+        })
+    }
+
+    #[test]
+    fn test_bounded_stdin() {
+        let compressed = zstd::encode_all([0; 1024].as_slice(), 0).unwrap();
+        let mut decoder = zstd::stream::write::Decoder::new(BoundedStdin::new(1024)).unwrap();
+        decoder.write_all(&compressed).unwrap();
+        decoder.flush().unwrap();
+        assert_eq!(decoder.into_inner().stdin, [0; 1024]);
+
+        let mut decoder = zstd::stream::write::Decoder::new(BoundedStdin::new(1023)).unwrap();
+        let err = decoder
+            .write_all(&compressed)
+            .and_then(|_| decoder.flush())
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds 1023 bytes"));
+    }
+
+    #[test]
+    fn test_decompress_stdin() {
+        let zstd = ApiCompression::Zstd.into();
+        let compressed = zstd::encode_all([0; 1024].as_slice(), 0).unwrap();
+        assert_eq!(
+            decompress_stdin(compressed.clone(), zstd, 1024).unwrap(),
+            [0; 1024]
+        );
+        let err = decompress_stdin(compressed, zstd, 1023).unwrap_err();
+        assert!(err.msg.contains("exceeds 1023 bytes"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prove_aborted_on_disconnect() {
+        let (release, prover) = TestProver::new();
+        let server = test_server(prover, None, None);
+
+        // The first prove is proving, and the second is queued behind it.
+        let proving = spawn_prove(&server);
+        wait_until(|| matches!(job_state(&server, 0), Some((JobState::Proving, _)))).await;
+        let queued = spawn_prove(&server);
+        wait_until(|| matches!(job_state(&server, 1), Some((JobState::Queued, _)))).await;
+
+        let cancelled = Some((JobState::Failed, Some("prove job cancelled".to_string())));
+
+        // Disconnecting the queued one never acquires the prove gate.
+        queued.abort();
+        wait_until(|| job_state(&server, 1) == cancelled).await;
+        assert_eq!(job_state(&server, 0).unwrap().0, JobState::Proving);
+
+        // Disconnecting the proving one fails it, and the prove gate is released once the zkVM
+        // returns.
+        proving.abort();
+        wait_until(|| job_state(&server, 0) == cancelled).await;
+        assert_eq!(server.prove_sem.available_permits(), 0);
+        release.send(()).unwrap();
+        wait_until(|| server.prove_sem.available_permits() == 1).await;
+        assert!(server.prove_state.started_at.lock().is_none());
+
+        // Further proves are not blocked.
+        release.send(()).unwrap();
+        let response = ZkvmService::prove(&server, Request::new(ProveRequest::default()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            response.into_body().result,
+            Some(ProveResult::Ok(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prove_timeout() {
+        let (release, prover) = TestProver::new();
+        let server = test_server(prover, Some(Duration::from_millis(100)), None);
+
+        let (_, mut result, _) = server.spawn_prove_job(ProveRequest::default()).unwrap();
+        let result = result.wait_for(Option::is_some).await.unwrap().clone();
+        let Some(Ok(Err(err))) = result else {
+            panic!("prove should fail");
+        };
+        assert!(
+            err.message.starts_with("prove timed out"),
+            "{}",
+            err.message
+        );
+
+        // Unhealthy until the zkVM returns and releases the prove gate.
+        wait_until(|| server.prove_state.is_timeout()).await;
+        assert_eq!(server.prove_sem.available_permits(), 0);
+        release.send(()).unwrap();
+        wait_until(|| server.prove_sem.available_permits() == 1).await;
+        assert!(!server.prove_state.is_timeout());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_restore_prove_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JobStore::open(dir.path().to_path_buf()).unwrap();
+        for (id, attempts) in [(1, 1), (2, MAX_PROVE_ATTEMPTS)] {
+            let record = JobRecord {
+                id,
+                program_digest: [0; 32],
+                request: ProveRequest::default(),
+                result: None,
+                attempts,
+            };
+            store.save(&record).unwrap();
+        }
+
+        let (release, prover) = TestProver::new();
+        let server = test_server(prover, None, Some(store));
+        server.restore_prove_jobs([0; 32]).unwrap();
+
+        // The job started fewer times is proved again, the other one is failed.
+        wait_until(|| matches!(job_state(&server, 0), Some((JobState::Proving, _)))).await;
+        let (state, err) = job_state(&server, 1).unwrap();
+        assert_eq!(state, JobState::Failed);
+        assert!(err.unwrap().contains("failed to restore prove job"));
+        release.send(()).unwrap();
+        wait_until(|| matches!(job_state(&server, 0), Some((JobState::Done, _)))).await;
+
+        // Both are persisted with their attempts and results, so neither is proved again.
+        let records = JobStore::open(dir.path().to_path_buf())
+            .unwrap()
+            .load()
+            .unwrap();
+        assert_eq!(records[0].attempts, 2);
+        assert!(matches!(records[0].result, Some(Ok(_))));
+        assert_eq!(records[1].attempts, MAX_PROVE_ATTEMPTS);
+        assert!(matches!(records[1].result, Some(Err(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_upload_programs_concurrently() {
+        let programs = Programs::new(
+            [0; 32],
+            TestProver::new().1,
+            NonZeroUsize::new(2).unwrap(),
+            Arc::new(|_: Elf| -> anyhow::Result<TestProver> {
+                // Slow enough for all uploads to pass the check before any is served.
+                std::thread::sleep(Duration::from_millis(200));
+                Ok(TestProver::new().1)
+            }),
+        );
+        let one = NonZeroUsize::new(1).unwrap();
+        let server = zkVMServer::new(
+            programs,
+            Arc::new(ProveState::new(None)),
+            one,
+            one,
+            None,
+            None,
+        );
+
+        let uploads = (1..=4u8)
+            .map(|idx| {
+                let server = server.clone();
+                tokio::spawn(async move { server.register_program(Elf::from(vec![idx])).await })
+            })
+            .collect::<Vec<_>>();
+        let mut uploaded = 0;
+        for upload in uploads {
+            if let Ok(result) = upload.await.unwrap() {
+                result.unwrap();
+                uploaded += 1;
+            }
+        }
+
+        // Only one slot is left besides the program the server started with.
+        assert_eq!(uploaded, 1);
+        assert_eq!(server.programs.zkvms.read().len(), 2);
+    }
+}
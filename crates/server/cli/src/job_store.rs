@@ -0,0 +1,95 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Error};
+use ere_server_api::{Error as ApiError, ProveOk, ProveRequest};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Extension of the files of [`JobRecord`]s.
+const JOB_EXTENSION: &str = "job";
+
+/// Prove job persisted by [`JobStore`].
+#[derive(Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: u64,
+    /// Digest of the ELF the job proves, see `Elf::digest`.
+    pub program_digest: [u8; 32],
+    pub request: ProveRequest,
+    /// Result of the job, `None` until it finishes.
+    pub result: Option<Result<ProveOk, ApiError>>,
+    /// Number of times the job was started, including the ones restored after the server stopped
+    /// before it finished.
+    pub attempts: u32,
+}
+
+/// Prove jobs persisted in a directory (e.g. a mounted volume), so jobs submitted to a server
+/// that restarts (e.g. rescheduled container) are resumed instead of lost.
+///
+/// Each job is stored as `{program_digest}-{id}.job` when submitted, and rewritten with its result
+/// when finished. Jobs are restored only for the programs served, the rest are left untouched.
+pub struct JobStore {
+    dir: PathBuf,
+}
+
+impl JobStore {
+    pub fn open(dir: PathBuf) -> Result<Self, Error> {
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create job directory {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the jobs of all programs ordered by id. Unreadable files are skipped.
+    pub fn load(&self) -> Result<Vec<JobRecord>, Error> {
+        let entries = fs::read_dir(&self.dir)
+            .with_context(|| format!("failed to read job directory {}", self.dir.display()))?;
+        let mut records = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != JOB_EXTENSION) {
+                continue;
+            }
+            match read_record(&path) {
+                Ok(record) => records.push(record),
+                Err(err) => warn!("skip unreadable job {}: {err:#}", path.display()),
+            }
+        }
+        records.sort_by_key(|record| record.id);
+        Ok(records)
+    }
+
+    /// Writes the job, replacing the previous one of the same id atomically.
+    pub fn save(&self, record: &JobRecord) -> Result<(), Error> {
+        let bytes = bincode::serde::encode_to_vec(record, bincode::config::legacy())
+            .context("failed to serialize job")?;
+        let path = self.path(&record.program_digest, record.id);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .with_context(|| format!("failed to write job {}", path.display()))
+    }
+
+    pub fn remove(&self, program_digest: &[u8; 32], id: u64) {
+        let path = self.path(program_digest, id);
+        if let Err(err) = fs::remove_file(&path) {
+            warn!("failed to remove job {}: {err}", path.display());
+        }
+    }
+
+    fn path(&self, program_digest: &[u8; 32], id: u64) -> PathBuf {
+        let program_digest = program_digest
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        self.dir
+            .join(format!("{program_digest}-{id}.{JOB_EXTENSION}"))
+    }
+}
+
+fn read_record(path: &Path) -> Result<JobRecord, Error> {
+    let bytes = fs::read(path)?;
+    let (record, _) = bincode::serde::decode_from_slice(&bytes, bincode::config::legacy())?;
+    Ok(record)
+}
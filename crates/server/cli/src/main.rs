@@ -1,18 +1,22 @@
 use std::{
     fs,
     io::{self, Read},
+    num::NonZeroUsize,
     path::PathBuf,
+    thread::available_parallelism,
     time::Duration,
 };
 
 use anyhow::{Context, Error};
 use clap::Parser;
 use ere_compiler_core::Elf;
-use ere_prover_core::{ProverResource, zkVMProver};
+use ere_prover_core::{Input, ProverResource, config::Config, zkVMProver};
 use tracing::info;
 use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
+mod auth;
 mod commands;
+mod job_store;
 mod metrics;
 mod otel;
 
@@ -40,10 +44,64 @@ struct Args {
     /// Port number for the server to listen on.
     #[arg(long, default_value = "3000")]
     port: u16,
-    /// Mark `/health` as 503 if a single prove has been running longer than this many
-    /// milliseconds. Disabled when not set.
+    /// Port number to serve `/metrics` on in addition to `--port`, e.g. to be published for
+    /// scraping without exposing the API. Disabled when not set.
+    #[arg(long, env = "ERE_METRICS_PORT")]
+    metrics_port: Option<u16>,
+    /// Require requests to the API to carry `Authorization: Bearer <TOKEN>`. `/health` and
+    /// `/metrics` are not protected.
+    #[arg(long, env = "ERE_AUTH_TOKEN", hide_env_values = true)]
+    auth_token: Option<String>,
+    /// Same as `--auth-token` but accepts any token in the file (one per line), which is re-read
+    /// on `SIGHUP` to rotate tokens without restarting.
+    #[arg(long, env = "ERE_AUTH_TOKEN_FILE", conflicts_with = "auth_token")]
+    auth_token_file: Option<PathBuf>,
+    /// Fail a prove job that has been proving longer than this many milliseconds, and mark
+    /// `/health` as 503 until the zkVM returns, as it can't be interrupted. Disabled when not set.
     #[arg(long, env = "ERE_PROVE_TIMEOUT_MS")]
     prove_timeout_ms: Option<u64>,
+    /// Maximum number of proofs verified concurrently, further verify requests are queued.
+    /// Defaults to the available parallelism.
+    #[arg(long, env = "ERE_VERIFY_THREADS")]
+    verify_threads: Option<NonZeroUsize>,
+    /// Maximum number of prove jobs queued or running, further prove requests are rejected.
+    /// Proves run one at a time in FIFO order.
+    #[arg(long, env = "ERE_MAX_PROVE_JOBS", default_value = "4")]
+    max_prove_jobs: NonZeroUsize,
+    /// Maximum number of programs served, including the one started with. Further programs can
+    /// be uploaded by `UploadProgram` until this many are served.
+    #[arg(long, env = "ERE_MAX_PROGRAMS", default_value = "4")]
+    max_programs: NonZeroUsize,
+    /// Directory (e.g. a mounted volume) to persist prove jobs in, so jobs queued or running when
+    /// the server stops are proved again after it restarts, and finished ones can still be
+    /// collected. Disabled when not set.
+    #[arg(long, env = "ERE_JOB_DIR")]
+    job_dir: Option<PathBuf>,
+    /// Directory shared with clients (e.g. bind-mounted into the container) to read the stdin
+    /// of requests from, written by clients as files instead of sent in the request. Disabled
+    /// when not set.
+    #[arg(long, env = "ERE_INPUT_DIR")]
+    input_dir: Option<PathBuf>,
+    /// Perform one-time costs of proving (e.g. proving key setup) before serving requests,
+    /// instead of lazily on the first prove.
+    #[arg(long, env = "ERE_WARMUP")]
+    warmup: bool,
+    /// Periodically execute, prove and verify as a self-test every this many seconds, and mark
+    /// `/health` as 503 if the last self-test failed. Disabled when not set.
+    #[arg(
+        long,
+        env = "ERE_SELFTEST_INTERVAL_SECS",
+        requires = "selftest_input_path"
+    )]
+    selftest_interval_secs: Option<u64>,
+    /// Path to read the stdin of the self-test input from, which the program must accept, since
+    /// a failing self-test marks the server unhealthy.
+    #[arg(
+        long,
+        env = "ERE_SELFTEST_INPUT_PATH",
+        requires = "selftest_interval_secs"
+    )]
+    selftest_input_path: Option<PathBuf>,
     #[command(
         flatten,
         next_help_heading = "ELF source (read from stdin if none set)"
@@ -94,12 +152,46 @@ async fn main() -> Result<(), Error> {
         )
         .init();
 
+    // Load the configuration upfront to fail on invalid one, instead of panicking on first use.
+    Config::load()?
+        .install()
+        .expect("configuration is installed only once");
+
     let elf = read_elf(args.elf).await?;
 
     match args.command {
         Command::Server(resource) => {
             let prove_timeout = args.prove_timeout_ms.map(Duration::from_millis);
-            commands::server::run(args.port, elf, resource, prove_timeout).await?
+            let verify_threads = args
+                .verify_threads
+                .or_else(|| available_parallelism().ok())
+                .unwrap_or(NonZeroUsize::MIN);
+            let selftest = match (args.selftest_interval_secs, args.selftest_input_path) {
+                (Some(interval_secs), Some(input_path)) => Some(commands::server::SelfTestConfig {
+                    interval: Duration::from_secs(interval_secs),
+                    input: read_selftest_input(input_path)?,
+                }),
+                _ => None,
+            };
+            let auth_tokens = match (args.auth_token, args.auth_token_file) {
+                (Some(token), _) => Some(auth::AuthTokens::new(token)),
+                (None, Some(path)) => Some(auth::AuthTokens::from_file(path)?),
+                (None, None) => None,
+            };
+            let config = commands::server::ServerConfig {
+                port: args.port,
+                metrics_port: args.metrics_port,
+                auth_tokens,
+                job_dir: args.job_dir,
+                input_dir: args.input_dir,
+                prove_timeout,
+                verify_threads,
+                max_prove_jobs: args.max_prove_jobs,
+                max_programs: args.max_programs,
+                warmup: args.warmup,
+                selftest,
+            };
+            commands::server::run(elf, resource, config).await?
         }
         Command::Keygen { program_vk_path } => commands::keygen::run(elf, &program_vk_path)?,
     }
@@ -111,12 +203,13 @@ async fn main() -> Result<(), Error> {
     Ok(())
 }
 
+/// Reads the ELF from the source, which is decompressed if compressed by `Elf::compress`.
 async fn read_elf(elf_source: ElfSource) -> Result<Elf, Error> {
     if let Some(path) = elf_source.elf_path {
         let bytes = fs::read(&path)
             .with_context(|| format!("failed to read ELF from {}", path.display()))?;
         info!("loaded ELF from {}", path.display());
-        Ok(Elf(bytes))
+        decompress_elf(bytes)
     } else if let Some(url) = elf_source.elf_url {
         let bytes = reqwest::get(&url)
             .await
@@ -128,15 +221,30 @@ async fn read_elf(elf_source: ElfSource) -> Result<Elf, Error> {
             .with_context(|| format!("failed to read ELF body from {url}"))?
             .to_vec();
         info!("downloaded ELF from {url}");
-        Ok(Elf(bytes))
+        decompress_elf(bytes)
     } else {
         let mut bytes = Vec::new();
         io::stdin()
             .read_to_end(&mut bytes)
             .context("failed to read ELF from stdin")?;
         info!("read ELF from stdin");
-        Ok(Elf(bytes))
+        decompress_elf(bytes)
+    }
+}
+
+fn decompress_elf(bytes: Vec<u8>) -> Result<Elf, Error> {
+    let len = bytes.len();
+    let elf = Elf::from_maybe_compressed(bytes).context("failed to decompress ELF")?;
+    if elf.len() != len {
+        info!("decompressed ELF from {len} bytes to {} bytes", elf.len());
     }
+    Ok(elf)
+}
+
+fn read_selftest_input(path: PathBuf) -> Result<Input, Error> {
+    Input::new()
+        .with_stdin_file(&path)
+        .with_context(|| format!("failed to read self-test input from {}", path.display()))
 }
 
 pub(crate) fn construct_zkvm(elf: Elf, resource: ProverResource) -> Result<impl zkVMProver, Error> {
@@ -1,7 +1,10 @@
 use std::time::{Duration, Instant};
 
+use ere_prover_core::{Input, SelfTestReport};
 use metrics::{counter, gauge, histogram};
 use metrics_exporter_prometheus::{BuildError, PrometheusBuilder, PrometheusHandle};
+use tokio::process::Command;
+use tracing::warn;
 use twirp::axum::{
     extract::{Request, State},
     middleware::Next,
@@ -14,11 +17,19 @@ const HTTP_REQUEST_DURATION_SECONDS: &str = "ere_server_http_request_duration_se
 const HTTP_REQUESTS_IN_FLIGHT: &str = "ere_server_http_requests_in_flight";
 const EXECUTE_TOTAL: &str = "ere_server_execute_total";
 const EXECUTE_DURATION_SECONDS: &str = "ere_server_execute_duration_seconds";
+const INPUT_BYTES: &str = "ere_server_input_bytes";
+const PROVE_STARTED_TOTAL: &str = "ere_server_prove_started_total";
 const PROVE_TOTAL: &str = "ere_server_prove_total";
 const PROVE_DURATION_SECONDS: &str = "ere_server_prove_duration_seconds";
+const PROVE_QUEUE_DURATION_SECONDS: &str = "ere_server_prove_queue_duration_seconds";
 const PROVE_PROOF_BYTES: &str = "ere_server_prove_proof_bytes";
 const VERIFY_TOTAL: &str = "ere_server_verify_total";
 const VERIFY_DURATION_SECONDS: &str = "ere_server_verify_duration_seconds";
+const VERIFY_QUEUED: &str = "ere_server_verify_queued";
+const VERIFY_QUEUE_DURATION_SECONDS: &str = "ere_server_verify_queue_duration_seconds";
+const SELFTEST_TOTAL: &str = "ere_server_selftest_total";
+const SELFTEST_DURATION_SECONDS: &str = "ere_server_selftest_duration_seconds";
+const GPU_MEMORY_HIGH_WATER_BYTES: &str = "ere_server_gpu_memory_high_water_bytes";
 
 pub fn init(
     zkvm_name: &'static str,
@@ -46,14 +57,71 @@ pub fn spawn_upkeep(handle: PrometheusHandle) {
     });
 }
 
+/// Samples the used memory of all visible GPUs by `nvidia-smi` every second, and keeps the highest
+/// sum seen in [`GPU_MEMORY_HIGH_WATER_BYTES`]. Stops sampling if `nvidia-smi` is unavailable.
+pub fn spawn_gpu_memory_sampler() {
+    tokio::spawn(async move {
+        let mut high_water = 0;
+        loop {
+            let Some(used) = gpu_memory_used().await else {
+                warn!("failed to query GPU memory by nvidia-smi, stop sampling");
+                return;
+            };
+            if used > high_water {
+                high_water = used;
+                gauge!(GPU_MEMORY_HIGH_WATER_BYTES).set(high_water as f64);
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    });
+}
+
+/// Returns the sum of used memory of all visible GPUs in bytes.
+async fn gpu_memory_used() -> Option<u64> {
+    let output = Command::new("nvidia-smi")
+        .args(["--query-gpu=memory.used", "--format=csv,noheader,nounits"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let used_mib = String::from_utf8(output.stdout)
+        .ok()?
+        .lines()
+        .map(|line| line.trim().parse::<u64>().ok())
+        .sum::<Option<u64>>()?;
+    Some(used_mib << 20)
+}
+
+/// Records the size of stdin, proofs and key-value store of the input of `method`.
+pub fn record_input_bytes(method: &'static str, input: &Input) {
+    let len = input.stdin_len() as usize
+        + input.proofs.as_ref().map_or(0, Vec::len)
+        + input.kv_store.as_ref().map_or(0, Vec::len);
+    histogram!(INPUT_BYTES, "method" => method).record(len as f64);
+}
+
 pub fn record_execute<T, E>(result: &Result<T, E>, elapsed: Duration) {
     record_call(EXECUTE_TOTAL, EXECUTE_DURATION_SECONDS, result, elapsed);
 }
 
+/// Records a prove job that starts proving, after waiting behind the previous ones.
+pub fn record_prove_started() {
+    counter!(PROVE_STARTED_TOTAL).increment(1);
+}
+
+/// Records a prove that finished, with `elapsed` since it started proving, excluding the time
+/// queued which is recorded by [`record_prove_queue_duration`]. Proves of cancelled jobs are not
+/// recorded.
 pub fn record_prove<T, E>(result: &Result<T, E>, elapsed: Duration) {
     record_call(PROVE_TOTAL, PROVE_DURATION_SECONDS, result, elapsed);
 }
 
+pub fn record_prove_queue_duration(elapsed: Duration) {
+    histogram!(PROVE_QUEUE_DURATION_SECONDS).record(elapsed.as_secs_f64());
+}
+
 pub fn record_prove_proof_bytes(len: usize) {
     histogram!(PROVE_PROOF_BYTES).record(len as f64);
 }
@@ -62,6 +130,41 @@ pub fn record_verify<T, E>(result: &Result<T, E>, elapsed: Duration) {
     record_call(VERIFY_TOTAL, VERIFY_DURATION_SECONDS, result, elapsed);
 }
 
+pub fn record_verify_queue_duration(elapsed: Duration) {
+    histogram!(VERIFY_QUEUE_DURATION_SECONDS).record(elapsed.as_secs_f64());
+}
+
+/// Records the self-test result, and on success, durations of each phase for timing trends.
+pub fn record_selftest<E>(result: &Result<SelfTestReport, E>) {
+    let status = if result.is_ok() { "success" } else { "error" };
+    counter!(SELFTEST_TOTAL, "status" => status).increment(1);
+    if let Ok(report) = result {
+        for (phase, duration) in [
+            ("execute", report.execution_duration),
+            ("prove", report.proving_duration),
+            ("verify", report.verifying_duration),
+        ] {
+            histogram!(SELFTEST_DURATION_SECONDS, "phase" => phase).record(duration.as_secs_f64());
+        }
+    }
+}
+
+/// Counts a verify request waiting for a permit in [`VERIFY_QUEUED`] until dropped.
+pub struct VerifyQueuedGuard;
+
+impl VerifyQueuedGuard {
+    pub fn new() -> Self {
+        gauge!(VERIFY_QUEUED).increment(1.0);
+        Self
+    }
+}
+
+impl Drop for VerifyQueuedGuard {
+    fn drop(&mut self) {
+        gauge!(VERIFY_QUEUED).decrement(1.0);
+    }
+}
+
 fn record_call<T, E>(
     total: &'static str,
     duration: &'static str,
@@ -123,6 +226,8 @@ pub fn path_to_method(path: &str) -> &'static str {
         "/twirp/api.ZkvmService/Execute" => "execute",
         "/twirp/api.ZkvmService/Prove" => "prove",
         "/twirp/api.ZkvmService/Verify" => "verify",
+        "/inputs" => "upload_input",
+        path if path.starts_with("/proofs/") => "download_proof",
         _ => "unknown",
     }
 }
@@ -1,30 +1,189 @@
-use core::{ops::Deref, time::Duration};
+use core::{mem, ops::Deref, time::Duration};
+use std::{
+    fs::File,
+    io::{self, Cursor, Read, Write},
+    path::PathBuf,
+    process,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU32, AtomicU64, Ordering},
+    },
+};
 
-use ere_prover_core::{Input, ProgramExecutionReport, ProgramProvingReport, PublicValues};
+use ere_prover_core::{
+    CommonError, Compression, ErrorKind, ExecuteOptions, Input, ProgramExecutionReport,
+    ProgramProvingReport, PublicValues, ReportEncoding, StdinFile, zkVMError,
+};
+pub use ere_server_api::{CRATE_VERSION, PROTOCOL_VERSION};
 use ere_server_api::{
-    ExecuteRequest, ProgramVkRequest, ProveRequest, VerifyRequest, ZkvmService,
-    execute_response::Result as ExecuteResult, program_vk_response::Result as ProgramVkResult,
-    prove_response::Result as ProveResult, verify_response::Result as VerifyResult,
+    Compression as ApiCompression, Error as ApiError, ExecuteRequest, HandshakeRequest, JobState,
+    JobStatusRequest, ProgramVkRequest, ProveRequest, ProveResponse,
+    ReportEncoding as ApiReportEncoding, ResumeProveRequest, UploadProgramRequest, VerifyRequest,
+    ZkvmService, execute_response::Result as ExecuteResult,
+    program_vk_response::Result as ProgramVkResult, prove_response::Result as ProveResult,
+    upload_program_response::Result as UploadProgramResult,
+    verify_response::Result as VerifyResult,
 };
+use futures_util::stream;
 #[cfg(feature = "otel")]
 pub use otel_propagation::OtelPropagation;
+pub use reqwest;
+use reqwest::header::{AUTHORIZATION, CONTENT_ENCODING};
 use thiserror::Error;
-use twirp::{Client, Middleware, Request, url::Url};
-pub use twirp::{TwirpErrorResponse, reqwest, url};
+use twirp::{Client, Middleware, Next, Request, TwirpErrorCode, url::Url};
+pub use twirp::{TwirpErrorResponse, url};
 
 const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
 
+/// Inputs with stdin at least this large are streamed by `POST /inputs` instead of sent in the
+/// request, when the server supports it (protocol version 3).
+pub const STREAM_THRESHOLD: usize = 16 << 20;
+
+/// Size of the chunks of stdin compressed at a time while streamed.
+const STREAM_CHUNK_SIZE: usize = 1 << 20;
+
+/// Counter to name the stdin files written to the input directory uniquely.
+static NEXT_INPUT_FILE: AtomicU64 = AtomicU64::new(0);
+
 #[derive(Debug, Error)]
 #[allow(non_camel_case_types)]
 pub enum Error {
+    #[error(transparent)]
+    CommonError(#[from] CommonError),
     #[error("Invalid URL: {0}")]
     ParseUrl(#[from] url::ParseError),
-    #[error("zkVM method error: {0}")]
-    zkVM(String),
+    #[error("zkVM method error: {message}")]
+    zkVM { message: String, kind: ErrorKind },
     #[error("RPC error: {0}")]
     Rpc(#[from] TwirpErrorResponse),
 }
 
+impl zkVMError for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommonError(err) => err.kind(),
+            Self::zkVM { kind, .. } => kind.clone(),
+            Self::ParseUrl(_) => ErrorKind::ProverInfrastructure,
+            Self::Rpc(err) => rpc_error_kind(err),
+        }
+    }
+}
+
+/// Classifies a Twirp error by its code, so only failures that might succeed on retry (e.g. the
+/// server is unavailable or its prove queue is full) are [`ErrorKind::Transient`].
+pub fn rpc_error_kind(err: &TwirpErrorResponse) -> ErrorKind {
+    match err.code {
+        TwirpErrorCode::Canceled
+        | TwirpErrorCode::DeadlineExceeded
+        | TwirpErrorCode::ResourceExhausted
+        | TwirpErrorCode::Aborted
+        | TwirpErrorCode::Unavailable => ErrorKind::Transient,
+        TwirpErrorCode::InvalidArgument
+        | TwirpErrorCode::Malformed
+        | TwirpErrorCode::OutOfRange => ErrorKind::InvalidInput,
+        _ => ErrorKind::ProverInfrastructure,
+    }
+}
+
+impl TryFrom<ApiError> for Error {
+    type Error = TwirpErrorResponse;
+
+    fn try_from(err: ApiError) -> Result<Self, Self::Error> {
+        let (kind, _) = bincode::serde::decode_from_slice(&err.kind, bincode::config::legacy())
+            .map_err(|err| twirp::internal(format!("failed to deserialize error kind: {err}")))?;
+        Ok(Self::zkVM {
+            message: err.message,
+            kind,
+        })
+    }
+}
+
+impl Error {
+    /// Returns the zkVM error of a response with the message `err` and `error`, which servers
+    /// predating [`ErrorKind`] leave out.
+    fn from_response(err: String, error: Option<ApiError>) -> Result<Self, TwirpErrorResponse> {
+        match error {
+            Some(error) => Self::try_from(error),
+            None => Ok(Self::zkVM {
+                message: err,
+                kind: ErrorKind::ProverInfrastructure,
+            }),
+        }
+    }
+}
+
+/// Server returned by [`zkVMClient::handshake`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ServerInfo {
+    /// Lower one of [`PROTOCOL_VERSION`] and the server's, or `0` if the server predates the
+    /// handshake.
+    pub protocol_version: u32,
+    /// Version of the `ere-server` crate, empty if the server predates it.
+    pub crate_version: String,
+    /// Name of the zkVM, empty if the server predates it.
+    pub zkvm_name: String,
+    /// Version of the zkVM SDK, empty if the server predates it.
+    pub zkvm_sdk_version: String,
+}
+
+/// Status of a prove job, see [`zkVMClient::job_status`].
+#[derive(Debug)]
+pub enum JobStatus {
+    /// Waiting for the previous prove jobs to finish.
+    Queued,
+    Proving,
+    /// Finished, the result can be collected by [`zkVMClient::resume_prove_job`].
+    Done,
+    Failed(Error),
+}
+
+/// Middleware setting `Authorization: Bearer {token}` on requests, for servers started with
+/// `--auth-token` or `--auth-token-file`. No header is set while the token is empty.
+///
+/// Clones share the token, so one kept aside can [`AuthToken::set`] the rotated token for the
+/// client it's installed in.
+#[derive(Clone, Default)]
+pub struct AuthToken(Arc<RwLock<String>>);
+
+impl core::fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("AuthToken(..)")
+    }
+}
+
+impl AuthToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(Arc::new(RwLock::new(token.into())))
+    }
+
+    pub fn set(&self, token: impl Into<String>) {
+        *self.0.write().unwrap_or_else(|err| err.into_inner()) = token.into();
+    }
+
+    /// Returns the value of `Authorization`, or `None` while the token is empty.
+    fn header_value(&self) -> Option<String> {
+        let token = self.0.read().unwrap_or_else(|err| err.into_inner());
+        (!token.is_empty()).then(|| format!("Bearer {token}"))
+    }
+}
+
+#[twirp::async_trait::async_trait]
+impl Middleware for AuthToken {
+    async fn handle(
+        &self,
+        mut req: reqwest::Request,
+        next: Next<'_>,
+    ) -> twirp::Result<reqwest::Response> {
+        if let Some(value) = self.header_value() {
+            let value = value
+                .parse()
+                .map_err(|_| twirp::internal("auth token is not a valid header value"))?;
+            req.headers_mut().insert(AUTHORIZATION, value);
+        }
+        next.run(req).await
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EncodedProof(pub Vec<u8>);
 
@@ -60,20 +219,57 @@ impl AsRef<[u8]> for EncodedProgramVk {
 }
 
 /// zkVM client of the `zkVMServer`.
+///
+/// Once [`zkVMClient::handshake`] negotiates protocol version 3, inputs with stdin of at least
+/// [`STREAM_THRESHOLD`] and proofs are streamed in chunks instead of buffered in a request or
+/// response.
+///
+/// Below protocol version 1, stdin is sent uncompressed, and requests with a cycle limit or a
+/// key-value store the server wouldn't know are rejected with [`CommonError::UnsupportedInput`],
+/// as are requests for a program of [`zkVMClient::with_program`] below protocol version 2.
 #[allow(non_camel_case_types)]
 #[derive(Clone, Debug)]
 pub struct zkVMClient {
     endpoint: Url,
     http_client: reqwest::Client,
     client: Client,
+    auth_token: AuthToken,
+    /// Protocol version negotiated by [`zkVMClient::handshake`], shared by clones.
+    protocol_version: Arc<AtomicU32>,
+    /// Digest of the program requests are for, empty for the server's default program.
+    program_digest: Vec<u8>,
+    /// Directory shared with the server and the threshold of stdin written there, see
+    /// [`zkVMClient::with_input_dir`].
+    input_dir: Option<(PathBuf, usize)>,
+}
+
+/// Stdin fields of `ExecuteRequest` and `ProveRequest`, see [`zkVMClient::send_stdin`].
+struct StdinFields {
+    input_stdin: Vec<u8>,
+    input_stdin_compression: ApiCompression,
+    input_upload_id: Option<u64>,
+    input_stdin_file: Option<String>,
+}
+
+impl StdinFields {
+    fn inline(input_stdin: Vec<u8>, input_stdin_compression: ApiCompression) -> Self {
+        Self {
+            input_stdin,
+            input_stdin_compression,
+            input_upload_id: None,
+            input_stdin_file: None,
+        }
+    }
 }
 
 impl zkVMClient {
     pub fn new(
         endpoint: Url,
         http_client: reqwest::Client,
-        middlewares: Vec<Box<dyn Middleware>>,
+        mut middlewares: Vec<Box<dyn Middleware>>,
     ) -> Result<Self, Error> {
+        let auth_token = AuthToken::default();
+        middlewares.push(Box::new(auth_token.clone()));
         let client = Client::new(
             endpoint.join("twirp")?,
             http_client.clone(),
@@ -84,6 +280,10 @@ impl zkVMClient {
             endpoint,
             http_client,
             client,
+            auth_token,
+            protocol_version: Arc::new(AtomicU32::new(0)),
+            program_digest: Vec::new(),
+            input_dir: None,
         })
     }
 
@@ -91,6 +291,29 @@ impl zkVMClient {
         Self::new(endpoint, reqwest::Client::new(), vec![])
     }
 
+    /// Returns the client for the program with `program_digest` uploaded by
+    /// [`zkVMClient::upload_program`], instead of the program the server started with.
+    pub fn with_program(mut self, program_digest: [u8; 32]) -> Self {
+        self.program_digest = program_digest.to_vec();
+        self
+    }
+
+    /// Returns the client writing stdin of at least `threshold` bytes to a file in `dir` instead
+    /// of sending it, for the server started with `--input-dir` of the same directory (e.g.
+    /// bind-mounted into its container) to read it directly.
+    ///
+    /// Requires protocol version 4, stdin is sent as usual otherwise.
+    pub fn with_input_dir(mut self, dir: impl Into<PathBuf>, threshold: usize) -> Self {
+        self.input_dir = Some((dir.into(), threshold));
+        self
+    }
+
+    /// Returns the token sent by all requests, including the streaming ones which don't go
+    /// through the middlewares. Empty by default, for servers started without auth.
+    pub fn auth_token(&self) -> &AuthToken {
+        &self.auth_token
+    }
+
     pub async fn is_healthy(&self) -> bool {
         let Ok(url) = self.endpoint.join("health") else {
             return false;
@@ -103,25 +326,76 @@ impl zkVMClient {
             .is_ok_and(|r| r.status().is_success())
     }
 
+    /// Negotiates the protocol version with the server, and returns it along with the versions
+    /// of the server, see [`ServerInfo`].
+    pub async fn handshake(&self) -> Result<ServerInfo, Error> {
+        let request = Request::new(HandshakeRequest {
+            protocol_version: PROTOCOL_VERSION,
+        });
+
+        let info = match self.client.handshake(request).await {
+            Ok(response) => {
+                let response = response.into_body();
+                ServerInfo {
+                    protocol_version: response.protocol_version.min(PROTOCOL_VERSION),
+                    crate_version: response.crate_version,
+                    zkvm_name: response.zkvm_name,
+                    zkvm_sdk_version: response.zkvm_sdk_version,
+                }
+            }
+            Err(err) if matches!(err.code, TwirpErrorCode::BadRoute) => ServerInfo::default(),
+            Err(err) => return Err(err.into()),
+        };
+        self.protocol_version
+            .store(info.protocol_version, Ordering::Relaxed);
+        Ok(info)
+    }
+
     pub async fn execute(
         &self,
         input: Input,
     ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        self.execute_with_options(input, ExecuteOptions::default())
+            .await
+    }
+
+    pub async fn execute_with_options(
+        &self,
+        input: Input,
+        options: ExecuteOptions,
+    ) -> Result<(PublicValues, ProgramExecutionReport), Error> {
+        // The profile would be written on the server host, so it's not part of the protocol.
+        options.check_profile_unsupported()?;
+        if options.max_cycles.is_some() {
+            self.check_protocol_version(1, "cycle limit")?;
+        }
+        let program_digest = self.program_digest()?;
+        self.check_kv_store(&input)?;
+        let stdin = self
+            .send_stdin(input.stdin, input.stdin_file, input.compression)
+            .await?;
+        let encoding = self.report_encoding();
         let request = Request::new(ExecuteRequest {
-            input_stdin: input.stdin,
+            input_stdin: stdin.input_stdin,
             input_proofs: input.proofs,
+            max_cycles: options.max_cycles,
+            input_stdin_compression: stdin.input_stdin_compression.into(),
+            input_kv_store: input.kv_store,
+            program_digest,
+            input_upload_id: stdin.input_upload_id,
+            input_stdin_file: stdin.input_stdin_file,
+            report_encoding: api_report_encoding(encoding).into(),
         });
 
-        let response = self.client.execute(request).await?;
+        let response = self.client.execute(request).await?.into_body();
 
-        match response.into_body().result.ok_or_else(result_none_err)? {
+        match response.result.ok_or_else(result_none_err)? {
             ExecuteResult::Ok(result) => Ok((
                 result.public_values.into(),
-                bincode::serde::decode_from_slice(&result.report, bincode::config::legacy())
-                    .map_err(deserialize_report_err)?
-                    .0,
+                ProgramExecutionReport::decode(&result.report, encoding)
+                    .map_err(deserialize_report_err)?,
             )),
-            ExecuteResult::Err(err) => Err(Error::zkVM(err)),
+            ExecuteResult::Err(err) => Err(Error::from_response(err, response.error)?),
         }
     }
 
@@ -129,53 +403,434 @@ impl zkVMClient {
         &self,
         input: Input,
     ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport), Error> {
-        let request = Request::new(ProveRequest {
-            input_stdin: input.stdin,
-            input_proofs: input.proofs,
-        });
+        // A job of `Prove` is aborted when this client disconnects, so it's submitted instead to
+        // be resumed by another client (e.g. after this process crashed). The proof is also only
+        // streamed for jobs collected by `ResumeProve`.
+        if self.protocol_version.load(Ordering::Relaxed) >= 1 {
+            let job_id = self.submit_prove(input).await?;
+            return self.resume_prove_job(job_id).await;
+        }
+
+        let request = Request::new(self.prove_request(input).await?);
 
         let response = self.client.prove(request).await?;
 
-        match response.into_body().result.ok_or_else(result_none_err)? {
-            ProveResult::Ok(result) => Ok((
-                result.public_values.into(),
-                EncodedProof(result.proof),
-                bincode::serde::decode_from_slice(&result.report, bincode::config::legacy())
-                    .map_err(deserialize_report_err)?
-                    .0,
-            )),
-            ProveResult::Err(err) => Err(Error::zkVM(err)),
-        }
+        decode_prove_response(response.into_body(), self.report_encoding())
+    }
+
+    /// Submits a prove job without waiting for it, and returns its id to be polled by
+    /// [`zkVMClient::job_status`] and collected by [`zkVMClient::resume_prove_job`].
+    ///
+    /// Requires protocol version 1, see [`zkVMClient::handshake`].
+    pub async fn submit_prove(&self, input: Input) -> Result<u64, Error> {
+        let request = Request::new(self.prove_request(input).await?);
+
+        let response = self.client.submit_prove(request).await?;
+
+        Ok(response.into_body().job_id)
+    }
+
+    /// Waits for the prove of the input with `input_commitment` (see [`Input::commitment`]) and
+    /// returns its result, which is useful to collect the result of a prove requested by another
+    /// client that disconnected (e.g. crashed).
+    ///
+    /// The server only keeps the latest prove jobs, and returns an error of not found if none is
+    /// of the input.
+    pub async fn resume_prove(
+        &self,
+        input_commitment: [u8; 32],
+    ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport), Error> {
+        self.resume(input_commitment.to_vec(), None).await
+    }
+
+    /// Waits for the prove job with `job_id` and returns its result.
+    ///
+    /// Requires protocol version 1, see [`zkVMClient::handshake`].
+    pub async fn resume_prove_job(
+        &self,
+        job_id: u64,
+    ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport), Error> {
+        self.resume(Vec::new(), Some(job_id)).await
+    }
+
+    /// Returns the status of the prove job with `job_id`.
+    ///
+    /// Requires protocol version 1, see [`zkVMClient::handshake`].
+    pub async fn job_status(&self, job_id: u64) -> Result<JobStatus, Error> {
+        let request = Request::new(JobStatusRequest { job_id });
+
+        let response = self.client.job_status(request).await?.into_body();
+
+        Ok(match response.state() {
+            JobState::Queued => JobStatus::Queued,
+            JobState::Proving => JobStatus::Proving,
+            JobState::Done => JobStatus::Done,
+            JobState::Failed => JobStatus::Failed(match response.err {
+                Some(err) => Error::from_response(err, response.error)?,
+                None => twirp::internal("failed job should have an error").into(),
+            }),
+        })
     }
 
     pub async fn verify(&self, proof: EncodedProof) -> Result<PublicValues, Error> {
-        let request = Request::new(VerifyRequest { proof: proof.0 });
+        let request = Request::new(VerifyRequest {
+            proof: proof.0,
+            program_digest: self.program_digest()?,
+        });
 
-        let response = self.client.verify(request).await?;
+        let response = self.client.verify(request).await?.into_body();
 
-        match response.into_body().result.ok_or_else(result_none_err)? {
+        match response.result.ok_or_else(result_none_err)? {
             VerifyResult::Ok(result) => Ok(result.public_values.into()),
-            VerifyResult::Err(err) => Err(Error::zkVM(err)),
+            VerifyResult::Err(err) => Err(Error::from_response(err, response.error)?),
         }
     }
 
     pub async fn program_vk(&self) -> Result<EncodedProgramVk, Error> {
-        let request = Request::new(ProgramVkRequest {});
+        let request = Request::new(ProgramVkRequest {
+            program_digest: self.program_digest()?,
+        });
 
-        let response = self.client.program_vk(request).await?;
+        let response = self.client.program_vk(request).await?.into_body();
 
-        match response.into_body().result.ok_or_else(result_none_err)? {
+        match response.result.ok_or_else(result_none_err)? {
             ProgramVkResult::Ok(result) => Ok(EncodedProgramVk(result.program_vk)),
-            ProgramVkResult::Err(err) => Err(Error::zkVM(err)),
+            ProgramVkResult::Err(err) => Err(Error::from_response(err, response.error)?),
+        }
+    }
+
+    /// Uploads the ELF to be served besides the program the server started with, and returns its
+    /// digest to select it by [`zkVMClient::with_program`], and its program verifying key.
+    /// Uploading a program already served returns the same without setting it up again.
+    ///
+    /// Requires protocol version 2, see [`zkVMClient::handshake`].
+    pub async fn upload_program(&self, elf: &[u8]) -> Result<([u8; 32], EncodedProgramVk), Error> {
+        let elf = zstd::encode_all(elf, 0)
+            .map_err(|err| CommonError::io("Failed to compress ELF", err))?;
+        let request = Request::new(UploadProgramRequest { elf });
+
+        let response = self.client.upload_program(request).await?.into_body();
+
+        match response.result.ok_or_else(result_none_err)? {
+            UploadProgramResult::Ok(result) => {
+                let program_digest = result
+                    .program_digest
+                    .try_into()
+                    .map_err(|_| twirp::internal("program digest should be 32 bytes"))?;
+                Ok((program_digest, EncodedProgramVk(result.program_vk)))
+            }
+            UploadProgramResult::Err(err) => Err(Error::from_response(err, response.error)?),
+        }
+    }
+
+    async fn prove_request(&self, input: Input) -> Result<ProveRequest, Error> {
+        let program_digest = self.program_digest()?;
+        self.check_kv_store(&input)?;
+        let stdin = self
+            .send_stdin(input.stdin, input.stdin_file, input.compression)
+            .await?;
+        Ok(ProveRequest {
+            input_stdin: stdin.input_stdin,
+            input_proofs: input.proofs,
+            input_stdin_compression: stdin.input_stdin_compression.into(),
+            input_kv_store: input.kv_store,
+            program_digest,
+            input_upload_id: stdin.input_upload_id,
+            input_stdin_file: stdin.input_stdin_file,
+            report_encoding: api_report_encoding(self.report_encoding()).into(),
+        })
+    }
+
+    async fn resume(
+        &self,
+        input_commitment: Vec<u8>,
+        job_id: Option<u64>,
+    ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport), Error> {
+        let omit_proof = self.streams();
+        let encoding = self.report_encoding();
+        let request = Request::new(ResumeProveRequest {
+            input_commitment,
+            job_id,
+            omit_proof,
+            report_encoding: api_report_encoding(encoding).into(),
+            program_digest: self.program_digest()?,
+        });
+
+        let response = self.client.resume_prove(request).await?.into_body();
+
+        let job_id = response.job_id;
+        let (public_values, proof, report) = decode_prove_response(response, encoding)?;
+        let proof = if omit_proof {
+            self.download_proof(job_id).await?
+        } else {
+            proof
+        };
+        Ok((public_values, proof, report))
+    }
+
+    /// Returns the encoding of reports the server supports, see [`zkVMClient::handshake`].
+    fn report_encoding(&self) -> ReportEncoding {
+        if self.protocol_version.load(Ordering::Relaxed) >= 5 {
+            ReportEncoding::Json
+        } else {
+            ReportEncoding::Legacy
         }
     }
+
+    /// Returns `true` if the server supports streaming, see [`zkVMClient::handshake`].
+    fn streams(&self) -> bool {
+        self.protocol_version.load(Ordering::Relaxed) >= 3
+    }
+
+    /// Returns [`CommonError::UnsupportedInput`] of `feature` if the negotiated protocol version
+    /// is lower than `version`, since the server would silently ignore the fields of requests it
+    /// doesn't know.
+    fn check_protocol_version(&self, version: u32, feature: &str) -> Result<(), CommonError> {
+        let negotiated = self.protocol_version.load(Ordering::Relaxed);
+        if negotiated < version {
+            return Err(CommonError::unsupported_input(format!(
+                "{feature} requires protocol version {version}, negotiated {negotiated}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the program digest of requests, see [`zkVMClient::with_program`].
+    ///
+    /// Requires protocol version 2 unless empty.
+    fn program_digest(&self) -> Result<Vec<u8>, CommonError> {
+        if !self.program_digest.is_empty() {
+            self.check_protocol_version(2, "program digest")?;
+        }
+        Ok(self.program_digest.clone())
+    }
+
+    /// Requires protocol version 1 if `input` has a key-value store.
+    fn check_kv_store(&self, input: &Input) -> Result<(), CommonError> {
+        if input.kv_store.is_some() {
+            self.check_protocol_version(1, "key-value store")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the stdin to be sent in the request if it's small. Otherwise writes it to a file
+    /// in the input directory (see [`zkVMClient::with_input_dir`]), or streams it by
+    /// `POST /inputs`, and returns the file name or upload id instead.
+    ///
+    /// Stdin set via [`Input::with_stdin_file`] is copied or streamed from its file, and only read
+    /// into memory if it's small enough to be sent in the request.
+    async fn send_stdin(
+        &self,
+        stdin: Vec<u8>,
+        stdin_file: Option<StdinFile>,
+        compression: Compression,
+    ) -> Result<StdinFields, Error> {
+        let len = stdin_file
+            .as_ref()
+            .map_or(stdin.len() as u64, StdinFile::len);
+
+        if let Some((dir, threshold)) = &self.input_dir
+            && len >= *threshold as u64
+            && self.protocol_version.load(Ordering::Relaxed) >= 4
+        {
+            let file = format!(
+                "{}-{}.stdin",
+                process::id(),
+                NEXT_INPUT_FILE.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = dir.join(&file);
+            match &stdin_file {
+                Some(stdin_file) => tokio::fs::copy(stdin_file.path(), &path).await.map(drop),
+                None => tokio::fs::write(&path, stdin).await,
+            }
+            .map_err(|err| CommonError::write_file("input stdin", &path, err))?;
+            return Ok(StdinFields {
+                input_stdin_file: Some(file),
+                ..StdinFields::inline(Vec::new(), ApiCompression::None)
+            });
+        }
+
+        if !self.streams() || len < STREAM_THRESHOLD as u64 {
+            let stdin = match &stdin_file {
+                Some(stdin_file) => tokio::fs::read(stdin_file.path())
+                    .await
+                    .map_err(|err| CommonError::read_file("input stdin", stdin_file.path(), err))?,
+                None => stdin,
+            };
+            // Compression of stdin in requests requires protocol version 1.
+            let compression = match self.protocol_version.load(Ordering::Relaxed) {
+                0 => Compression::None,
+                _ => compression,
+            };
+            let (stdin, compression) = compress_stdin(stdin, compression)?;
+            return Ok(StdinFields::inline(stdin, compression));
+        }
+
+        let mut request = self.raw_request(reqwest::Method::POST, "inputs")?;
+        if let Compression::Zstd = compression {
+            request = request.header(CONTENT_ENCODING, "zstd");
+        }
+        let body = match (stdin_file, compression) {
+            (Some(stdin_file), _) => {
+                let file = File::open(stdin_file.path())
+                    .map_err(|err| CommonError::read_file("input stdin", stdin_file.path(), err))?;
+                stream_stdin(file, compression)?
+            }
+            (None, Compression::None) => stdin.into(),
+            (None, Compression::Zstd) => stream_stdin(Cursor::new(stdin), compression)?,
+        };
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| twirp::unavailable(format!("failed to upload input: {err}")))?;
+        let upload_id = error_for_status(response)
+            .await?
+            .text()
+            .await
+            .map_err(|err| twirp::unavailable(format!("failed to upload input: {err}")))?
+            .parse::<u64>()
+            .map_err(|_| twirp::internal("upload id should be an integer"))?;
+        Ok(StdinFields {
+            input_upload_id: Some(upload_id),
+            ..StdinFields::inline(Vec::new(), ApiCompression::None)
+        })
+    }
+
+    /// Downloads the proof of the finished prove job streamed by `GET /proofs/{job_id}`.
+    async fn download_proof(&self, job_id: u64) -> Result<EncodedProof, Error> {
+        let response = self
+            .raw_request(reqwest::Method::GET, &format!("proofs/{job_id}"))?
+            .send()
+            .await
+            .map_err(|err| twirp::unavailable(format!("failed to download proof: {err}")))?;
+        let mut response = error_for_status(response).await?;
+
+        let mut proof = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| twirp::unavailable(format!("failed to download proof: {err}")))?
+        {
+            proof.extend_from_slice(&chunk);
+        }
+        Ok(EncodedProof(proof))
+    }
+
+    /// Returns a request to `path` of the server outside of Twirp, with the auth token if any.
+    fn raw_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder, Error> {
+        let request = self.http_client.request(method, self.endpoint.join(path)?);
+        Ok(match self.auth_token.header_value() {
+            Some(value) => request.header(AUTHORIZATION, value),
+            None => request,
+        })
+    }
+}
+
+fn decode_prove_response(
+    response: ProveResponse,
+    encoding: ReportEncoding,
+) -> Result<(PublicValues, EncodedProof, ProgramProvingReport), Error> {
+    match response.result.ok_or_else(result_none_err)? {
+        ProveResult::Ok(result) => Ok((
+            result.public_values.into(),
+            EncodedProof(result.proof),
+            ProgramProvingReport::decode(&result.report, encoding)
+                .map_err(deserialize_report_err)?,
+        )),
+        ProveResult::Err(err) => Err(Error::from_response(err, response.error)?),
+    }
+}
+
+/// Compresses `stdin` with `compression` for the request.
+fn compress_stdin(
+    stdin: Vec<u8>,
+    compression: Compression,
+) -> Result<(Vec<u8>, ApiCompression), CommonError> {
+    match compression {
+        Compression::None => Ok((stdin, ApiCompression::None)),
+        Compression::Zstd => zstd::encode_all(stdin.as_slice(), 0)
+            .map(|stdin| (stdin, ApiCompression::Zstd))
+            .map_err(|err| CommonError::io("Failed to compress input stdin", err)),
+    }
+}
+
+/// Streams stdin read from `reader` chunk by chunk, compressed with `compression`, instead of
+/// reading and compressing it whole before sending.
+fn stream_stdin(
+    reader: impl Read + Send + Sync + 'static,
+    compression: Compression,
+) -> Result<reqwest::Body, CommonError> {
+    let encoder = match compression {
+        Compression::None => None,
+        Compression::Zstd => Some(
+            zstd::stream::write::Encoder::new(Vec::new(), 0)
+                .map_err(|err| CommonError::io("Failed to compress input stdin", err))?,
+        ),
+    };
+    let chunks = stream::unfold(Some((reader, encoder)), |state| async move {
+        let (mut reader, mut encoder) = state?;
+        Some(match next_stdin_chunk(&mut reader, &mut encoder) {
+            Ok((chunk, false)) => (Ok(chunk), Some((reader, encoder))),
+            Ok((chunk, true)) => (Ok(chunk), None),
+            Err(err) => (Err(err), None),
+        })
+    });
+    Ok(reqwest::Body::wrap_stream(chunks))
+}
+
+/// Reads the next chunk of stdin from `reader` compressed with `encoder` if any, and returns it
+/// with whether it's the last one.
+fn next_stdin_chunk(
+    reader: &mut impl Read,
+    encoder: &mut Option<zstd::stream::write::Encoder<'static, Vec<u8>>>,
+) -> io::Result<(Vec<u8>, bool)> {
+    let mut chunk = Vec::with_capacity(STREAM_CHUNK_SIZE);
+    let n = reader
+        .by_ref()
+        .take(STREAM_CHUNK_SIZE as u64)
+        .read_to_end(&mut chunk)?;
+    let last = n < STREAM_CHUNK_SIZE;
+    let Some(mut inner) = encoder.take() else {
+        return Ok((chunk, last));
+    };
+    inner.write_all(&chunk)?;
+    if last {
+        return Ok((inner.finish()?, true));
+    }
+    let chunk = mem::take(inner.get_mut());
+    *encoder = Some(inner);
+    Ok((chunk, false))
+}
+
+/// Returns the Twirp error of the response to a request outside of Twirp, if it's not a success.
+async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response, Error> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.bytes().await.unwrap_or_default();
+    Err(serde_json::from_slice::<TwirpErrorResponse>(&body)
+        .unwrap_or_else(|_| twirp::internal(format!("unexpected response status {status}")))
+        .into())
 }
 
 fn result_none_err() -> TwirpErrorResponse {
     twirp::internal("response result should always be Some")
 }
 
-fn deserialize_report_err(err: bincode::error::DecodeError) -> TwirpErrorResponse {
+fn api_report_encoding(encoding: ReportEncoding) -> ApiReportEncoding {
+    match encoding {
+        ReportEncoding::Legacy => ApiReportEncoding::Legacy,
+        ReportEncoding::Json => ApiReportEncoding::Json,
+    }
+}
+
+fn deserialize_report_err(err: CommonError) -> TwirpErrorResponse {
     twirp::internal(format!("failed to deserialize report: {err}"))
 }
 
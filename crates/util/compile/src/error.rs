@@ -6,6 +6,8 @@ use std::{
 
 use thiserror::Error;
 
+use crate::CargoDiagnostic;
+
 #[derive(Debug, Error)]
 pub enum CommonError {
     #[error("{ctx}: {err}")]
@@ -40,6 +42,16 @@ pub enum CommonError {
         stderr: String,
     },
 
+    #[error("Command `{cmd}` exit with {status}{diagnostics}",
+        diagnostics = diagnostics.iter().map(|diagnostic| format!("\n{diagnostic}")).collect::<String>())]
+    CargoBuild {
+        cmd: String,
+        status: ExitStatus,
+        /// Errors emitted by `rustc`, empty if the build failed before compiling (e.g.
+        /// unresolvable dependencies).
+        diagnostics: Vec<CargoDiagnostic>,
+    },
+
     #[error("`cargo metadata` in {manifest_dir} failed: {err}")]
     CargoMetadata {
         manifest_dir: PathBuf,
@@ -56,6 +68,13 @@ pub enum CommonError {
         var_error: std::env::VarError,
     },
 
+    #[error("Guest pins toolchain {pinned} in {path}, which differs from {toolchain}")]
+    ToolchainMismatch {
+        path: PathBuf,
+        pinned: String,
+        toolchain: String,
+    },
+
     #[error("Failed to parse compiler args: {0}")]
     InvalidArgs(String),
 }
@@ -125,6 +144,18 @@ impl CommonError {
         }
     }
 
+    pub fn cargo_build(
+        cmd: &Command,
+        status: ExitStatus,
+        diagnostics: Vec<CargoDiagnostic>,
+    ) -> Self {
+        Self::CargoBuild {
+            cmd: format!("{cmd:?}"),
+            status,
+            diagnostics,
+        }
+    }
+
     pub fn cargo_metadata(manifest_dir: PathBuf, err: cargo_metadata::Error) -> Self {
         Self::CargoMetadata { manifest_dir, err }
     }
@@ -6,7 +6,9 @@ mod rust;
 pub use crate::{
     error::CommonError,
     rust::{
-        CargoBuildCmd, RustTarget, cargo_metadata, parse_cargo_features, rustc_path,
-        rustup_active_toolchain, rustup_add_components, rustup_add_rust_src, rustup_add_target,
+        CargoBuildCmd, CargoDiagnostic, CompileArgs, CompileOption, RustTarget,
+        RustToolchainPolicy, cargo_metadata, cargo_metadata_with_config, parse_cargo_features,
+        parse_compile_args, pinned_toolchain, rustc_path, rustup_active_toolchain,
+        rustup_add_components, rustup_add_rust_src, rustup_add_target,
     },
 };
@@ -1,36 +1,61 @@
-use core::iter;
+use core::{fmt, iter};
 use std::{
-    fs, io,
+    borrow::Cow,
+    env, fs,
+    io::{self, BufReader},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
     sync::Mutex,
 };
 
-use cargo_metadata::{Metadata, MetadataCommand};
-use clap::Parser;
+use cargo_metadata::{Message, Metadata, MetadataCommand, diagnostic::DiagnosticLevel};
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
 use tempfile::tempdir;
+use tracing::{info, warn};
 
 use crate::CommonError;
 
 const CARGO_ENCODED_RUSTFLAGS_SEPARATOR: &str = "\x1f";
 
 /// Target specification for cargo build.
-#[derive(Debug, Clone, Copy)]
+///
+/// Both static and owned strings are accepted, e.g. a target spec JSON embedded with
+/// `include_str!` or one read from a path given at runtime.
+#[derive(Debug, Clone)]
 pub enum RustTarget {
     /// Built-in target name (e.g., "riscv64im-unknown-none-elf").
-    Name(&'static str),
+    Name(Cow<'static, str>),
     /// Custom target specification JSON content.
     SpecJson {
         /// Target name (e.g., "riscv64ima-unknown-none-elf").
-        name: &'static str,
+        name: Cow<'static, str>,
         /// Raw JSON content of the target specification.
-        json: &'static str,
+        json: Cow<'static, str>,
     },
 }
 
 impl RustTarget {
+    /// Returns the custom target of the specification JSON file at `path`, named by its file stem
+    /// like cargo does.
+    pub fn spec_json_file(path: impl AsRef<Path>) -> Result<Self, CommonError> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| {
+                CommonError::invalid_args(format!("Target spec path {path:?} has no file name"))
+            })?;
+        let json = fs::read_to_string(path)
+            .map_err(|err| CommonError::read_file("target spec", path, err))?;
+        Ok(Self::SpecJson {
+            name: name.into(),
+            json: json.into(),
+        })
+    }
+
     /// Returns the target name.
-    pub const fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         match self {
             Self::Name(name) => name,
             Self::SpecJson { name, .. } => name,
@@ -40,7 +65,48 @@ impl RustTarget {
 
 impl From<&'static str> for RustTarget {
     fn from(name: &'static str) -> Self {
-        Self::Name(name)
+        Self::Name(name.into())
+    }
+}
+
+impl From<String> for RustTarget {
+    fn from(name: String) -> Self {
+        Self::Name(name.into())
+    }
+}
+
+/// Policy when the guest pins a toolchain in `rust-toolchain.toml` (or legacy `rust-toolchain`)
+/// that differs from the toolchain of the compiler.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RustToolchainPolicy {
+    /// Build with the toolchain pinned by the guest, unless the compiler is given an explicit
+    /// toolchain (e.g. by `ERE_RUST_TOOLCHAIN`), see [`CargoBuildCmd::explicit_toolchain`].
+    #[default]
+    Honour,
+    /// Fail the compilation.
+    Error,
+    /// Build with the toolchain of the compiler and log a warning.
+    Warn,
+    /// Build with the toolchain of the compiler silently.
+    ForcePin,
+}
+
+/// Error emitted by `rustc` while building a crate, collected by [`CargoBuildCmd::diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoDiagnostic {
+    /// Name of the crate that failed to build.
+    pub crate_name: String,
+    /// Error code (e.g., "E0463"), `None` if the error has no code.
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for CargoDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.code {
+            Some(code) => write!(f, "error[{code}] in {}: {}", self.crate_name, self.message),
+            None => write!(f, "error in {}: {}", self.crate_name, self.message),
+        }
     }
 }
 
@@ -48,22 +114,32 @@ impl From<&'static str> for RustTarget {
 #[derive(Clone)]
 pub struct CargoBuildCmd {
     toolchain: String,
+    explicit_toolchain: bool,
+    toolchain_policy: RustToolchainPolicy,
     profile: String,
     rustflags: Vec<String>,
     build_options: Vec<String>,
+    config: Vec<String>,
     linker_script: Option<String>,
     features: Vec<String>,
+    no_default_features: bool,
+    diagnostics: bool,
 }
 
 impl Default for CargoBuildCmd {
     fn default() -> Self {
         Self {
             toolchain: "stable".into(),
+            explicit_toolchain: false,
+            toolchain_policy: Default::default(),
             profile: "release".into(),
             rustflags: Default::default(),
             build_options: Default::default(),
+            config: Default::default(),
             linker_script: Default::default(),
             features: Default::default(),
+            no_default_features: Default::default(),
+            diagnostics: Default::default(),
         }
     }
 }
@@ -76,6 +152,30 @@ impl CargoBuildCmd {
     /// Toolchain to use.
     pub fn toolchain(mut self, toolchain: impl AsRef<str>) -> Self {
         self.toolchain = toolchain.as_ref().to_string();
+        self.explicit_toolchain = false;
+        self
+    }
+
+    /// Toolchain to use even if the guest pins another one under [`RustToolchainPolicy::Honour`],
+    /// e.g. one set by the user or a customized toolchain required by the target.
+    pub fn explicit_toolchain(mut self, toolchain: impl AsRef<str>) -> Self {
+        self.toolchain = toolchain.as_ref().to_string();
+        self.explicit_toolchain = true;
+        self
+    }
+
+    /// Toolchain set by `ERE_RUST_TOOLCHAIN` as [`CargoBuildCmd::explicit_toolchain`], or
+    /// `default` otherwise.
+    pub fn toolchain_from_env(self, default: impl AsRef<str>) -> Self {
+        match env::var("ERE_RUST_TOOLCHAIN") {
+            Ok(toolchain) => self.explicit_toolchain(toolchain),
+            Err(_) => self.toolchain(default),
+        }
+    }
+
+    /// Policy when the guest pins a different toolchain.
+    pub fn toolchain_policy(mut self, toolchain_policy: RustToolchainPolicy) -> Self {
+        self.toolchain_policy = toolchain_policy;
         self
     }
 
@@ -103,6 +203,13 @@ impl CargoBuildCmd {
         self
     }
 
+    /// Cargo configuration values in TOML (e.g. `patch.crates-io.sha2.git="..."`), each passed
+    /// as `--config`.
+    pub fn config(mut self, config: &[impl AsRef<str>]) -> Self {
+        self.config = config.iter().map(|v| v.as_ref().to_string()).collect();
+        self
+    }
+
     /// Linker script to be saved into a file and pass to `RUSTFLAGS`.
     pub fn linker_script(mut self, linker_script: Option<impl AsRef<str>>) -> Self {
         self.linker_script = linker_script.map(|v| v.as_ref().to_string());
@@ -115,6 +222,29 @@ impl CargoBuildCmd {
         self
     }
 
+    /// Whether to pass `--no-default-features`.
+    pub fn no_default_features(mut self, no_default_features: bool) -> Self {
+        self.no_default_features = no_default_features;
+        self
+    }
+
+    /// Whether to collect the errors of `rustc` into [`CommonError::CargoBuild`] when the build
+    /// fails, instead of only printing them.
+    pub fn diagnostics(mut self, diagnostics: bool) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Applies features, profile and toolchain policy of [`CompileArgs`].
+    pub fn compile_args(mut self, args: &CompileArgs) -> Self {
+        if let Some(profile) = &args.profile {
+            self = self.profile(profile);
+        }
+        self.features(&args.features)
+            .no_default_features(args.no_default_features)
+            .toolchain_policy(args.toolchain_policy)
+    }
+
     /// Takes the path to the manifest directory and the target, then
     /// runs configured `cargo build` and returns built ELF.
     pub fn exec(
@@ -125,12 +255,15 @@ impl CargoBuildCmd {
         let metadata = cargo_metadata(manifest_dir.as_ref())?;
         let package = metadata.root_package().unwrap();
 
+        let toolchain = self.resolve_toolchain(&metadata)?;
+        info!("Building {} with toolchain {toolchain}", package.name);
+
         if self
             .build_options
             .iter()
             .any(|opt| opt.contains("build-std"))
         {
-            rustup_add_rust_src(&self.toolchain)?;
+            rustup_add_rust_src(&toolchain)?;
         }
 
         let tempdir = tempdir().map_err(CommonError::tempdir)?;
@@ -146,7 +279,7 @@ impl CargoBuildCmd {
         }
 
         let target = target.into();
-        let target_arg = match target {
+        let target_arg = match &target {
             RustTarget::Name(name) => name.to_string(),
             RustTarget::SpecJson { name, json } => {
                 let json_name = format!("{name}.json");
@@ -175,42 +308,193 @@ impl CargoBuildCmd {
             .flatten();
 
         let args = iter::empty()
-            .chain([plus_toolchain(&self.toolchain)])
+            .chain([plus_toolchain(&toolchain)])
             .chain(["build".into()])
             .chain(self.build_options.iter().cloned())
+            .chain(
+                self.config
+                    .iter()
+                    .flat_map(|config| ["--config".into(), config.clone()]),
+            )
             .chain(["--profile".into(), self.profile.clone()])
             .chain(["--target".into(), target_arg])
             .chain(["--manifest-path".into(), package.manifest_path.to_string()])
-            .chain(features_args);
+            .chain(features_args)
+            .chain(
+                self.no_default_features
+                    .then(|| "--no-default-features".into()),
+            );
 
         let mut cmd = Command::new("cargo");
-        let status = cmd
-            .env("CARGO_ENCODED_RUSTFLAGS", encoded_rustflags)
-            .args(args)
-            .status()
-            .map_err(|err| CommonError::command(&cmd, err))?;
-
-        if !status.success() {
-            return Err(CommonError::command_exit_non_zero(&cmd, status, None));
+        cmd.env("CARGO_ENCODED_RUSTFLAGS", encoded_rustflags)
+            .args(args);
+        if self.diagnostics {
+            run_with_diagnostics(&mut cmd)?;
+        } else {
+            let status = cmd
+                .status()
+                .map_err(|err| CommonError::command(&cmd, err))?;
+            if !status.success() {
+                return Err(CommonError::command_exit_non_zero(&cmd, status, None));
+            }
         }
 
         let elf_path = metadata
             .target_directory
             .join(target.name())
-            .join(&self.profile)
+            .join(profile_dir(&self.profile))
             .join(&package.name);
         let elf =
             fs::read(&elf_path).map_err(|err| CommonError::read_file("elf", &elf_path, err))?;
 
         Ok(elf)
     }
+
+    /// Returns the effective toolchain to build the root package of `metadata` with, according
+    /// to the toolchain policy.
+    pub fn resolve_toolchain(&self, metadata: &Metadata) -> Result<String, CommonError> {
+        let package = metadata.root_package().unwrap();
+        let manifest_dir = package.manifest_path.parent().unwrap().as_std_path();
+        let Some((path, pinned)) =
+            pinned_toolchain(manifest_dir, metadata.workspace_root.as_std_path())?
+        else {
+            return Ok(self.toolchain.clone());
+        };
+
+        if pinned == self.toolchain {
+            return Ok(pinned);
+        }
+
+        match self.toolchain_policy {
+            RustToolchainPolicy::Honour if self.explicit_toolchain => {
+                info!(
+                    "Guest pins toolchain {pinned} in {}, building with explicit {} instead",
+                    path.display(),
+                    self.toolchain,
+                );
+                Ok(self.toolchain.clone())
+            }
+            RustToolchainPolicy::Honour => Ok(pinned),
+            RustToolchainPolicy::Error => Err(CommonError::ToolchainMismatch {
+                path,
+                pinned,
+                toolchain: self.toolchain.clone(),
+            }),
+            RustToolchainPolicy::Warn => {
+                warn!(
+                    "Guest pins toolchain {pinned} in {}, building with {} instead",
+                    path.display(),
+                    self.toolchain,
+                );
+                Ok(self.toolchain.clone())
+            }
+            RustToolchainPolicy::ForcePin => Ok(self.toolchain.clone()),
+        }
+    }
+}
+
+/// Runs `cargo build` with JSON messages, printing the rendered diagnostics as usual and
+/// collecting the errors into [`CommonError::CargoBuild`] if it fails.
+fn run_with_diagnostics(cmd: &mut Command) -> Result<(), CommonError> {
+    let mut child = cmd
+        .arg("--message-format=json-diagnostic-rendered-ansi")
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| CommonError::command(cmd, err))?;
+
+    let mut diagnostics = Vec::new();
+    let stdout = child.stdout.take().expect("stdout should be piped");
+    for message in Message::parse_stream(BufReader::new(stdout)) {
+        let message = message.map_err(|err| CommonError::command(cmd, err))?;
+        let Message::CompilerMessage(message) = message else {
+            continue;
+        };
+        if let Some(rendered) = &message.message.rendered {
+            eprint!("{rendered}");
+        }
+        if matches!(
+            message.message.level,
+            DiagnosticLevel::Error | DiagnosticLevel::Ice
+        ) {
+            diagnostics.push(CargoDiagnostic {
+                crate_name: message.target.name,
+                code: message.message.code.map(|code| code.code),
+                message: message.message.message,
+            });
+        }
+    }
+
+    let status = child.wait().map_err(|err| CommonError::command(cmd, err))?;
+    if !status.success() {
+        return Err(CommonError::cargo_build(cmd, status, diagnostics));
+    }
+    Ok(())
+}
+
+/// Returns path to the toolchain file and the toolchain pinned by it, searching from
+/// `manifest_dir` up to `workspace_root` like `rustup` does.
+pub fn pinned_toolchain(
+    manifest_dir: impl AsRef<Path>,
+    workspace_root: impl AsRef<Path>,
+) -> Result<Option<(PathBuf, String)>, CommonError> {
+    #[derive(Deserialize)]
+    struct ToolchainFile {
+        toolchain: Toolchain,
+    }
+
+    #[derive(Deserialize)]
+    struct Toolchain {
+        channel: String,
+    }
+
+    let workspace_root = workspace_root.as_ref();
+    for dir in manifest_dir.as_ref().ancestors() {
+        for file_name in ["rust-toolchain", "rust-toolchain.toml"] {
+            let path = dir.join(file_name);
+            if !path.is_file() {
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .map_err(|err| CommonError::read_file("toolchain file", &path, err))?;
+            // Legacy `rust-toolchain` could contain only the channel name.
+            let channel = match toml::from_str::<ToolchainFile>(&content) {
+                Ok(file) => file.toolchain.channel,
+                Err(_) if file_name == "rust-toolchain" && !content.trim().contains('\n') => {
+                    content.trim().to_string()
+                }
+                Err(err) => return Err(CommonError::deserialize("toolchain file", "toml", err)),
+            };
+            return Ok(Some((path, channel)));
+        }
+        if dir == workspace_root {
+            break;
+        }
+    }
+    Ok(None)
 }
 
 /// Returns `Metadata` of `manifest_dir` and guarantees the `root_package` can be resolved.
 pub fn cargo_metadata(manifest_dir: impl AsRef<Path>) -> Result<Metadata, CommonError> {
+    cargo_metadata_with_config(manifest_dir, &[] as &[&str])
+}
+
+/// Returns `Metadata` of `manifest_dir` like [`cargo_metadata`], resolved with the cargo
+/// configuration values passed to [`CargoBuildCmd::config`].
+pub fn cargo_metadata_with_config(
+    manifest_dir: impl AsRef<Path>,
+    config: &[impl AsRef<str>],
+) -> Result<Metadata, CommonError> {
     let manifest_dir = manifest_dir.as_ref().to_path_buf();
     let manifest_path = manifest_dir.join("Cargo.toml");
-    let metadata = match MetadataCommand::new().manifest_path(&manifest_path).exec() {
+    let config = config
+        .iter()
+        .flat_map(|config| ["--config".to_string(), config.as_ref().to_string()])
+        .collect::<Vec<_>>();
+    let metadata = match MetadataCommand::new()
+        .manifest_path(&manifest_path)
+        .other_options(config)
+        .exec()
+    {
         Ok(metadata) => metadata,
         Err(err) => return Err(CommonError::CargoMetadata { err, manifest_dir }),
     };
@@ -328,16 +612,238 @@ fn plus_toolchain(toolchain: &str) -> String {
     format!("+{toolchain}")
 }
 
+/// Returns the output directory name of cargo `profile`, where built-in `dev` and `test` are
+/// output to `debug`, and `bench` to `release`.
+fn profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        _ => profile,
+    }
+}
+
+/// Arguments to the compilers.
+#[derive(Parser, Debug, Clone, Default)]
+#[command(no_binary_name = true)]
+pub struct CompileArgs {
+    /// Cargo features to enable.
+    #[arg(short = 'F', long = "features", value_delimiter = ',')]
+    pub features: Vec<String>,
+    /// Do not enable the `default` feature.
+    #[arg(long)]
+    pub no_default_features: bool,
+    /// Cargo profile to build with (default `release`).
+    #[arg(long)]
+    pub profile: Option<String>,
+    /// Policy when the guest pins a different toolchain.
+    #[arg(long, value_enum, default_value_t)]
+    pub toolchain_policy: RustToolchainPolicy,
+    /// Patch dependencies with the forks accelerated by the zkVM precompiles (e.g. `sha2`).
+    #[arg(long)]
+    pub accelerator_patches: bool,
+}
+
+/// Option of [`CompileArgs`] besides features, which not every compiler supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompileOption {
+    NoDefaultFeatures,
+    Profile,
+    ToolchainPolicy,
+    AcceleratorPatches,
+}
+
+impl CompileOption {
+    /// Options supported by compilers building with [`CargoBuildCmd::compile_args`].
+    pub const CARGO_BUILD: &[Self] = &[
+        Self::NoDefaultFeatures,
+        Self::Profile,
+        Self::ToolchainPolicy,
+    ];
+}
+
+impl CompileArgs {
+    /// Returns error if an option not in `supported` is set, instead of ignoring it silently.
+    pub fn check_supported(&self, supported: &[CompileOption]) -> Result<(), CommonError> {
+        let options = [
+            (
+                CompileOption::NoDefaultFeatures,
+                "--no-default-features",
+                self.no_default_features,
+            ),
+            (CompileOption::Profile, "--profile", self.profile.is_some()),
+            (
+                CompileOption::ToolchainPolicy,
+                "--toolchain-policy",
+                self.toolchain_policy != RustToolchainPolicy::default(),
+            ),
+            (
+                CompileOption::AcceleratorPatches,
+                "--accelerator-patches",
+                self.accelerator_patches,
+            ),
+        ];
+        match options
+            .into_iter()
+            .find(|(option, _, set)| *set && !supported.contains(option))
+        {
+            Some((_, flag, _)) => Err(CommonError::invalid_args(format!(
+                "{flag} is not supported by the compiler"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse [`CompileArgs`] out of `args`.
+pub fn parse_compile_args(args: &[String]) -> Result<CompileArgs, CommonError> {
+    CompileArgs::try_parse_from(args).map_err(CommonError::invalid_args)
+}
+
 /// Parse cargo-style `--features` / `-F` flags out of `args`.
 pub fn parse_cargo_features(args: &[String]) -> Result<Vec<String>, CommonError> {
-    #[derive(Parser, Debug)]
-    #[command(no_binary_name = true)]
-    struct Args {
-        #[arg(short = 'F', long = "features", value_delimiter = ',')]
-        features: Vec<String>,
+    parse_compile_args(args).map(|args| args.features)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        CommonError,
+        rust::{
+            CargoBuildCmd, CompileOption, RustTarget, RustToolchainPolicy, cargo_metadata,
+            parse_compile_args, pinned_toolchain, profile_dir,
+        },
+    };
+
+    #[test]
+    fn test_spec_json_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("riscv64ima-custom-none-elf.json");
+        fs::write(&path, r#"{"arch":"riscv64"}"#).unwrap();
+        let target = RustTarget::spec_json_file(&path).unwrap();
+        assert_eq!(target.name(), "riscv64ima-custom-none-elf");
+        assert!(
+            matches!(&target, RustTarget::SpecJson { json, .. } if json == r#"{"arch":"riscv64"}"#)
+        );
+
+        let err = RustTarget::spec_json_file(dir.path().join("missing.json")).unwrap_err();
+        assert!(matches!(err, CommonError::Io { .. }));
     }
 
-    Args::try_parse_from(args)
-        .map(|p| p.features)
-        .map_err(CommonError::invalid_args)
+    #[test]
+    fn test_pinned_toolchain() {
+        let workspace_root = tempfile::tempdir().unwrap();
+        let manifest_dir = workspace_root.path().join("guest");
+        fs::create_dir(&manifest_dir).unwrap();
+        assert_eq!(
+            pinned_toolchain(&manifest_dir, &workspace_root).unwrap(),
+            None
+        );
+
+        let path = workspace_root.path().join("rust-toolchain.toml");
+        fs::write(&path, "[toolchain]\nchannel = \"nightly-2025-08-01\"\n").unwrap();
+        assert_eq!(
+            pinned_toolchain(&manifest_dir, &workspace_root).unwrap(),
+            Some((path, "nightly-2025-08-01".to_string())),
+        );
+
+        let path = manifest_dir.join("rust-toolchain");
+        fs::write(&path, "1.88.0\n").unwrap();
+        assert_eq!(
+            pinned_toolchain(&manifest_dir, &workspace_root).unwrap(),
+            Some((path, "1.88.0".to_string())),
+        );
+    }
+
+    #[test]
+    fn test_resolve_toolchain() {
+        let manifest_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            manifest_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"guest\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        fs::create_dir(manifest_dir.path().join("src")).unwrap();
+        fs::write(manifest_dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(
+            manifest_dir.path().join("rust-toolchain.toml"),
+            "[toolchain]\nchannel = \"nightly-2025-08-01\"\n",
+        )
+        .unwrap();
+        let metadata = cargo_metadata(&manifest_dir).unwrap();
+
+        let resolve = |cmd: CargoBuildCmd| cmd.resolve_toolchain(&metadata).unwrap();
+        assert_eq!(
+            resolve(CargoBuildCmd::new().toolchain("nightly")),
+            "nightly-2025-08-01"
+        );
+        assert_eq!(
+            resolve(CargoBuildCmd::new().explicit_toolchain("nightly")),
+            "nightly"
+        );
+        assert_eq!(
+            resolve(
+                CargoBuildCmd::new()
+                    .toolchain("nightly")
+                    .toolchain_policy(RustToolchainPolicy::ForcePin)
+            ),
+            "nightly"
+        );
+    }
+
+    #[test]
+    fn test_parse_compile_args() {
+        let args = parse_compile_args(&[]).unwrap();
+        assert!(args.features.is_empty());
+        assert!(!args.no_default_features);
+        assert_eq!(args.profile, None);
+        assert_eq!(args.toolchain_policy, RustToolchainPolicy::Honour);
+        assert!(!args.accelerator_patches);
+
+        let args = [
+            "-F",
+            "a,b",
+            "--no-default-features",
+            "--profile",
+            "dev",
+            "--toolchain-policy",
+            "force-pin",
+            "--accelerator-patches",
+        ]
+        .map(String::from);
+        let args = parse_compile_args(&args).unwrap();
+        assert_eq!(args.features, ["a", "b"]);
+        assert!(args.no_default_features);
+        assert_eq!(args.profile.as_deref(), Some("dev"));
+        assert_eq!(args.toolchain_policy, RustToolchainPolicy::ForcePin);
+        assert!(args.accelerator_patches);
+    }
+
+    #[test]
+    fn test_check_supported() {
+        let args = parse_compile_args(&["-F".to_string(), "a".to_string()]).unwrap();
+        args.check_supported(&[]).unwrap();
+
+        let args = parse_compile_args(&["--profile".to_string(), "dev".to_string()]).unwrap();
+        args.check_supported(CompileOption::CARGO_BUILD).unwrap();
+        assert!(matches!(
+            args.check_supported(&[CompileOption::NoDefaultFeatures]),
+            Err(CommonError::InvalidArgs(_))
+        ));
+
+        let args = parse_compile_args(&["--accelerator-patches".to_string()]).unwrap();
+        assert!(matches!(
+            args.check_supported(CompileOption::CARGO_BUILD),
+            Err(CommonError::InvalidArgs(_))
+        ));
+    }
+
+    #[test]
+    fn test_profile_dir() {
+        assert_eq!(profile_dir("dev"), "debug");
+        assert_eq!(profile_dir("release"), "release");
+        assert_eq!(profile_dir("bench"), "release");
+        assert_eq!(profile_dir("guest"), "guest");
+    }
 }
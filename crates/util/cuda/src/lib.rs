@@ -0,0 +1,107 @@
+use std::{env, process::Command};
+
+/// Parses a CUDA architecture (compute capability) in the format of `89`, `8.9` or `sm_89`.
+pub fn parse_cuda_arch(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let value = value.strip_prefix("sm_").unwrap_or(value);
+    value.replace('.', "").parse().ok()
+}
+
+/// Parses comma-separated CUDA architectures (e.g. `89,120`), sorted and deduplicated, skipping
+/// the invalid ones.
+pub fn parse_cuda_archs(value: &str) -> Vec<u32> {
+    let mut archs = value
+        .split(',')
+        .filter_map(parse_cuda_arch)
+        .collect::<Vec<_>>();
+    archs.sort_unstable();
+    archs.dedup();
+    archs
+}
+
+/// Detects CUDA compute capabilities of all visible GPUs by `nvidia-smi`.
+///
+/// Returns a sorted, deduplicated list of numeric compute capabilities (e.g. `[89, 120]` for a
+/// mix of RTX 40 and RTX 50 series GPUs), or an empty vec if `nvidia-smi` is not available or
+/// fails.
+pub fn detect_compute_caps() -> Vec<u32> {
+    let Ok(output) = Command::new("nvidia-smi")
+        .args(["--query-gpu=compute_cap", "--format=csv,noheader"])
+        .output()
+    else {
+        return vec![];
+    };
+    if !output.status.success() {
+        return vec![];
+    }
+    let caps = String::from_utf8_lossy(&output.stdout);
+    parse_cuda_archs(&caps.lines().collect::<Vec<_>>().join(","))
+}
+
+/// Returns the CUDA architectures to build GPU kernels for in build scripts, which is env `key`
+/// if set and not `native`, otherwise the compute capabilities of the GPUs of the build machine.
+///
+/// Returns an empty vec if neither is available.
+pub fn build_cuda_archs(key: &str) -> Vec<u32> {
+    println!("cargo:rerun-if-env-changed={key}");
+    match env::var(key) {
+        Ok(archs) if archs != "native" => parse_cuda_archs(&archs),
+        _ => detect_compute_caps(),
+    }
+}
+
+/// Returns `true` if GPU kernels compiled for `built` can run on a GPU of compute capability
+/// `device`.
+///
+/// Kernels are binary compatible with GPUs of the same major version and equal or higher minor
+/// version (e.g. `sm_80` kernels run on `sm_86` but not on `sm_75` or `sm_90`).
+pub fn is_cuda_arch_compatible(built: u32, device: u32) -> bool {
+    built / 10 == device / 10 && built <= device
+}
+
+/// Returns `true` if GPU kernels compiled for `built` can run on all GPUs of `devices`, see
+/// [`is_cuda_arch_compatible`].
+pub fn covers_cuda_archs(built: &[u32], devices: &[u32]) -> bool {
+    devices.iter().all(|device| {
+        built
+            .iter()
+            .any(|built| is_cuda_arch_compatible(*built, *device))
+    })
+}
+
+/// Returns the architectures of `devices` the kernels compiled for `built` run on without the
+/// exact architecture, which might be slower than kernels compiled for them.
+pub fn inexact_cuda_archs(built: &[u32], devices: &[u32]) -> Vec<u32> {
+    devices
+        .iter()
+        .copied()
+        .filter(|device| !built.contains(device))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{covers_cuda_archs, inexact_cuda_archs, parse_cuda_arch, parse_cuda_archs};
+
+    #[test]
+    fn test_parse_cuda_archs() {
+        assert_eq!(parse_cuda_arch("89"), Some(89));
+        assert_eq!(parse_cuda_arch(" 8.9 "), Some(89));
+        assert_eq!(parse_cuda_arch("sm_120"), Some(120));
+        assert_eq!(parse_cuda_arch("ampere"), None);
+        assert_eq!(parse_cuda_archs(""), Vec::<u32>::new());
+        assert_eq!(parse_cuda_archs("120,8.9, 89"), vec![89, 120]);
+    }
+
+    #[test]
+    fn test_covers_cuda_archs() {
+        assert!(covers_cuda_archs(&[89, 120], &[89]));
+        assert!(covers_cuda_archs(&[89, 120], &[89, 120]));
+        assert!(covers_cuda_archs(&[80], &[86, 89]));
+        assert!(!covers_cuda_archs(&[86], &[80]));
+        assert!(!covers_cuda_archs(&[90], &[89]));
+        assert!(!covers_cuda_archs(&[89], &[89, 120]));
+        assert!(!covers_cuda_archs(&[100], &[120]));
+        assert_eq!(inexact_cuda_archs(&[80, 120], &[86, 120]), vec![86]);
+    }
+}
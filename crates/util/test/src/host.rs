@@ -1,11 +1,14 @@
-use core::{marker::PhantomData, ops::Deref};
-use std::{env, fs, path::PathBuf};
+use core::{fmt, marker::PhantomData, ops::Deref};
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
 
 use ere_codec::{Decode, Encode};
-use ere_prover_core::{Input, PublicValues, zkVMProver};
+use ere_prover_core::{Input, ProgramExecutionReport, PublicValues, zkVMProver};
 use sha2::{Digest, Sha256};
 
-use crate::program::Program;
+use crate::program::{
+    Program,
+    primitives::{Primitive, PrimitivesProgram},
+};
 
 fn workspace() -> PathBuf {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -55,6 +58,98 @@ pub fn run_zkvm_prove(zkvm: &impl zkVMProver, test_case: &impl TestCase) -> Publ
     verifier_public_values
 }
 
+/// Cycles per operation of a [`Primitive`] measured by [`measure_primitive_costs`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrimitiveCost {
+    pub primitive: Primitive,
+    pub size: u32,
+    pub cycles_per_op: f64,
+}
+
+/// Measures cycles per operation of [`PrimitivesProgram::SUITE`] by `execute` with `iterations`
+/// and `2 * iterations`, so the fixed cost of the program cancels out.
+///
+/// The `execute` should execute the `primitives` guest program of a zkVM, e.g. by
+/// [`zkVMProver::execute`] or a zkVM running in a container.
+pub fn measure_primitive_costs<E: fmt::Debug>(
+    execute: impl Fn(&Input) -> Result<(PublicValues, ProgramExecutionReport), E>,
+    iterations: u32,
+) -> Vec<PrimitiveCost> {
+    let total_num_cycles = |primitive, iterations, size| {
+        let test_case = PrimitivesProgram::test_case(primitive, iterations, size);
+        let (public_values, report) =
+            execute(&test_case.input()).expect("execute should not fail with valid input");
+        test_case.assert_output(&public_values);
+        report.total_num_cycles
+    };
+
+    PrimitivesProgram::SUITE
+        .iter()
+        .map(|&(primitive, size)| {
+            let base = total_num_cycles(primitive, iterations, size);
+            let doubled = total_num_cycles(primitive, 2 * iterations, size);
+            PrimitiveCost {
+                primitive,
+                size,
+                cycles_per_op: doubled.saturating_sub(base) as f64 / iterations.max(1) as f64,
+            }
+        })
+        .collect()
+}
+
+/// Table of [`PrimitiveCost`]s across zkVMs, displayed in Markdown with a row per primitive and
+/// a column per zkVM.
+///
+/// The table of all zkVMs is built by the ignored `test_primitive_cost_table` of
+/// `ere-dockerized`.
+#[derive(Clone, Debug, Default)]
+pub struct PrimitiveCostTable {
+    zkvms: Vec<String>,
+    costs: BTreeMap<(Primitive, u32), BTreeMap<String, f64>>,
+}
+
+impl PrimitiveCostTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts costs measured with the zkVM `name`.
+    pub fn insert(&mut self, name: &str, costs: &[PrimitiveCost]) {
+        if !self.zkvms.iter().any(|zkvm| zkvm == name) {
+            self.zkvms.push(name.to_string());
+        }
+        for cost in costs {
+            self.costs
+                .entry((cost.primitive, cost.size))
+                .or_default()
+                .insert(name.to_string(), cost.cycles_per_op);
+        }
+    }
+}
+
+impl fmt::Display for PrimitiveCostTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "| Primitive | Size |")?;
+        for zkvm in &self.zkvms {
+            write!(f, " {zkvm} |")?;
+        }
+        write!(f, "\n|---|---|")?;
+        for _ in &self.zkvms {
+            write!(f, "---|")?;
+        }
+        for ((primitive, size), costs) in &self.costs {
+            write!(f, "\n| {primitive:?} | {size} |")?;
+            for zkvm in &self.zkvms {
+                match costs.get(zkvm) {
+                    Some(cycles_per_op) => write!(f, " {cycles_per_op:.1} |")?,
+                    None => write!(f, " - |")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Test case for specific [`Program`] that provides serialized
 /// [`Program::Input`], and is able to assert if the [`PublicValues`] returned
 /// by [`zkVMProver`] methods is correct or not.
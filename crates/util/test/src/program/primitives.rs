@@ -0,0 +1,187 @@
+use alloc::{vec, vec::Vec};
+use core::hint::black_box;
+
+use ere_codec::impl_codec_by_bincode_legacy;
+use k256::ecdsa::{
+    Signature, SigningKey,
+    signature::{Signer, Verifier},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::program::Program;
+
+/// Primitive operation measured by [`PrimitivesProgram`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Primitive {
+    /// SHA-256 of `size` bytes chained with the previous digest.
+    ///
+    /// The guests link the unpatched `sha2` crate, so this measures SHA-256 in software rather
+    /// than the accelerated precompile of the zkVM.
+    Sha256,
+    /// Copy of `size` bytes between buffers.
+    Memcpy,
+    /// Wrapping multiplication of 256-bit integers, `size` is ignored.
+    BigintMul,
+    /// ECDSA verification over secp256k1 of a signature of a message of `size` bytes.
+    ///
+    /// Like [`Primitive::Sha256`], the guests link the unpatched `k256` crate, so this measures
+    /// the curve arithmetic in software.
+    EcdsaVerify,
+}
+
+/// The primitives program runs a [`Primitive`] for `iterations` times, and outputs a checksum of
+/// the final state so the loop can't be optimized out.
+///
+/// The cost per operation is measured by the difference in cycles of different `iterations`,
+/// which cancels out the fixed cost of reading input and writing output.
+pub struct PrimitivesProgram;
+
+impl Program for PrimitivesProgram {
+    type Input = PrimitivesProgramInput;
+    type Output = PrimitivesProgramOutput;
+
+    fn compute(input: PrimitivesProgramInput) -> PrimitivesProgramOutput {
+        let size = input.size as usize;
+        let checksum = match input.op {
+            Primitive::Sha256 => {
+                let data = vec![0xa5; size];
+                let mut digest = [0; 32];
+                for _ in 0..input.iterations {
+                    digest = Sha256::new()
+                        .chain_update(digest)
+                        .chain_update(black_box(&data))
+                        .finalize()
+                        .into();
+                }
+                digest
+            }
+            Primitive::Memcpy => {
+                let mut src = vec![0; size];
+                let mut dst = vec![0; size];
+                for i in 0..input.iterations {
+                    if let Some(byte) = src.first_mut() {
+                        *byte = i as u8;
+                    }
+                    dst.copy_from_slice(black_box(&src));
+                    core::mem::swap(&mut src, &mut dst);
+                }
+                Sha256::digest(&src).into()
+            }
+            Primitive::BigintMul => {
+                let mut acc = [1, 0, 0, 0];
+                let factor = [
+                    0x9e37_79b9_7f4a_7c15,
+                    0xbf58_476d_1ce4_e5b9,
+                    0x94d0_49bb_1331_11eb,
+                    0x2545_f491_4f6c_dd1d,
+                ];
+                for _ in 0..input.iterations {
+                    acc = mul_u256(black_box(acc), factor);
+                }
+                let mut checksum = [0; 32];
+                for (chunk, limb) in checksum.chunks_exact_mut(8).zip(acc) {
+                    chunk.copy_from_slice(&limb.to_le_bytes());
+                }
+                checksum
+            }
+            Primitive::EcdsaVerify => {
+                // Signed once with a fixed key, which is part of the fixed cost.
+                let message = vec![0xa5; size];
+                let signing_key = SigningKey::from_bytes(&[0x42; 32].into()).unwrap();
+                let signature: Signature = signing_key.sign(&message);
+                let verifying_key = signing_key.verifying_key();
+                let mut verified = 0u32;
+                for _ in 0..input.iterations {
+                    if verifying_key
+                        .verify(black_box(&message), black_box(&signature))
+                        .is_ok()
+                    {
+                        verified += 1;
+                    }
+                }
+                Sha256::new()
+                    .chain_update(signature.to_bytes())
+                    .chain_update(verified.to_le_bytes())
+                    .finalize()
+                    .into()
+            }
+        };
+        PrimitivesProgramOutput { checksum }
+    }
+}
+
+/// Schoolbook multiplication of 256-bit integers in little-endian u64 limbs, truncated to 256 bits.
+fn mul_u256(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    let mut out = [0u64; 4];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 - i {
+            let t = a[i] as u128 * b[j] as u128 + out[i + j] as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+    }
+    out
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrimitivesProgramInput {
+    pub op: Primitive,
+    pub iterations: u32,
+    pub size: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrimitivesProgramOutput {
+    pub checksum: [u8; 32],
+}
+
+impl_codec_by_bincode_legacy!(PrimitivesProgramInput);
+impl_codec_by_bincode_legacy!(PrimitivesProgramOutput);
+
+#[cfg(feature = "host")]
+mod host {
+    use crate::{
+        host::ProgramTestCase,
+        program::primitives::{Primitive, PrimitivesProgram, PrimitivesProgramInput},
+    };
+
+    impl PrimitivesProgram {
+        /// Primitives with sizes measured by [`measure_primitive_costs`].
+        ///
+        /// [`measure_primitive_costs`]: crate::host::measure_primitive_costs
+        pub const SUITE: &[(Primitive, u32)] = &[
+            (Primitive::Sha256, 32),
+            (Primitive::Sha256, 1024),
+            (Primitive::Memcpy, 1024),
+            (Primitive::Memcpy, 16384),
+            (Primitive::BigintMul, 0),
+            (Primitive::EcdsaVerify, 32),
+        ];
+
+        pub fn test_case(op: Primitive, iterations: u32, size: u32) -> ProgramTestCase<Self> {
+            ProgramTestCase::new(PrimitivesProgramInput {
+                op,
+                iterations,
+                size,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::program::primitives::mul_u256;
+
+    #[test]
+    fn test_mul_u256() {
+        assert_eq!(mul_u256([3, 0, 0, 0], [5, 0, 0, 0]), [15, 0, 0, 0]);
+        assert_eq!(
+            mul_u256([u64::MAX, 0, 0, 0], [2, 0, 0, 0]),
+            [u64::MAX - 1, 1, 0, 0]
+        );
+        assert_eq!(mul_u256([0, 0, 0, 1], [0, 1, 0, 0]), [0, 0, 0, 0]);
+        assert_eq!(mul_u256([0, 1, 0, 0], [0, 0, 1, 0]), [0, 0, 0, 1]);
+    }
+}
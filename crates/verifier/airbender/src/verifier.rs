@@ -55,6 +55,10 @@ impl zkVMVerifier for AirbenderVerifier {
         Ok(words_to_le_bytes(public_values).into())
     }
 
+    fn public_values_padded_len(&self) -> Option<usize> {
+        Some(32)
+    }
+
     fn name(&self) -> &'static str {
         NAME
     }
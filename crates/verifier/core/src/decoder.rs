@@ -0,0 +1,144 @@
+use thiserror::Error;
+
+/// Convention a guest program follows to write its output via `Platform::write_output`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PublicValuesEncoding {
+    /// Output is written as-is.
+    Raw,
+    /// Output is prefixed with its u32 LE byte length.
+    LengthPrefixed,
+    /// Digest of `digest_len` bytes of the output is written instead (e.g. SHA-256 with 32).
+    Hashed { digest_len: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum PublicValuesDecodeError {
+    #[error("Public values too short, expected at least {expected} bytes, got {got} bytes")]
+    TooShort { expected: usize, got: usize },
+
+    #[error("Public values have {trailing} trailing bytes without padding")]
+    TrailingBytes { trailing: usize },
+
+    #[error("Public values have non-zero padding at byte {index}")]
+    NonZeroPadding { index: usize },
+}
+
+/// Decodes public values written by a guest program following a [`PublicValuesEncoding`].
+///
+/// zkVMs with fixed size public values (e.g. OpenVM and Airbender) zero-pad the output to 32
+/// bytes, while others (e.g. SP1) commit the output as-is. The padding is checked and stripped
+/// where the encoding allows to determine the output length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicValuesDecoder {
+    encoding: PublicValuesEncoding,
+    padded_len: Option<usize>,
+}
+
+impl PublicValuesDecoder {
+    pub fn new(encoding: PublicValuesEncoding) -> Self {
+        Self {
+            encoding,
+            padded_len: None,
+        }
+    }
+
+    /// Sets the length the zkVM pads the output to, see [`zkVMVerifier::public_values_padded_len`].
+    ///
+    /// [`zkVMVerifier::public_values_padded_len`]: crate::zkVMVerifier::public_values_padded_len
+    pub fn with_padded_len(mut self, padded_len: Option<usize>) -> Self {
+        self.padded_len = padded_len;
+        self
+    }
+
+    /// Decodes the output from `public_values`.
+    ///
+    /// For [`PublicValuesEncoding::Raw`] the output length is unknown, so padding is kept.
+    pub fn decode<'a>(&self, public_values: &'a [u8]) -> Result<&'a [u8], PublicValuesDecodeError> {
+        let len = match self.encoding {
+            PublicValuesEncoding::Raw => return Ok(public_values),
+            PublicValuesEncoding::LengthPrefixed => {
+                let prefix = ensure_len(public_values, 4)?;
+                let len = u32::from_le_bytes(prefix.try_into().unwrap());
+                // Overflows only on 16/32-bit targets, where such output can't fit anyway.
+                usize::try_from(len)
+                    .ok()
+                    .and_then(|len| len.checked_add(4))
+                    .ok_or(PublicValuesDecodeError::TooShort {
+                        expected: usize::MAX,
+                        got: public_values.len(),
+                    })?
+            }
+            PublicValuesEncoding::Hashed { digest_len } => digest_len,
+        };
+
+        let output = ensure_len(public_values, len)?;
+        let trailing = &public_values[len..];
+        if self.padded_len.is_none() && !trailing.is_empty() {
+            return Err(PublicValuesDecodeError::TrailingBytes {
+                trailing: trailing.len(),
+            });
+        }
+        if let Some(index) = trailing.iter().position(|byte| *byte != 0) {
+            return Err(PublicValuesDecodeError::NonZeroPadding { index: len + index });
+        }
+
+        Ok(match self.encoding {
+            PublicValuesEncoding::LengthPrefixed => &output[4..],
+            _ => output,
+        })
+    }
+}
+
+fn ensure_len(public_values: &[u8], len: usize) -> Result<&[u8], PublicValuesDecodeError> {
+    public_values
+        .get(..len)
+        .ok_or(PublicValuesDecodeError::TooShort {
+            expected: len,
+            got: public_values.len(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decoder::{PublicValuesDecodeError, PublicValuesDecoder, PublicValuesEncoding};
+
+    #[test]
+    fn test_decode() {
+        let raw = PublicValuesDecoder::new(PublicValuesEncoding::Raw);
+        assert_eq!(raw.decode(&[1, 2, 0]).unwrap(), [1, 2, 0]);
+
+        let length_prefixed = PublicValuesDecoder::new(PublicValuesEncoding::LengthPrefixed);
+        assert_eq!(length_prefixed.decode(&[2, 0, 0, 0, 1, 2]).unwrap(), [1, 2]);
+        assert!(matches!(
+            length_prefixed.decode(&[3, 0, 0, 0, 1, 2]),
+            Err(PublicValuesDecodeError::TooShort {
+                expected: 7,
+                got: 6
+            })
+        ));
+        assert!(matches!(
+            length_prefixed.decode(&[0xff, 0xff, 0xff, 0xff]),
+            Err(PublicValuesDecodeError::TooShort { got: 4, .. })
+        ));
+        assert!(matches!(
+            length_prefixed.decode(&[2, 0, 0, 0, 1, 2, 0]),
+            Err(PublicValuesDecodeError::TrailingBytes { trailing: 1 })
+        ));
+
+        let mut padded = [0; 32];
+        padded[..6].copy_from_slice(&[2, 0, 0, 0, 1, 2]);
+        let length_prefixed = length_prefixed.with_padded_len(Some(32));
+        assert_eq!(length_prefixed.decode(&padded).unwrap(), [1, 2]);
+        padded[31] = 1;
+        assert!(matches!(
+            length_prefixed.decode(&padded),
+            Err(PublicValuesDecodeError::NonZeroPadding { index: 31 })
+        ));
+
+        let hashed = PublicValuesDecoder::new(PublicValuesEncoding::Hashed { digest_len: 4 })
+            .with_padded_len(Some(32));
+        let mut padded = [0; 32];
+        padded[..4].copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(hashed.decode(&padded).unwrap(), [1, 2, 3, 4]);
+    }
+}
@@ -1,6 +1,13 @@
+mod decoder;
 mod public_values;
+mod schema;
 mod verifier;
 
 pub use ere_codec as codec;
 
-pub use crate::{public_values::PublicValues, verifier::zkVMVerifier};
+pub use crate::{
+    decoder::{PublicValuesDecodeError, PublicValuesDecoder, PublicValuesEncoding},
+    public_values::PublicValues,
+    schema::{TypedVerifier, TypedVerifyError},
+    verifier::zkVMVerifier,
+};
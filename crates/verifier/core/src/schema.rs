@@ -0,0 +1,149 @@
+use core::{error::Error, fmt::Debug, marker::PhantomData};
+
+use thiserror::Error;
+
+use crate::{
+    PublicValuesDecodeError, PublicValuesDecoder, PublicValuesEncoding, codec::Decode, zkVMVerifier,
+};
+
+#[derive(Debug, Error)]
+pub enum TypedVerifyError<E> {
+    #[error(transparent)]
+    Verify(E),
+
+    #[error(transparent)]
+    PublicValues(#[from] PublicValuesDecodeError),
+
+    #[error("Decode output failed: {0}")]
+    Output(Box<dyn Error + Send + Sync>),
+}
+
+/// zkVM verifier with the schema of public values registered, which returns the output decoded
+/// as `T` instead of raw public values.
+///
+/// The zkVM padding is stripped according to [`zkVMVerifier::public_values_padded_len`] before
+/// decoding, so the same `T` decodes public values of any zkVM.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let verifier = TypedVerifier::<_, [u8; 32]>::new(verifier, PublicValuesEncoding::LengthPrefixed);
+/// let block_hash = verifier.verify(&proof)?;
+/// ```
+pub struct TypedVerifier<V, T> {
+    verifier: V,
+    decoder: PublicValuesDecoder,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<V: zkVMVerifier, T: Decode> TypedVerifier<V, T> {
+    /// Registers that the guest writes output of `T` following `encoding` for `verifier`.
+    pub fn new(verifier: V, encoding: PublicValuesEncoding) -> Self {
+        let decoder =
+            PublicValuesDecoder::new(encoding).with_padded_len(verifier.public_values_padded_len());
+        Self {
+            verifier,
+            decoder,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying verifier.
+    pub fn verifier(&self) -> &V {
+        &self.verifier
+    }
+
+    /// Verifies a proof and returns the output decoded from the public values.
+    pub fn verify(&self, proof: &V::Proof) -> Result<T, TypedVerifyError<V::Error>> {
+        let public_values = self
+            .verifier
+            .verify(proof)
+            .map_err(TypedVerifyError::Verify)?;
+        self.decode(&public_values)
+    }
+
+    /// Decodes the output from `public_values` (e.g. returned by execute).
+    pub fn decode(&self, public_values: &[u8]) -> Result<T, TypedVerifyError<V::Error>> {
+        let output = self.decoder.decode(public_values)?;
+        T::decode_from_slice(output).map_err(|err| TypedVerifyError::Output(Box::new(err)))
+    }
+}
+
+impl<V: Clone, T> Clone for TypedVerifier<V, T> {
+    fn clone(&self) -> Self {
+        Self {
+            verifier: self.verifier.clone(),
+            decoder: self.decoder,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<V: Debug, T> Debug for TypedVerifier<V, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TypedVerifier")
+            .field("verifier", &self.verifier)
+            .field("decoder", &self.decoder)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::Infallible;
+
+    use crate::{
+        PublicValues, PublicValuesDecodeError, PublicValuesEncoding,
+        schema::{TypedVerifier, TypedVerifyError},
+        zkVMVerifier,
+    };
+
+    /// Verifier that returns the proof as public values, padded to 32 bytes.
+    #[derive(Clone, Copy, Debug)]
+    struct PaddedVerifier;
+
+    impl zkVMVerifier for PaddedVerifier {
+        type ProgramVk = ();
+        type Proof = Vec<u8>;
+        type Error = Infallible;
+
+        fn verify(&self, proof: &Vec<u8>) -> Result<PublicValues, Infallible> {
+            let mut public_values = proof.clone();
+            public_values.resize(32, 0);
+            Ok(public_values.into())
+        }
+
+        fn program_vk(&self) -> &() {
+            &()
+        }
+
+        fn public_values_padded_len(&self) -> Option<usize> {
+            Some(32)
+        }
+
+        fn name(&self) -> &'static str {
+            "padded"
+        }
+
+        fn sdk_version(&self) -> &'static str {
+            "0.0.0"
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let verifier =
+            TypedVerifier::<_, [u8; 2]>::new(PaddedVerifier, PublicValuesEncoding::LengthPrefixed);
+        assert_eq!(verifier.verify(&vec![2, 0, 0, 0, 1, 2]).unwrap(), [1, 2]);
+        assert!(matches!(
+            verifier.verify(&vec![3, 0, 0, 0, 1, 2, 3]),
+            Err(TypedVerifyError::Output(_))
+        ));
+        assert!(matches!(
+            verifier.verify(&vec![64, 0, 0, 0]),
+            Err(TypedVerifyError::PublicValues(
+                PublicValuesDecodeError::TooShort { .. }
+            ))
+        ));
+    }
+}
@@ -23,6 +23,12 @@ pub trait zkVMVerifier: 'static + Send + Sync + Clone + Copy + Debug {
     /// Returns the verifying key for the specific program.
     fn program_vk(&self) -> &Self::ProgramVk;
 
+    /// Returns the length the zkVM zero-pads the guest output to in public values, `None` if the
+    /// output is committed as-is.
+    fn public_values_padded_len(&self) -> Option<usize> {
+        None
+    }
+
     /// Returns the name of the zkVM.
     fn name(&self) -> &'static str;
 
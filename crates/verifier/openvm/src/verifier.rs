@@ -43,6 +43,10 @@ impl zkVMVerifier for OpenVMVerifier {
         extract_public_values(&proof.0.user_public_values)
     }
 
+    fn public_values_padded_len(&self) -> Option<usize> {
+        Some(32)
+    }
+
     fn name(&self) -> &'static str {
         NAME
     }
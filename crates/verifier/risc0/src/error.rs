@@ -1,7 +1,10 @@
 use alloc::string::String;
 
+use ere_verifier_core::PublicValuesDecodeError;
 use thiserror::Error;
 
+use crate::Risc0ProgramVk;
+
 #[derive(Debug, Error)]
 pub enum Error {
     /// Failed to deserialize a proof.
@@ -12,10 +15,21 @@ pub enum Error {
     #[error("Invalid ProgramVk length, expected: {expected}, got: {got}")]
     InvalidProgramVkLength { expected: usize, got: usize },
 
-    /// Inner receipt was not `Succinct`.
-    #[error("Unexpected proof kind, expected: Succinct, got: {0}")]
+    /// Inner receipt was not `Succinct` or `Groth16`.
+    #[error("Unexpected proof kind, expected: Succinct or Groth16, got: {0}")]
     UnexpectedProofKind(String),
 
+    /// Receipt was proven for another program.
+    #[error("Image ID mismatch, expected: {expected:?}, got: {got:?}")]
+    ImageIdMismatch {
+        expected: Risc0ProgramVk,
+        got: Risc0ProgramVk,
+    },
+
+    /// Journal doesn't follow the expected `PublicValuesEncoding`.
+    #[error("Failed to decode journal: {0}")]
+    DecodeJournal(#[from] PublicValuesDecodeError),
+
     /// Upstream `risc0-zkp` rejected the proof.
     #[error("Failed to verify: {0}")]
     Verify(risc0_zkp::verify::VerificationError),
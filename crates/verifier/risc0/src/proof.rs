@@ -5,7 +5,8 @@ use serde::{Deserialize, Serialize};
 
 /// A proof produced by the host prover that bundles everything needed for verification.
 ///
-/// Wraps a `risc0_zkvm::Receipt`; verifiable only when its `inner` is `InnerReceipt::Succinct`.
+/// Wraps a `risc0_zkvm::Receipt`; verifiable when its `inner` is `InnerReceipt::Succinct` or
+/// `InnerReceipt::Groth16`.
 /// Serialized via bincode legacy.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(transparent)]
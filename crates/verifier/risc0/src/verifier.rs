@@ -1,7 +1,10 @@
-use alloc::string::ToString;
+use alloc::{string::ToString, vec::Vec};
 
-use ere_verifier_core::{PublicValues, zkVMVerifier};
-use risc0_zkvm::InnerReceipt;
+use ere_verifier_core::{PublicValues, PublicValuesDecoder, PublicValuesEncoding, zkVMVerifier};
+use risc0_zkvm::{
+    InnerReceipt, Receipt,
+    sha::{Digestible, Impl},
+};
 
 use crate::{Error, Risc0ProgramVk, Risc0Proof};
 
@@ -21,6 +24,43 @@ impl Risc0Verifier {
     pub fn new(program_vk: Risc0ProgramVk) -> Self {
         Self { program_vk }
     }
+
+    /// Verifies `proof` and decodes the output the guest wrote via `Platform::write_output` from
+    /// the journal, following `encoding`.
+    ///
+    /// For [`PublicValuesEncoding::Hashed`] the digest is returned, which the caller compares
+    /// against the digest of the expected output.
+    pub fn verify_and_decode_journal(
+        &self,
+        proof: &Risc0Proof,
+        encoding: PublicValuesEncoding,
+    ) -> Result<Vec<u8>, Error> {
+        let public_values = self.verify(proof)?;
+        let output = PublicValuesDecoder::new(encoding)
+            .with_padded_len(self.public_values_padded_len())
+            .decode(&public_values)?;
+        Ok(output.to_vec())
+    }
+
+    /// Returns error if the claim of `receipt` is for another image id than the program.
+    ///
+    /// Pruned claims can't be checked here, which are checked by `Receipt::verify` instead.
+    fn check_image_id(&self, receipt: &Receipt) -> Result<(), Error> {
+        let Ok(claim) = receipt.claim() else {
+            return Ok(());
+        };
+        let Ok(claim) = claim.as_value() else {
+            return Ok(());
+        };
+        let image_id = claim.pre.digest::<Impl>();
+        if image_id != self.program_vk.0 {
+            return Err(Error::ImageIdMismatch {
+                expected: self.program_vk,
+                got: Risc0ProgramVk(image_id),
+            });
+        }
+        Ok(())
+    }
 }
 
 impl zkVMVerifier for Risc0Verifier {
@@ -35,17 +75,21 @@ impl zkVMVerifier for Risc0Verifier {
     fn verify(&self, proof: &Risc0Proof) -> Result<PublicValues, Self::Error> {
         let receipt = &proof.0;
 
-        if !matches!(receipt.inner, InnerReceipt::Succinct(_)) {
+        // Fake receipts (from dev mode) are rejected since they prove nothing, and composite ones
+        // since the prover never produces them.
+        if !matches!(
+            receipt.inner,
+            InnerReceipt::Succinct(_) | InnerReceipt::Groth16(_)
+        ) {
             let got = match &receipt.inner {
                 InnerReceipt::Composite(_) => "Composite",
-                InnerReceipt::Succinct(_) => "Succinct",
-                InnerReceipt::Groth16(_) => "Groth16",
                 InnerReceipt::Fake(_) => "Fake",
                 _ => "Unknown",
             };
             return Err(Error::UnexpectedProofKind(got.to_string()));
         }
 
+        self.check_image_id(receipt)?;
         receipt.verify(self.program_vk.0).map_err(Error::Verify)?;
 
         Ok(receipt.journal.bytes.as_slice().into())
@@ -1,5 +1,5 @@
 use bincode::error::DecodeError;
-use ere_verifier_core::{codec::Decode, zkVMVerifier};
+use ere_verifier_core::{PublicValuesEncoding, codec::Decode, zkVMVerifier};
 use ere_verifier_risc0::{Error, Risc0ProgramVk, Risc0Proof, Risc0Verifier};
 use risc0_zkvm::InnerReceipt;
 
@@ -74,7 +74,34 @@ fn test_invalid_proof_verify() {
     let verifier = verifier_with_unexpected_program_vk();
     let proof = Risc0Proof::decode_from_slice(PROOF).unwrap();
     let err = verifier.verify(&proof).unwrap_err();
-    assert!(matches!(err, Error::Verify(_)));
+    assert!(matches!(err, Error::ImageIdMismatch { .. }));
+}
+
+#[test]
+fn test_verify_and_decode_journal() {
+    let program_vk = Decode::decode_from_slice(PROGRAM_VK).unwrap();
+    let verifier = Risc0Verifier::new(program_vk);
+    let proof = Risc0Proof::decode_from_slice(PROOF).unwrap();
+    let output = verifier
+        .verify_and_decode_journal(&proof, PublicValuesEncoding::Raw)
+        .unwrap();
+    assert_eq!(output, PUBLIC_VALUES);
+
+    let err = verifier
+        .verify_and_decode_journal(
+            &proof,
+            PublicValuesEncoding::Hashed {
+                digest_len: PUBLIC_VALUES.len() + 1,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, Error::DecodeJournal(_)));
+
+    let verifier = verifier_with_unexpected_program_vk();
+    let err = verifier
+        .verify_and_decode_journal(&proof, PublicValuesEncoding::Raw)
+        .unwrap_err();
+    assert!(matches!(err, Error::ImageIdMismatch { .. }));
 }
 
 fn proof_with_unexpected_public_values() -> Risc0Proof {
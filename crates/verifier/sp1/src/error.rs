@@ -1,4 +1,4 @@
-use sp1_verifier::{SP1ProofMode, compressed::CompressedError};
+use sp1_verifier::{Groth16Error, PlonkError, SP1ProofMode, compressed::CompressedError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -15,11 +15,23 @@ pub enum Error {
     #[error("Non-canonical ProgramVk")]
     NonCanonicalProgramVk,
 
-    /// Proof was not in the expected `Compressed` form.
-    #[error("Unexpected proof kind, expected: Compressed, got: {0:?}")]
+    /// Proof was not in the expected `Compressed`, `Groth16` or `Plonk` form.
+    #[error("Unexpected proof kind, expected: Compressed, Groth16 or Plonk, got: {0:?}")]
     UnexpectedProofKind(SP1ProofMode),
 
+    /// Encoded SNARK proof is not valid hex.
+    #[error("Invalid encoded proof: {0}")]
+    InvalidEncodedProof(#[from] hex::FromHexError),
+
     /// `sp1-verifier` rejected the proof.
     #[error("Failed to verify: {0}")]
     Verify(#[from] CompressedError),
+
+    /// `sp1-verifier` rejected the Groth16 proof.
+    #[error("Failed to verify Groth16 proof: {0}")]
+    VerifyGroth16(#[from] Groth16Error),
+
+    /// `sp1-verifier` rejected the Plonk proof.
+    #[error("Failed to verify Plonk proof: {0}")]
+    VerifyPlonk(#[from] PlonkError),
 }
@@ -44,6 +44,26 @@ impl Decode for SP1ProgramVk {
     }
 }
 
+impl SP1ProgramVk {
+    /// Returns the digest packed into a BN254 field element as a `0x`-prefixed 32-byte hex
+    /// string, which is the verifying key hash committed by Groth16 and Plonk proofs, same as
+    /// `HashableKey::bytes32` of `sp1_sdk`.
+    pub fn bytes32(&self) -> String {
+        // Words are less than 2^31, so 8 of them packed by 31 bits fit in 248 bits without
+        // reduction modulo the BN254 scalar field.
+        let mut bytes = [0u8; 32];
+        for word in self.0.map(|word| word.as_canonical_u32()) {
+            let mut carry = word;
+            for byte in bytes.iter_mut().rev() {
+                let shifted = ((*byte as u64) << 31) + carry as u64;
+                *byte = shifted as u8;
+                carry = (shifted >> 8) as u32;
+            }
+        }
+        format!("0x{}", hex::encode(bytes))
+    }
+}
+
 fn from_canonical_u32<F: PrimeField32>(word: u32) -> F {
     F::from_canonical_u32(word)
 }
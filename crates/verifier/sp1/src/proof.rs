@@ -3,10 +3,13 @@ use sp1_verifier::ProofFromNetwork;
 
 /// A proof produced by the host prover that bundles everything needed for verification.
 ///
-/// Only the [`Compressed`] variant is accepted by [`SP1Verifier::verify`], any other variant
-/// returns [`Error::UnexpectedProofKind`]. Serialized via bincode legacy.
+/// Only the [`Compressed`], [`Groth16`] and [`Plonk`] variants are accepted by
+/// [`SP1Verifier::verify`], any other variant returns [`Error::UnexpectedProofKind`]. Serialized
+/// via bincode legacy.
 ///
 /// [`Compressed`]: sp1_verifier::SP1Proof::Compressed
+/// [`Groth16`]: sp1_verifier::SP1Proof::Groth16
+/// [`Plonk`]: sp1_verifier::SP1Proof::Plonk
 /// [`SP1Verifier::verify`]: crate::SP1Verifier::verify
 /// [`Error::UnexpectedProofKind`]: crate::Error::UnexpectedProofKind
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1,7 +1,10 @@
 use std::sync::LazyLock;
 
 use ere_verifier_core::{PublicValues, zkVMVerifier};
-use sp1_verifier::compressed::SP1CompressedVerifier;
+use sp1_verifier::{
+    GROTH16_VK_BYTES, Groth16Verifier, PLONK_VK_BYTES, PlonkVerifier, SP1Proof as SP1ProofVariant,
+    compressed::SP1CompressedVerifier,
+};
 
 use crate::{Error, SP1ProgramVk, SP1Proof};
 
@@ -36,15 +39,44 @@ impl zkVMVerifier for SP1Verifier {
     fn verify(&self, proof: &SP1Proof) -> Result<PublicValues, Error> {
         let public_values = proof.0.public_values.as_slice();
 
-        let Some(proof) = proof.0.proof.try_as_compressed_ref() else {
-            return Err(Error::UnexpectedProofKind(proof.0.mode()));
-        };
-
-        COMPRESSED_VERIFIER.verify_compressed_with_public_values(
-            proof,
-            public_values,
-            &self.program_vk.0,
-        )?;
+        // SNARK proofs are prefixed by the first 4 bytes of the hash of the circuit verifying key,
+        // same as `SP1ProofWithPublicValues::bytes` of `sp1_sdk`.
+        match &proof.0.proof {
+            SP1ProofVariant::Compressed(proof) => {
+                COMPRESSED_VERIFIER.verify_compressed_with_public_values(
+                    proof,
+                    public_values,
+                    &self.program_vk.0,
+                )?;
+            }
+            SP1ProofVariant::Groth16(proof) => {
+                let bytes = [
+                    &proof.groth16_vkey_hash[..4],
+                    &hex::decode(&proof.encoded_proof)?,
+                ]
+                .concat();
+                Groth16Verifier::verify(
+                    &bytes,
+                    public_values,
+                    &self.program_vk.bytes32(),
+                    &GROTH16_VK_BYTES,
+                )?;
+            }
+            SP1ProofVariant::Plonk(proof) => {
+                let bytes = [
+                    &proof.plonk_vkey_hash[..4],
+                    &hex::decode(&proof.encoded_proof)?,
+                ]
+                .concat();
+                PlonkVerifier::verify(
+                    &bytes,
+                    public_values,
+                    &self.program_vk.bytes32(),
+                    &PLONK_VK_BYTES,
+                )?;
+            }
+            _ => return Err(Error::UnexpectedProofKind(proof.0.mode())),
+        }
 
         Ok(public_values.into())
     }
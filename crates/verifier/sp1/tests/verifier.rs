@@ -7,6 +7,8 @@ use sp1_primitives::io::SP1PublicValues;
 const PROGRAM_VK: &[u8] = include_bytes!("./fixtures/program_vk.bin");
 const PROOF: &[u8] = include_bytes!("./fixtures/proof.bin");
 const PUBLIC_VALUES: &[u8] = include_bytes!("./fixtures/public_values.bin");
+// `sdk.vk().bytes32()` of the program the fixtures are generated from.
+const PROGRAM_VK_BYTES32: &str = include_str!("./fixtures/program_vk_bytes32.txt");
 
 #[test]
 fn test_verifier() {
@@ -17,6 +19,12 @@ fn test_verifier() {
     assert_eq!(&*public_values, PUBLIC_VALUES);
 }
 
+#[test]
+fn test_program_vk_bytes32() {
+    let program_vk = SP1ProgramVk::decode_from_slice(PROGRAM_VK).unwrap();
+    assert_eq!(program_vk.bytes32(), PROGRAM_VK_BYTES32.trim());
+}
+
 #[test]
 fn test_invalid_program_vk_decode() {
     let truncated = &PROGRAM_VK[..PROGRAM_VK.len() - 1];
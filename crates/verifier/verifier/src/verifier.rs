@@ -1,3 +1,5 @@
+use std::io::Read;
+
 use ere_catalog::zkVMKind;
 use ere_verifier_core::{PublicValues, codec::Decode, zkVMVerifier};
 
@@ -88,4 +90,16 @@ impl Verifier {
             }
         })
     }
+
+    /// Same as [`Verifier::verify`] but reads the encoded proof from `reader`.
+    ///
+    /// The proof is read to the end and then decoded by the proof type's [`Decode`] impl, so
+    /// it is accepted exactly when [`Verifier::verify`] would accept the same bytes.
+    pub fn verify_from_reader(&self, mut reader: impl Read) -> Result<PublicValues, Error> {
+        let mut encoded_proof = Vec::new();
+        reader
+            .read_to_end(&mut encoded_proof)
+            .map_err(Error::decode_proof)?;
+        self.verify(&encoded_proof)
+    }
 }
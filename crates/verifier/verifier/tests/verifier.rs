@@ -16,6 +16,22 @@ macro_rules! test_verifier {
                     assert_eq!(&*public_values, PUBLIC_VALUES);
                 }
 
+                #[test]
+                fn test_verifier_from_reader() {
+                    let verifier = Verifier::new(zkVMKind::$zkvm_kind, PROGRAM_VK).unwrap();
+                    let public_values = verifier.verify_from_reader(PROOF).unwrap();
+                    assert_eq!(&*public_values, PUBLIC_VALUES);
+
+                    let truncated = &PROOF[..PROOF.len() - 1];
+                    let err = verifier.verify_from_reader(truncated).unwrap_err();
+                    assert!(matches!(err, Error::DecodeProof(_)));
+
+                    let mut extended = PROOF.to_vec();
+                    extended.push(0xFF);
+                    let err = verifier.verify_from_reader(extended.as_slice()).unwrap_err();
+                    assert!(matches!(err, Error::DecodeProof(_)));
+                }
+
                 #[test]
                 fn test_invalid_program_vk_decode() {
                     let truncated = &PROGRAM_VK[..PROGRAM_VK.len() - 1];
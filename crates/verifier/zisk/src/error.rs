@@ -1,3 +1,4 @@
+use ere_verifier_core::PublicValuesDecodeError;
 use thiserror::Error;
 
 use crate::ZiskProgramVk;
@@ -32,6 +33,17 @@ pub enum Error {
     #[error("Invalid proof")]
     InvalidProof,
 
+    /// Public values are not a digest followed by zero padding.
+    #[error("Failed to decode output digest: {0}")]
+    DecodeOutputDigest(#[from] PublicValuesDecodeError),
+
+    /// Output digest committed by the guest did not match the digest of the supplied output.
+    #[error("Output digest mismatch, committed: {committed:02x?}, computed: {computed:02x?}")]
+    OutputDigestMismatch {
+        committed: Vec<u8>,
+        computed: Vec<u8>,
+    },
+
     /// ProgramVk inside the proof did not match the expected one.
     #[error("Unexpected ProgramVk, expected: {expected:?}, got: {got:?}")]
     UnexpectedProgramVk {
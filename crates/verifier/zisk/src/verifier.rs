@@ -1,7 +1,10 @@
-use ere_verifier_core::{PublicValues, zkVMVerifier};
+use ere_verifier_core::{PublicValues, PublicValuesDecoder, PublicValuesEncoding, zkVMVerifier};
 use proofman_verifier::verify_vadcop_final_compressed_u64;
+use sha2::{Digest, Sha256};
 
-use crate::{Error, ZiskProgramVk, ZiskProof, verifier::vk::VADCOP_FINAL_COMPRESSED_VK};
+use crate::{
+    Error, PUBLIC_VALUES_BYTES, ZiskProgramVk, ZiskProof, verifier::vk::VADCOP_FINAL_COMPRESSED_VK,
+};
 
 include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
 
@@ -21,6 +24,30 @@ impl ZiskVerifier {
     pub fn new(program_vk: ZiskProgramVk) -> Self {
         Self { program_vk }
     }
+
+    /// Verifies `proof` of a guest writing its output by `ZiskOutputHashedPlatform` of
+    /// `ere-platform-zisk`, and checks the SHA-256 digest it committed against `output` supplied
+    /// out-of-band, which could exceed the 256 bytes of public values.
+    ///
+    /// Returns the public values on success, which are the digest followed by zero padding.
+    pub fn verify_with_output(
+        &self,
+        proof: &ZiskProof,
+        output: &[u8],
+    ) -> Result<PublicValues, Error> {
+        let public_values = self.verify(proof)?;
+        let committed = PublicValuesDecoder::new(PublicValuesEncoding::Hashed { digest_len: 32 })
+            .with_padded_len(Some(PUBLIC_VALUES_BYTES))
+            .decode(&public_values)?;
+        let computed = Sha256::digest(output);
+        if committed != computed.as_slice() {
+            return Err(Error::OutputDigestMismatch {
+                committed: committed.to_vec(),
+                computed: computed.to_vec(),
+            });
+        }
+        Ok(public_values)
+    }
 }
 
 impl zkVMVerifier for ZiskVerifier {
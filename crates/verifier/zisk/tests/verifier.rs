@@ -17,6 +17,20 @@ fn test_verifier() {
     assert_eq!(&*public_values, PUBLIC_VALUES);
 }
 
+#[test]
+fn test_verify_with_output() {
+    let program_vk = Decode::decode_from_slice(PROGRAM_VK).unwrap();
+    let verifier = ZiskVerifier::new(program_vk);
+    let proof = Decode::decode_from_slice(PROOF).unwrap();
+    // The fixture guest commits its output as-is, which is longer than a digest.
+    assert!(matches!(
+        verifier
+            .verify_with_output(&proof, PUBLIC_VALUES)
+            .unwrap_err(),
+        Error::DecodeOutputDigest(_)
+    ));
+}
+
 #[test]
 fn test_invalid_program_vk_decode() {
     let truncated = &PROGRAM_VK[..PROGRAM_VK.len() - 1];
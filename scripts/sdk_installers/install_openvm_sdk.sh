@@ -45,5 +45,7 @@ else
     exit 1
 fi
 
-# Setup aggregation keys
-cargo openvm setup
+# Setup aggregation keys, unless only compiling guests
+if [ -z "$COMPILER_ONLY" ]; then
+    cargo openvm setup
+fi
@@ -32,10 +32,11 @@ ensure_tool_installed "cargo" "to pre-build lib-c"
 
 # Step 1: Download and run the script that installs the ziskup binary itself.
 # Export USE_GPU to download pre-built cargo-zisk and zisk-worker with or without cuda support.
-# Export SETUP_KEY=proving-no-consttree to download proving key without doing setup.
+# Export SETUP_KEY=proving-no-consttree to download proving key without doing setup, or
+# SETUP_KEY=none to skip it when only compiling guests.
 export ZISK_VERSION="0.18.0"
 export USE_GPU=$([ -n "$CUDA" ] && echo true || echo false)
-export SETUP_KEY=${SETUP_KEY:=proving-no-consttree}
+export SETUP_KEY=${SETUP_KEY:=$([ -n "$COMPILER_ONLY" ] && echo none || echo proving-no-consttree)}
 curl "https://raw.githubusercontent.com/0xPolygonHermez/zisk/v$ZISK_VERSION/ziskup/ziskup" | bash
 unset SETUP_KEY
 
@@ -0,0 +1,10 @@
+#![no_main]
+
+use ere_platform_airbender::{AirbenderPlatform, entrypoint};
+use ere_util_test::program::{Program, primitives::PrimitivesProgram};
+
+entrypoint!(main);
+
+fn main() {
+    PrimitivesProgram::run::<AirbenderPlatform>();
+}
@@ -0,0 +1,6 @@
+use ere_platform_openvm::OpenVMPlatform;
+use ere_util_test::program::{Program, primitives::PrimitivesProgram};
+
+fn main() {
+    PrimitivesProgram::run::<OpenVMPlatform>();
+}
@@ -0,0 +1,6 @@
+use ere_platform_risc0::Risc0Platform;
+use ere_util_test::program::{Program, primitives::PrimitivesProgram};
+
+fn main() {
+    PrimitivesProgram::run::<Risc0Platform>();
+}
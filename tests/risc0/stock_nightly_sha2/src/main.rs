@@ -0,0 +1,17 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use sha2::{Digest, Sha256};
+
+mod risc0_rt;
+
+fn main() {
+    let digest = Sha256::digest(core::hint::black_box(b"abc"));
+
+    // SHA-256 test vector of "abc" from FIPS 180-2.
+    if digest[..4] != [0xba, 0x78, 0x16, 0xbf] {
+        panic!("Something went wrong!");
+    }
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use ere_platform_sp1::{SP1Platform, sp1_zkvm};
+use ere_util_test::program::{Program, primitives::PrimitivesProgram};
+
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    PrimitivesProgram::run::<SP1Platform>();
+}
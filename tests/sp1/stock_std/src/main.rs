@@ -0,0 +1,3 @@
+fn main() {
+    println!("std is unavailable for the bare metal target");
+}
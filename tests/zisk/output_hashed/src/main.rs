@@ -0,0 +1,12 @@
+#![no_main]
+
+use ere_platform_zisk::{ziskos, Platform, ZiskOutputHashedPlatform};
+
+ziskos::entrypoint!(main);
+
+/// Writes the input repeated to 1024 bytes, which exceeds the 256-byte output cap of ZisK.
+fn main() {
+    let input = ZiskOutputHashedPlatform::read_input();
+    let output = input.iter().cycle().take(1024).copied().collect::<Vec<_>>();
+    ZiskOutputHashedPlatform::write_output(&output);
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use ere_platform_zisk::{ZiskPlatform, ziskos};
+use ere_util_test::program::{Program, primitives::PrimitivesProgram};
+
+ziskos::entrypoint!(main);
+
+fn main() {
+    PrimitivesProgram::run::<ZiskPlatform>();
+}